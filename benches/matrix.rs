@@ -0,0 +1,69 @@
+/*
+ This file contains the benchmark of the Strassen-Winograd matrix multiplication against the naive one
+*/
+use fhe::core::math::matrix::Matrix;
+use fhe::core::math::matrixstrassen::MatrixStrassen;
+use num::bigint::{BigInt, Sign};
+use rand::{Rng, SeedableRng};
+use std::hint::black_box;
+use std::ops::{Add, Mul, Sub};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Среднее время одного вызова f за примерно 200 мс
+fn time_per_call<R>(mut f: impl FnMut() -> R) -> Duration {
+    let budget = Duration::from_millis(200);
+    let start = Instant::now();
+    let mut calls = 0u32;
+    while start.elapsed() < budget {
+        black_box(f());
+        calls += 1;
+    }
+    start.elapsed() / calls
+}
+
+fn compare<T>(name: &str, sizes: &[usize], cutoffs: &[usize], mut random: impl FnMut() -> T)
+where
+    T: Clone + Send + Sync + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + 'static,
+    T: Default,
+{
+    println!("{}", name);
+    println!(
+        "{:>6} {:>7} {:>14} {:>14} {:>9}",
+        "N", "cutoff", "naive", "strassen", "speedup"
+    );
+    for &n in sizes {
+        let mut random_matrix = || {
+            let data = (0..n * n).map(|_| random()).collect();
+            Matrix::from_data(Arc::new(T::default), n, n, data)
+        };
+        let (a, b) = (random_matrix(), random_matrix());
+        let naive = time_per_call(|| a.mult(&b));
+        for &cutoff in cutoffs {
+            let strassen = MatrixStrassen::new(cutoff);
+            let fast = time_per_call(|| strassen.mult(&a, &b));
+            println!(
+                "{:>6} {:>7} {:>14?} {:>14?} {:>8.2}x",
+                n,
+                cutoff,
+                naive,
+                fast,
+                naive.as_secs_f64() / fast.as_secs_f64()
+            );
+        }
+    }
+}
+
+fn main() {
+    let mut prng = rand::rngs::StdRng::seed_from_u64(20);
+    /* умножение элементов дорого, как у многочленов: выигрыш уже на небольших матрицах */
+    compare("BigInt, 4096 bits", &[16, 32, 64], &[4, 8, 16], || {
+        let digits: Vec<u32> = (0..128).map(|_| prng.gen()).collect();
+        BigInt::from_slice(if prng.gen() { Sign::Plus } else { Sign::Minus }, &digits)
+    });
+    let mut prng = rand::rngs::StdRng::seed_from_u64(20);
+    /* умножение почти так же дёшево, как сложение: нужен большой порог */
+    compare("f64", &[128, 256, 512], &[32, 64, 128], || {
+        prng.gen_range(-1.0..1.0)
+    });
+}
@@ -0,0 +1,91 @@
+/*
+ This file contains the benchmark of the negacyclic NTT multiplication against the naive one
+*/
+use fhe::core::math::hal::intnat::mubintvecnat::NativeVector;
+use fhe::core::math::hal::intnat::transformnat::ChineseRemainderTransformFTTNat;
+use fhe::core::math::hal::intnat::ubintnat::NativeInteger;
+use fhe::core::math::nbtheory::{first_prime, root_of_unity};
+use rand::{Rng, SeedableRng};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Произведение в Z_q[x]/(x^n + 1) за O(n^2)
+fn naive_negacyclic(a: &NativeVector, b: &NativeVector) -> NativeVector {
+    let n = a.get_length();
+    let q = *a.get_modulus();
+    let mut result = NativeVector::new(n, q);
+    for i in 0..n {
+        for j in 0..n {
+            let prod = a[i].mod_mul(&b[j], &q);
+            let k = (i + j) % n;
+            result[k] = if i + j < n {
+                result[k].mod_add(&prod, &q)
+            } else {
+                result[k].mod_sub(&prod, &q)
+            };
+        }
+    }
+    result
+}
+
+/// Произведение в Z_q[x]/(x^n + 1) через NTT за O(n log n)
+fn ntt_negacyclic(a: &NativeVector, b: &NativeVector, root: &NativeInteger) -> NativeVector {
+    let cyclo_order = 2 * a.get_length();
+    let (mut a_ntt, mut b_ntt) = (a.clone(), b.clone());
+    ChineseRemainderTransformFTTNat::forward_transform_to_bit_reverse_in_place(
+        root,
+        cyclo_order,
+        &mut a_ntt,
+    );
+    ChineseRemainderTransformFTTNat::forward_transform_to_bit_reverse_in_place(
+        root,
+        cyclo_order,
+        &mut b_ntt,
+    );
+    a_ntt.mod_mul_vec_eq(&b_ntt);
+    ChineseRemainderTransformFTTNat::inverse_transform_from_bit_reverse_in_place(
+        root,
+        cyclo_order,
+        &mut a_ntt,
+    );
+    a_ntt
+}
+
+/// Среднее время одного вызова f за примерно 100 мс
+fn time_per_call<R>(mut f: impl FnMut() -> R) -> Duration {
+    let budget = Duration::from_millis(100);
+    let start = Instant::now();
+    let mut calls = 0u32;
+    while start.elapsed() < budget {
+        black_box(f());
+        calls += 1;
+    }
+    start.elapsed() / calls
+}
+
+fn main() {
+    let mut prng = rand::rngs::StdRng::seed_from_u64(16);
+    println!("{:>6} {:>14} {:>14} {:>9}", "N", "naive", "ntt", "speedup");
+    for logn in 4..=12 {
+        let n = 1usize << logn;
+        let q: NativeInteger = first_prime(59, 2 * n as u64);
+        let root = root_of_unity(2 * n as u64, &q);
+        let mut random_vector = || {
+            let values: Vec<u64> = (0..n).map(|_| prng.gen()).collect();
+            NativeVector::from_values(&values, q)
+        };
+        let (a, b) = (random_vector(), random_vector());
+        /* первый вызов заполняет кэш таблиц корней */
+        assert_eq!(naive_negacyclic(&a, &b), ntt_negacyclic(&a, &b, &root));
+
+        let naive = time_per_call(|| naive_negacyclic(&a, &b));
+        let ntt = time_per_call(|| ntt_negacyclic(&a, &b, &root));
+        println!(
+            "{:>6} {:>14?} {:>14?} {:>8.1}x",
+            n,
+            naive,
+            ntt,
+            naive.as_secs_f64() / ntt.as_secs_f64()
+        );
+    }
+}
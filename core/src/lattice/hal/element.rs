@@ -38,12 +38,9 @@
 
 use std::ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
 
+use crate::math::discrete_gaussian_generator::DiscreteGaussianGenerator;
 use crate::types::Format;
 
-pub struct DiscreteGaussianGeneratorImpl<X> {
-    _phantom: std::marker::PhantomData<X>,
-}
-
 /// 🇷🇺 Интерфейс для идеальных решеток
 ///
 /// Каждая решетка должна реализовать эти чистые виртуальные функции, чтобы
@@ -228,7 +225,7 @@ pub trait ILElement<Idx>:
     /// 🇬🇧 Clones the element with parameters and with noise for the vector
     fn clone_with_noise(
         &self,
-        dgg: &DiscreteGaussianGeneratorImpl<Self::VecType>,
+        dgg: &DiscreteGaussianGenerator<Self::VecType>,
         format: Format,
     ) -> Self::Element;
 
@@ -237,15 +234,17 @@ pub trait ILElement<Idx>:
 
     /// 🇷🇺 Оператор присваивания, копирующий элементы.
     /// 🇬🇧 Assignment operator that copies elements.
-    fn assign(&self, rhs: &Self::Element) -> &Self::Element;
+    fn assign(&mut self, rhs: &Self::Element) -> &mut Self;
 
-    /// 🇷🇺 Оператор присваивания, копирующий элементы.
-    /// 🇬🇧 Assignment operator that copies elements.
-    fn assign_move(&self, rhs: &Self::Element) -> &Self::Element;
+    /// 🇷🇺 Оператор присваивания, забирающий элементы.
+    /// 🇬🇧 Assignment operator that moves elements.
+    fn assign_move(&mut self, rhs: Self::Element) -> &mut Self;
 
-    /// 🇷🇺 Оператор присваивания, копирующий элементы.
-    /// 🇬🇧 Assignment operator that copies elements.
-    fn assign_initializer_list(&self, rhs: Vec<u64>) -> &Self::Element;
+    /// 🇷🇺 Оператор присваивания списком значений: первые значения заменяются,
+    /// остальные обнуляются.
+    /// 🇬🇧 Assignment operator with a list of values: the leading values are replaced,
+    /// the rest are set to zero.
+    fn assign_initializer_list(&mut self, rhs: &[u64]) -> &mut Self;
 
     // 🇷🇺 Функции получения данных
     // 🇬🇧 GETTERS
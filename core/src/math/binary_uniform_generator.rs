@@ -37,8 +37,8 @@
  relies on the built-in Rust generator for 32-bit unsigned integers defined in rand crate
 */
 
+use super::distribution_generator::PRNG;
 use rand::distributions::{Bernoulli, Distribution};
-use rand::thread_rng;
 
 pub struct BinaryUniformGeneratorImpl;
 
@@ -47,15 +47,13 @@ impl BinaryUniformGeneratorImpl {
         BinaryUniformGeneratorImpl
     }
 
-    pub fn generate_integer() -> bool {
-        let mut rng = thread_rng();
+    pub fn generate_integer(prng: &mut PRNG) -> bool {
         let distribution = Bernoulli::new(0.5).unwrap();
-        distribution.sample(&mut rng)
+        distribution.sample(prng)
     }
 
-    pub fn generate_vector(size: usize) -> Vec<bool> {
-        let mut rng = thread_rng();
+    pub fn generate_vector(size: usize, prng: &mut PRNG) -> Vec<bool> {
         let distribution = Bernoulli::new(0.5).unwrap();
-        (0..size).map(|_| distribution.sample(&mut rng)).collect()
+        (0..size).map(|_| distribution.sample(prng)).collect()
     }
 }
@@ -36,26 +36,25 @@
  the built-in Rust generator for 32-bit unsigned integers defined in rand crate
 */
 
+use super::distribution_generator::PRNG;
 use rand::distributions::{Bernoulli, Distribution};
-use rand::thread_rng;
 
 pub struct BinaryUniformGenerator;
 
 impl BinaryUniformGenerator {
-    fn generate_integer() -> u32 {
-        let mut rng = thread_rng();
+    fn generate_integer(prng: &mut PRNG) -> u32 {
         let distribution = Bernoulli::new(0.5).unwrap();
-        if distribution.sample(&mut rng) {
+        if distribution.sample(prng) {
             1
         } else {
             0
         }
     }
 
-    pub fn generate_vector(size: usize) -> Vec<u32> {
+    pub fn generate_vector(size: usize, prng: &mut PRNG) -> Vec<u32> {
         let mut v = Vec::with_capacity(size);
         for _ in 0..size {
-            v.push(Self::generate_integer());
+            v.push(Self::generate_integer(prng));
         }
         v
     }
@@ -64,12 +63,21 @@ impl BinaryUniformGenerator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::SeedableRng;
 
     #[test]
     fn test_generate_vector() {
         let size = 10;
-        let vector = BinaryUniformGenerator::generate_vector(size);
+        let vector = BinaryUniformGenerator::generate_vector(size, &mut PRNG::new());
         dbg!(&vector);
         assert_eq!(vector.len(), size);
     }
+
+    #[test]
+    fn test_generate_vector_seeded() {
+        let size = 64;
+        let a = BinaryUniformGenerator::generate_vector(size, &mut PRNG::seed_from_u64(1));
+        let b = BinaryUniformGenerator::generate_vector(size, &mut PRNG::seed_from_u64(1));
+        assert_eq!(a, b);
+    }
 }
@@ -105,10 +105,11 @@ pub trait VectorType {
     fn zero(size: usize) -> Self;
 }
 
-use rand::prelude::*;
+use super::distribution_generator::PRNG;
+use rand::distributions::{Distribution, Uniform};
 use std::{f64::consts::E, marker::PhantomData};
 
-pub struct DiscreteGaussianGenerator<VecType: VectorType> {
+pub struct DiscreteGaussianGenerator<VecType> {
     m_std: f64,
     _m_a: f64,
     m_vals: Vec<f64>,
@@ -116,7 +117,7 @@ pub struct DiscreteGaussianGenerator<VecType: VectorType> {
     _marker: PhantomData<VecType>,
 }
 
-impl<VecType: VectorType> DiscreteGaussianGenerator<VecType> {
+impl<VecType> DiscreteGaussianGenerator<VecType> {
     /// 🇷🇺 Базовый конструктор для задания параметра распределения и модуля.
     ///
     /// Параметры:
@@ -176,28 +177,32 @@ impl<VecType: VectorType> DiscreteGaussianGenerator<VecType> {
         self.m_std = std;
     }
 
-    /// 🇷🇺 Возвращает сгенерированное знаковое целое число. Использует метод инверсии Пейкерта
+    /// 🇷🇺 Возвращает сгенерированное знаковое целое число с центром 0. Использует метод Карни,
+    /// который точен при любом стандартном отклонении.
     /// Возвращает значение, полученное с помощью распределения.
     ///
-    /// 🇬🇧 Returns a generated signed integer. Uses Peikert's Inversion Method
+    /// 🇬🇧 Returns a generated signed integer centered at 0. Uses Karney's method,
+    /// which is exact for any standard deviation.
     /// Return a value generated with the distribution.
-    pub fn generate_int(&self) -> i32 {
-        // Implement Peikert's Inversion Method here
-        0
+    pub fn generate_int(&self, prng: &mut PRNG) -> i64 {
+        Self::generate_integer_karney(0.0, self.m_std, prng)
     }
 
-    /// 🇷🇺 Возвращает сгенерированное целое число. Использует метод инверсии Пейкерта.
+    /// 🇷🇺 Возвращает вектор сгенерированных целых чисел с центром 0. Использует метод Карни.
     ///
     /// Результат: Вектор целочисленных значений, сгенерированных с помощью распределения.
     ///
-    /// 🇬🇧 Returns a generated integer. Uses Peikert's inversion method.
+    /// 🇬🇧 Returns a vector of generated integers centered at 0. Uses Karney's method.
     ///
     /// Result: A Vector of integer values generated with the distribution.
-    pub fn generate_int_vector(&self, size: u32) -> Vec<i64> {
-        // Implement Peikert's Inversion Method here
-        vec![0; size as usize]
+    pub fn generate_int_vector(&self, size: u32, prng: &mut PRNG) -> Vec<i64> {
+        (0..size)
+            .map(|_| Self::generate_integer_karney(0.0, self.m_std, prng))
+            .collect()
     }
+}
 
+impl<VecType: VectorType> DiscreteGaussianGenerator<VecType> {
     /// 🇷🇺 Возвращает сгенерированное целое число. Использует метод инверсии Пейкерта.
     ///
     /// Результат: Случайная величина в рамках данного дискретного гауссова распределения.
@@ -205,7 +210,11 @@ impl<VecType: VectorType> DiscreteGaussianGenerator<VecType> {
     /// 🇬🇧 Returns a generated integer. Uses Peikert's inversion method.
     ///
     /// Result: A random value within this Discrete Gaussian Distribution.
-    pub fn generate_integer(&self, _modulus: VecType::Integer) -> VecType::Integer {
+    pub fn generate_integer(
+        &self,
+        _modulus: VecType::Integer,
+        _prng: &mut PRNG,
+    ) -> VecType::Integer {
         // Implement Peikert's Inversion Method here
         VecType::Integer::zero()
     }
@@ -226,7 +235,12 @@ impl<VecType: VectorType> DiscreteGaussianGenerator<VecType> {
     /// modulus - modulus of the polynomial ring.
     ///
     /// Result: The vector of values within this Discrete Gaussian Distribution.
-    pub fn generate_vector(&self, size: u32, _modulus: VecType::Integer) -> VecType {
+    pub fn generate_vector(
+        &self,
+        size: u32,
+        _modulus: VecType::Integer,
+        _prng: &mut PRNG,
+    ) -> VecType {
         // Implement Peikert's Inversion Method here
         VecType::zero(size as usize)
     }
@@ -254,11 +268,14 @@ impl<VecType: VectorType> DiscreteGaussianGenerator<VecType> {
         _std_dev: f64,
         _n: usize,
         _modulus: VecType::Integer,
+        _prng: &mut PRNG,
     ) -> VecType::Integer {
         // Implement rejection method here
         VecType::Integer::zero()
     }
+}
 
+impl<VecType> DiscreteGaussianGenerator<VecType> {
     /// 🇷🇺 Возвращает сгенерированное целое число. Использует метод [выборки с отклонением](https://en.wikipedia.org/wiki/Rejection_sampling)
     ///
     /// Параметры:
@@ -276,7 +293,13 @@ impl<VecType: VectorType> DiscreteGaussianGenerator<VecType> {
     /// n - ring dimension
     ///
     /// Result: a random value within this Discrete Gaussian Distribution.
-    pub fn generate_integer_rejection2(&self, _mean: f64, _std_dev: f64, _n: usize) -> i32 {
+    pub fn generate_integer_rejection2(
+        &self,
+        _mean: f64,
+        _std_dev: f64,
+        _n: usize,
+        _prng: &mut PRNG,
+    ) -> i32 {
         // Implement rejection method here
         0
     }
@@ -286,6 +309,7 @@ impl<VecType: VectorType> DiscreteGaussianGenerator<VecType> {
     /// Параметры:
     /// mean - центр дискретного гауссовского распределения.
     /// std_dev - стандартное отклонение дискретного гауссовского распределения.
+    /// prng - генератор псевдослучайных чисел.
     ///
     /// Возвращает: случайное целое число, принадлежащее этому дискретному гауссовскому распределению.
     ///
@@ -293,11 +317,49 @@ impl<VecType: VectorType> DiscreteGaussianGenerator<VecType> {
     /// Parameters:
     /// mean - center of discrete Gaussian distribution.
     /// std_dev - standard deviation of discrete Gaussian distribution.
+    /// prng - pseudorandom number generator.
     ///
     /// Returns: A random value within this Discrete Gaussian Distribution.
-    pub fn generate_integer_karney(_mean: f64, _std_dev: f64) -> i64 {
-        // Implement Karney's method here
-        0
+    pub fn generate_integer_karney(mean: f64, std_dev: f64, prng: &mut PRNG) -> i64 {
+        let uniform_sign = Uniform::new_inclusive(0, 1);
+        let uniform_j = Uniform::new_inclusive(0, std_dev.ceil() as i64 - 1);
+
+        loop {
+            // Шаг D1
+            let k = Self::algorithm_g(prng);
+
+            // Шаг D2
+            if !Self::algorithm_p(prng, k * (k - 1)) {
+                continue;
+            }
+
+            // Шаг D3
+            let s: i64 = if uniform_sign.sample(prng) == 0 {
+                -1
+            } else {
+                1
+            };
+
+            // Шаг D4
+            let di0 = std_dev * k as f64 + s as f64 * mean;
+            let i0 = di0.ceil() as i64;
+            let x0 = (i0 as f64 - di0) / std_dev;
+            let j = uniform_j.sample(prng);
+            let x = x0 + j as f64 / std_dev;
+
+            // Шаги D5 и D6
+            if x >= 1.0 || (x == 0.0 && s < 0 && k == 0) {
+                continue;
+            }
+
+            // Шаг D7
+            if !(0..=k).all(|_| Self::algorithm_b(prng, k, x)) {
+                continue;
+            }
+
+            // Шаг D8
+            return s * (i0 + j);
+        }
     }
 
     pub fn find_in_vector(&self, _s: &[f64], _search: f64) -> u32 {
@@ -330,7 +392,7 @@ impl<VecType: VectorType> DiscreteGaussianGenerator<VecType> {
     /// 🇷🇺 Метод Карни использует подпрограмму для генерации целого числа с вероятностью exp(-n/2).
     ///
     /// Параметры:
-    /// g (Вихрь Мерсенна)[https://ru.wikipedia.org/wiki/Вихрь_Мерсенна] используется для отклонений
+    /// g - генератор псевдослучайных чисел для отклонений
     /// n Число для проверки с вероятностью exp(-n/2)
     ///
     /// Результат: Принято или нет
@@ -339,19 +401,18 @@ impl<VecType: VectorType> DiscreteGaussianGenerator<VecType> {
     ///
     /// Parameters:
     ///
-    /// g Mersenne Twister Engine used for deviates
+    /// g Pseudorandom number generator used for deviates
     /// n Number to test with exp(-n/2) probability
     ///
     /// Result: Accept/Reject result
-    pub fn algorithm_p(_g: &mut ThreadRng, _n: i32) -> bool {
-        // Implement AlgorithmP here
-        false
+    pub fn algorithm_p(g: &mut PRNG, n: i32) -> bool {
+        (0..n).all(|_| Self::algorithm_h(g))
     }
 
     /// 🇷🇺 Метод Карни использует подпрограмму для генерации целого числа с вероятностью exp(-k/2)(1 - exp(-1/2)).
     ///
     /// Параметры:
-    /// g (Вихрь Мерсенна)[https://ru.wikipedia.org/wiki/Вихрь_Мерсенна] используется для отклонений
+    /// g - генератор псевдослучайных чисел для отклонений
     ///
     /// Результат: Случайная число k
     ///
@@ -359,18 +420,21 @@ impl<VecType: VectorType> DiscreteGaussianGenerator<VecType> {
     ///
     /// Parameters:
     ///
-    /// g Mersenne Twister Engine used for deviates
+    /// g Pseudorandom number generator used for deviates
     ///
     /// Result: Random number k
-    pub fn algorithm_g(_g: &mut ThreadRng) -> i32 {
-        // Implement AlgorithmG here
-        0
+    pub fn algorithm_g(g: &mut PRNG) -> i32 {
+        let mut n = 0;
+        while Self::algorithm_h(g) {
+            n += 1;
+        }
+        n
     }
 
     /// 🇷🇺 Генерирует случайную величину Бернулли H, которая истинна с вероятностью exp(-1/2).
     ///
     /// Параметры:
-    /// g (Вихрь Мерсенна)[https://ru.wikipedia.org/wiki/Вихрь_Мерсенна] используется для равномерных отклонений
+    /// g - генератор псевдослучайных чисел для равномерных отклонений
     ///
     /// Результат: Случайная величина Бернулли H
     ///
@@ -378,35 +442,71 @@ impl<VecType: VectorType> DiscreteGaussianGenerator<VecType> {
     ///
     /// Parameters:
     ///
-    /// g Mersenne Twister Engine used for uniform deviates
+    /// g Pseudorandom number generator used for uniform deviates
     ///
     /// Result: Bernoulli random value H
-    pub fn algorithm_h(_g: &mut ThreadRng) -> bool {
-        // Implement AlgorithmH here
-        false
+    pub fn algorithm_h(g: &mut PRNG) -> bool {
+        let dist = Uniform::new(0.0f32, 1.0);
+        let mut h_a = dist.sample(g);
+
+        // При совпадении значений одинарной точности не хватает, повторяем с двойной
+        if h_a > 0.5 {
+            return true;
+        } else if h_a == 0.5 {
+            return Self::algorithm_h_double(g);
+        }
+
+        loop {
+            let h_b = dist.sample(g);
+            if h_b > h_a {
+                return false;
+            } else if h_b == h_a {
+                return Self::algorithm_h_double(g);
+            }
+            h_a = dist.sample(g);
+            if h_a > h_b {
+                return true;
+            } else if h_a == h_b {
+                return Self::algorithm_h_double(g);
+            }
+        }
     }
 
     /// 🇷🇺 Генерирует случайную величину Бернулли H, которая истинна с вероятностью exp(-1/2). Используется двойная точность.
     ///
     /// Параметры:
-    /// g (Вихрь Мерсенна)[https://ru.wikipedia.org/wiki/Вихрь_Мерсенна] используется для равномерных отклонений
+    /// g - генератор псевдослучайных чисел для равномерных отклонений
     ///
     /// Результат: Случайная величина Бернулли H
     ///
     /// 🇬🇧 Generates a Bernoulli random value H which is true with probability exp(-1/2). Uses double precision.
     ///
-    /// Parameters: g Mersenne Twister Engine used for uniform deviates
+    /// Parameters: g Pseudorandom number generator used for uniform deviates
     ///
     /// Result: Bernoulli random value H
-    pub fn algorithm_h_double(_g: &mut ThreadRng) -> bool {
-        // Implement AlgorithmHDouble here
-        false
+    pub fn algorithm_h_double(g: &mut PRNG) -> bool {
+        let dist = Uniform::new(0.0f64, 1.0);
+        let mut h_a = dist.sample(g);
+        if h_a >= 0.5 {
+            return true;
+        }
+
+        loop {
+            let h_b = dist.sample(g);
+            if h_b >= h_a {
+                return false;
+            }
+            h_a = dist.sample(g);
+            if h_a >= h_b {
+                return true;
+            }
+        }
     }
 
     /// 🇷🇺 Метод Бернулли с вероятностью exp(-x(2k + x)/(2k + 2)). Используется двойная точность.
     ///
     /// Параметры:
-    /// g (Вихрь Мерсенна)[https://ru.wikipedia.org/wiki/Вихрь_Мерсенна] используется для равномерных отклонений
+    /// g - генератор псевдослучайных чисел для равномерных отклонений
     /// Отклонение k, используемое для расчетов
     /// Отклонение x, используемое для расчетов
     ///
@@ -415,20 +515,43 @@ impl<VecType: VectorType> DiscreteGaussianGenerator<VecType> {
     /// 🇬🇧 Bernoulli trial with probability exp(-x(2k + x)/(2k + 2)). Uses double precision.
     ///
     /// Parameters:
-    /// g Mersenne Twister Engine used for uniform deviates
+    /// g Pseudorandom number generator used for uniform deviates
     /// k Deviate k used for calculations
     /// x Deviate x used for calculations
     ///
     /// Return Whether the number of runs are even or not
-    pub fn algorithm_b(_g: &mut ThreadRng, _k: i32, _x: f64) -> bool {
-        // Implement AlgorithmB here
-        false
+    pub fn algorithm_b(g: &mut PRNG, k: i32, x: f64) -> bool {
+        let dist = Uniform::new(0.0f32, 1.0);
+        let mut y = x as f32;
+        let r_temp = ((2 * k) as f64 + x) as f32 / (2 * k + 2) as f32;
+        let mut n = 0;
+
+        loop {
+            let z = dist.sample(g);
+            if z > y {
+                break;
+            } else if z == y {
+                return Self::algorithm_b_double(g, k, x);
+            }
+
+            let r = dist.sample(g);
+            if r > r_temp {
+                break;
+            } else if r == r_temp {
+                return Self::algorithm_b_double(g, k, x);
+            }
+
+            y = z;
+            n += 1;
+        }
+
+        n % 2 == 0
     }
 
     /// 🇷🇺 Метод Бернулли с вероятностью exp(-x(2k + x)/(2k + 2)). Используется двойная точность.
     ///
     /// Параметры:
-    /// g (Вихрь Мерсенна)[https://ru.wikipedia.org/wiki/Вихрь_Мерсенна] используется для равномерных отклонений
+    /// g - генератор псевдослучайных чисел для равномерных отклонений
     /// Отклонение k, используемое для расчетов
     /// Отклонение x, используемое для расчетов
     ///
@@ -437,13 +560,30 @@ impl<VecType: VectorType> DiscreteGaussianGenerator<VecType> {
     /// 🇬🇧 Bernoulli trial with probability exp(-x(2k + x)/(2k + 2)). Uses double precision.
     ///
     /// Parameters:
-    /// g Mersenne Twister Engine used for uniform deviates
+    /// g Pseudorandom number generator used for uniform deviates
     /// k Deviate k used for calculations
     /// x Deviate x used for calculations
     ///
     /// Return Whether the number of runs are even or not
-    pub fn algorithm_b_double(_g: &mut ThreadRng, _k: i32, _x: f64) -> bool {
-        // Implement AlgorithmBDouble here
-        false
+    pub fn algorithm_b_double(g: &mut PRNG, k: i32, x: f64) -> bool {
+        let dist = Uniform::new(0.0f64, 1.0);
+        let mut y = x;
+        let r_temp = ((2 * k) as f64 + x) / (2 * k + 2) as f64;
+        let mut n = 0;
+
+        loop {
+            let z = dist.sample(g);
+            if z >= y {
+                break;
+            }
+            let r = dist.sample(g);
+            if r >= r_temp {
+                break;
+            }
+            y = z;
+            n += 1;
+        }
+
+        n % 2 == 0
     }
 }
@@ -152,6 +152,8 @@
    discretegaussiangeneratorgeneric.cpp
 */
 
+use super::distribution_generator::PRNG;
+use rand::Rng;
 use std::{collections::VecDeque, fmt::Debug};
 
 const PRECISION: u32 = 53;
@@ -190,9 +192,9 @@ impl BitGenerator {
 
     /// 🇷🇺 Метод генерации случайного бита.
     /// 🇬🇧 Method for generating a random bit. Return A random bit
-    pub fn generate(&mut self) -> i16 {
+    pub fn generate(&mut self, prng: &mut PRNG) -> i16 {
         if self.counter == 0 {
-            self.sequence = rand::random::<u32>();
+            self.sequence = prng.gen::<u32>();
             self.counter = 32;
         }
         let bit = (self.sequence >> (self.counter - 1)) & 0x1;
@@ -200,8 +202,8 @@ impl BitGenerator {
         bit as i16
     }
 
-    pub fn generate_bit(&mut self) -> bool {
-        self.generate() != 0
+    pub fn generate_bit(&mut self, prng: &mut PRNG) -> bool {
+        self.generate(prng) != 0
     }
 }
 
@@ -249,7 +251,7 @@ pub struct BaseSamplerObject {
 /// 🇷🇺 Трейт для базовых сэмплеров
 /// 🇬🇧 Trait for base samplers
 trait BaseSampler {
-    fn generate_integer(&mut self) -> i64;
+    fn generate_integer(&mut self, prng: &mut PRNG) -> i64;
 
     fn clone_dyn(&self) -> Box<dyn BaseSampler>;
 }
@@ -313,8 +315,8 @@ impl BaseSamplerObject {
 
     /// 🇷🇺 Метод генерации случайного бита с помощью генератора битов в пределах возврата случайного бита
     /// 🇬🇧 Method for generating a random bit from the bit generator within return a random bit
-    fn random_bit(&mut self) -> bool {
-        self.bg.generate() != 0
+    fn random_bit(&mut self, prng: &mut PRNG) -> bool {
+        self.bg.generate(prng) != 0
     }
 
     /// 🇷🇺 Подпроцедура, вызываемая инверсионной выборкой Пейкерта
@@ -498,7 +500,7 @@ impl BaseSamplerObject {
     /// Возвращает случайную величину в рамках дискретного гауссова распределения
     /// 🇬🇧 Returns a generated integer. Uses Naive Knuth-Yao method. Return a random value within the Discrete Gaussian Distribution
 
-    fn generate_integer_knuth_yao(&mut self) -> i64 {
+    fn generate_integer_knuth_yao(&mut self, prng: &mut PRNG) -> i64 {
         // Начальное недопустимое значение для результата
         let mut ans = -1;
         // Флаг, указывающий на удачную генерацию числа
@@ -513,7 +515,7 @@ impl BaseSamplerObject {
             // Цикл по глубине дерева
             for i in 0..MAX_TREE_DEPTH {
                 // Генерация случайного бита
-                let bit = self.bg.generate_bit();
+                let bit = self.bg.generate_bit(prng);
                 // Построение индекса пути по дереву: если bit == true, выбирается правый потомок, иначе - левый
                 node_index *= 2;
                 if bit {
@@ -558,11 +560,8 @@ impl BaseSamplerObject {
 
     /// 🇷🇺 Возвращает сгенерированное целое число. Использует метод инверсии Пейкерта.
     /// 🇬🇧 Returns a generated integer. Uses Peikert's inversion method.
-    fn generate_integer_peikert(&self) -> i64 {
-        use rand::prelude::*;
-
-        let mut rng = thread_rng();
-        let seed: f64 = rng.gen();
+    fn generate_integer_peikert(&self, prng: &mut PRNG) -> i64 {
+        let seed: f64 = prng.gen();
         let val = self.find_in_vector(&self.m_vals, seed);
         (val as i32 - self.fin + self.b_mean as i32) as i64
     }
@@ -571,10 +570,10 @@ impl BaseSamplerObject {
 impl BaseSampler for BaseSamplerObject {
     /// 🇷🇺 Метод генерации целого числа из базового сэмпла случайного целого числа из распределения
     /// 🇬🇧 Method for generating integer from the base sampler a random integer from the distribution
-    fn generate_integer(&mut self) -> i64 {
+    fn generate_integer(&mut self, prng: &mut PRNG) -> i64 {
         match self.b_type {
-            BaseSamplerType::KnuthYao => self.generate_integer_knuth_yao(),
-            BaseSamplerType::PeikertInversion => self.generate_integer_peikert(),
+            BaseSamplerType::KnuthYao => self.generate_integer_knuth_yao(prng),
+            BaseSamplerType::PeikertInversion => self.generate_integer_peikert(prng),
         }
     }
 
@@ -624,8 +623,9 @@ impl SamplerCombiner {
 impl BaseSampler for SamplerCombiner {
     /// 🇷🇺 Возвращает комбинированное значение для двух сэмплеров с заданными коэффициентами
     /// 🇬🇧 Return the combined value for two samplers with given coefficients
-    fn generate_integer(&mut self) -> i64 {
-        self.x1 * self.sampler1.generate_integer() + self.x2 * self.sampler2.generate_integer()
+    fn generate_integer(&mut self, prng: &mut PRNG) -> i64 {
+        self.x1 * self.sampler1.generate_integer(prng)
+            + self.x2 * self.sampler2.generate_integer(prng)
     }
 
     fn clone_dyn(&self) -> Box<dyn BaseSampler> {
@@ -748,9 +748,9 @@ impl DiscreteGaussianGeneratorGeneric {
     //     let sample = gaussian.sample(&mut rng);
     //     sample.round() as i64
     // }
-    fn generate_integer(&mut self, center: f64, std: f64) -> i64 {
+    fn generate_integer(&mut self, center: f64, std: f64, prng: &mut PRNG) -> i64 {
         let variance = std.powi(2);
-        let x = self.wide_sampler.generate_integer();
+        let x = self.wide_sampler.generate_integer(prng);
 
         let c =
             center + (x as f64) * ((variance - self.sampler_variance) / self.wide_variance).sqrt();
@@ -758,7 +758,7 @@ impl DiscreteGaussianGeneratorGeneric {
         let ci = c.floor() as i64;
         let c = c - ci as f64;
 
-        ci + self.flip_and_round(c)
+        ci + self.flip_and_round(c, prng)
     }
 
     /// 🇷🇺 Возвращает сгенерированное целое число с использованием базового сэмплера.
@@ -779,21 +779,21 @@ impl DiscreteGaussianGeneratorGeneric {
     ///
     /// * `center` - Center of the distribution
     ///
-    fn flip_and_round(&mut self, center: f64) -> i64 {
+    fn flip_and_round(&mut self, center: f64, prng: &mut PRNG) -> i64 {
         let c = (center as u64 * (1u64 << PRECISION)) as i64;
         let base_c = c >> BERNOULLI_FLIPS;
         let mut random_bit;
 
         for i in (0..BERNOULLI_FLIPS).rev() {
-            random_bit = self.base_samplers[0].generate_integer();
+            random_bit = self.base_samplers[0].generate_integer(prng);
             if random_bit > self.extract_bit(c, i as i32).into() {
-                return self.sample_c(base_c);
+                return self.sample_c(base_c, prng);
             }
             if random_bit < self.extract_bit(c, i as i32).into() {
-                return self.sample_c(base_c + 1);
+                return self.sample_c(base_c + 1, prng);
             }
         }
-        self.sample_c(base_c + 1)
+        self.sample_c(base_c + 1, prng)
     }
 
     // fn flip_and_round(&self, center: f64) -> i64 {
@@ -814,11 +814,11 @@ impl DiscreteGaussianGeneratorGeneric {
     /// # Arguments
     ///
     /// * `center` - Center of the distribution
-    fn sample_c(&mut self, center: i64) -> i64 {
+    fn sample_c(&mut self, center: i64, prng: &mut PRNG) -> i64 {
         let mut c = center;
         let mut sample;
         for _ in 0..self.k {
-            sample = self.base_samplers[(self.mask & c as u64) as usize].generate_integer();
+            sample = self.base_samplers[(self.mask & c as u64) as usize].generate_integer(prng);
             if (self.mask & c as u64) > 0 && c < 0 {
                 sample -= 1;
             }
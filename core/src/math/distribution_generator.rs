@@ -0,0 +1,274 @@
+//==================================================================================
+// BSD 2-Clause License
+//
+// Copyright (c) 2014-2023, NJIT, Duality Technologies Inc. and other contributors
+//            translated from C++ and upgraded by Anton Sinitsyn
+//
+// All rights reserved.
+//
+// Author TPOC: contact@openfhe.org
+// Anton Sinitsyn: antonsinitsyn@outlook.de
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//==================================================================================
+
+/*
+    🇷🇺 Единый генератор псевдослучайных чисел для всех распределений библиотеки.
+
+    В OpenFHE для этого используется движок на основе BLAKE2. Здесь вместо него взят
+    потоковый шифр ChaCha20: он криптографически стойкий, быстрый и позволяет из одного
+    ключа получить 2^64 независимых потоков. Все генераторы (равномерный, тернарный,
+    бинарный, гауссовский, случайное округление кодировщика) принимают генератор
+    параметром `&mut PRNG`, поэтому одинаковое зерно воспроизводит весь прогон побитово.
+
+    Для кода, который не хочет хранить генератор сам, есть генератор по умолчанию,
+    свой для каждого потока (`with_prng`). Обычно он инициализируется энтропией ОС.
+    В детерминированном режиме (`set_seed` или feature `fixed_seed`) все потоки
+    используют одно зерно, но разные потоки ChaCha20 в порядке первого обращения.
+
+    🇬🇧 A single pseudorandom number generator for all distributions of the library.
+
+    OpenFHE uses a BLAKE2-based engine for this. Here the ChaCha20 stream cipher is used
+    instead: it is cryptographically secure, fast and yields 2^64 independent streams
+    from one key. All generators (uniform, ternary, binary, Gaussian, encoder random
+    rounding) take the generator as a `&mut PRNG` parameter, so the same seed reproduces
+    a whole run bit for bit.
+
+    For code that does not want to keep a generator itself there is a per-thread default
+    generator (`with_prng`). Normally it is seeded from OS entropy. In the deterministic
+    mode (`set_seed` or the `fixed_seed` feature) all threads share one seed but use
+    different ChaCha20 streams, assigned in the order of first use.
+*/
+
+use rand::{CryptoRng, Error, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// 🇷🇺 Размер зерна генератора в байтах (ключ ChaCha20)
+/// 🇬🇧 Seed size of the generator in bytes (ChaCha20 key)
+pub const SEED_SIZE: usize = 32;
+
+/// 🇷🇺 Зерно генератора
+/// 🇬🇧 Generator seed
+pub type Seed = [u8; SEED_SIZE];
+
+/// 🇷🇺 Зерно, которое используется при включённой feature `fixed_seed`
+/// 🇬🇧 The seed used when the `fixed_seed` feature is enabled
+pub const FIXED_SEED: Seed = {
+    let mut seed = [0u8; SEED_SIZE];
+    seed[0] = 1;
+    seed
+};
+
+/// 🇷🇺 Криптографический генератор псевдослучайных чисел на основе ChaCha20.
+/// 🇬🇧 Cryptographic pseudorandom number generator based on ChaCha20.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PseudoRandomNumberGenerator {
+    engine: ChaCha20Rng,
+}
+
+/// 🇷🇺 Краткое имя генератора
+/// 🇬🇧 Short name of the generator
+pub type PRNG = PseudoRandomNumberGenerator;
+
+impl PseudoRandomNumberGenerator {
+    /// 🇷🇺 Генератор, инициализированный энтропией операционной системы
+    /// 🇬🇧 Generator seeded from the operating system entropy
+    pub fn new() -> Self {
+        Self {
+            engine: ChaCha20Rng::from_entropy(),
+        }
+    }
+
+    /// 🇷🇺 Генератор с заданным зерном и номером потока.
+    /// Генераторы с одним зерном и разными потоками выдают независимые последовательности.
+    ///
+    /// 🇬🇧 Generator with the given seed and stream number.
+    /// Generators with the same seed and different streams produce independent sequences.
+    pub fn with_stream(seed: Seed, stream: u64) -> Self {
+        let mut engine = ChaCha20Rng::from_seed(seed);
+        engine.set_stream(stream);
+        Self { engine }
+    }
+
+    /// 🇷🇺 Порождает новый независимый генератор, зерно которого берётся из текущего.
+    /// Удобно для раздачи генераторов потокам: результат детерминирован, если детерминирован родитель.
+    ///
+    /// 🇬🇧 Spawns a new independent generator seeded from the current one.
+    /// Handy for handing generators out to threads: the result is deterministic if the parent is.
+    pub fn split(&mut self) -> Self {
+        let mut seed = [0u8; SEED_SIZE];
+        self.engine.fill_bytes(&mut seed);
+        Self::from_seed(seed)
+    }
+
+    /// 🇷🇺 Зерно, из которого создан генератор
+    /// 🇬🇧 The seed the generator was created from
+    pub fn get_seed(&self) -> Seed {
+        self.engine.get_seed()
+    }
+
+    /// 🇷🇺 Номер потока ChaCha20
+    /// 🇬🇧 ChaCha20 stream number
+    pub fn get_stream(&self) -> u64 {
+        self.engine.get_stream()
+    }
+}
+
+impl Default for PseudoRandomNumberGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RngCore for PseudoRandomNumberGenerator {
+    fn next_u32(&mut self) -> u32 {
+        self.engine.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.engine.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.engine.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.engine.try_fill_bytes(dest)
+    }
+}
+
+impl CryptoRng for PseudoRandomNumberGenerator {}
+
+impl SeedableRng for PseudoRandomNumberGenerator {
+    type Seed = Seed;
+
+    fn from_seed(seed: Seed) -> Self {
+        Self {
+            engine: ChaCha20Rng::from_seed(seed),
+        }
+    }
+}
+
+/* 🇷🇺 Генератор по умолчанию для каждого потока
+ * 🇬🇧 Per-thread default generator */
+
+/// 🇷🇺 Глобальный режим: `None` - энтропия ОС, `Some(seed)` - детерминированный режим
+/// 🇬🇧 Global mode: `None` - OS entropy, `Some(seed)` - deterministic mode
+static GLOBAL_SEED: Mutex<Option<Seed>> = Mutex::new(if cfg!(feature = "fixed_seed") {
+    Some(FIXED_SEED)
+} else {
+    None
+});
+
+/// 🇷🇺 Поколение настроек: при каждом вызове `set_seed` генераторы потоков пересоздаются
+/// 🇬🇧 Settings generation: every `set_seed` call makes the thread generators re-initialize
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 🇷🇺 Следующий свободный номер потока ChaCha20 в детерминированном режиме
+/// 🇬🇧 Next free ChaCha20 stream number in the deterministic mode
+static NEXT_STREAM: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    static THREAD_PRNG: RefCell<Option<(u64, PRNG)>> = const { RefCell::new(None) };
+}
+
+/// 🇷🇺 Переключает генераторы по умолчанию всех потоков. `Some(seed)` включает
+/// детерминированный режим для тестов с известным ответом, `None` возвращает энтропию ОС.
+/// Потоки получают номера потоков ChaCha20 0, 1, 2, ... в порядке первого обращения
+/// к генератору после вызова, поэтому для побитовой воспроизводимости многопоточного
+/// кода лучше передавать генераторы явно (`PRNG::with_stream`, `PRNG::split`).
+///
+/// 🇬🇧 Switches the default generators of all threads. `Some(seed)` enables the
+/// deterministic mode for known-answer tests, `None` goes back to OS entropy.
+/// Threads get ChaCha20 streams 0, 1, 2, ... in the order they first touch the
+/// generator after the call, so for bit-exact reproducibility of multithreaded code
+/// prefer passing generators explicitly (`PRNG::with_stream`, `PRNG::split`).
+pub fn set_seed(seed: Option<Seed>) {
+    let mut global = GLOBAL_SEED.lock().unwrap();
+    *global = seed;
+    NEXT_STREAM.store(0, Ordering::SeqCst);
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// 🇷🇺 Вызывает `f` с генератором по умолчанию текущего потока
+/// 🇬🇧 Calls `f` with the default generator of the current thread
+pub fn with_prng<T>(f: impl FnOnce(&mut PRNG) -> T) -> T {
+    THREAD_PRNG.with(|cell| {
+        let mut slot = cell.borrow_mut();
+        let generation = GENERATION.load(Ordering::SeqCst);
+        if !matches!(*slot, Some((g, _)) if g == generation) {
+            *slot = Some((generation, thread_prng()));
+        }
+        let (_, prng) = slot.as_mut().unwrap();
+        f(prng)
+    })
+}
+
+fn thread_prng() -> PRNG {
+    match *GLOBAL_SEED.lock().unwrap() {
+        Some(seed) => PRNG::with_stream(seed, NEXT_STREAM.fetch_add(1, Ordering::SeqCst)),
+        None => PRNG::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = PRNG::seed_from_u64(42);
+        let mut b = PRNG::seed_from_u64(42);
+        let x: Vec<u64> = (0..16).map(|_| a.gen()).collect();
+        let y: Vec<u64> = (0..16).map(|_| b.gen()).collect();
+        assert_eq!(x, y);
+    }
+
+    #[test]
+    fn test_streams_are_independent() {
+        let seed = [7u8; SEED_SIZE];
+        let mut a = PRNG::with_stream(seed, 0);
+        let mut b = PRNG::with_stream(seed, 1);
+        assert_eq!(b.get_stream(), 1);
+        assert_ne!(a.next_u64(), b.next_u64());
+
+        let mut parent = PRNG::from_seed(seed);
+        let mut child = parent.split();
+        assert_ne!(parent.get_seed(), child.get_seed());
+        assert_ne!(parent.next_u64(), child.next_u64());
+    }
+
+    #[test]
+    fn test_seeded_thread_prng() {
+        set_seed(Some(FIXED_SEED));
+        let x = with_prng(|prng| prng.next_u64());
+        set_seed(Some(FIXED_SEED));
+        let y = with_prng(|prng| prng.next_u64());
+        set_seed(None);
+        assert_eq!(x, y);
+    }
+}
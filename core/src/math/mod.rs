@@ -3,4 +3,6 @@ pub mod discrete_gaussian_generator_generic;
 pub mod discrete_gaussian_generator;
 pub mod binary_uniform_generator;
 pub mod binary_uniform_generator_impl;
-pub mod discrete_uniform_generator_impl;
\ No newline at end of file
+pub mod discrete_uniform_generator_impl;
+pub mod distribution_generator;
+pub mod ternary_uniform_generator;
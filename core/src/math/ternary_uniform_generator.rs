@@ -0,0 +1,108 @@
+//==================================================================================
+// BSD 2-Clause License
+//
+// Copyright (c) 2014-2023, NJIT, Duality Technologies Inc. and other contributors
+//            translated from C++ and upgraded by Anton Sinitsyn
+//
+// All rights reserved.
+//
+// Author TPOC: contact@openfhe.org
+// Anton Sinitsyn: antonsinitsyn@outlook.de
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//==================================================================================
+
+/*
+    🇷🇺 Генерация равномерного распределения троичных значений {-1, 0, 1}.
+    Используется для секретных ключей и эфемерных многочленов шифрования.
+
+    🇬🇧 Generation of a uniform distribution of ternary values {-1, 0, 1}.
+    Used for secret keys and ephemeral encryption polynomials.
+*/
+
+use super::distribution_generator::PRNG;
+use rand::distributions::{Distribution, Uniform};
+use rand::seq::index;
+
+pub struct TernaryUniformGenerator;
+
+impl TernaryUniformGenerator {
+    /// 🇷🇺 Генерирует вектор троичных значений.
+    ///
+    /// Параметры:
+    /// size - количество возвращаемых значений.
+    /// h - вес Хэмминга: если h = 0, каждое значение равномерно выбирается из {-1, 0, 1},
+    ///     иначе ровно h случайных позиций получают значения ±1, а остальные равны 0.
+    /// prng - генератор псевдослучайных чисел.
+    ///
+    /// 🇬🇧 Generates a vector of ternary values.
+    ///
+    /// Parameters:
+    /// size - the number of values to return.
+    /// h - Hamming weight: if h = 0, every value is drawn uniformly from {-1, 0, 1},
+    ///     otherwise exactly h random positions get ±1 and the rest are 0.
+    /// prng - pseudorandom number generator.
+    pub fn generate_int_vector(size: usize, h: usize, prng: &mut PRNG) -> Vec<i64> {
+        if h == 0 {
+            let distribution = Uniform::new_inclusive(-1, 1);
+            return (0..size).map(|_| distribution.sample(prng)).collect();
+        }
+
+        assert!(h <= size, "Hamming weight {} exceeds the size {}", h, size);
+        let sign = Uniform::new_inclusive(0, 1);
+        let mut v = vec![0; size];
+        for i in index::sample(prng, size, h) {
+            v[i] = if sign.sample(prng) == 0 { -1 } else { 1 };
+        }
+        v
+    }
+
+    /// 🇷🇺 Генерирует вектор троичных значений по модулю modulus: -1 переходит в modulus - 1.
+    /// 🇬🇧 Generates a vector of ternary values modulo modulus: -1 maps to modulus - 1.
+    pub fn generate_vector(size: usize, modulus: u64, h: usize, prng: &mut PRNG) -> Vec<u64> {
+        Self::generate_int_vector(size, h, prng)
+            .into_iter()
+            .map(|x| if x < 0 { modulus - 1 } else { x as u64 })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_generate_int_vector() {
+        let mut prng = PRNG::seed_from_u64(3);
+        let v = TernaryUniformGenerator::generate_int_vector(1024, 0, &mut prng);
+        assert!(v.iter().all(|x| (-1..=1).contains(x)));
+
+        let v = TernaryUniformGenerator::generate_int_vector(1024, 64, &mut prng);
+        assert_eq!(v.iter().filter(|&&x| x != 0).count(), 64);
+
+        let a = TernaryUniformGenerator::generate_vector(16, 17, 0, &mut PRNG::seed_from_u64(9));
+        let b = TernaryUniformGenerator::generate_vector(16, 17, 0, &mut PRNG::seed_from_u64(9));
+        assert_eq!(a, b);
+        assert!(a.iter().all(|&x| x == 0 || x == 1 || x == 16));
+    }
+}
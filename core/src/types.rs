@@ -1,3 +1,6 @@
+/// 🇷🇺 Представление элемента кольца: значения в корнях из единицы или коэффициенты
+/// 🇬🇧 Representation of a ring element: values at the roots of unity or coefficients
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Format {
     EVALUATION = 0,
     COEFFICIENT = 1,
@@ -8,6 +8,7 @@ pub enum SecretKeyDist {
 }
 
 impl SecretKeyDist {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
             "GAUSSIAN" => Some(SecretKeyDist::Gaussian),
@@ -1,2802 +1,967 @@
-use std::rc::Rc;
-use std::vec::Vec;
+/*
+  This file contains the implementation of the double-CRT polynomial: tower-wise ring arithmetic,
+  CRT interpolation into a big-modulus polynomial, dropping of towers with rounding, basis
+  extension for key switching and the BFV scale-and-round primitives
+*/
+use super::dcrtpoly::DCRTPolyImpl;
+use super::ildcrtparams::ILDCRTParams;
+use super::ilparams::ILParamsImpl;
+use super::poly::{Format, PolyImpl};
+use crate::core::lattice::hal::dcrtpoly_interface::DCRTPolyInterface;
+use crate::core::math::binaryuniformgenerator::BinaryUniformGeneratorImpl;
+use crate::core::math::discretegaussiangenerator::DiscreteGaussianGenerator;
+use crate::core::math::discreteuniformgenerator::DiscreteUniformGeneratorImpl;
+use crate::core::math::distributiongenerator::PRNG;
+use crate::core::math::hal::integer::{BigIntegerInterface, IntegerInterface, NativeInteger};
+use crate::core::math::hal::transform::TransformInterface;
+use crate::core::math::hal::vector::{BigVectorInterface, NativeVector};
+use crate::core::math::rnsbase::RnsBaseConverterT;
+use crate::core::math::ternaryuniformgenerator::TernaryUniformGenerator;
+use fhe_core::lattice::hal::element::ILElement;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
 use std::sync::Arc;
-use std::cmp::PartialEq;
-use std::ops::{Add, Sub, Mul};
-use crate::errors::{ConfigError, NotImplementedError, NotAvailableError};
-use crate::core::math::polynomial::DcrtpolyImpl;
-use crate::core::math::integer::NativeInteger;
-use crate::pke::scheme::gen_cryptocontext_parameters::Params;
-use std::{f64, fmt};
-use rayon::prelude::*;
-
-
-struct DCRTPolyImpl<VecType> {
-    params: Arc<Params>,
-    format: Format,
-    vectors: Vec<VecType>,
-}
-
-impl<VecType> fmt::Display for DCRTPolyImpl<VecType>
-where
-    VecType: fmt::Display, // Ensure VecType supports Display trait
-{
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "---START PRINT DOUBLE CRT-- WITH SIZE {}", self.m_vectors.len())?;
-        for (i, v) in self.m_vectors.iter().enumerate() {
-            writeln!(f, "VECTOR {}", i)?;
-            writeln!(f, "{}", v)?;
-        }
-        writeln!(f, "---END PRINT DOUBLE CRT--")
-    }
-}
-
-
-impl<VecType> DCRTPolyImpl<VecType>
-where
-    VecType: SwitchFormat + SwitchModulus + InverseExists + Sync + Send, // Traits to be defined based on VecType capabilities
- {
-    fn new_from_poly_large_type(rhs: &PolyLargeType, params: Arc<Params>) -> Self {
-        let mut instance = DCRTPolyImpl {
-            params: params.clone(),
-            format: rhs.get_format(),
-            vectors: Vec::with_capacity(params.get_params().len()),
-        };
-        instance.params.set_original_modulus(rhs.get_modulus());
-        let rdim = rhs.get_length();
-        for p in params.get_params().iter() {
-            let mut v = VecType::new(p.clone(), instance.format, true);
-            let m = p.get_modulus();
-            for j in 0..rdim {
-                v[j] = rhs[j].modulus(m);
-            }
-            instance.vectors.push(v);
-        }
-        instance
-    }
-
-    pub fn drop_last_element(&mut self) {
-        self.m_vectors.pop();
-    }
-    
-
-    fn assign_from_poly_large_type(&mut self, rhs: &PolyLargeType) {
-        self.params.set_original_modulus(rhs.get_modulus());
-        self.vectors.clear();
-        let rdim = rhs.get_length();
-        for p in self.params.get_params().iter() {
-            let mut v = VecType::new(p.clone(), self.format, true);
-            let m = p.get_modulus();
-            for e in 0..rdim {
-                v[e] = rhs[e].modulus(m);
-            }
-            self.vectors.push(v);
-        }
-    }
 
-    fn new_from_poly_type(rhs: &PolyType, params: Arc<Params>) -> Self {
-        let mut instance = DCRTPolyImpl {
-            params: params.clone(),
-            format: rhs.get_format(),
-            vectors: vec![VecType::new_from_poly(rhs); params.get_params().len()],
-        };
-        let p = params.get_params();
-        for (i, v) in instance.vectors.iter_mut().enumerate().skip(1) {
-            v.switch_modulus(&p[i].get_modulus(), &p[i].get_root_of_unity(), 0, 0);
+type NativePoly = PolyImpl<NativeVector>;
+type Params<V> = Arc<ILDCRTParams<<V as BigVectorInterface>::Integer>>;
+
+/// Вспомогательный модуль m̃ редукции Монтгомери в BEHZ
+pub const BEHZ_M_TILDE: u64 = 1 << 16;
+
+/* Конструкторы */
+impl<V: TransformInterface> DCRTPolyImpl<V> {
+    /// Многочлен с параметрами params; без initialize_element_to_zero значения
+    /// башен не выделяются
+    pub fn new(params: Params<V>, format: Format, initialize_element_to_zero: bool) -> Self {
+        let towers = params
+            .get_params()
+            .iter()
+            .map(|p| NativePoly::new(p.clone(), format, initialize_element_to_zero))
+            .collect();
+        Self {
+            format,
+            params,
+            towers,
         }
-        instance
     }
 
-    fn assign_from_poly_type(&mut self, rhs: &PolyType) {
-        self.vectors.clear();
-        let mut first = true;
-        for p in self.params.get_params().iter() {
-            let mut v = VecType::new_from_poly(rhs);
-            if !first {
-                v.switch_modulus(&p.get_modulus(), &p.get_root_of_unity(), 0, 0);
-            }
-            first = false;
-            self.vectors.push(v);
+    /// Многочлен из готовых башен; параметры башен должны совпадать с цепочкой
+    /// params, формат у всех башен один
+    pub fn with_towers(params: Params<V>, towers: Vec<NativePoly>) -> Self {
+        if towers.len() != params.len() {
+            panic!("DCRTPolyImpl: the number of towers does not match the parameters");
         }
-    }
-
-
-    pub fn new(towers: Vec<PolyType>) -> Self {
-        let m_params = towers[0].get_params();
-        let m_format = towers[0].get_format();
-        let mut parms = Vec::new();
-        let cyclotomic_order = towers[0].get_cyclotomic_order();
-        for v in &towers {
-            if v.get_cyclotomic_order() != cyclotomic_order {
-                panic!("Polys provided to constructor must have the same ring dimension");
+        let format = towers[0].get_format();
+        for (tower, p) in towers.iter().zip(params.get_params()) {
+            if tower.get_params() != p {
+                panic!("DCRTPolyImpl: tower parameters do not match the chain");
             }
-            parms.push(v.get_params());
-        }
-        let m_params = Rc::new(Params::new(cyclotomic_order, parms));
-        DCRTPolyImpl {
-            m_params: Some(m_params),
-            m_format,
-            m_vectors: towers,
-        }
-    }
-
-    pub fn new_with_dgg(
-        dgg: DggType,
-        dcrt_params: Rc<Params>,
-        format: Format,
-    ) -> Self {
-        let rdim = dcrt_params.get_ring_dimension();
-        let dgg_values = dgg.generate_int_vector(rdim);
-        let mut m_vectors = Vec::with_capacity(dcrt_params.get_params().len());
-        for p in dcrt_params.get_params() {
-            let modulus = p.get_modulus();
-            let mut ildv = NativeVector::new(rdim, modulus);
-            for j in 0..rdim {
-                let k = dgg_values[j];
-                let m = modulus.convert_to_int();
-                let dcrt_qmodulus = m as NativeInteger::SignedNativeInt;
-                let dgg_stddev = dgg.get_std();
-                let k = if dgg_stddev > dcrt_qmodulus {
-                    k % dcrt_qmodulus
-                } else {
-                    k
-                };
-                let ildv_j = if k < 0 {
-                    k *= -1;
-                    modulus.convert_to_int() - k as NativeInteger::Integer
-                } else {
-                    k as NativeInteger::Integer
-                };
-                ildv[j] = ildv_j;
+            if tower.get_format() != format {
+                panic!("DCRTPolyImpl: towers are in different formats");
             }
-            let mut ilvector = PolyType::new(p);
-            ilvector.set_values(ildv, Format::COEFFICIENT);
-            ilvector.set_format(format);
-            m_vectors.push(ilvector);
         }
-        DCRTPolyImpl {
-            m_params: Some(dcrt_params),
-            m_format: format,
-            m_vectors,
-        }
-    }
-
-    pub fn new_with_dug(
-        dug: DugType,
-        dcrt_params: Rc<Params>,
+        Self {
+            format,
+            params,
+            towers,
+        }
+    }
+
+    /// Остатки многочлена element по модулям цепочки. Коэффициенты берутся
+    /// центрированными по модулю element, поэтому знаковые многочлены
+    /// переносятся без изменений
+    pub fn from_poly(element: &PolyImpl<V>, params: Params<V>) -> Self {
+        if element.get_format() != Format::COEFFICIENT {
+            panic!("DCRTPolyImpl: the big polynomial must be in COEFFICIENT format");
+        }
+        if element.get_length() != params.get_ring_dimension() as usize {
+            panic!("DCRTPolyImpl: the polynomial length does not match the ring dimension");
+        }
+        let towers = params
+            .get_params()
+            .iter()
+            .map(|p| {
+                let mut values = element.get_values().clone();
+                values.switch_modulus(V::Integer::from_u64(p.get_modulus().value()));
+                let residues: Vec<u64> = (0..values.get_length())
+                    .map(|j| values[j].to_u64().unwrap())
+                    .collect();
+                let values = NativeVector::from_values(&residues, *p.get_modulus());
+                NativePoly::with_values(p.clone(), Format::COEFFICIENT, values)
+            })
+            .collect();
+        Self::with_towers(params, towers)
+    }
+
+    /// Гауссовский шум: одни и те же коэффициенты приводятся по всем модулям
+    pub fn from_dgg(
+        dgg: &DiscreteGaussianGenerator<V>,
+        params: Params<V>,
         format: Format,
+        prng: &mut PRNG,
     ) -> Self {
-        let mut m_vectors = Vec::with_capacity(dcrt_params.get_params().len());
-        for p in dcrt_params.get_params() {
-            let vals = dug.generate_vector(p.get_ring_dimension(), p.get_modulus());
-            let mut ilvector = PolyType::new(p);
-            ilvector.set_values(vals, format);
-            m_vectors.push(ilvector);
-        }
-        DCRTPolyImpl {
-            m_params: Some(dcrt_params),
-            m_format: format,
-            m_vectors,
-        }
+        let noise = dgg.generate_int_vector(params.get_ring_dimension(), prng);
+        Self::from_signed(&noise, params, format)
     }
 
-    pub fn new_with_bug(
-        bug: BugType,
-        dcrt_params: Rc<Params>,
+    /// Равномерно распределённый элемент: башни выбираются независимо
+    pub fn from_dug(
+        dug: &mut DiscreteUniformGeneratorImpl<NativeVector>,
+        params: Params<V>,
         format: Format,
+        prng: &mut PRNG,
     ) -> Self {
-        let mut m_vectors = Vec::with_capacity(dcrt_params.get_params().len());
-        let mut first = true;
-        let mut ilvector = PolyType::new_with_bug(bug, dcrt_params.get_params()[0], Format::COEFFICIENT);
-        for p in dcrt_params.get_params() {
-            if !first {
-                ilvector.switch_modulus(p.get_modulus(), p.get_root_of_unity(), 0, 0);
-            }
-            let new_vector = ilvector.clone();
-            new_vector.set_format(format);
-            m_vectors.push(new_vector);
-            first = false;
-        }
-        DCRTPolyImpl {
-            m_params: Some(dcrt_params),
-            m_format: format,
-            m_vectors,
-        }
+        let towers = params
+            .get_params()
+            .iter()
+            .map(|p| NativePoly::from_dug(dug, p.clone(), format, prng))
+            .collect();
+        Self::with_towers(params, towers)
+    }
+
+    /// Многочлен с коэффициентами из {-1, 0, 1}; при h > 0 ровно h коэффициентов
+    /// отличны от нуля
+    pub fn from_tug(params: Params<V>, format: Format, h: usize, prng: &mut PRNG) -> Self {
+        let values = TernaryUniformGenerator::generate_int_vector(
+            params.get_ring_dimension() as usize,
+            h,
+            prng,
+        );
+        Self::from_signed(&values, params, format)
     }
 
-    pub fn new_with_tug(
-        tug: TugType,
-        dcrt_params: Rc<Params>,
+    /// Многочлен с коэффициентами из {0, 1}
+    pub fn from_bug(
+        bug: &BinaryUniformGeneratorImpl<NativeVector>,
+        params: Params<V>,
         format: Format,
-        h: u32,
+        prng: &mut PRNG,
     ) -> Self {
-        let rdim = dcrt_params.get_ring_dimension();
-        let tug_values = tug.generate_int_vector(rdim, h);
-        let mut m_vectors = Vec::with_capacity(dcrt_params.get_params().len());
-        for p in dcrt_params.get_params() {
-            let mut iltvs = NativeVector::new(rdim, p.get_modulus());
-            for j in 0..rdim {
-                let k = tug_values[j];
-                let iltvs_j = if k < 0 {
-                    k *= -1;
-                    p.get_modulus().convert_to_int() - k as NativeInteger::Integer
-                } else {
-                    k as NativeInteger::Integer
-                };
-                iltvs[j] = iltvs_j;
-            }
-            let mut ilvector = PolyType::new(p);
-            ilvector.set_values(iltvs, Format::COEFFICIENT);
-            ilvector.set_format(format);
-            m_vectors.push(ilvector);
-        }
-        DCRTPolyImpl {
-            m_params: Some(dcrt_params),
-            m_format: format,
-            m_vectors,
-        }
+        let values: Vec<i64> = (0..params.get_ring_dimension())
+            .map(|_| bug.generate_integer(prng).value() as i64)
+            .collect();
+        Self::from_signed(&values, params, format)
     }
 
-    pub fn clone_with_noise(
-        &self,
-        dgg: DiscreteGaussianGeneratorImpl<VecType>,
-        format: Format,
-    ) -> Self {
-        let res = DCRTPolyImpl {
-            m_params: self.m_params.clone(),
-            m_format: self.m_format,
-            m_vectors: Vec::new(),
-        };
-        let c = self.m_params.get_cyclotomic_order();
-        let m = self.m_params.get_modulus();
-        let parm = Rc::new(ILParamsImpl::new(c, m, 1));
-        let mut element = PolyLargeType::new(parm);
-        element.set_values(dgg.generate_vector(c / 2, m), m_format);
-        res = element;
-        res
-    }
-
-    pub fn clone_towers(&self, start_tower: u32, end_tower: u32) -> Self {
-        let mut m = Vec::new();
-        let mut r = Vec::new();
-        for i in start_tower..=end_tower {
-            m.push(self.m_params.get_params()[i].get_modulus());
-            r.push(self.m_params.get_params()[i].get_root_of_unity());
-        }
-        let co = self.m_params.get_cyclotomic_order();
-        let params = Rc::new(Params::new(co, m, r));
-        let mut res = DCRTPolyImpl {
-            m_params: Some(params),
-            m_format: Format::EVALUATION,
-            m_vectors: Vec::new(),
-        };
-        for i in start_tower..=end_tower {
-            res.set_element_at_index(i - start_tower, self.get_element_at_index(i));
-        }
-        res
-    }
-
-    pub fn base_decompose(&self, base_bits: usize, eval_mode_answer: bool) -> Vec<Self> {
-        let bdv = CRTInterpolate().base_decompose(base_bits, false);
-        let mut result = Vec::with_capacity(bdv.len());
-        for dv in bdv {
-            let mut dv = DCRTPolyImpl {
-                m_params: self.m_params.clone(),
-                m_format: self.m_format,
-                m_vectors: Vec::new(),
-            };
-            dv.set_params(self.m_params.clone());
-            if eval_mode_answer {
-                dv.switch_format();
-            }
-            result.push(dv);
-        }
-        result
+    /// Многочлен с коэффициентами values, приведёнными по каждому модулю
+    fn from_signed(values: &[i64], params: Params<V>, format: Format) -> Self {
+        let towers = params
+            .get_params()
+            .iter()
+            .map(|p| NativePoly::from_signed(values, p.clone(), format))
+            .collect();
+        Self::with_towers(params, towers)
     }
+}
 
-        pub fn crt_decompose(&self, base_bits: u32) -> Vec<DCRTPolyImpl<VecType>> {
-        let mut cp = DCRTPolyImpl {
-            m_format: self.m_format,
-            m_params: self.m_params.clone(),
-            m_vectors: self.m_vectors.clone(),
-        };
-        cp.switch_format();
-        let coef = if self.m_format == Format::COEFFICIENT {
-            self
-        } else {
-            &cp
-        };
-        let eval = if self.m_format == Format::COEFFICIENT {
-            &cp
-        } else {
-            self
-        };
-        let size = self.m_vectors.len();
-        if base_bits == 0 {
-            let mut result = vec![eval.clone(); size];
-            for i in 0..size {
-                for k in 0..size {
-                    if i != k {
-                        let mut tmp = DCRTPolyImpl::PolyType::new(coef.m_vectors[i].clone());
-                        tmp.switch_modulus(
-                            coef.m_vectors[k].get_modulus(),
-                            coef.m_vectors[k].get_root_of_unity(),
-                            0,
-                            0,
-                        );
-                        tmp.set_format(Format::EVALUATION);
-                        result[i].m_vectors[k] = tmp;
-                    }
-                }
-            }
-            return result;
-        }
-        let mut n_windows = 0;
-        let mut arr_windows = vec![0; size];
-        for i in 0..size {
-            let n_bits = self.m_vectors[i].get_modulus().get_length_for_base(2);
-            let mut cur_windows = n_bits / base_bits;
-            if n_bits % base_bits != 0 {
-                cur_windows += 1;
-            }
-            arr_windows[i] = n_windows;
-            n_windows += cur_windows;
-        }
-        let mut result = vec![DCRTPolyType::new(); n_windows];
-        for i in 0..size {
-            let decomposed = coef.m_vectors[i].base_decompose(base_bits, false);
-            for j in 0..decomposed.len() {
-                let mut current_dcrtpoly = DCRTPolyImpl {
-                    m_params: coef.m_params.clone(),
-                    m_format: coef.m_format,
-                    m_vectors: coef.m_vectors.clone(),
-                };
-                for k in 0..size {
-                    let mut tmp = decomposed[j].clone();
-                    if i != k {
-                        tmp.switch_modulus(
-                            coef.m_vectors[k].get_modulus(),
-                            coef.m_vectors[k].get_root_of_unity(),
-                            0,
-                            0,
-                        );
-                    }
-                    current_dcrtpoly.m_vectors[k] = tmp;
-                }
-                current_dcrtpoly.switch_format();
-                result[j + arr_windows[i]] = current_dcrtpoly;
-            }
-        }
-        result
+/* Доступ к данным */
+impl<V: TransformInterface> DCRTPolyImpl<V> {
+    pub fn get_params(&self) -> &Params<V> {
+        &self.params
     }
 
-    pub fn powers_of_base(&self, base_bits: u32) -> Vec<DCRTPolyImpl<VecType>> {
-        let mut mods = Vec::with_capacity(self.m_params.get_params().len());
-        for p in self.m_params.get_params() {
-            mods.push(p.get_modulus());
-        }
-        let n_bits = self.m_params.get_modulus().get_length_for_base(2);
-        let mut n_windows = n_bits / base_bits;
-        if n_bits % base_bits != 0 {
-            n_windows += 1;
-        }
-        let mut result = Vec::with_capacity(n_windows);
-        let mut two_pow = Integer::from(1);
-        let size = self.m_vectors.len();
-        for _ in 0..n_windows {
-            let mut x = DCRTPolyImpl {
-                m_params: self.m_params.clone(),
-                m_format: self.m_format,
-                m_vectors: self.m_vectors.clone(),
-            };
-            two_pow.lshift_eq(base_bits);
-            for t in 0..size {
-                x.m_vectors[t] = self.m_vectors[t] * two_pow.mod_op(mods[t]).convert_to_int();
-            }
-            result.push(x);
-        }
-        result
+    pub fn get_format(&self) -> Format {
+        self.format
     }
 
-    pub fn automorphism_transform(&self, i: u32) -> DCRTPolyImpl<VecType> {
-        let mut result = DCRTPolyImpl {
-            m_format: self.m_format,
-            m_params: self.m_params.clone(),
-            m_vectors: Vec::with_capacity(self.m_vectors.len()),
-        };
-        for v in &self.m_vectors {
-            result.m_vectors.push(v.automorphism_transform(i));
-        }
-        result
+    pub fn get_ring_dimension(&self) -> usize {
+        self.params.get_ring_dimension() as usize
     }
 
-    pub fn automorphism_transform_with_vec(
-        &self,
-        i: u32,
-        vec: &Vec<u32>,
-    ) -> DCRTPolyImpl<VecType> {
-        let mut result = DCRTPolyImpl {
-            m_format: self.m_format,
-            m_params: self.m_params.clone(),
-            m_vectors: Vec::with_capacity(self.m_vectors.len()),
-        };
-        for v in &self.m_vectors {
-            result.m_vectors.push(v.automorphism_transform_with_vec(i, vec));
-        }
-        result
+    pub fn get_cyclotomic_order(&self) -> usize {
+        self.params.get_cyclotomic_order() as usize
     }
 
-    pub fn multiplicative_inverse(&self) -> DCRTPolyImpl<VecType> {
-        let mut tmp = DCRTPolyImpl {
-            m_params: self.m_params.clone(),
-            m_format: self.m_format,
-            m_vectors: Vec::with_capacity(self.m_vectors.len()),
-        };
-        for v in &self.m_vectors {
-            tmp.m_vectors.push(v.multiplicative_inverse());
-        }
-        tmp
+    /// Q - произведение модулей башен
+    pub fn get_modulus(&self) -> &V::Integer {
+        self.params.get_modulus()
     }
 
-    pub fn negate(&self) -> DCRTPolyImpl<VecType> {
-        let mut tmp = DCRTPolyImpl {
-            m_params: self.m_params.clone(),
-            m_format: self.m_format,
-            m_vectors: Vec::with_capacity(self.m_vectors.len()),
-        };
-        for v in &self.m_vectors {
-            tmp.m_vectors.push(v.negate());
-        }
-        tmp
+    /// Число башен
+    pub fn get_num_of_elements(&self) -> usize {
+        self.towers.len()
     }
 
-    pub fn minus(&self, rhs: &DCRTPolyImpl<VecType>) -> DCRTPolyImpl<VecType> {
-        if self.m_vectors.len() != rhs.m_vectors.len() {
-            panic!("tower size mismatch; cannot subtract");
-        }
-        let mut tmp = DCRTPolyImpl {
-            m_params: self.m_params.clone(),
-            m_format: self.m_format,
-            m_vectors: Vec::with_capacity(self.m_vectors.len()),
-        };
-        for i in 0..self.m_vectors.len() {
-            tmp.m_vectors.push(self.m_vectors[i].minus(&rhs.m_vectors[i]));
-        }
-        tmp
+    pub fn get_element_at_index(&self, i: usize) -> &NativePoly {
+        &self.towers[i]
     }
 
-    pub fn add_assign(&mut self, rhs: &DCRTPolyImpl<VecType>) -> &mut Self {
-        for i in 0..self.m_vectors.len() {
-            self.m_vectors[i] += &rhs.m_vectors[i];
-        }
-        self
+    pub fn get_all_elements(&self) -> &[NativePoly] {
+        &self.towers
     }
 
-    pub fn add_assign_integer(&mut self, rhs: &Integer) -> &mut Self {
-        let val = NativeInteger::from(rhs);
-        for i in 0..self.m_vectors.len() {
-            self.m_vectors[i] += &val;
+    /// Заменяет башню i; её параметры и формат должны совпадать с прежними
+    pub fn set_element_at_index(&mut self, i: usize, element: NativePoly) {
+        if element.get_params() != self.towers[i].get_params() {
+            panic!("DCRTPolyImpl: tower parameters do not match the chain");
         }
-        self
-    }
-
-    pub fn add_assign_native_integer(&mut self, rhs: &NativeInteger) -> &mut Self {
-        for i in 0..self.m_vectors.len() {
-            self.m_vectors[i] += rhs;
+        if element.get_format() != self.format {
+            panic!("DCRTPolyImpl: the tower is in a different format");
         }
-        self
+        self.towers[i] = element;
     }
 
-    pub fn sub_assign(&mut self, rhs: &DCRTPolyImpl<VecType>) -> &mut Self {
-        for i in 0..self.m_vectors.len() {
-            self.m_vectors[i] -= &rhs.m_vectors[i];
-        }
-        self
+    /// Многочлен из башен с номерами start..=end
+    pub fn clone_towers(&self, start: usize, end: usize) -> Self {
+        let params = self.params.get_param_partition(start, end);
+        let params = Arc::new(ILDCRTParams::with_params(
+            self.params.get_cyclotomic_order(),
+            params,
+        ));
+        Self::with_towers(params, self.towers[start..=end].to_vec())
     }
 
-    pub fn sub_assign_integer(&mut self, rhs: &Integer) -> &mut Self {
-        let val = NativeInteger::from(rhs);
-        for i in 0..self.m_vectors.len() {
-            self.m_vectors[i] -= &val;
-        }
-        self
+    pub fn is_empty(&self) -> bool {
+        self.towers.iter().all(|t| t.is_empty())
     }
 
-    pub fn sub_assign_native_integer(&mut self, rhs: &NativeInteger) -> &mut Self {
-        for i in 0..self.m_vectors.len() {
-            self.m_vectors[i] -= rhs;
+    fn map_towers(&self, f: impl Fn(&NativePoly) -> NativePoly) -> Self {
+        Self {
+            format: self.format,
+            params: self.params.clone(),
+            towers: self.towers.iter().map(f).collect(),
         }
-        self
     }
 
-
-    fn set_values_mod_switch(&mut self, element: &DcrtpolyImpl<VecType>, modulus: &NativeInteger) {
-        if element.get_num_of_elements() != 1 {
-            panic!("SetValuesModSwitch is implemented only for a DCRTPoly with one tower.");
+    fn zip_towers(&self, rhs: &Self, f: impl Fn(&NativePoly, &NativePoly) -> NativePoly) -> Self {
+        if self.params != rhs.params {
+            panic!("DCRTPolyImpl: operands have different parameters");
         }
-        let q = element.get_modulus();
-        let qmod_double = modulus.convert_to_double() / q.convert_to_double();
-        self.params.set_original_modulus(modulus.clone());
-        let input = element.get_element_at_index(0);
-        let input = input.set_format(Format::Coefficient);
-        let size = self.vectors.len();
-        let n_elem = element.params.get_ring_dimension();
-        let n = self.get_ring_dimension();
-        if n_elem > n {
-            panic!("The ring dimension of the element to copy is larger than the ring dimension of the element to copy to.");
+        if self.format != rhs.format {
+            panic!("DCRTPolyImpl: operands are in different formats");
         }
-        for i in 0..size {
-            let mut tmp = NativeVector::new_with_modulus(n, modulus.clone());
-            for j in 0..n_elem {
-                tmp[j] = Integer::from((0.5 + input[j].convert_to_double() * qmod_double).floor() as u64).modulus(modulus);
-            }
-            self.vectors[i].set_values(tmp, Format::Coefficient);
+        Self {
+            format: self.format,
+            params: self.params.clone(),
+            towers: self
+                .towers
+                .iter()
+                .zip(&rhs.towers)
+                .map(|(a, b)| f(a, b))
+                .collect(),
         }
     }
+}
 
-    fn add_il_element_one(&mut self) {
-        if self.format != Format::Evaluation {
-            panic!("Cannot call AddILElementOne() on DCRTPoly in COEFFICIENT format.");
-        }
-        let size = self.vectors.len();
-        self.vectors.par_iter_mut().for_each(|vector| {
-            vector.add_il_element_one();
-        });
+/* Арифметика по башням */
+impl<V: TransformInterface> DCRTPolyImpl<V> {
+    pub fn plus(&self, rhs: &Self) -> Self {
+        self.zip_towers(rhs, |a, b| a.plus(b))
     }
 
-    fn is_empty(&self) -> bool {
-        for v in &self.vectors {
-            if !v.is_empty() {
-                return false;
-            }
-        }
-        true
+    pub fn minus(&self, rhs: &Self) -> Self {
+        self.zip_towers(rhs, |a, b| a.minus(b))
     }
 
-    fn drop_last_element(&mut self) {
-        if self.vectors.is_empty() {
-            panic!("Input has no elements to drop!");
-        }
-        if self.vectors.len() == 1 {
-            panic!("Removing last element of DCRTPoly object renders it invalid!");
-        }
-        self.vectors.pop();
-        let mut new_params = self.params.clone();
-        new_params.pop_last_param();
-        self.params = Arc::new(new_params);
+    /// Произведение в кольце; в COEFFICIENT башни временно переводятся в
+    /// EVALUATION
+    pub fn times(&self, rhs: &Self) -> Self {
+        self.zip_towers(rhs, |a, b| a.times(b))
     }
 
-    fn drop_last_elements(&mut self, i: usize) {
-        if self.vectors.len() <= i {
-            panic!("There are not enough towers in the current ciphertext to perform the modulus reduction");
-        }
-        self.vectors.resize(self.vectors.len() - i, Default::default());
-        let mut new_params = self.params.clone();
-        for _ in 0..i {
-            new_params.pop_last_param();
-        }
-        self.params = Arc::new(new_params);
-    }
-
-    fn drop_last_element_and_scale(&mut self, ql_ql_inv_modql_divql_modq: Vec<NativeInteger>, ql_ql_inv_modql_divql_modq_precon: Vec<NativeInteger>, ql_inv_modq: Vec<NativeInteger>, ql_inv_modq_precon: Vec<NativeInteger>) {
-        let last_poly = self.vectors.pop().expect("No elements to pop");
-        let last_poly = last_poly.set_format(Format::Coefficient);
-        let size = self.vectors.len();
-        self.vectors.par_iter_mut().enumerate().for_each(|(i, vector)| {
-            let mut tmp = last_poly.clone();
-            tmp.switch_modulus(&vector.get_modulus(), &vector.get_root_of_unity(), &0, &0);
-            tmp *= &ql_ql_inv_modql_divql_modq[i];
-            if self.format == Format::Evaluation {
-                tmp.switch_format();
-            }
-            *vector *= &ql_inv_modq[i];
-            *vector += &tmp;
-            if self.format == Format::Coefficient {
-                vector.switch_format();
-            }
-        });
+    pub fn negate(&self) -> Self {
+        self.map_towers(|t| t.negate())
     }
 
-   pub fn mod_reduce(
-        &mut self,
-        t: &NativeInteger,
-        t_modq_precon: &[NativeInteger],
-        negt_inv_modq: &NativeInteger,
-        negt_inv_modq_precon: &NativeInteger,
-        ql_inv_modq: &[NativeInteger],
-        ql_inv_modq_precon: &[NativeInteger],
-    ) {
-        let mut delta = self.m_vectors.last().unwrap().clone();
-        delta.set_format(Format::COEFFICIENT);
-        delta *= negt_inv_modq;
-        self.m_vectors.pop();
-        let size = self.m_vectors.len();
-        self.m_vectors
-            .par_iter_mut()
-            .with_num_threads(OpenFHEParallelControls.get_thread_limit(size))
-            .for_each(|m_vector| {
-                let mut tmp = delta.clone();
-                tmp.switch_modulus(m_vector.get_modulus(), m_vector.get_root_of_unity(), 0, 0);
-                if self.m_format == Format::EVALUATION {
-                    tmp.switch_format();
-                }
-                *m_vector += (tmp *= t);
-                *m_vector *= ql_inv_modq[i];
-            });
+    /// Прибавляет скаляр из [0, Q) (к свободному члену в COEFFICIENT)
+    pub fn plus_scalar(&self, element: &V::Integer) -> Self {
+        let residues = self.params.get_crt_base().decompose(element);
+        self.map_towers_with(&residues, |t, r| t.plus_scalar(r))
     }
 
-    pub fn at(&self, i: usize) -> &VecType::Integer {
-        if self.m_vectors.is_empty() {
-            panic!("No values in DCRTPolyImpl");
-        }
-        if i >= self.m_vectors.len() {
-            panic!("out of range in DCRTPolyImpl.at()");
-        }
-        &self.crt_interpolate_index(i)[i]
+    /// Вычитает скаляр из [0, Q) (из свободного члена в COEFFICIENT)
+    pub fn minus_scalar(&self, element: &V::Integer) -> Self {
+        let residues = self.params.get_crt_base().decompose(element);
+        self.map_towers_with(&residues, |t, r| t.minus_scalar(r))
     }
 
-    pub fn at_mut(&mut self, i: usize) -> &mut VecType::Integer {
-        if self.m_vectors.is_empty() {
-            panic!("No values in DCRTPolyImpl");
-        }
-        if i >= self.m_vectors.len() {
-            panic!("out of range in DCRTPolyImpl.at()");
-        }
-        &mut self.crt_interpolate_index(i)[i]
+    pub fn times_scalar(&self, element: &V::Integer) -> Self {
+        let residues = self.params.get_crt_base().decompose(element);
+        self.map_towers_with(&residues, |t, r| t.times_scalar(r))
     }
 
-    pub fn crt_interpolate(&self) -> PolyLargeType {
-        let ring_dimension = self.m_params.get_ring_dimension();
-        let n_towers = self.m_vectors.len();
-        let big_modulus = self.m_params.get_modulus();
-
-        let mut coefficients = VecType::new(ring_dimension, big_modulus);
-
-        let mut multiplier = Vec::with_capacity(n_towers);
-        for vi in 0..n_towers {
-            let qj = self.m_vectors[vi].get_modulus().convert_to_int();
-            let div_by = big_modulus / qj;
-            let mod_inv = div_by.mod_inverse(qj).modulus(qj);
-            multiplier.push(div_by * mod_inv);
-        }
-
-        let vecs = if self.m_format == Format::EVALUATION {
-            let mut coeff_vecs = Vec::with_capacity(self.m_vectors.len());
-            for i in 0..self.m_vectors.len() {
-                let mut vec_copy = self.m_vectors[i].clone();
-                vec_copy.set_format(Format::COEFFICIENT);
-                coeff_vecs.push(vec_copy);
-            }
-            &coeff_vecs
-        } else {
-            &self.m_vectors
-        };
-
-        let mu = big_modulus.compute_mu();
+    /// Умножение на знаковое целое
+    pub fn times_signed(&self, element: i64) -> Self {
+        self.map_towers(|t| t.times_signed(element))
+    }
 
-        for ri in 0..ring_dimension {
-            coefficients[ri] = 0;
-            for vi in 0..n_towers {
-                coefficients[ri] +=
-                    Integer::from(vecs[vi].get_values()[ri].convert_to_int()) * multiplier[vi];
-            }
-            coefficients[ri].mod_eq(big_modulus, mu);
-        }
+    /// Автоморфизм x -> x^k в каждой башне
+    pub fn automorphism_transform(&self, k: u32) -> Self {
+        self.map_towers(|t| t.automorphism_transform(k))
+    }
 
-        let polynomial_reconstructed = PolyLargeType::new(
-            Arc::new(ILParamsImpl::new(
-                self.m_params.get_cyclotomic_order(),
-                big_modulus,
-                1,
-            )),
-        );
-        polynomial_reconstructed.set_values(coefficients, Format::COEFFICIENT);
-        polynomial_reconstructed
-    }
-
-    pub fn crt_interpolate_index(&self, i: usize) -> PolyLargeType {
-        let ring_dimension = self.m_params.get_ring_dimension();
-        let n_towers = self.m_vectors.len();
-        let big_modulus = self.m_params.get_modulus();
-        let mut coefficients = VecType::new(ring_dimension, big_modulus);
-        let mut multiplier = Vec::with_capacity(n_towers);
-        for vi in 0..n_towers {
-            let qj = self.m_vectors[vi].get_modulus().convert_to_int();
-            let div_by = big_modulus / qj;
-            let mod_inv = div_by.mod_inverse(qj).modulus(qj);
-            multiplier.push(div_by * mod_inv);
+    pub fn switch_format(&mut self) {
+        for tower in self.towers.iter_mut() {
+            tower.switch_format();
         }
-
-        let vecs = if self.m_format == Format::EVALUATION {
-            let mut coeff_vecs = Vec::with_capacity(self.m_vectors.len());
-            for ii in 0..self.m_vectors.len() {
-                let mut vec_copy = self.m_vectors[ii].clone();
-                vec_copy.set_format(Format::COEFFICIENT);
-                coeff_vecs.push(vec_copy);
-            }
-            &coeff_vecs
-        } else {
-            &self.m_vectors
+        self.format = match self.format {
+            Format::COEFFICIENT => Format::EVALUATION,
+            Format::EVALUATION => Format::COEFFICIENT,
         };
-
-        let mu = big_modulus.compute_mu();
-
-        for ri in 0..ring_dimension {
-            coefficients[ri] = 0;
-            if ri == i {
-                for vi in 0..n_towers {
-                    coefficients[ri] +=
-                        Integer::from(vecs[vi].get_values()[ri].convert_to_int()) * multiplier[vi];
-                }
-                coefficients[ri].mod_eq(big_modulus, mu);
-            }
-        }
-        let polynomial_reconstructed = PolyLargeType::new(
-            Arc::new(ILParamsImpl::new(
-                self.m_params.get_cyclotomic_order(),
-                big_modulus,
-                1,
-            )),
-        );
-        polynomial_reconstructed.set_values(coefficients, Format::COEFFICIENT);
-        polynomial_reconstructed
     }
 
-    pub fn decryption_crt_interpolate(&self, ptm: PlaintextModulus) -> PolyType {
-        self.crt_interpolate().decryption_crt_interpolate(ptm)
+    pub fn set_format(&mut self, format: Format) {
+        if self.format != format {
+            self.switch_format();
+        }
     }
 
-    pub fn to_native_poly(&self) -> PolyType {
-        self.crt_interpolate().to_native_poly()
+    fn map_towers_with(
+        &self,
+        residues: &[NativeInteger],
+        f: impl Fn(&NativePoly, &NativeInteger) -> NativePoly,
+    ) -> Self {
+        Self {
+            format: self.format,
+            params: self.params.clone(),
+            towers: self
+                .towers
+                .iter()
+                .zip(residues)
+                .map(|(t, r)| f(t, r))
+                .collect(),
+        }
+    }
+}
+
+/* Восстановление по CRT и смена модуля */
+impl<V: TransformInterface> DCRTPolyImpl<V> {
+    /// Многочлен по модулю Q с коэффициентами из [0, Q), восстановленными по
+    /// остаткам башен; результат в COEFFICIENT
+    pub fn crt_interpolate(&self) -> PolyImpl<V> {
+        let mut element = self.clone();
+        element.set_format(Format::COEFFICIENT);
+        let base = self.params.get_crt_base();
+        let values: Vec<V::Integer> = (0..self.get_ring_dimension())
+            .map(|j| {
+                let residues: Vec<NativeInteger> = element.towers.iter().map(|t| t[j]).collect();
+                base.reconstruct(&residues)
+            })
+            .collect();
+        let modulus = self.get_modulus().clone();
+        let params = Arc::new(ILParamsImpl::with_root_of_unity(
+            self.params.get_cyclotomic_order(),
+            modulus.clone(),
+            V::Integer::one(),
+        ));
+        PolyImpl::with_values(
+            params,
+            Format::COEFFICIENT,
+            V::from_values(&values, modulus),
+        )
+    }
+
+    /// Центрированные коэффициенты по модулю Q, приведённые по модулю
+    /// открытого текста ptm
+    pub fn decryption_crt_interpolate(&self, ptm: u64) -> NativePoly {
+        let mut values = self.crt_interpolate().get_values().clone();
+        values.switch_modulus(V::Integer::from_u64(ptm));
+        let residues: Vec<u64> = (0..values.get_length())
+            .map(|j| values[j].to_u64().unwrap())
+            .collect();
+        let modulus = NativeInteger::new(ptm);
+        let params = Arc::new(ILParamsImpl::with_root_of_unity(
+            self.params.get_cyclotomic_order(),
+            modulus,
+            NativeInteger::new(1),
+        ));
+        NativePoly::with_values(
+            params,
+            Format::COEFFICIENT,
+            NativeVector::from_values(&residues, modulus),
+        )
     }
 
-    pub fn get_working_modulus(&self) -> VecType::Integer {
-        let mut modulus_q = 1;
-        for p in self.m_params.get_params() {
-            modulus_q.mul_eq(p.get_modulus());
-        }
-        modulus_q
+    /// Отбрасывает последнюю башню без масштабирования: x mod Q / q_l
+    pub fn drop_last_element(&mut self) {
+        self.params = Arc::new(self.params.pop_last_param());
+        self.towers.pop();
+    }
+
+    /// Отбрасывает последнюю башню и делит на её модуль с округлением:
+    /// x -> round(x / q_l) по модулю Q / q_l
+    pub fn drop_last_element_and_scale(&mut self) {
+        self.drop_last_element_with(|_| NativeInteger::new(1), |lifted, _| lifted.clone());
+    }
+
+    /// Переключение модуля BGV с сохранением остатка по модулю открытого
+    /// текста t: x -> (x + delta) / q_l, где delta = 0 mod t и
+    /// x + delta = 0 mod q_l, поэтому результат равен x q_l^-1 mod t
+    pub fn mod_reduce(&mut self, t: &NativeInteger) {
+        self.drop_last_element_with(
+            |q_l| {
+                let t_inv = t.modulo(q_l).mod_inverse(q_l);
+                NativeInteger::new(0).mod_sub(&t_inv, q_l)
+            },
+            |lifted, q_i| lifted.times_scalar(&t.modulo(q_i)).negate(),
+        );
     }
 
-    pub fn get_extended_crt_basis(
-        &self,
-        params_p: Arc<Params>,
-    ) -> Arc<Params> {
-        let size_q = self.m_vectors.len();
-        let size_p = params_p.get_params().len();
-        let size_qp = size_q + size_p;
-        let mut moduli_qp = Vec::with_capacity(size_qp);
-        let mut roots_qp = Vec::with_capacity(size_qp);
-        for i in 0..size_q {
-            moduli_qp.push(self.m_params.get_params()[i].get_modulus());
-            roots_qp.push(self.m_params.get_params()[i].get_root_of_unity());
-        }
-        for i in size_q..size_qp {
-            moduli_qp.push(params_p.get_params()[i - size_q].get_modulus());
-            roots_qp.push(params_p.get_params()[i - size_q].get_root_of_unity());
-        }
-        Arc::new(Params::new(2 * self.m_params.get_ring_dimension(), moduli_qp, roots_qp))
+    /// Заменяет модуль башни index: коэффициенты переводятся центрированным
+    /// подъёмом, башня должна быть в COEFFICIENT
+    pub fn switch_modulus_at_index(
+        &mut self,
+        index: usize,
+        modulus: &NativeInteger,
+        root_of_unity: &NativeInteger,
+    ) {
+        let zero = NativeInteger::new(0);
+        self.towers[index].switch_modulus(modulus, root_of_unity, &zero, &zero);
+        let tower_params = self.towers[index].get_params().clone();
+        self.params = Arc::new(self.params.with_tower_at_index(index, tower_params));
     }
 
-    pub fn times_qovert(
+    /// Общая часть отбрасывания башни: последняя башня умножается на
+    /// scale(q_l) и центрированно поднимается в каждый модуль q_i,
+    /// correction(lifted, q_i) даёт вычитаемое, разность делится на q_l
+    fn drop_last_element_with(
         &mut self,
-        params_q: Arc<Params>,
-        t_inv_modq: &[NativeInteger],
-        t: &NativeInteger,
-        neg_q_modt: &NativeInteger,
-        neg_q_modt_precon: &NativeInteger,
+        scale: impl Fn(&NativeInteger) -> NativeInteger,
+        correction: impl Fn(&NativePoly, &NativeInteger) -> NativePoly,
     ) {
-        if t_inv_modq.len() < self.m_vectors.len() {
-            panic!("Sizes of vectors do not match.");
+        if self.towers.len() < 2 {
+            panic!("DCRTPolyImpl: cannot drop the last remaining tower");
         }
-        let size = self.m_vectors.len();
-        let ring_dim = self.m_params.get_ring_dimension();
-        self.m_vectors
-            .par_iter_mut()
-            .with_num_threads(OpenFHEParallelControls.get_thread_limit(size))
-            .for_each(|m_vector| {
-                for ri in 0..ring_dim {
-                    let xi = &mut m_vector[ri];
-                    xi.mod_mul_fast_const_eq(neg_q_modt, t, neg_q_modt_precon);
-                }
-                *m_vector = m_vector.times(t_inv_modq[i]);
-            });
-    }
-
-    pub fn approx_switch_crt_basis(
-        &self,
-        params_q: Arc<Params>,
-        params_p: Arc<Params>,
-        q_hat_inv_modq: &[NativeInteger],
-        q_hat_inv_modq_precon: &[NativeInteger],
-        q_hat_modp: &[Vec<NativeInteger>],
-        modp_barrett_mu: &[DoubleNativeInt],
-    ) -> DCRTPolyImpl<VecType> {
-        let mut ans = DCRTPolyImpl::new(params_p, self.m_format, true);
-        let ring_dim = self.m_params.get_ring_dimension();
-        let size_q = if self.m_vectors.len() > params_q.get_params().len() {
-            params_q.get_params().len()
-        } else {
-            self.m_vectors.len()
-        };
-        let size_p = ans.m_vectors.len();
-        for ri in 0..ring_dim {
-            let mut sum = vec![0; size_p];
-            for i in 0..size_q {
-                let xi = &self.m_vectors[i][ri];
-                let qi = self.m_vectors[i].get_modulus();
-                let x_q_hat_inv_modqi = xi.mod_mul_fast_const(q_hat_inv_modq[i], qi, q_hat_inv_modq_precon[i]);
-                for j in 0..size_p {
-                    sum[j] += x_q_hat_inv_modqi.convert_to_int() * q_hat_modp[i][j].convert_to_int();
-                }
-            }
-            for j in 0..size_p {
-                let pj = ans.m_vectors[j].get_modulus();
-                ans.m_vectors[j][ri] = barrett_uint128_mod_uint64(sum[j], pj.convert_to_int(), modp_barrett_mu[j]);
-            }
+        let mut last = self.towers.pop().unwrap();
+        last.set_format(Format::COEFFICIENT);
+        let q_l = *last.get_modulus();
+        let last = last.times_scalar(&scale(&q_l));
+        self.params = Arc::new(self.params.pop_last_param());
+        for tower in self.towers.iter_mut() {
+            let q_i = *tower.get_modulus();
+            let mut values = last.get_values().clone();
+            values.switch_modulus(q_i);
+            let mut lifted =
+                NativePoly::with_values(tower.get_params().clone(), Format::COEFFICIENT, values);
+            lifted.set_format(self.format);
+            let q_l_inv = q_l.modulo(&q_i).mod_inverse(&q_i);
+            *tower = tower
+                .minus(&correction(&lifted, &q_i))
+                .times_scalar(&q_l_inv);
         }
-        ans
     }
 }
 
-impl<T: PartialEq> PartialEq for DCRTPolyImpl<T> {
-    fn eq(&self, rhs: &Self) -> bool {
-        (self.m_format == rhs.m_format) && (self.m_params.GetCyclotomicOrder() == rhs.m_params.GetCyclotomicOrder()) &&
-        (self.m_params.GetModulus() == rhs.m_params.GetModulus()) && (self.m_vectors.len() == rhs.m_vectors.len()) &&
-        (self.m_vectors == rhs.m_vectors)
+/* Расширение и сужение основания для гибридного переключения ключей */
+impl<V: TransformInterface> DCRTPolyInterface for DCRTPolyImpl<V> {
+    type Integer = V::Integer;
+    type TowerType = NativePoly;
+    type PolyLargeType = PolyImpl<V>;
+
+    fn get_params(&self) -> &Params<V> {
+        DCRTPolyImpl::get_params(self)
     }
-}
 
-impl<T> DCRTPolyImpl<T> {
-    fn set_values_to_zero(&mut self) {
-        for v in &mut self.m_vectors {
-            v.set_values_to_zero();
-        }
+    fn get_num_of_elements(&self) -> usize {
+        DCRTPolyImpl::get_num_of_elements(self)
     }
-}
 
-impl<T> Add<Integer> for DCRTPolyImpl<T> {
-    type Output = DCRTPolyImpl<T>;
+    fn get_element_at_index(&self, i: usize) -> &NativePoly {
+        DCRTPolyImpl::get_element_at_index(self, i)
+    }
 
-    fn add(self, rhs: Integer) -> Self::Output {
-        let val = NativeInteger::from(rhs);
-        let mut tmp = DCRTPolyImpl::new(self.m_params, self.m_format);
-        let size = self.m_vectors.len();
-        self.m_vectors
-            .par_iter()
-            .enumerate()
-            .for_each(|(i, v)| tmp.m_vectors[i] = v.plus(&val));
-        tmp
+    fn get_all_elements(&self) -> &[NativePoly] {
+        DCRTPolyImpl::get_all_elements(self)
     }
-}
 
-impl<T> Add<Vec<Integer>> for DCRTPolyImpl<T> {
-    type Output = DCRTPolyImpl<T>;
+    fn clone_towers(&self, start: usize, end: usize) -> Self {
+        DCRTPolyImpl::clone_towers(self, start, end)
+    }
 
-    fn add(self, rhs: Vec<Integer>) -> Self::Output {
-        let mut tmp = DCRTPolyImpl::new(self.m_params, self.m_format);
-        let size = self.m_vectors.len();
-        self.m_vectors
-            .par_iter()
-            .enumerate()
-            .for_each(|(i, v)| tmp.m_vectors[i] = v.plus(&NativeInteger::from(rhs[i])));
-        tmp
+    fn crt_interpolate(&self) -> PolyImpl<V> {
+        DCRTPolyImpl::crt_interpolate(self)
     }
-}
 
-impl<T> Sub<Integer> for DCRTPolyImpl<T> {
-    type Output = DCRTPolyImpl<T>;
+    fn get_extended_crt_basis(&self, params_p: &ILDCRTParams<V::Integer>) -> Params<V> {
+        let params = [self.params.get_params(), params_p.get_params()].concat();
+        Arc::new(ILDCRTParams::with_params(
+            self.params.get_cyclotomic_order(),
+            params,
+        ))
+    }
 
-    fn sub(self, rhs: Integer) -> Self::Output {
-        let val = NativeInteger::from(rhs);
-        let mut tmp = DCRTPolyImpl::new(self.m_params, self.m_format);
-        let size = self.m_vectors.len();
-        self.m_vectors
-            .par_iter()
-            .enumerate()
-            .for_each(|(i, v)| tmp.m_vectors[i] = v.minus(&val));
-        tmp
+    fn approx_switch_crt_basis(&self, params_p: &Params<V>) -> Self {
+        self.switch_crt_basis(params_p, false)
     }
-}
 
-impl<T> Sub<Vec<Integer>> for DCRTPolyImpl<T> {
-    type Output = DCRTPolyImpl<T>;
+    fn expand_crt_basis(&self, params_p: &Params<V>) -> Self {
+        let mut x = self.clone();
+        x.set_format(Format::COEFFICIENT);
+        let mut ext = x.switch_crt_basis(params_p, true);
+        ext.set_format(self.format);
+        let params_qp = self.get_extended_crt_basis(params_p);
+        Self::with_towers(params_qp, [self.towers.clone(), ext.towers].concat())
+    }
 
-    fn sub(self, rhs: Vec<Integer>) -> Self::Output {
-        let mut tmp = DCRTPolyImpl::new(self.m_params, self.m_format);
-        let size = self.m_vectors.len();
-        self.m_vectors
-            .par_iter()
-            .enumerate()
-            .for_each(|(i, v)| tmp.m_vectors[i] = v.minus(&NativeInteger::from(rhs[i])));
-        tmp
+    fn approx_mod_up(&self, params_qp: &Params<V>) -> Self {
+        let moduli = self.params.get_moduli();
+        if let Some(q) = moduli.iter().find(|q| !params_qp.get_moduli().contains(q)) {
+            panic!(
+                "DCRTPolyImpl: the modulus {} is missing in the extended basis",
+                q
+            );
+        }
+        let complement: Vec<_> = params_qp
+            .get_params()
+            .iter()
+            .filter(|p| !moduli.contains(p.get_modulus()))
+            .cloned()
+            .collect();
+        let complement = Arc::new(ILDCRTParams::with_params(
+            self.params.get_cyclotomic_order(),
+            complement,
+        ));
+        let mut x = self.clone();
+        x.set_format(Format::COEFFICIENT);
+        let mut ext = x.approx_switch_crt_basis(&complement).towers.into_iter();
+        let towers = params_qp
+            .get_moduli()
+            .iter()
+            .map(|q| match moduli.iter().position(|p| p == q) {
+                Some(i) => self.towers[i].clone(),
+                None => {
+                    let mut tower = ext.next().unwrap();
+                    tower.set_format(self.format);
+                    tower
+                }
+            })
+            .collect();
+        Self::with_towers(params_qp.clone(), towers)
+    }
+
+    fn approx_mod_down(&self, params_q: &Params<V>) -> Self {
+        self.mod_down(params_q, false)
+    }
+}
+
+impl<V: TransformInterface> DCRTPolyImpl<V> {
+    /// Общая часть ModDown: [x]_P переводится в Q быстрым (exact = false)
+    /// или точным центрированным преобразованием
+    fn mod_down(&self, params_q: &Params<V>, exact: bool) -> Self {
+        let q_moduli = params_q.get_moduli();
+        let position = |q: &NativeInteger| self.params.get_moduli().iter().position(|p| p == q);
+        let part_p: Vec<NativePoly> = self
+            .towers
+            .iter()
+            .filter(|t| !q_moduli.contains(t.get_modulus()))
+            .cloned()
+            .collect();
+        if part_p.len() + q_moduli.len() != self.towers.len() {
+            panic!("DCRTPolyImpl: the target basis is not a part of the current one");
+        }
+        let params_p = Arc::new(ILDCRTParams::with_params(
+            self.params.get_cyclotomic_order(),
+            part_p.iter().map(|t| t.get_params().clone()).collect(),
+        ));
+        let mut part_p = Self::with_towers(params_p, part_p);
+        part_p.set_format(Format::COEFFICIENT);
+        let mut switched = part_p.switch_crt_basis(params_q, exact);
+        switched.set_format(self.format);
+        let p_moduli = part_p.params.get_moduli();
+        let towers = q_moduli
+            .iter()
+            .zip(switched.towers)
+            .map(|(q, switched)| {
+                let tower = match position(q) {
+                    Some(i) => &self.towers[i],
+                    None => panic!("DCRTPolyImpl: the modulus {} is missing in the basis", q),
+                };
+                let p_inv = p_moduli
+                    .iter()
+                    .fold(NativeInteger::new(1), |acc, p| acc.mod_mul(&p.modulo(q), q))
+                    .mod_inverse(q);
+                tower.minus(&switched).times_scalar(&p_inv)
+            })
+            .collect();
+        Self::with_towers(params_q.clone(), towers)
+    }
+
+    fn switch_crt_basis(&self, params_p: &Params<V>, exact: bool) -> Self {
+        if self.format != Format::COEFFICIENT {
+            panic!("DCRTPolyImpl: CRT basis switching requires COEFFICIENT format");
+        }
+        let converter =
+            RnsBaseConverterT::<V::Integer>::get(self.params.get_moduli(), params_p.get_moduli());
+        let towers: Vec<NativeVector> =
+            self.towers.iter().map(|t| t.get_values().clone()).collect();
+        let converted = if exact {
+            converter.exact_base_conv_vectors(&towers)
+        } else {
+            converter.fast_base_conv_vectors(&towers)
+        };
+        Self::from_vectors(params_p.clone(), converted)
+    }
+
+    /// Многочлен в COEFFICIENT из векторов остатков по башням params
+    fn from_vectors(params: Params<V>, values: Vec<NativeVector>) -> Self {
+        let towers = params
+            .get_params()
+            .iter()
+            .zip(values)
+            .map(|(p, values)| NativePoly::with_values(p.clone(), Format::COEFFICIENT, values))
+            .collect();
+        Self::with_towers(params, towers)
+    }
+}
+
+/* Масштабирование с округлением для BFV: HPS и BEHZ */
+impl<V: TransformInterface> DCRTPolyImpl<V> {
+    /// Расшифрование BFV по HPS: round(t x / Q) mod t для x из [0, Q).
+    /// Целая часть считается точно, дробная суммируется в f64, поэтому
+    /// результат точен, если t x / Q не ближе k 2^-52 к полуцелому
+    pub fn scale_and_round(&self, t: &NativeInteger) -> NativePoly {
+        self.check_coefficient("scale and round");
+        let scale = V::Integer::from_u64(t.value());
+        let values = self
+            .scale_and_round_vectors(self.towers.len(), &scale, &[*t], true)
+            .pop()
+            .unwrap();
+        let params = Arc::new(ILParamsImpl::with_root_of_unity(
+            self.params.get_cyclotomic_order(),
+            *t,
+            NativeInteger::new(1),
+        ));
+        NativePoly::with_values(params, Format::COEFFICIENT, values)
+    }
+
+    /// Умножение BFV по HPS: x по модулю Q ∪ P (сначала башни Q, затем башни
+    /// params_p) переводится в round(t x / Q) по модулю P
+    pub fn scale_and_round_to_basis(&self, params_p: &Params<V>, t: &NativeInteger) -> Self {
+        self.scale_to_basis(params_p, t, true)
+    }
+
+    /// Как scale_and_round_to_basis, но без дробной части: результат меньше
+    /// t x / Q не больше чем на k, где k - число башен Q
+    pub fn approx_scale_and_round(&self, params_p: &Params<V>, t: &NativeInteger) -> Self {
+        self.scale_to_basis(params_p, t, false)
+    }
+
+    /// Точный ModDown: x по модулю Q ∪ P переводится в round(x / P) по модулю
+    /// Q; [x]_P центрируется и переводится в Q точно
+    pub fn scale_and_round_p_over_q(&self, params_q: &Params<V>) -> Self {
+        self.mod_down(params_q, true)
+    }
+
+    /// Расширение HPS P/Q: центрированный x по модулю Q переводится в
+    /// round(P x / Q) по модулю Q ∪ P, где P - модуль params_p. Остатки по P
+    /// получаются масштабированием, по Q - точным преобразованием из P
+    pub fn fast_expand_crt_basis_plover_q(&self, params_p: &Params<V>) -> Self {
+        self.check_coefficient("P/Q basis expansion");
+        let values = self.scale_and_round_vectors(
+            self.towers.len(),
+            params_p.get_modulus(),
+            params_p.get_moduli(),
+            true,
+        );
+        let part_p = Self::from_vectors(params_p.clone(), values);
+        let part_q = part_p.switch_crt_basis(&self.params, true);
+        Self::with_towers(
+            self.get_extended_crt_basis(params_p),
+            [part_q.towers, part_p.towers].concat(),
+        )
+    }
+
+    /// floor(Q m / t) по модулю Q (дельта BFV) для m, заданного остатками
+    /// башен по модулю t: floor(Q m / t) = -[Q m]_t t^-1 mod q_i
+    pub fn times_qovert(&self, t: &NativeInteger) -> Self {
+        self.check_coefficient("multiplication by Q/t");
+        let q_mod_t = residue(self.get_modulus(), t);
+        self.map_towers(|tower| {
+            let q_i = *tower.get_modulus();
+            let t_inv = t.modulo(&q_i).mod_inverse(&q_i);
+            let mut values = tower.get_values().clone();
+            for c in 0..values.get_length() {
+                let r = values[c].modulo(t).mod_mul(&q_mod_t, t).modulo(&q_i);
+                values[c] = NativeInteger::new(0)
+                    .mod_sub(&r, &q_i)
+                    .mod_mul(&t_inv, &q_i);
+            }
+            NativePoly::with_values(tower.get_params().clone(), Format::COEFFICIENT, values)
+        })
+    }
+
+    /// Первый шаг умножения BEHZ: x по модулю Q переводится в Q ∪ Bsk, где
+    /// Bsk = B ∪ {m_sk} задан params_bsk. Быстрое преобразование [m̃ x]_Q в
+    /// Bsk ∪ {m̃} и редукция Монтгомери по m̃ = BEHZ_M_TILDE убирают
+    /// переполнение uQ: остатки по Bsk равны x или x - Q
+    pub fn fast_base_convq_to_bsk_montgomery(&self, params_bsk: &Params<V>) -> Self {
+        self.check_coefficient("BEHZ basis extension");
+        let q_moduli = self.params.get_moduli();
+        let m_tilde = NativeInteger::new(BEHZ_M_TILDE);
+        let targets = [params_bsk.get_moduli(), &[m_tilde]].concat();
+        let scaled: Vec<NativeVector> = self
+            .towers
+            .iter()
+            .zip(q_moduli)
+            .map(|(tower, q)| tower.get_values().mod_mul(&m_tilde.modulo(q)))
+            .collect();
+        let mut converted = RnsBaseConverterT::<V::Integer>::get(q_moduli, &targets)
+            .fast_base_conv_vectors(&scaled);
+        let y_tilde = converted.pop().unwrap();
+        let q = self.get_modulus();
+        let neg_q_inv =
+            NativeInteger::new(0).mod_sub(&residue(q, &m_tilde).mod_inverse(&m_tilde), &m_tilde);
+        let values = params_bsk
+            .get_moduli()
+            .iter()
+            .zip(converted)
+            .map(|(b, mut y)| {
+                let q_mod_b = residue(q, b);
+                let m_tilde_inv = m_tilde.modulo(b).mod_inverse(b);
+                for c in 0..y.get_length() {
+                    // r = -y Q^-1 mod m̃ в центрированном представлении
+                    let r = y_tilde[c].mod_mul(&neg_q_inv, &m_tilde).value();
+                    let correction = if r < BEHZ_M_TILDE / 2 {
+                        NativeInteger::new(r).mod_mul(&q_mod_b, b)
+                    } else {
+                        let r = NativeInteger::new(BEHZ_M_TILDE - r).mod_mul(&q_mod_b, b);
+                        NativeInteger::new(0).mod_sub(&r, b)
+                    };
+                    y[c] = y[c].mod_add(&correction, b).mod_mul(&m_tilde_inv, b);
+                }
+                y
+            })
+            .collect();
+        let ext = Self::from_vectors(params_bsk.clone(), values);
+        Self::with_towers(
+            self.get_extended_crt_basis(params_bsk),
+            [self.towers.clone(), ext.towers].concat(),
+        )
+    }
+
+    /// Второй шаг умножения BEHZ: x по модулю Q ∪ Bsk (последние башни -
+    /// params_bsk) переводится в floor(t x / Q) - u по модулю Bsk,
+    /// 0 <= u < k, где k - число башен Q
+    pub fn fast_rns_floorq(&self, t: &NativeInteger, params_bsk: &Params<V>) -> Self {
+        self.check_coefficient("BEHZ flooring");
+        let q_len = self.leading_towers(params_bsk);
+        let q_moduli = &self.params.get_moduli()[..q_len];
+        let scaled: Vec<NativeVector> = self.towers[..q_len]
+            .iter()
+            .zip(q_moduli)
+            .map(|(tower, q)| tower.get_values().mod_mul(&t.modulo(q)))
+            .collect();
+        let converted = RnsBaseConverterT::<V::Integer>::get(q_moduli, params_bsk.get_moduli())
+            .fast_base_conv_vectors(&scaled);
+        let q = self.get_modulus().clone() / params_bsk.get_modulus().clone();
+        let values = self.towers[q_len..]
+            .iter()
+            .zip(converted)
+            .map(|(tower, conv)| {
+                let b = tower.get_modulus();
+                let q_inv = residue(&q, b).mod_inverse(b);
+                tower
+                    .get_values()
+                    .mod_mul(&t.modulo(b))
+                    .mod_sub_vec(&conv)
+                    .mod_mul(&q_inv)
+            })
+            .collect();
+        Self::from_vectors(params_bsk.clone(), values)
+    }
+
+    /// Последний шаг умножения BEHZ: преобразование Шеноя-Кумаресана из
+    /// Bsk = B ∪ {m_sk} (m_sk - последняя башня) в params_q. Для x из
+    /// (-B/2, B/2) результат точен: избыток alpha быстрого преобразования из
+    /// B находится по остатку m_sk, поэтому нужно m_sk > |B| + 1
+    pub fn fast_base_conv_sk(&self, params_q: &Params<V>) -> Self {
+        self.check_coefficient("Shenoy-Kumaresan conversion");
+        let len = self.towers.len();
+        if len < 2 {
+            panic!("DCRTPolyImpl: the Bsk basis must contain B and m_sk");
+        }
+        let moduli = self.params.get_moduli();
+        let m_sk = moduli[len - 1];
+        let targets = [params_q.get_moduli(), &[m_sk]].concat();
+        let towers: Vec<NativeVector> = self.towers[..len - 1]
+            .iter()
+            .map(|tower| tower.get_values().clone())
+            .collect();
+        let mut converted = RnsBaseConverterT::<V::Integer>::get(&moduli[..len - 1], &targets)
+            .fast_base_conv_vectors(&towers);
+        let b = self.get_modulus().clone() / V::Integer::from_u64(m_sk.value());
+        let b_inv = residue(&b, &m_sk).mod_inverse(&m_sk);
+        let alpha = converted
+            .pop()
+            .unwrap()
+            .mod_sub_vec(self.towers[len - 1].get_values())
+            .mod_mul(&b_inv);
+        let values = params_q
+            .get_moduli()
+            .iter()
+            .zip(converted)
+            .map(|(q, mut y)| {
+                let b_mod_q = residue(&b, q);
+                for c in 0..y.get_length() {
+                    y[c] = y[c].mod_sub(&alpha[c].modulo(q).mod_mul(&b_mod_q, q), q);
+                }
+                y
+            })
+            .collect();
+        Self::from_vectors(params_q.clone(), values)
+    }
+
+    fn scale_to_basis(&self, params_p: &Params<V>, t: &NativeInteger, exact: bool) -> Self {
+        self.check_coefficient("scale and round");
+        let q_len = self.leading_towers(params_p);
+        let scale = V::Integer::from_u64(t.value());
+        let values = self.scale_and_round_vectors(q_len, &scale, params_p.get_moduli(), exact);
+        Self::from_vectors(params_p.clone(), values)
+    }
+
+    /// Остатки round(scale x / Q) (exact) или приближения снизу по модулям
+    /// outputs. Башни self - основание S = Q ∪ P, Q - первые q_len башен; при
+    /// непустом P outputs - модули P. Для s_i из Q разложим
+    /// scale P [(S/s_i)^-1]_{s_i} = B_i s_i + A_i: вклад башни равен
+    /// x_i B_i + floor(x_i A_i / s_i) и дробной части, которая копится в f64.
+    /// Вклад башни p_j из P равен x_j scale (P/p_j) [(S/p_j)^-1]_{p_j} mod p_j
+    fn scale_and_round_vectors(
+        &self,
+        q_len: usize,
+        scale: &V::Integer,
+        outputs: &[NativeInteger],
+        exact: bool,
+    ) -> Vec<NativeVector> {
+        let moduli = self.params.get_moduli();
+        let s_hat_inv = self.params.get_crt_base().get_q_hat_inv_mod_q();
+        let big = |x: &NativeInteger| V::Integer::from_u64(x.value());
+        let p = moduli[q_len..]
+            .iter()
+            .fold(V::Integer::one(), |acc, p| acc * big(p));
+        let (whole, frac): (Vec<Vec<u128>>, Vec<u128>) = moduli[..q_len]
+            .iter()
+            .zip(s_hat_inv)
+            .map(|(q, s_hat_inv)| {
+                let num = scale.clone() * p.clone() * big(s_hat_inv);
+                let quotient = num.clone() / big(q);
+                let whole = outputs
+                    .iter()
+                    .map(|o| residue(&quotient, o).value() as u128)
+                    .collect();
+                (whole, residue(&num, q).value() as u128)
+            })
+            .unzip();
+        let p_terms: Vec<u128> = moduli[q_len..]
+            .iter()
+            .zip(&s_hat_inv[q_len..])
+            .map(|(p_j, s_hat_inv)| {
+                let num = scale.clone() * (p.clone() / big(p_j)) * big(s_hat_inv);
+                residue(&num, p_j).value() as u128
+            })
+            .collect();
+        let n = self.get_ring_dimension();
+        let mut result = vec![vec![0u64; n]; outputs.len()];
+        for c in 0..n {
+            let x: Vec<u128> = self.towers.iter().map(|t| t[c].value() as u128).collect();
+            let (mut carry, mut fraction) = (0u128, 0f64);
+            for ((x_i, a_i), q) in x.iter().zip(&frac).zip(&moduli[..q_len]) {
+                let (prod, q) = (x_i * a_i, q.value() as u128);
+                carry += prod / q;
+                fraction += (prod % q) as f64 / q as f64;
+            }
+            if exact {
+                carry += fraction.round() as u128;
+            }
+            for (j, (out, o)) in result.iter_mut().zip(outputs).enumerate() {
+                let o = o.value() as u128;
+                let mut acc = carry % o;
+                for (x_i, whole) in x.iter().zip(&whole) {
+                    acc = (acc + x_i * whole[j]) % o;
+                }
+                if let Some(p_term) = p_terms.get(j) {
+                    acc = (acc + x[q_len + j] * p_term) % o;
+                }
+                out[c] = acc as u64;
+            }
+        }
+        result
+            .iter()
+            .zip(outputs)
+            .map(|(values, o)| NativeVector::from_values(values, *o))
+            .collect()
     }
-}
 
-impl<T> Mul<Integer> for DCRTPolyImpl<T> {
-    type Output = DCRTPolyImpl<T>;
+    /// Число первых башен, если последние башни self образуют params_p
+    fn leading_towers(&self, params_p: &Params<V>) -> usize {
+        let moduli = self.params.get_moduli();
+        let len = params_p.len();
+        if len >= moduli.len() || moduli[moduli.len() - len..] != *params_p.get_moduli() {
+            panic!("DCRTPolyImpl: the last towers do not form the target basis");
+        }
+        moduli.len() - len
+    }
 
-    fn mul(self, rhs: Integer) -> Self::Output {
-        let val = NativeInteger::from(rhs);
-        let mut tmp = DCRTPolyImpl::new(self.m_params, self.m_format);
-        let size = self.m_vectors.len();
-        self.m_vectors
-            .par_iter()
-            .enumerate()
-            .for_each(|(i, v)| tmp.m_vectors[i] = v.times(&val));
-        tmp
+    fn check_coefficient(&self, operation: &str) {
+        if self.format != Format::COEFFICIENT {
+            panic!("DCRTPolyImpl: {} requires COEFFICIENT format", operation);
+        }
     }
 }
 
-impl<T> Mul<NativeInteger::SignedNativeInt> for DCRTPolyImpl<T> {
-    type Output = DCRTPolyImpl<T>;
+impl<V: TransformInterface> Index<usize> for DCRTPolyImpl<V> {
+    type Output = NativePoly;
 
-    fn mul(self, rhs: NativeInteger::SignedNativeInt) -> Self::Output {
-        let mut tmp = DCRTPolyImpl::new(self.m_params, self.m_format);
-        let size = self.m_vectors.len();
-        self.m_vectors
-            .par_iter()
-            .enumerate()
-            .for_each(|(i, v)| tmp.m_vectors[i] = v.times(rhs));
-        tmp
+    fn index(&self, index: usize) -> &NativePoly {
+        &self.towers[index]
     }
 }
 
-impl<T> Mul<Vec<Integer>> for DCRTPolyImpl<T> {
-    type Output = DCRTPolyImpl<T>;
-
-    fn mul(self, rhs: Vec<Integer>) -> Self::Output {
-        let mut tmp = DCRTPolyImpl::new(self.m_params, self.m_format);
-        let size = self.m_vectors.len();
-        self.m_vectors
-            .par_iter()
-            .enumerate()
-            .for_each(|(i, v)| tmp.m_vectors[i] = v.times(&NativeInteger::from(rhs[i])));
-        tmp
+impl<V: TransformInterface> IndexMut<usize> for DCRTPolyImpl<V> {
+    fn index_mut(&mut self, index: usize) -> &mut NativePoly {
+        &mut self.towers[index]
     }
 }
 
-impl<T> Mul<Vec<NativeInteger>> for DCRTPolyImpl<T> {
-    type Output = DCRTPolyImpl<T>;
+impl<V: TransformInterface> Neg for DCRTPolyImpl<V> {
+    type Output = Self;
 
-    fn mul(self, rhs: Vec<NativeInteger>) -> Self::Output {
-        if self.m_vectors.len() != rhs.len() {
-            panic!("tower size mismatch; cannot multiply");
-        }
-        let mut tmp = DCRTPolyImpl::new(self.m_params, self.m_format);
-        let size = self.m_vectors.len();
-        self.m_vectors
-            .par_iter()
-            .enumerate()
-            .for_each(|(i, v)| tmp.m_vectors[i] = v.times(rhs[i]));
-        tmp
+    fn neg(self) -> Self {
+        self.negate()
     }
 }
 
-impl<T> Mul<Vec<NativeInteger>> for DCRTPolyImpl<T> {
-    type Output = DCRTPolyImpl<T>;
+impl<V: TransformInterface> Add for DCRTPolyImpl<V> {
+    type Output = Self;
 
-    fn mul(self, rhs: Vec<NativeInteger>) -> Self::Output {
-        let vec_size = self.m_vectors.len().min(rhs.len());
-        let mut tmp = DCRTPolyImpl::new(self.m_params, self.m_format);
-        self.m_vectors
-            .par_iter()
-            .enumerate()
-            .take(vec_size)
-            .for_each(|(i, v)| tmp.m_vectors[i] = v.times(rhs[i]));
-        tmp
+    fn add(self, rhs: Self) -> Self {
+        self.plus(&rhs)
     }
 }
 
-impl<T> MulAssign<Integer> for DCRTPolyImpl<T> {
-    fn mul_assign(&mut self, rhs: Integer) {
-        let val = NativeInteger::from(rhs);
-        let size = self.m_vectors.len();
-        self.m_vectors
-            .par_iter_mut()
-            .enumerate()
-            .for_each(|(i, v)| *v *= val);
-    }
-}
+impl<V: TransformInterface> Sub for DCRTPolyImpl<V> {
+    type Output = Self;
 
-impl<T> MulAssign<NativeInteger> for DCRTPolyImpl<T> {
-    fn mul_assign(&mut self, rhs: NativeInteger) {
-        let size = self.m_vectors.len();
-        self.m_vectors
-            .par_iter_mut()
-            .enumerate()
-            .for_each(|(i, v)| *v *= rhs);
+    fn sub(self, rhs: Self) -> Self {
+        self.minus(&rhs)
     }
 }
 
-impl<T> MulAssign<Vec<Integer>> for DCRTPolyImpl<T> {
-    fn mul_assign(&mut self, rhs: Vec<Integer>) {
-        let size = self.m_vectors.len();
-        self.m_vectors
-            .par_iter_mut()
-            .enumerate()
-            .for_each(|(i, v)| *v *= rhs);
-        self.m_format = Format::COEFFICIENT;
+impl<V: TransformInterface> Mul for DCRTPolyImpl<V> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        self.times(&rhs)
     }
 }
 
-impl<T> MulAssign<Vec<Integer>> for DCRTPolyImpl<T> {
-    fn mul_assign(&mut self, rhs: Vec<Integer>) {
-        let size = self.m_vectors.len();
-        self.m_vectors
-            .par_iter_mut()
-            .enumerate()
-            .for_each(|(i, v)| *v *= rhs);
-        self.m_format = Format::COEFFICIENT;
+impl<V: TransformInterface> AddAssign for DCRTPolyImpl<V> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = self.plus(&rhs);
     }
 }
 
-impl<T> MulAssign<Vec<Integer>> for DCRTPolyImpl<T> {
-    fn mul_assign(&mut self, rhs: Vec<Integer>) {
-        let size = self.m_vectors.len();
-        self.m_vectors
-            .par_iter_mut()
-            .enumerate()
-            .for_each(|(i, v)| *v *= rhs);
-        self.m_format = Format::COEFFICIENT;
+impl<V: TransformInterface> SubAssign for DCRTPolyImpl<V> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = self.minus(&rhs);
     }
 }
 
-impl<T> MulAssign<Vec<Integer>> for DCRTPolyImpl<T> {
-    fn mul_assign(&mut self, rhs: Vec<Integer>) {
-        let size = self.m_vectors.len();
-        self.m_vectors
-            .par_iter_mut()
-            .enumerate()
-            .for_each(|(i, v)| *v *= rhs);
-        self.m_format = Format::COEFFICIENT;
+impl<V: TransformInterface> MulAssign for DCRTPolyImpl<V> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = self.times(&rhs);
     }
 }
 
-
-
-
-
-
-
-
-
-
-
-
-
-use std::sync::Arc;
-
-pub struct DCRTPolyImpl<VecType> {
-    m_vectors: Vec<VecType>,
-    m_params: Arc<Params>,
-    m_format: Format,
+/// x mod q
+fn residue<I: BigIntegerInterface>(x: &I, q: &NativeInteger) -> NativeInteger {
+    NativeInteger::new(x.modulo(&I::from_u64(q.value())).to_u64().unwrap())
 }
-
-impl<VecType> DCRTPolyImpl<VecType> {
-    pub fn mod_reduce(
-        &mut self,
-        t: &NativeInteger,
-        t_modq_precon: &[NativeInteger],
-        negt_inv_modq: &NativeInteger,
-        negt_inv_modq_precon: &NativeInteger,
-        ql_inv_modq: &[NativeInteger],
-        ql_inv_modq_precon: &[NativeInteger],
-    ) {
-        let mut delta = self.m_vectors.last().unwrap().clone();
-        delta.set_format(Format::COEFFICIENT);
-        delta *= negt_inv_modq;
-        self.m_vectors.pop();
-        let size = self.m_vectors.len();
-        self.m_vectors
-            .par_iter_mut()
-            .with_num_threads(OpenFHEParallelControls.get_thread_limit(size))
-            .for_each(|m_vector| {
-                let mut tmp = delta.clone();
-                tmp.switch_modulus(m_vector.get_modulus(), m_vector.get_root_of_unity(), 0, 0);
-                if self.m_format == Format::EVALUATION {
-                    tmp.switch_format();
-                }
-                *m_vector += (tmp *= t);
-                *m_vector *= ql_inv_modq[i];
-            });
-    }
-
-    pub fn at(&self, i: usize) -> &VecType::Integer {
-        if self.m_vectors.is_empty() {
-            panic!("No values in DCRTPolyImpl");
-        }
-        if i >= self.m_vectors.len() {
-            panic!("out of range in DCRTPolyImpl.at()");
-        }
-        &self.crt_interpolate_index(i)[i]
-    }
-
-    pub fn at_mut(&mut self, i: usize) -> &mut VecType::Integer {
-        if self.m_vectors.is_empty() {
-            panic!("No values in DCRTPolyImpl");
-        }
-        if i >= self.m_vectors.len() {
-            panic!("out of range in DCRTPolyImpl.at()");
-        }
-        &mut self.crt_interpolate_index(i)[i]
-    }
-
-    pub fn crt_interpolate(&self) -> PolyLargeType {
-        let ring_dimension = self.m_params.get_ring_dimension();
-        let n_towers = self.m_vectors.len();
-        let big_modulus = self.m_params.get_modulus();
-
-        let mut coefficients = VecType::new(ring_dimension, big_modulus);
-
-        let mut multiplier = Vec::with_capacity(n_towers);
-        for vi in 0..n_towers {
-            let qj = self.m_vectors[vi].get_modulus().convert_to_int();
-            let div_by = big_modulus / qj;
-            let mod_inv = div_by.mod_inverse(qj).modulus(qj);
-            multiplier.push(div_by * mod_inv);
-        }
-
-        let vecs = if self.m_format == Format::EVALUATION {
-            let mut coeff_vecs = Vec::with_capacity(self.m_vectors.len());
-            for i in 0..self.m_vectors.len() {
-                let mut vec_copy = self.m_vectors[i].clone();
-                vec_copy.set_format(Format::COEFFICIENT);
-                coeff_vecs.push(vec_copy);
-            }
-            &coeff_vecs
-        } else {
-            &self.m_vectors
-        };
-
-        let mu = big_modulus.compute_mu();
-
-        for ri in 0..ring_dimension {
-            coefficients[ri] = 0;
-            for vi in 0..n_towers {
-                coefficients[ri] +=
-                    Integer::from(vecs[vi].get_values()[ri].convert_to_int()) * multiplier[vi];
-            }
-            coefficients[ri].mod_eq(big_modulus, mu);
-        }
-
-        let polynomial_reconstructed = PolyLargeType::new(
-            Arc::new(ILParamsImpl::new(
-                self.m_params.get_cyclotomic_order(),
-                big_modulus,
-                1,
-            )),
-        );
-        polynomial_reconstructed.set_values(coefficients, Format::COEFFICIENT);
-        polynomial_reconstructed
-    }
-
-    pub fn crt_interpolate_index(&self, i: usize) -> PolyLargeType {
-        let ring_dimension = self.m_params.get_ring_dimension();
-        let n_towers = self.m_vectors.len();
-        let big_modulus = self.m_params.get_modulus();
-        let mut coefficients = VecType::new(ring_dimension, big_modulus);
-        let mut multiplier = Vec::with_capacity(n_towers);
-        for vi in 0..n_towers {
-            let qj = self.m_vectors[vi].get_modulus().convert_to_int();
-            let div_by = big_modulus / qj;
-            let mod_inv = div_by.mod_inverse(qj).modulus(qj);
-            multiplier.push(div_by * mod_inv);
-        }
-
-        let vecs = if self.m_format == Format::EVALUATION {
-            let mut coeff_vecs = Vec::with_capacity(self.m_vectors.len());
-            for ii in 0..self.m_vectors.len() {
-                let mut vec_copy = self.m_vectors[ii].clone();
-                vec_copy.set_format(Format::COEFFICIENT);
-                coeff_vecs.push(vec_copy);
-            }
-            &coeff_vecs
-        } else {
-            &self.m_vectors
-        };
-
-        let mu = big_modulus.compute_mu();
-
-        for ri in 0..ring_dimension {
-            coefficients[ri] = 0;
-            if ri == i {
-                for vi in 0..n_towers {
-                    coefficients[ri] +=
-                        Integer::from(vecs[vi].get_values()[ri].convert_to_int()) * multiplier[vi];
-                }
-                coefficients[ri].mod_eq(big_modulus, mu);
-            }
-        }
-        let polynomial_reconstructed = PolyLargeType::new(
-            Arc::new(ILParamsImpl::new(
-                self.m_params.get_cyclotomic_order(),
-                big_modulus,
-                1,
-            )),
-        );
-        polynomial_reconstructed.set_values(coefficients, Format::COEFFICIENT);
-        polynomial_reconstructed
-    }
-
-    pub fn decryption_crt_interpolate(&self, ptm: PlaintextModulus) -> PolyType {
-        self.crt_interpolate().decryption_crt_interpolate(ptm)
-    }
-
-    pub fn to_native_poly(&self) -> PolyType {
-        self.crt_interpolate().to_native_poly()
-    }
-
-    pub fn get_working_modulus(&self) -> VecType::Integer {
-        let mut modulus_q = 1;
-        for p in self.m_params.get_params() {
-            modulus_q.mul_eq(p.get_modulus());
-        }
-        modulus_q
-    }
-
-    pub fn get_extended_crt_basis(
-        &self,
-        params_p: Arc<Params>,
-    ) -> Arc<Params> {
-        let size_q = self.m_vectors.len();
-        let size_p = params_p.get_params().len();
-        let size_qp = size_q + size_p;
-        let mut moduli_qp = Vec::with_capacity(size_qp);
-        let mut roots_qp = Vec::with_capacity(size_qp);
-        for i in 0..size_q {
-            moduli_qp.push(self.m_params.get_params()[i].get_modulus());
-            roots_qp.push(self.m_params.get_params()[i].get_root_of_unity());
-        }
-        for i in size_q..size_qp {
-            moduli_qp.push(params_p.get_params()[i - size_q].get_modulus());
-            roots_qp.push(params_p.get_params()[i - size_q].get_root_of_unity());
-        }
-        Arc::new(Params::new(2 * self.m_params.get_ring_dimension(), moduli_qp, roots_qp))
-    }
-
-    pub fn times_qovert(
-        &mut self,
-        params_q: Arc<Params>,
-        t_inv_modq: &[NativeInteger],
-        t: &NativeInteger,
-        neg_q_modt: &NativeInteger,
-        neg_q_modt_precon: &NativeInteger,
-    ) {
-        if t_inv_modq.len() < self.m_vectors.len() {
-            panic!("Sizes of vectors do not match.");
-        }
-        let size = self.m_vectors.len();
-        let ring_dim = self.m_params.get_ring_dimension();
-        self.m_vectors
-            .par_iter_mut()
-            .with_num_threads(OpenFHEParallelControls.get_thread_limit(size))
-            .for_each(|m_vector| {
-                for ri in 0..ring_dim {
-                    let xi = &mut m_vector[ri];
-                    xi.mod_mul_fast_const_eq(neg_q_modt, t, neg_q_modt_precon);
-                }
-                *m_vector = m_vector.times(t_inv_modq[i]);
-            });
-    }
-
-    pub fn approx_switch_crt_basis(
-        &self,
-        params_q: Arc<Params>,
-        params_p: Arc<Params>,
-        q_hat_inv_modq: &[NativeInteger],
-        q_hat_inv_modq_precon: &[NativeInteger],
-        q_hat_modp: &[Vec<NativeInteger>],
-        modp_barrett_mu: &[DoubleNativeInt],
-    ) -> DCRTPolyImpl<VecType> {
-        let mut ans = DCRTPolyImpl::new(params_p, self.m_format, true);
-        let ring_dim = self.m_params.get_ring_dimension();
-        let size_q = if self.m_vectors.len() > params_q.get_params().len() {
-            params_q.get_params().len()
-        } else {
-            self.m_vectors.len()
-        };
-        let size_p = ans.m_vectors.len();
-        for ri in 0..ring_dim {
-            let mut sum = vec![0; size_p];
-            for i in 0..size_q {
-                let xi = &self.m_vectors[i][ri];
-                let qi = self.m_vectors[i].get_modulus();
-                let x_q_hat_inv_modqi = xi.mod_mul_fast_const(q_hat_inv_modq[i], qi, q_hat_inv_modq_precon[i]);
-                for j in 0..size_p {
-                    sum[j] += x_q_hat_inv_modqi.convert_to_int() * q_hat_modp[i][j].convert_to_int();
-                }
-            }
-            for j in 0..size_p {
-                let pj = ans.m_vectors[j].get_modulus();
-                ans.m_vectors[j][ri] = barrett_uint128_mod_uint64(sum[j], pj.convert_to_int(), modp_barrett_mu[j]);
-            }
-        }
-        ans
-    }
-}
-
-pub enum Format {
-    COEFFICIENT,
-    EVALUATION,
-}
-
-pub struct PolyLargeType {
-    values: Vec<Integer>,
-    format: Format,
-}
-
-impl PolyLargeType {
-    pub fn new(params: Arc<ILParamsImpl<Integer>>) -> Self {
-        Self {
-            values: vec![],
-            format: Format::COEFFICIENT,
-        }
-    }
-
-    pub fn set_values(&mut self, values: Vec<Integer>, format: Format) {
-        self.values = values;
-        self.format = format;
-    }
-}
-
-pub struct PolyType {
-    values: Vec<Integer>,
-    format: Format,
-}
-
-impl PolyType {
-    pub fn new(params: Arc<ILParamsImpl<Integer>>) -> Self {
-        Self {
-            values: vec![],
-            format: Format::COEFFICIENT,
-        }
-    }
-
-    pub fn set_values(&mut self, values: Vec<Integer>, format: Format) {
-        self.values = values;
-        self.format = format;
-    }
-
-    pub fn decryption_crt_interpolate(&self, ptm: PlaintextModulus) -> Self {
-        unimplemented!()
-    }
-
-    pub fn to_native_poly(&self) -> Self {
-        unimplemented!()
-    }
-}
-
-pub struct ILParamsImpl<Integer> {
-    cyclotomic_order: usize,
-    modulus: Integer,
-    _phantom: std::marker::PhantomData<Integer>,
-}
-
-impl<Integer> ILParamsImpl<Integer> {
-    pub fn new(cyclotomic_order: usize, modulus: Integer, _: usize) -> Self {
-        Self {
-            cyclotomic_order,
-            modulus,
-            _phantom: std::marker::PhantomData,
-        }
-    }
-
-    pub fn get_cyclotomic_order(&self) -> usize {
-        self.cyclotomic_order
-    }
-
-    pub fn get_modulus(&self) -> &Integer {
-        &self.modulus
-    }
-}
-
-pub struct NativeInteger;
-
-impl NativeInteger {
-    pub fn convert_to_int(&self) -> i64 {
-        unimplemented!()
-    }
-
-    pub fn mod_inverse(&self, _: NativeInteger) -> NativeInteger {
-        unimplemented!()
-    }
-
-    pub fn modulus(&self, _: NativeInteger) -> NativeInteger {
-        unimplemented!()
-    }
-
-    pub fn mod_eq(&mut self, _: NativeInteger, _: NativeInteger) {
-        unimplemented!()
-    }
-
-    pub fn mod_mul_fast_const_eq(&mut self, _: NativeInteger, _: NativeInteger, _: NativeInteger) {
-        unimplemented!()
-    }
-}
-
-pub struct DoubleNativeInt;
-
-fn barrett_uint128_mod_uint64(_: i64, _: i64, _: DoubleNativeInt) -> i64 {
-    unimplemented!()
-}
-
-pub struct Integer;
-
-impl Integer {
-    pub fn from(_: i64) -> Self {
-        unimplemented!()
-    }
-
-    pub fn mod_eq(&mut self, _: NativeInteger, _: NativeInteger) {
-        unimplemented!()
-    }
-
-    pub fn modulus(&self, _: NativeInteger) -> NativeInteger {
-        unimplemented!()
-    }
-
-    pub fn mul_eq(&mut self, _: NativeInteger) {
-        unimplemented!()
-    }
-}
-
-pub struct PlaintextModulus;
-
-impl DCRTPolyImpl<VecType> {
-    pub fn new(params: Arc<Params>, format: Format, _: bool) -> Self {
-        Self {
-            m_vectors: vec![],
-            m_params: params,
-            m_format: format,
-        }
-    }
-}
-
-pub struct VecType {
-    modulus: NativeInteger,
-    root_of_unity: NativeInteger,
-}
-
-impl VecType {
-    pub fn new(_: usize, modulus: NativeInteger) -> Self {
-        Self {
-            modulus,
-            root_of_unity: NativeInteger,
-        }
-    }
-
-    pub fn get_modulus(&self) -> &NativeInteger {
-        &self.modulus
-    }
-
-    pub fn get_root_of_unity(&self) -> &NativeInteger {
-        &self.root_of_unity
-    }
-
-    pub fn set_format(&mut self, _: Format) {
-        unimplemented!()
-    }
-
-    pub fn times(&self, _: NativeInteger) -> Self {
-        unimplemented!()
-    }
-}
-
-pub fn mul128(_: i64, _: i64) -> i64 {
-    unimplemented!()
-}
-
-impl PolyType {
-    pub fn switch_modulus(&mut self, _: NativeInteger, _: NativeInteger, _: usize, _: usize) {
-        unimplemented!()
-    }
-
-    pub fn switch_format(&mut self) {
-        unimplemented!()
-    }
-}
-
-impl PolyLargeType {
-    pub fn switch_modulus(&mut self, _: NativeInteger, _: NativeInteger, _: usize, _: usize) {
-        unimplemented!()
-    }
-
-    pub fn switch_format(&mut self) {
-        unimplemented!()
-    }
-}
-
-pub struct OpenFHEParallelControls;
-
-impl OpenFHEParallelControls {
-    pub fn get_thread_limit(&self, _: usize) -> usize {
-        unimplemented!()
-    }
-}
-
-fn approx_mod_up(
-    params_q: Rc<Params>,
-    params_p: Rc<Params>,
-    params_qp: Rc<Params>,
-    q_hat_inv_modq: &Vec<NativeInteger>,
-    q_hat_inv_modq_precon: &Vec<NativeInteger>,
-    q_hat_modp: &Vec<Vec<NativeInteger>>,
-    modp_barrett_mu: &Vec<DoubleNativeInt>,
-) {
-    let mut poly_in_ntt: Vec<DCRTPolyImpl::PolyType> = Vec::new();
-
-    if m_format == Format::EVALUATION {
-        poly_in_ntt = m_vectors;
-        self.set_format(Format::COEFFICIENT);
-    }
-
-    let size_q = m_vectors.len();
-    let size_p = params_p.get_params().len();
-    let size_qp = params_qp.get_params().len();
-    let part_p = approx_switch_crt_basis(
-        params_q,
-        params_p,
-        q_hat_inv_modq,
-        q_hat_inv_modq_precon,
-        q_hat_modp,
-        modp_barrett_mu,
-    );
-    m_vectors.resize(size_qp);
-
-    for j in 0..size_p {
-        m_vectors[size_q + j] = part_p.m_vectors[j];
-        m_vectors[size_q + j].set_format(Format::EVALUATION);
-    }
-
-    if poly_in_ntt.len() > 0 {
-        for i in 0..size_q {
-            m_vectors[i] = poly_in_ntt[i];
-        }
-    } else {
-        for i in 0..size_q {
-            m_vectors[i].switch_format();
-        }
-    }
-
-    m_format = Format::EVALUATION;
-    m_params = params_qp;
-}
-
-fn approx_mod_down(
-    params_q: Rc<Params>,
-    params_p: Rc<Params>,
-    p_inv_modq: &Vec<NativeInteger>,
-    p_inv_modq_precon: &Vec<NativeInteger>,
-    p_hat_inv_modp: &Vec<NativeInteger>,
-    p_hat_inv_modp_precon: &Vec<NativeInteger>,
-    p_hat_modq: &Vec<Vec<NativeInteger>>,
-    modq_barrett_mu: &Vec<DoubleNativeInt>,
-    t_inv_modp: &Vec<NativeInteger>,
-    t_inv_modp_precon: &Vec<NativeInteger>,
-    t: &NativeInteger,
-    t_modq_precon: &Vec<NativeInteger>,
-) -> DCRTPolyImpl<VecType> {
-    let size_qp = m_vectors.len();
-    let size_p = params_p.get_params().len();
-    let size_q = size_qp - size_p;
-    let mut part_p = DCRTPolyImpl::new(params_p, m_format, true);
-
-    for j in 0..size_p {
-        part_p.m_vectors[j] = m_vectors[size_q + j];
-        part_p.m_vectors[j].set_format(Format::COEFFICIENT);
-
-        if t > 0 {
-            part_p.m_vectors[j] *= t_inv_modp[j];
-        }
-    }
-
-    part_p.override_format(Format::COEFFICIENT);
-    let part_p_switched_to_q = part_p.approx_switch_crt_basis(
-        params_p,
-        params_q,
-        p_hat_inv_modp,
-        p_hat_inv_modp_precon,
-        p_hat_modq,
-        modq_barrett_mu,
-    );
-
-    let mut ans = DCRTPolyImpl::new(params_q, Format::EVALUATION, true);
-    let diff_q = params_q.get_params().len() - size_q;
-
-    if diff_q > 0 {
-        ans.drop_last_elements(diff_q);
-    }
-
-    for i in 0..size_q {
-        if t > 0 {
-            part_p_switched_to_q.m_vectors[i] *= t;
-        }
-
-        part_p_switched_to_q.m_vectors[i].set_format(Format::EVALUATION);
-        ans.m_vectors[i] = (m_vectors[i] - part_p_switched_to_q.m_vectors[i]) * p_inv_modq[i];
-    }
-
-    ans
-}
-
-fn switch_crt_basis(
-    params_p: Rc<Params>,
-    q_hat_inv_modq: &Vec<NativeInteger>,
-    q_hat_inv_modq_precon: &Vec<NativeInteger>,
-    q_hat_modp: &Vec<Vec<NativeInteger>>,
-    alpha_q_modp: &Vec<Vec<NativeInteger>>,
-    modp_barrett_mu: &Vec<DoubleNativeInt>,
-    q_inv: &Vec<f64>,
-) -> DCRTPolyImpl<VecType> {
-    let mut ans = DCRTPolyImpl::new(params_p, m_format, true);
-    let ring_dim = m_params.get_ring_dimension();
-    let size_q = m_vectors.len();
-    let size_p = ans.m_vectors.len();
-
-    for ri in 0..ring_dim {
-        let mut x_q_hat_inv_modq: Vec<NativeInteger> = Vec::new();
-        let mut nu = 0.5;
-
-        for i in 0..size_q {
-            let qi = m_vectors[i].get_modulus();
-            x_q_hat_inv_modq[i] = m_vectors[i][ri].mod_mul_fast_const(q_hat_inv_modq[i], qi, q_hat_inv_modq_precon[i]);
-            nu += x_q_hat_inv_modq[i].convert_to_double() * q_inv[i];
-        }
-
-        let alpha = nu as usize;
-        let alpha_q_modpri = alpha_q_modp[alpha];
-
-        for j in 0..size_p {
-            let mut cur_value = 0;
-            let pj = ans.m_vectors[j].get_modulus();
-            let q_hat_modpj = q_hat_modp[j];
-
-            for i in 0..size_q {
-                cur_value += mul128(x_q_hat_inv_modq[i].convert_to_int(), q_hat_modpj[i].convert_to_int());
-            }
-
-            let cur_native_value = NativeInteger(barrett_uint128_mod_uint64(
-                cur_value,
-                pj.convert_to_int(),
-                modp_barrett_mu[j],
-            ));
-
-            ans.m_vectors[j][ri] = cur_native_value.mod_sub_fast(alpha_q_modpri[j], pj);
-        }
-    }
-
-    ans
-}
-
-
-impl<VecType: 'static> DCRTPolyImpl<VecType> {
-    fn expand_crt_basis(
-        &mut self,
-        params_qp: Arc<Params>,
-        params_p: Arc<Params>,
-        q_hat_inv_modq: Vec<NativeInteger>,
-        q_hat_inv_modq_precon: Vec<NativeInteger>,
-        q_hat_modp: Vec<Vec<NativeInteger>>,
-        alpha_q_modp: Vec<Vec<NativeInteger>>,
-        modp_barrett_mu: Vec<DoubleNativeInt>,
-        q_inv: Vec<f64>,
-        result_format: Format,
-    ) {
-        let mut poly_in_ntt: Vec<PolyType> = Vec::new();
-
-        if self.format == Format::Evaluation {
-            poly_in_ntt = self.vectors.clone();
-            self.set_format(Format::Coefficient);
-        }
-        let part_p = self.switch_crt_basis(
-            params_p,
-            q_hat_inv_modq.clone(),
-            q_hat_inv_modq_precon.clone(),
-            q_hat_modp.clone(),
-            alpha_q_modp.clone(),
-            modp_barrett_mu.clone(),
-            q_inv.clone(),
-        );
-        let size_q = self.vectors.len();
-        let size_p = part_p.vectors.len();
-        let size_qp = size_p + size_q;
-        self.vectors.resize(size_qp, Default::default());
-
-        self.vectors[size_q..]
-            .clone_from_slice(&part_p.vectors[..size_p]);
-
-        for j in size_q..size_qp {
-            self.vectors[j].set_format(result_format);
-        }
-
-        if result_format == Format::Evaluation {
-            if !poly_in_ntt.is_empty() {
-                for i in 0..size_q {
-                    self.vectors[i] = poly_in_ntt[i].clone();
-                }
-            } else {
-                for i in 0..size_q {
-                    self.vectors[i].set_format(Format::Evaluation);
-                }
-            }
-        }
-
-        self.format = result_format;
-        self.params = params_qp;
-    }
-
-    fn expand_crt_basis_reverse_order(
-        &mut self,
-        params_qp: Arc<Params>,
-        params_p: Arc<Params>,
-        q_hat_inv_modq: Vec<NativeInteger>,
-        q_hat_inv_modq_precon: Vec<NativeInteger>,
-        q_hat_modp: Vec<Vec<NativeInteger>>,
-        alpha_q_modp: Vec<Vec<NativeInteger>>,
-        modp_barrett_mu: Vec<DoubleNativeInt>,
-        q_inv: Vec<f64>,
-        result_format: Format,
-    ) {
-        let mut poly_in_ntt: Vec<PolyType> = Vec::new();
-
-        if self.format == Format::Evaluation {
-            poly_in_ntt = self.vectors.clone();
-            self.set_format(Format::Coefficient);
-        }
-        let part_p = self.switch_crt_basis(
-            params_p,
-            q_hat_inv_modq.clone(),
-            q_hat_inv_modq_precon.clone(),
-            q_hat_modp.clone(),
-            alpha_q_modp.clone(),
-            modp_barrett_mu.clone(),
-            q_inv.clone(),
-        );
-        let size_q = self.vectors.len();
-        let size_p = part_p.vectors.len();
-        let size_qp = size_p + size_q;
-        let mut temp: Vec<PolyType> = Vec::with_capacity(size_qp);
-        temp.extend(part_p.vectors.into_iter());
-        temp.extend(self.vectors.into_iter());
-
-        for i in 0..size_qp {
-            temp[i].set_format(result_format);
-        }
-
-        if result_format == Format::Evaluation {
-            if !poly_in_ntt.is_empty() {
-                for i in 0..size_q {
-                    temp[size_p + i] = poly_in_ntt[i].clone();
-                }
-            } else {
-                for i in 0..size_q {
-                    temp[size_p + i].set_format(Format::Evaluation);
-                }
-            }
-        }
-
-        self.format = result_format;
-        self.params = params_qp;
-        self.vectors = temp;
-    }
-
-    fn fast_expand_crt_basis_plover_q(&mut self, precomputed: &Precomputations) {
-        let ring_dim = self.params.get_ring_dimension();
-        let size_q = self.vectors.len();
-        let mut part_pl = DCRTPolyImpl::new(precomputed.params_pl.clone(), self.format, true);
-        let size_pl = part_pl.vectors.len();
-
-        #[cfg(all(feature = "HAVE_INT128", feature = "NATIVEINT_64"))]
-        {
-            #[cfg(feature = "OPENMP")]
-            let thread_limit = OpenFHEParallelControls::get_thread_limit(size_pl);
-
-            #[cfg(feature = "OPENMP")]
-            rayon::scope(|s| {
-                s.spawn(|_| {
-                    for ri in 0..ring_dim {
-                        let mut sum = vec![0; size_pl];
-                        for i in 0..size_q {
-                            let xi = self.vectors[i][ri].clone();
-                            let qi = self.vectors[i].get_modulus();
-                            let q_inv_modpi = precomputed.q_inv_modp[i].clone();
-                            let x_q_hat_inv_modqi = xi.mod_mul_fast_const(
-                                precomputed.m_pl_q_hat_inv_modq[i].clone(),
-                                qi.clone(),
-                                precomputed.m_pl_q_hat_inv_modq_precon[i].clone(),
-                            );
-                            for j in 0..size_pl {
-                                let a = x_q_hat_inv_modqi.convert_to_int();
-                                let b = q_inv_modpi[j].convert_to_int();
-                                sum[j] += mul128(a, b);
-                            }
-                        }
-                        for j in 0..size_pl {
-                            let pj = part_pl.vectors[j].get_modulus();
-                            part_pl.vectors[j][ri] =
-                                barrett_uint128_mod_uint64(sum[j], pj.convert_to_int(), precomputed.modp_barrett_mu[j]);
-                        }
-                    }
-                });
-            });
-
-            let part_ql = part_pl.switch_crt_basis(
-                precomputed.params_ql.clone(),
-                precomputed.pl_hat_inv_modp.clone(),
-                precomputed.pl_hat_inv_modp_precon.clone(),
-                precomputed.pl_hat_modq.clone(),
-                precomputed.alpha_pl_modq.clone(),
-                precomputed.modq_barrett_mu.clone(),
-                precomputed.p_inv.clone(),
-            );
-            let size_ql = size_pl;
-            let size_ql_pl = size_pl + size_ql;
-
-            self.vectors.resize(size_ql_pl, Default::default());
-
-            self.vectors[..size_ql].clone_from_slice(&part_ql.vectors[..size_ql]);
-
-            self.vectors[size_ql..].clone_from_slice(&part_pl.vectors[..size_pl]);
-
-            self.params = precomputed.params_ql_pl.clone();
-        }
-
-        #[cfg(not(all(feature = "HAVE_INT128", feature = "NATIVEINT_64")))]
-        {
-            #[cfg(feature = "OPENMP")]
-            let thread_limit = OpenFHEParallelControls::get_thread_limit(size_pl);
-
-            #[cfg(feature = "OPENMP")]
-            rayon::scope(|s| {
-                s.spawn(|_| {
-                    for ri in 0..ring_dim {
-                        for i in 0..size_q {
-                            let xi = self.vectors[i][ri].clone();
-                            let qi = self.vectors[i].get_modulus();
-                            let q_inv_modpi = precomputed.q_inv_modp[i].clone();
-                            let x_q_hat_inv_modqi = xi.mod_mul_fast_const(
-                                precomputed.m_pl_q_hat_inv_modq[i].clone(),
-                                qi.clone(),
-                                precomputed.m_pl_q_hat_inv_modq_precon[i].clone(),
-                            );
-                            for j in 0..size_pl {
-                                let pj = part_pl.vectors[j].get_modulus();
-                                let mu_j = pj.compute_mu();
-                                part_pl.vectors[j][ri].mod_add_fast_eq(
-                                    x_q_hat_inv_modqi.mod_mul_fast(q_inv_modpi[j].clone(), pj.clone(), mu_j),
-                                    pj,
-                                );
-                            }
-                        }
-                    }
-                });
-            });
-
-            let part_ql = part_pl.switch_crt_basis(
-                precomputed.params_ql.clone(),
-                precomputed.pl_hat_inv_modp.clone(),
-                precomputed.pl_hat_inv_modp_precon.clone(),
-                precomputed.pl_hat_modq.clone(),
-                precomputed.alpha_pl_modq.clone(),
-                precomputed.modq_barrett_mu.clone(),
-                precomputed.p_inv.clone(),
-            );
-            let size_ql = size_pl;
-            let size_ql_pl = size_pl + size_ql;
-
-            self.vectors.resize(size_ql_pl, Default::default());
-
-            self.vectors[..size_ql].clone_from_slice(&part_ql.vectors[..size_ql]);
-
-            self.vectors[size_ql..].clone_from_slice(&part_pl.vectors[..size_pl]);
-
-            self.params = precomputed.params_ql_pl.clone();
-        }
-    }
-
-    fn expand_crt_basis_ql_hat(
-        &mut self,
-        params_q: Arc<Params>,
-        ql_hat_modq: Vec<NativeInteger>,
-        ql_hat_modq_precon: Vec<NativeInteger>,
-        size_q: usize,
-    ) {
-        let size_ql = self.vectors.len();
-        let ring_dim = self.params.get_ring_dimension();
-
-        #[cfg(feature = "OPENMP")]
-        let thread_limit = OpenFHEParallelControls::get_thread_limit(size_ql);
-
-        #[cfg(feature = "OPENMP")]
-        rayon::scope(|s| {
-            s.spawn(|_| {
-                for i in 0..size_ql {
-                    let qi = self.vectors[i].get_modulus();
-                    let ql_hat_modqi = ql_hat_modq[i].clone();
-                    let ql_hat_modqi_precon = ql_hat_modq_precon[i].clone();
-                    for ri in 0..ring_dim {
-                        self.vectors[i][ri].mod_mul_fast_const_eq(ql_hat_modqi.clone(), qi.clone(), ql_hat_modqi_precon.clone());
-                    }
-                }
-            });
-        });
-
-        self.vectors.resize(size_q, Default::default());
-
-        for i in size_ql..size_q {
-            let new_vec = PolyType::new(params_q.get_params()[i].clone(), self.format, true);
-            self.vectors[i] = new_vec;
-        }
-
-        self.params = params_q;
-    }
-}
-
-
-fn scale_and_round(
-    &self,
-    t: &NativeInteger,
-    t_q_hat_inv_mod_q_div_q_mod_t: &Vec<NativeInteger>,
-    t_q_hat_inv_mod_q_div_q_mod_t_precon: &Vec<NativeInteger>,
-    t_q_hat_inv_mod_q_b_div_q_mod_t: &Vec<NativeInteger>,
-    t_q_hat_inv_mod_q_b_div_q_mod_t_precon: &Vec<NativeInteger>,
-    t_q_hat_inv_mod_q_div_q_frac: &Vec<f64>,
-    t_q_hat_inv_mod_q_div_q_b_frac: &Vec<f64>,
-) -> PolyType {
-    let ring_dim = self.params.get_ring_dimension();
-    let size_q = self.vectors.len();
-
-    let q_msb = self.vectors[0].get_modulus().get_msb();
-
-    let t_msb = t.get_msb();
-
-    let size_q_msb = get_msb64(size_q);
-    let mut coefficients = PolyType::new(ring_dim, t.convert_to_int());
-
-    if is_power_of_two(t.convert_to_int()) {
-        let t_minus_1 = t.convert_to_int() - 1;
-
-        if q_msb + size_q_msb < 52 {
-            if q_msb + t_msb + size_q_msb < 63 {
-                for ri in 0..ring_dim {
-                    let mut float_sum = 0.5;
-                    let mut int_sum = 0;
-                    for i in 0..size_q {
-                        let tmp = self.vectors[i][ri];
-                        float_sum += tmp.convert_to_double() * t_q_hat_inv_mod_q_div_q_frac[i];
-
-                        let mut tmp = tmp;
-                        tmp.mul_eq_fast(t_q_hat_inv_mod_q_div_q_mod_t[i]);
-                        int_sum.add_eq_fast(tmp);
-                    }
-                    int_sum += float_sum as u64;
-
-                    coefficients[ri] = int_sum.convert_to_int() & t_minus_1;
-                }
-            } else {
-                for ri in 0..ring_dim {
-                    let mut float_sum = 0.5;
-                    let mut int_sum = 0;
-                    for i in 0..size_q {
-                        let tmp = self.vectors[i][ri];
-                        float_sum += tmp.convert_to_double() * t_q_hat_inv_mod_q_div_q_frac[i];
-                        let mut tmp = tmp;
-                        tmp.mod_mul_fast_const_eq(
-                            t_q_hat_inv_mod_q_div_q_mod_t[i],
-                            t,
-                            t_q_hat_inv_mod_q_div_q_mod_t_precon[i],
-                        );
-                        int_sum.add_eq_fast(tmp);
-                    }
-                    int_sum += float_sum as u64;
-
-                    coefficients[ri] = int_sum.convert_to_int() & t_minus_1;
-                }
-            }
-        } else {
-            let q_msb_hf = q_msb >> 1;
-            if q_msb_hf + t_msb + size_q_msb < 62 {
-                for ri in 0..ring_dim {
-                    let mut float_sum = 0.5;
-                    let mut int_sum = 0;
-                    for i in 0..size_q {
-                        let mut tmp_lo = self.vectors[i][ri];
-                        let mut tmp_hi = tmp_lo.r_shift(q_msb_hf);
-                        tmp_lo.sub_eq_fast(tmp_hi.l_shift(q_msb_hf));
-                        float_sum += tmp_lo.convert_to_double() * t_q_hat_inv_mod_q_div_q_frac[i];
-                        float_sum += tmp_hi.convert_to_double() * t_q_hat_inv_mod_q_div_q_b_frac[i];
-
-                        tmp_lo.mul_eq_fast(t_q_hat_inv_mod_q_div_q_mod_t[i]);
-                        tmp_hi.mul_eq_fast(t_q_hat_inv_mod_q_b_div_q_mod_t[i]);
-                        int_sum.add_eq_fast(tmp_lo);
-                        int_sum.add_eq_fast(tmp_hi);
-                    }
-                    int_sum += float_sum as u64;
-
-                    coefficients[ri] = int_sum.convert_to_int() & t_minus_1;
-                }
-            } else {
-                for ri in 0..ring_dim {
-                    let mut float_sum = 0.5;
-                    let mut int_sum = 0;
-                    for i in 0..size_q {
-                        let mut tmp_lo = self.vectors[i][ri];
-                        let mut tmp_hi = tmp_lo.r_shift(q_msb_hf);
-                        tmp_lo.sub_eq_fast(tmp_hi.l_shift(q_msb_hf));
-                        float_sum += tmp_lo.convert_to_double() * t_q_hat_inv_mod_q_div_q_frac[i];
-                        float_sum += tmp_hi.convert_to_double() * t_q_hat_inv_mod_q_div_q_b_frac[i];
-                        tmp_lo.mod_mul_fast_const_eq(
-                            t_q_hat_inv_mod_q_div_q_mod_t[i],
-                            t,
-                            t_q_hat_inv_mod_q_div_q_mod_t_precon[i],
-                        );
-                        tmp_hi.mod_mul_fast_const_eq(
-                            t_q_hat_inv_mod_q_b_div_q_mod_t[i],
-                            t,
-                            t_q_hat_inv_mod_q_b_div_q_mod_t_precon[i],
-                        );
-                        int_sum.add_eq_fast(tmp_lo);
-                        int_sum.add_eq_fast(tmp_hi);
-                    }
-                    int_sum += float_sum as u64;
-
-                    coefficients[ri] = int_sum.convert_to_int() & t_minus_1;
-                }
-            }
-        }
-    } else {
-        let td = t.convert_to_int() as f64;
-        let t_inv = 1.0 / td;
-
-        if q_msb + size_q_msb < 52 {
-            if q_msb + t_msb + size_q_msb < 52 {
-                for ri in 0..ring_dim {
-                    let mut float_sum = 0.0;
-                    let mut int_sum = 0;
-                    for i in 0..size_q {
-                        let tmp = self.vectors[i][ri];
-                        float_sum += tmp.convert_to_double() * t_q_hat_inv_mod_q_div_q_frac[i];
-
-                        let mut tmp = tmp;
-                        tmp.mul_eq_fast(t_q_hat_inv_mod_q_div_q_mod_t[i]);
-                        int_sum.add_eq_fast(tmp);
-                    }
-
-                    float_sum += int_sum.convert_to_int() as f64;
-                    let quot = (float_sum * t_inv) as u64;
-                    float_sum -= td * quot as f64;
-
-                    coefficients[ri] = (float_sum + 0.5) as u64;
-                }
-            } else {
-                for ri in 0..ring_dim {
-                    let mut float_sum = 0.0;
-                    let mut int_sum = 0;
-                    for i in 0..size_q {
-                        let tmp = self.vectors[i][ri];
-                        float_sum += tmp.convert_to_double() * t_q_hat_inv_mod_q_div_q_frac[i];
-                        let mut tmp = tmp;
-                        tmp.mod_mul_fast_const_eq(
-                            t_q_hat_inv_mod_q_div_q_mod_t[i],
-                            t,
-                            t_q_hat_inv_mod_q_div_q_mod_t_precon[i],
-                        );
-                        int_sum.add_eq_fast(tmp);
-                    }
-
-                    float_sum += int_sum.convert_to_double();
-                    let quot = (float_sum * t_inv) as u64;
-                    float_sum -= td * quot as f64;
-
-                    coefficients[ri] = (float_sum + 0.5) as u64;
-                }
-            }
-        } else {
-            let q_msb_hf = q_msb >> 1;
-            if q_msb_hf + t_msb + size_q_msb < 52 {
-                for ri in 0..ring_dim {
-                    let mut float_sum = 0.0;
-                    let mut int_sum = 0;
-                    for i in 0..size_q {
-                        let mut tmp_lo = self.vectors[i][ri];
-                        let mut tmp_hi = tmp_lo.r_shift(q_msb_hf);
-                        tmp_lo.sub_eq_fast(tmp_hi.l_shift(q_msb_hf));
-                        float_sum += tmp_lo.convert_to_double() * t_q_hat_inv_mod_q_div_q_frac[i];
-                        float_sum += tmp_hi.convert_to_double() * t_q_hat_inv_mod_q_div_q_b_frac[i];
-
-                        let mut tmp_lo = tmp_lo;
-                        let mut tmp_hi = tmp_hi;
-                        tmp_lo.mul_eq_fast(t_q_hat_inv_mod_q_div_q_mod_t[i]);
-                        tmp_hi.mul_eq_fast(t_q_hat_inv_mod_q_b_div_q_mod_t[i]);
-                        int_sum.add_eq_fast(tmp_lo);
-                        int_sum.add_eq_fast(tmp_hi);
-                    }
-
-                    float_sum += int_sum.convert_to_int() as f64;
-                    let quot = (float_sum * t_inv) as u64;
-                    float_sum -= td * quot as f64;
-
-                    coefficients[ri] = (float_sum + 0.5) as u64;
-                }
-            } else {
-                for ri in 0..ring_dim {
-                    let mut float_sum = 0.0;
-                    let mut int_sum = 0;
-                    for i in 0..size_q {
-                        let mut tmp_lo = self.vectors[i][ri];
-                        let mut tmp_hi = tmp_lo.r_shift(q_msb_hf);
-                        tmp_lo.sub_eq_fast(tmp_hi.l_shift(q_msb_hf));
-                        float_sum += tmp_lo.convert_to_double() * t_q_hat_inv_mod_q_div_q_frac[i];
-                        float_sum += tmp_hi.convert_to_double() * t_q_hat_inv_mod_q_div_q_b_frac[i];
-                        tmp_lo.mod_mul_fast_const_eq(
-                            t_q_hat_inv_mod_q_div_q_mod_t[i],
-                            t,
-                            t_q_hat_inv_mod_q_div_q_mod_t_precon[i],
-                        );
-                        tmp_hi.mod_mul_fast_const_eq(
-                            t_q_hat_inv_mod_q_b_div_q_mod_t[i],
-                            t,
-                            t_q_hat_inv_mod_q_b_div_q_mod_t_precon[i],
-                        );
-                        int_sum.add_eq_fast(tmp_lo);
-                        int_sum.add_eq_fast(tmp_hi);
-                    }
-
-                    float_sum += int_sum.convert_to_int() as f64;
-                    let quot = (float_sum * t_inv) as u64;
-                    float_sum -= td * quot as f64;
-
-                    coefficients[ri] = (float_sum + 0.5) as u64;
-                }
-            }
-        }
-    }
-
-    let result = PolyType::new(
-        Rc::new(PolyType::Params::new(self.params.get_cyclotomic_order(), t.convert_to_int(), 1)),
-    );
-    result.set_values(coefficients, Format::Coefficient);
-    result
-}
-
-fn approx_scale_and_round(
-    &self,
-    params_p: &Rc<Params>,
-    t_p_s_hat_inv_mods_divs_mod_p: &Vec<Vec<NativeInteger>>,
-    mod_p_barret_mu: &Vec<DoubleNativeInt>,
-) -> DCRTPolyImpl<VecType> {
-    let mut ans = DCRTPolyImpl::new(params_p, self.format, true);
-    let ring_dim = self.params.get_ring_dimension();
-    let size_qp = self.vectors.len();
-    let size_p = ans.m_vectors.len();
-    let size_q = size_qp - size_p;
-
-    let mut coefficients = Vec::with_capacity(ring_dim);
-    for ri in 0..ring_dim {
-        let mut ri_coefficients = Vec::with_capacity(size_p);
-        for j in 0..size_p {
-            let mut cur_value = 0;
-            let pj = params_p.get_params()[j].get_modulus();
-            let t_p_s_hat_inv_mods_divs_mod_pj = &t_p_s_hat_inv_mods_divs_mod_p[j];
-            for i in 0..size_q {
-                let xi = self.vectors[i][ri];
-                cur_value += mul_128(xi.convert_to_int(), t_p_s_hat_inv_mods_divs_mod_pj[i].convert_to_int());
-            }
-            let xi = self.vectors[size_q + j][ri];
-            cur_value += mul_128(xi.convert_to_int(), t_p_s_hat_inv_mods_divs_mod_pj[size_q].convert_to_int());
-            ri_coefficients.push(barrett_uint128_mod_uint64(cur_value, pj.convert_to_int(), mod_p_barret_mu[j]));
-        }
-        coefficients.push(ri_coefficients);
-    }
-    ans.set_values(coefficients, Format::Coefficient);
-    ans
-}
-
-
-fn scale_and_round(
-    params_output: &std::rc::Rc<Params>,
-    t_o_shat_inv_mods_divs_modo: &Vec<Vec<NativeInteger>>,
-    t_o_shat_inv_mods_divs_frac: &Vec<f64>,
-    modo_barret_mu: &Vec<DoubleNativeInt>,
-) -> DCRTPolyImpl<VecType> {
-    if NATIVEINT == 32 {
-        panic!("Use of ScaleAndRound with NATIVEINT == 32 may lead to overflow");
-    }
-    let ans = DCRTPolyImpl::new(params_output.clone(), m_format, true);
-    let ring_dim = m_params.get_ring_dimension();
-    let size_qp = m_vectors.len();
-    let size_o = ans.m_vectors.len();
-    let size_i = size_qp - size_o;
-    let mut input_index = 0;
-    let mut output_index = 0;
-    if params_output.get_params()[0].get_modulus() == m_params.get_params()[0].get_modulus() {
-        input_index = size_o;
-    } else {
-        output_index = size_i;
-    }
-    let mut mu = vec![NativeInteger::default(); size_o];
-    for j in 0..size_o {
-        mu[j] = params_output.get_params()[j].get_modulus().compute_mu();
-    }
-    #[cfg(feature = "HAVE_INT128")]
-    #[cfg(NATIVEINT == 64)]
-    {
-        #pragma omp parallel for
-        for ri in 0..ring_dim {
-            let mut nu = 0.5;
-            for i in 0..size_i {
-                let xi = m_vectors[i + input_index][ri];
-                nu += t_o_shat_inv_mods_divs_frac[i] * xi.convert_to_double();
-            }
-            if is_convertable_to_native_int(nu) {
-                let alpha = nu as BasicInteger;
-                for j in 0..size_o {
-                    let t_o_shat_inv_mods_divs_modoj = &t_o_shat_inv_mods_divs_modo[j];
-                    let mut cur_value = DoubleNativeInt::default();
-                    for i in 0..size_i {
-                        let xi = m_vectors[i + input_index][ri];
-                        cur_value += xi.convert_to_int() * t_o_shat_inv_mods_divs_modoj[i].convert_to_int();
-                    }
-                    let xi = m_vectors[output_index + j][ri];
-                    cur_value += xi.convert_to_int() * t_o_shat_inv_mods_divs_modoj[size_i].convert_to_int();
-                    let oj = params_output.get_params()[j].get_modulus();
-                    let cur_native_value = barrett_uint128_mod_uint64(cur_value, oj.convert_to_int(), modo_barret_mu[j]);
-                    let mut cur_alpha = alpha;
-                    if alpha >= oj {
-                        cur_alpha = alpha.modulus(oj, mu[j]);
-                    }
-                    ans.m_vectors[j][ri] = NativeInteger::default().mod_add_fast(cur_native_value, cur_alpha, oj);
-                }
-            } else {
-                let alpha = nu as DoubleNativeInt;
-                for j in 0..size_o {
-                    let t_o_shat_inv_mods_divs_modoj = &t_o_shat_inv_mods_divs_modo[j];
-                    let mut cur_value = DoubleNativeInt::default();
-                    for i in 0..size_i {
-                        let xi = m_vectors[i + input_index][ri];
-                        cur_value += xi.convert_to_int() * t_o_shat_inv_mods_divs_modoj[i].convert_to_int();
-                    }
-                    let xi = m_vectors[output_index + j][ri];
-                    cur_value += xi.convert_to_int() * t_o_shat_inv_mods_divs_modoj[size_i].convert_to_int();
-                    let oj = params_output.get_params()[j].get_modulus();
-                    let cur_native_value = barrett_uint128_mod_uint64(cur_value, oj.convert_to_int(), modo_barret_mu[j]);
-                    ans.m_vectors[j][ri] = NativeInteger::default().mod_add_fast(cur_native_value, alpha, oj);
-                }
-            }
-        }
-    }
-    #[cfg(not(feature = "HAVE_INT128"))]
-    {
-        #pragma omp parallel for
-        for ri in 0..ring_dim {
-            let mut nu = 0.5;
-            for i in 0..size_i {
-                let xi = m_vectors[i + input_index][ri];
-                nu += t_o_shat_inv_mods_divs_frac[i] * xi.convert_to_double();
-            }
-            if is_convertable_to_native_int(nu) {
-                let alpha = nu as BasicInteger;
-                for j in 0..size_o {
-                    let t_o_shat_inv_mods_divs_modoj = &t_o_shat_inv_mods_divs_modo[j];
-                    let oj = ans.m_vectors[j].get_modulus();
-                    let cur_value = &mut ans.m_vectors[j][ri];
-                    for i in 0..size_i {
-                        let xi = m_vectors[i + input_index][ri];
-                        cur_value.mod_add_fast_eq(xi.mod_mul_fast(t_o_shat_inv_mods_divs_modoj[i], oj, mu[j]), oj);
-                    }
-                    let xi = m_vectors[output_index + j][ri];
-                    cur_value.mod_add_fast_eq(xi.mod_mul_fast(t_o_shat_inv_mods_divs_modoj[size_i], oj, mu[j]), oj);
-                    cur_value.mod_add_fast_eq(alpha >= oj ? alpha.modulus(oj, mu[j]) : alpha, oj);
-                }
-            } else {
-                let (exp, mant) = std::frexp(nu);
-                let mantissa = (mant * (1ULL << 53)) as BasicInteger;
-                let exponent = (1ULL << (exp - 53)) as BasicInteger;
-                for j in 0..size_o {
-                    let t_o_shat_inv_mods_divs_modoj = &t_o_shat_inv_mods_divs_modo[j];
-                    let oj = ans.m_vectors[j].get_modulus();
-                    let cur_value = &mut ans.m_vectors[j][ri];
-                    for i in 0..size_i {
-                        let xi = m_vectors[i + input_index][ri];
-                        cur_value.mod_add_fast_eq(xi.mod_mul_fast(t_o_shat_inv_mods_divs_modoj[i], oj, mu[j]), oj);
-                    }
-                    let xi = m_vectors[output_index + j][ri];
-                    cur_value.mod_add_fast_eq(xi.mod_mul_fast(t_o_shat_inv_mods_divs_modoj[size_i], oj, mu[j]), oj);
-                    cur_value.mod_add_fast_eq(exponent.mod_mul(mantissa, oj, mu[j]), oj);
-                }
-            }
-        }
-    }
-    ans
-}
-
-fn scale_and_round(
-    moduli_q: &Vec<NativeInteger>,
-    t: &NativeInteger,
-    tgamma: &NativeInteger,
-    tgamma_q_hat_mod_q: &Vec<NativeInteger>,
-    tgamma_q_hat_mod_q_precon: &Vec<NativeInteger>,
-    neg_inv_q_mod_tgamma: &Vec<NativeInteger>,
-    neg_inv_q_mod_tgamma_precon: &Vec<NativeInteger>,
-) -> PolyType {
-    let n = m_params.get_ring_dimension();
-    let size_q = m_vectors.len();
-    let gamma_minus_1 = (1 << 26) - 1;
-    let mut coefficients = vec![t.convert_to_int(); n];
-    #pragma omp parallel for
-    for k in 0..n {
-        let mut s = NativeInteger::default();
-        let mut tmp = NativeInteger::default();
-        for i in 0..size_q {
-            let qi = moduli_q[i];
-            tmp = m_vectors[i][k];
-            tmp.mod_mul_fast_const_eq(tgamma_q_hat_mod_q[i], qi, tgamma_q_hat_mod_q_precon[i]);
-            tmp = tmp.mod_mul_fast_const(neg_inv_q_mod_tgamma[i], tgamma, neg_inv_q_mod_tgamma_precon[i]);
-            s.mod_add_fast_eq(tmp, tgamma);
-        }
-        s += NativeInteger(s.convert_to_int() & gamma_minus_1);
-        coefficients[k] = s >> 26;
-    }
-    let result = PolyType::new(
-        std::rc::Rc::new(PolyType::Params::new(m_params.get_cyclotomic_order(), t.convert_to_int(), 1)),
-    );
-    result.set_values(coefficients, Format::COEFFICIENT);
-    result
-}
-
-fn scale_and_round_p_over_q(params_q: &std::rc::Rc<Params>, p_inv_mod_q: &Vec<NativeInteger>) {
-    let size_q = m_vectors.len() - 1;
-    let ring_dim = m_params.get_ring_dimension();
-    for i in 0..size_q {
-        let qi = params_q.get_params()[i].get_modulus();
-        for ri in 0..ring_dim {
-            m_vectors[i][ri].mod_sub_eq(m_vectors[size_q][ri], qi);
-        }
-    }
-    m_vectors.resize(size_q);
-    m_params = params_q;
-    *this = this.times(p_inv_mod_q);
-}
-
-fn fast_base_conv_q_to_bsk_montgomery(
-    params_q_bsk: &std::rc::Rc<Params>,
-    moduli_q: &Vec<NativeInteger>,
-    moduli_bsk: &Vec<NativeInteger>,
-    modbsk_barret_mu: &Vec<DoubleNativeInt>,
-    mtilde_q_hat_inv_mod_q: &Vec<NativeInteger>,
-    mtilde_q_hat_inv_mod_q_precon: &Vec<NativeInteger>,
-    q_hat_mod_bsk: &Vec<Vec<NativeInteger>>,
-    q_hat_mod_mtilde: &Vec<u64>,
-    q_mod_bsk: &Vec<NativeInteger>,
-    q_mod_bsk_precon: &Vec<NativeInteger>,
-    neg_q_inv_mod_mtilde: u64,
-    mtilde_inv_mod_bsk: &Vec<NativeInteger>,
-    mtilde_inv_mod_bsk_precon: &Vec<NativeInteger>,
-) {
-    let poly_in_ntt = if m_format == Format::EVALUATION {
-        let poly_in_ntt = m_vectors.clone();
-        this.set_format(Format::COEFFICIENT);
-        poly_in_ntt
-    } else {
-        vec![]
-    };
-    m_params = params_q_bsk;
-    m_vectors.resize(m_params.get_params().len());
-    let num_q = moduli_q.len();
-    let num_bsk = moduli_bsk.len();
-    let n = m_params.get_ring_dimension();
-    let mut ximtilde_q_hat_mod_qi = vec![NativeInteger::default(); n * num_q];
-    for i in 0..num_q {
-        let current_mtilde_q_hat_inv_mod_q = mtilde_q_hat_inv_mod_q[i];
-        let current_mtilde_q_hat_inv_mod_q_precon = mtilde_q_hat_inv_mod_q_precon[i];
-        for k in 0..n {
-            ximtilde_q_hat_mod_qi[i * n + k] =
-                m_vectors[i][k].mod_mul_fast_const(current_mtilde_q_hat_inv_mod_q, moduli_q[i], current_mtilde_q_hat_inv_mod_q_precon);
-        }
-    }
-    #[cfg(feature = "HAVE_INT128")]
-    #[cfg(NATIVEINT == 64)]
-    {
-        for j in 0..num_bsk {
-            let newvec = DCRTPolyImpl::PolyType::new(m_params.get_params()[num_q + j], m_format, true);
-            m_vectors[num_q + j] = newvec;
-            for k in 0..n {
-                let mut result = DoubleNativeInt::default();
-                for i in 0..num_q {
-                    let q_hat_mod_bskij = q_hat_mod_bsk[i][j];
-                    result += ximtilde_q_hat_mod_qi[i * n + k].convert_to_int() * q_hat_mod_bskij.convert_to_int();
-                }
-                m_vectors[num_q + j][k] = barrett_uint128_mod_uint64(result, moduli_bsk[j].convert_to_int(), modbsk_barret_mu[j]);
-            }
-        }
-    }
-    #[cfg(not(feature = "HAVE_INT128"))]
-    {
-        let mut mu = vec![NativeInteger::default(); num_bsk];
-        for j in 0..num_bsk {
-            mu[j] = moduli_bsk[j].compute_mu();
-        }
-        for j in 0..num_bsk {
-            let newvec = DCRTPolyImpl::PolyType::new(m_params.get_params()[num_q + j], m_format, true);
-            m_vectors[num_q + j] = newvec;
-            for k in 0..n {
-                for i in 0..num_q {
-                    let q_hat_mod_bskij = q_hat_mod_bsk[i][j];
-                    m_vectors[num_q + j][k].mod_add_fast_eq(
-                        ximtilde_q_hat_mod_qi[i * n + k].mod_mul_fast(q_hat_mod_bskij, moduli_bsk[j], mu[j]),
-                        moduli_bsk[j],
-                    );
-                }
-            }
-        }
-    }
-    let mtilde = 1 << 16;
-    let mtilde_half = mtilde >> 1;
-    let mtilde_minus_1 = mtilde - 1;
-    let mut result_mtilde = vec![0u64; n];
-    #pragma omp parallel for
-    for k in 0..n {
-        for i in 0..num_q {
-            result_mtilde[k] += ximtilde_q_hat_mod_qi[i * n + k].convert_to_int() * q_hat_mod_mtilde[i];
-        }
-        result_mtilde[k] &= mtilde_minus_1;
-    }
-    #pragma omp parallel for
-    for k in 0..n {
-        result_mtilde[k] *= neg_q_inv_mod_mtilde;
-        result_mtilde[k] &= mtilde_minus_1;
-    }
-    for i in 0..num_bsk {
-        let current_q_mod_bski = q_mod_bsk[i];
-        let current_q_mod_bski_precon = q_mod_bsk_precon[i];
-        #pragma omp parallel for
-        for k in 0..n {
-            let mut r_m_tilde = NativeInteger::default();
-            if result_mtilde[k] >= mtilde_half {
-                r_m_tilde += moduli_bsk[i] - mtilde;
-            }
-            r_m_tilde.mod_mul_fast_const_eq(current_q_mod_bski, moduli_bsk[i], current_q_mod_bski_precon);
-            r_m_tilde.mod_add_fast_eq(m_vectors[num_q + i][k], moduli_bsk[i]);
-            m_vectors[num_q + i][k] = r_m_tilde.mod_mul_fast_const(mtilde_inv_mod_bsk[i], moduli_bsk[i], mtilde_inv_mod_bsk_precon[i]);
-        }
-    }
-    if poly_in_ntt.len() > 0 {
-        for i in 0..num_q {
-            m_vectors[i] = poly_in_ntt[i];
-        }
-    } else {
-        #pragma omp parallel for num_threads(OpenFHEParallelControls.GetThreadLimit(num_q))
-        for i in 0..num_q {
-            m_vectors[i].switch_format();
-        }
-    }
-    #pragma omp parallel for num_threads(OpenFHEParallelControls.GetThreadLimit(num_bsk))
-    for i in 0..num_bsk {
-        m_vectors[num_q + i].switch_format();
-    }
-    m_format = EVALUATION;
-}
-
-
-use std::sync::Arc;
-use std::vec::Vec;
-
-impl<VecType> DCRTPolyImpl<VecType> {
-    fn fast_rns_floorq(
-        &mut self,
-        t: &NativeInteger,
-        moduli_q: &Vec<NativeInteger>,
-        moduli_bsk: &Vec<NativeInteger>,
-        modbsk_barrett_mu: &Vec<DoubleNativeInt>,
-        t_qhat_inv_modq: &Vec<NativeInteger>,
-        t_qhat_inv_modq_precon: &Vec<NativeInteger>,
-        qhat_modbsk: &Vec<Vec<NativeInteger>>,
-        q_inv_modbsk: &Vec<Vec<NativeInteger>>,
-        t_q_inv_modbsk: &Vec<NativeInteger>,
-        t_q_inv_modbsk_precon: &Vec<NativeInteger>,
-    ) {
-        let num_q = moduli_q.len();
-        let num_bsk = moduli_bsk.len();
-        let n = self.get_length();
-
-        let mut txiqi_divq_modqi = vec![NativeInteger::default(); n * num_bsk];
-        for i in 0..num_q {
-            let current_tq_divqi_modqi = &t_qhat_inv_modq[i];
-            let current_tq_divqi_modqi_precon = &t_qhat_inv_modq_precon[i];
-            for k in 0..n {
-                self.m_vectors[i][k].mod_mul_fast_const_eq(
-                    current_tq_divqi_modqi,
-                    &moduli_q[i],
-                    current_tq_divqi_modqi_precon,
-                );
-            }
-        }
-
-        #[cfg(all(feature = "HAVE_INT128", NATIVEINT = "64"))]
-        {
-            for j in 0..num_bsk {
-                for k in 0..n {
-                    let mut aq = 0;
-                    for i in 0..num_q {
-                        let inv_qi_mod_bj_value = &q_inv_modbsk[i][j];
-                        let xi = &self.m_vectors[i][k];
-                        aq += xi.convert_to_int() * inv_qi_mod_bj_value.convert_to_int();
-                    }
-                    txiqi_divq_modqi[j * n + k] = barrett_uint128_mod_uint64(
-                        aq,
-                        moduli_bsk[j].convert_to_int(),
-                        modbsk_barrett_mu[j],
-                    );
-                }
-            }
-
-            for i in 0..num_bsk {
-                let current_t_divq_mod_bski = &t_q_inv_modbsk[i];
-                let current_t_divq_mod_bski_precon = &t_q_inv_modbsk_precon[i];
-                for k in 0..n {
-                    self.m_vectors[i + num_q][k].mod_mul_fast_const_eq(
-                        current_t_divq_mod_bski,
-                        &moduli_bsk[i],
-                        current_t_divq_mod_bski_precon,
-                    );
-                    self.m_vectors[i + num_q][k].mod_sub_fast_eq(
-                        &txiqi_divq_modqi[i * n + k],
-                        &moduli_bsk[i],
-                    );
-                }
-            }
-        }
-        #[cfg(not(all(feature = "HAVE_INT128", NATIVEINT = "64")))]
-        {
-            let mut mu = vec![NativeInteger::default(); num_bsk];
-            for j in 0..num_bsk {
-                mu[j] = moduli_bsk[j].compute_mu();
-            }
-            for j in 0..num_bsk {
-                for k in 0..n {
-                    for i in 0..num_q {
-                        let inv_qi_mod_bj_value = &q_inv_modbsk[i][j];
-                        let xi = &self.m_vectors[i][k];
-                        txiqi_divq_modqi[j * n + k].mod_add_fast_eq(
-                            &xi.mod_mul_fast(inv_qi_mod_bj_value, &moduli_bsk[j], &mu[j]),
-                            &moduli_bsk[j],
-                        );
-                    }
-                }
-            }
-
-            for i in 0..num_bsk {
-                let current_t_divq_mod_bski = &t_q_inv_modbsk[i];
-                let current_t_divq_mod_bski_precon = &t_q_inv_modbsk_precon[i];
-                for k in 0..n {
-                    self.m_vectors[i + num_q][k].mod_mul_fast_const_eq(
-                        current_t_divq_mod_bski,
-                        &moduli_bsk[i],
-                        current_t_divq_mod_bski_precon,
-                    );
-                    self.m_vectors[i + num_q][k].mod_sub_fast_eq(
-                        &txiqi_divq_modqi[i * n + k],
-                        &moduli_bsk[i],
-                    );
-                }
-            }
-        }
-
-        txiqi_divq_modqi.clear();
-    }
-
-    fn fast_base_conv_sk(
-        &mut self,
-        params_q: &Arc<Params>,
-        modq_barrett_mu: &Vec<DoubleNativeInt>,
-        moduli_bsk: &Vec<NativeInteger>,
-        modbsk_barrett_mu: &Vec<DoubleNativeInt>,
-        b_hat_inv_modb: &Vec<NativeInteger>,
-        b_hat_inv_modb_precon: &Vec<NativeInteger>,
-        b_hat_modmsk: &Vec<NativeInteger>,
-        b_inv_modmsk: &NativeInteger,
-        b_inv_modmsk_precon: &NativeInteger,
-        b_hat_modq: &Vec<Vec<NativeInteger>>,
-        b_modq: &Vec<NativeInteger>,
-        b_modq_precon: &Vec<NativeInteger>,
-    ) {
-        #[cfg(all(feature = "HAVE_INT128", NATIVEINT = "64"))]
-        {
-            self.m_params = params_q.clone();
-            let size_q = params_q.get_params().len();
-            let mut moduli_q = vec![NativeInteger::default(); size_q];
-            for i in 0..size_q {
-                moduli_q[i] = params_q.get_params()[i].get_modulus();
-            }
-
-            let size_bsk = moduli_bsk.len();
-            let n = self.get_length();
-            for i in 0..size_bsk - 1 {
-                let current_b_div_bi_mod_bi = &b_hat_inv_modb[i];
-                let current_b_div_bi_mod_bi_precon = &b_hat_inv_modb_precon[i];
-                for k in 0..n {
-                    self.m_vectors[size_q + i][k].mod_mul_fast_const_eq(
-                        current_b_div_bi_mod_bi,
-                        &moduli_bsk[i],
-                        current_b_div_bi_mod_bi_precon,
-                    );
-                }
-            }
-            for j in 0..size_q {
-                for k in 0..n {
-                    let mut result = 0;
-                    for i in 0..size_bsk - 1 {
-                        let current_b_div_bi_mod_qj = &b_hat_modq[i][j];
-                        let xi = &self.m_vectors[size_q + i][k];
-                        result += xi.convert_to_int() * current_b_div_bi_mod_qj.convert_to_int();
-                    }
-                    self.m_vectors[j][k] = barrett_uint128_mod_uint64(
-                        result,
-                        moduli_q[j].convert_to_int(),
-                        modq_barrett_mu[j],
-                    );
-                }
-            }
-
-            let mut alphaskx_vector = vec![NativeInteger::default(); n];
-            for k in 0..n {
-                let mut result = 0;
-                for i in 0..size_bsk - 1 {
-                    let current_b_div_bi_mod_msk = &b_hat_modmsk[i];
-                    result += self.m_vectors[size_q + i][k].convert_to_int()
-                        * current_b_div_bi_mod_msk.convert_to_int();
-                }
-                alphaskx_vector[k] = barrett_uint128_mod_uint64(
-                    result,
-                    moduli_bsk[size_bsk - 1].convert_to_int(),
-                    modbsk_barrett_mu[size_bsk - 1],
-                );
-            }
-
-            for k in 0..n {
-                alphaskx_vector[k] = alphaskx_vector[k].mod_sub_fast(
-                    &self.m_vectors[size_q + size_bsk - 1][k],
-                    &moduli_bsk[size_bsk - 1],
-                );
-                alphaskx_vector[k].mod_mul_fast_const_eq(
-                    b_inv_modmsk,
-                    &moduli_bsk[size_bsk - 1],
-                    b_inv_modmsk_precon,
-                );
-            }
-
-            let msk_div_two = moduli_bsk[size_bsk - 1] / 2;
-            for i in 0..size_q {
-                let current_b_mod_qi = &b_modq[i];
-                let current_b_mod_qi_precon = &b_modq_precon[i];
-                for k in 0..n {
-                    let mut alphask_b_mod_qi = alphaskx_vector[k];
-                    if alphask_b_mod_qi > msk_div_two {
-                        alphask_b_mod_qi = alphask_b_mod_qi.mod_sub_fast(
-                            &moduli_bsk[size_bsk - 1],
-                            &moduli_q[i],
-                        );
-                    }
-                    alphask_b_mod_qi.mod_mul_fast_const_eq(
-                        current_b_mod_qi,
-                        &moduli_q[i],
-                        current_b_mod_qi_precon,
-                    );
-                    self.m_vectors[i][k].mod_sub_fast_eq(&alphask_b_mod_qi, &moduli_q[i]);
-                }
-            }
-
-            if size_q < self.m_vectors.len() {
-                let start_i = size_q;
-                if start_i + size_bsk >= self.m_vectors.len() {
-                    self.m_vectors.drain(start_i..);
-                } else {
-                    self.m_vectors.drain(start_i..start_i + size_bsk);
-                }
-            }
-            alphaskx_vector.clear();
-        }
-        #[cfg(not(all(feature = "HAVE_INT128", NATIVEINT = "64")))]
-        {
-            self.m_params = params_q.clone();
-            let size_q = params_q.get_params().len();
-            let mut moduli_q = vec![NativeInteger::default(); size_q];
-            for i in 0..size_q {
-                moduli_q[i] = params_q.get_params()[i].get_modulus();
-            }
-
-            let size_bsk = moduli_bsk.len();
-            let n = self.get_length();
-            for i in 0..size_bsk - 1 {
-                let current_b_div_bi_mod_bi = &b_hat_inv_modb[i];
-                let current_b_div_bi_mod_bi_precon = &b_hat_inv_modb_precon[i];
-                for k in 0..n {
-                    self.m_vectors[size_q + i][k].mod_mul_fast_const_eq(
-                        current_b_div_bi_mod_bi,
-                        &moduli_bsk[i],
-                        current_b_div_bi_mod_bi_precon,
-                    );
-                }
-            }
-            let mut mu = vec![NativeInteger::default(); size_q];
-            for j in 0..size_q {
-                mu[j] = moduli_q[j].compute_mu();
-            }
-            for j in 0..size_q {
-                for k in 0..n {
-                    self.m_vectors[j][k] = NativeInteger::default();
-                    for i in 0..size_bsk - 1 {
-                        let current_b_div_bi_mod_qj = &b_hat_modq[i][j];
-                        let xi = &self.m_vectors[size_q + i][k];
-                        self.m_vectors[j][k].mod_add_fast_eq(
-                            &xi.mod_mul_fast(current_b_div_bi_mod_qj, &moduli_q[j], &mu[j]),
-                            &moduli_q[j],
-                        );
-                    }
-                }
-            }
-            let mu_bsk = moduli_bsk[size_bsk - 1].compute_mu();
-
-            let mut alphaskx_vector = vec![NativeInteger::default(); n];
-            for k in 0..n {
-                for i in 0..size_bsk - 1 {
-                    let current_b_div_bi_mod_msk = &b_hat_modmsk[i];
-                    alphaskx_vector[k].mod_add_eq(
-                        &self.m_vectors[size_q + i][k].mod_mul(
-                            current_b_div_bi_mod_msk,
-                            &moduli_bsk[size_bsk - 1],
-                            &mu_bsk,
-                        ),
-                        &moduli_bsk[size_bsk - 1],
-                    );
-                }
-            }
-
-            for k in 0..n {
-                alphaskx_vector[k] = alphaskx_vector[k].mod_sub_fast(
-                    &self.m_vectors[size_q + size_bsk - 1][k],
-                    &moduli_bsk[size_bsk - 1],
-                );
-                alphaskx_vector[k].mod_mul_fast_const_eq(
-                    b_inv_modmsk,
-                    &moduli_bsk[size_bsk - 1],
-                    b_inv_modmsk_precon,
-                );
-            }
-
-            let msk_div_two = moduli_bsk[size_bsk - 1] / 2;
-            for i in 0..size_q {
-                let current_b_mod_qi = &b_modq[i];
-                let current_b_mod_qi_precon = &b_modq_precon[i];
-                for k in 0..n {
-                    let mut alphask_b_mod_qi = alphaskx_vector[k];
-                    if alphask_b_mod_qi > msk_div_two {
-                        alphask_b_mod_qi = alphask_b_mod_qi.mod_sub_fast(
-                            &moduli_bsk[size_bsk - 1],
-                            &moduli_q[i],
-                        );
-                    }
-                    alphask_b_mod_qi.mod_mul_fast_const_eq(
-                        current_b_mod_qi,
-                        &moduli_q[i],
-                        current_b_mod_qi_precon,
-                    );
-                    self.m_vectors[i][k].mod_sub_fast_eq(&alphask_b_mod_qi, &moduli_q[i]);
-                }
-            }
-
-            if size_q < self.m_vectors.len() {
-                let start_i = size_q;
-                if start_i + size_bsk >= self.m_vectors.len() {
-                    self.m_vectors.drain(start_i..);
-                } else {
-                    self.m_vectors.drain(start_i..start_i + size_bsk);
-                }
-            }
-            alphaskx_vector.clear();
-        }
-    }
-}
-
-
@@ -1,468 +1,168 @@
-use std::cmp::PartialEq;
-use std::convert::From;
-use std::fmt::{self, Debug, Display};
-use std::ops::{Add, Div, Mul, Sub};
-use std::str::FromStr;
-use std::string::ToString;
-
-#[derive(Clone, PartialEq)]
-struct BasicInteger {
-    value: i64,
-}
-
-impl BasicInteger {
-    fn new(value: i64) -> Self {
-        BasicInteger { value }
-    }
-}
-
-impl From<i64> for BasicInteger {
-    fn from(value: i64) -> Self {
-        BasicInteger { value }
-    }
-}
-
-impl FromStr for BasicInteger {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.parse::<i64>() {
-            Ok(value) => Ok(BasicInteger { value }),
-            Err(_) => Err(()),
-        }
-    }
-}
-
-impl Display for BasicInteger {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.value)
-    }
-}
-
-impl Debug for BasicInteger {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.value)
-    }
-}
-
-impl Add for BasicInteger {
-    type Output = BasicInteger;
-
-    fn add(self, other: BasicInteger) -> BasicInteger {
-        BasicInteger::new(self.value + other.value)
-    }
-}
-
-impl Sub for BasicInteger {
-    type Output = BasicInteger;
-
-    fn sub(self, other: BasicInteger) -> BasicInteger {
-        BasicInteger::new(self.value - other.value)
-    }
-}
-
-impl Mul for BasicInteger {
-    type Output = BasicInteger;
-
-    fn mul(self, other: BasicInteger) -> BasicInteger {
-        BasicInteger::new(self.value * other.value)
-    }
-}
-
-impl Div for BasicInteger {
-    type Output = BasicInteger;
-
-    fn div(self, other: BasicInteger) -> BasicInteger {
-        BasicInteger::new(self.value / other.value)
-    }
-}
-
-#[derive(Clone, PartialEq)]
-struct NativeInteger {
-    value: i64,
-}
-
-impl NativeInteger {
-    fn new(value: i64) -> Self {
-        NativeInteger { value }
-    }
-}
-
-impl From<i64> for NativeInteger {
-    fn from(value: i64) -> Self {
-        NativeInteger { value }
-    }
-}
-
-impl FromStr for NativeInteger {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.parse::<i64>() {
-            Ok(value) => Ok(NativeInteger { value }),
-            Err(_) => Err(()),
-        }
-    }
-}
-
-impl Display for NativeInteger {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.value)
-    }
-}
-
-impl Debug for NativeInteger {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.value)
-    }
-}
-
-impl Add for NativeInteger {
-    type Output = NativeInteger;
-
-    fn add(self, other: NativeInteger) -> NativeInteger {
-        NativeInteger::new(self.value + other.value)
-    }
-}
-
-impl Sub for NativeInteger {
-    type Output = NativeInteger;
-
-    fn sub(self, other: NativeInteger) -> NativeInteger {
-        NativeInteger::new(self.value - other.value)
-    }
-}
-
-impl Mul for NativeInteger {
-    type Output = NativeInteger;
-
-    fn mul(self, other: NativeInteger) -> NativeInteger {
-        NativeInteger::new(self.value * other.value)
-    }
-}
-
-impl Div for NativeInteger {
-    type Output = NativeInteger;
-
-    fn div(self, other: NativeInteger) -> NativeInteger {
-        NativeInteger::new(self.value / other.value)
-    }
-}
-
-#[derive(Clone, PartialEq)]
-struct ILParamsImpl<T> {
-    corder: u32,
-    modulus: T,
-    root_of_unity: T,
-}
-
-impl<T> ILParamsImpl<T> {
-    fn new(corder: u32, modulus: T, root_of_unity: T) -> Self {
-        ILParamsImpl {
-            corder,
-            modulus,
-            root_of_unity,
+/*
+  This file contains the parameters of double-CRT lattice elements: a chain of native ring
+  parameters with pairwise coprime moduli and the CRT tables of their product
+*/
+use super::ilparams::ILParamsImpl;
+use crate::core::math::hal::integer::{BigIntegerInterface, NativeInteger};
+use crate::core::math::nbtheory::get_totient;
+use crate::core::math::nbtheory_impl::previous_prime;
+use crate::core::math::rnsbase::RnsBaseT;
+use std::fmt;
+use std::sync::Arc;
+
+/// Параметры кольца Z_Q[x]/Φ_m(x), Q = q_0 ... q_(k-1), в представлении
+/// двойного CRT (аналог ILDCRTParams в OpenFHE).
+///
+/// Каждой башне соответствуют машинные параметры ILParamsImpl<NativeInteger>
+/// с модулем q_i и своим корнем из единицы. Таблицы CRT для восстановления
+/// коэффициентов по модулю Q считаются один раз при построении параметров;
+/// параметры разделяются между многочленами через Arc. Равенство параметров
+/// определяется порядком и параметрами башен.
+#[derive(Clone, Debug)]
+pub struct ILDCRTParams<IntType: BigIntegerInterface> {
+    cyclotomic_order: u32,
+    ring_dimension: u32,
+    params: Vec<Arc<ILParamsImpl<NativeInteger>>>,
+    crt_base: RnsBaseT<IntType>,
+}
+
+impl<IntType: BigIntegerInterface> ILDCRTParams<IntType> {
+    /// Цепочка из depth наибольших простых модулей разрядности bits,
+    /// q_i = 1 mod 2m (mod m для степени двойки), по убыванию
+    pub fn new(order: u32, depth: usize, bits: u32) -> Self {
+        if depth == 0 {
+            panic!("ILDCRTParams: the chain must contain at least one modulus");
         }
-    }
-}
-
-impl<T: Display> Display for ILParamsImpl<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "corder: {}, modulus: {}, root_of_unity: {}",
-            self.corder, self.modulus, self.root_of_unity
-        )
-    }
-}
-
-impl<T: Debug> Debug for ILParamsImpl<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "corder: {:?}, modulus: {:?}, root_of_unity: {:?}",
-            self.corder, self.modulus, self.root_of_unity
-        )
-    }
-}
-
-#[derive(Clone, PartialEq)]
-struct ElemParams<T> {
-    corder: u32,
-    modulus: T,
-    ciphertext_modulus: T,
-}
-
-impl<T> ElemParams<T> {
-    fn new(corder: u32, modulus: T) -> Self {
-        ElemParams {
-            corder,
-            modulus,
-            ciphertext_modulus: modulus.clone(),
+        let root_order = ILParamsImpl::<NativeInteger>::root_order(order);
+        let mut moduli = vec![*ILParamsImpl::<NativeInteger>::new(order, bits).get_modulus()];
+        while moduli.len() < depth {
+            moduli.push(previous_prime(moduli.last().unwrap(), root_order));
         }
+        Self::with_moduli(order, &moduli)
     }
-}
-
-impl<T: Display> Display for ElemParams<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "corder: {}, modulus: {}, ciphertext_modulus: {}",
-            self.corder, self.modulus, self.ciphertext_modulus
-        )
-    }
-}
 
-impl<T: Debug> Debug for ElemParams<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "corder: {:?}, modulus: {:?}, ciphertext_modulus: {:?}",
-            self.corder, self.modulus, self.ciphertext_modulus
-        )
+    /// Цепочка с заданными модулями и наименьшими корнями из единицы
+    pub fn with_moduli(order: u32, moduli: &[NativeInteger]) -> Self {
+        let params = moduli
+            .iter()
+            .map(|q| Arc::new(ILParamsImpl::with_modulus(order, *q)))
+            .collect();
+        Self::with_params(order, params)
     }
-}
-
-#[derive(Clone, PartialEq)]
-struct ILDCRTParams<T> {
-    corder: u32,
-    original_modulus: T,
-    params: Vec<ILParamsImpl<T>>,
-}
 
-impl<T> ILDCRTParams<T> {
-    fn new(corder: u32, modulus: T, root_of_unity: T) -> Self {
-        let params = vec![ILParamsImpl::new(corder, modulus, root_of_unity)];
-        let composite_modulus = modulus.clone();
-        let ciphertext_modulus = composite_modulus.clone();
-        ElemParams {
-            corder,
-            modulus,
-            ciphertext_modulus,
-        };
-        ILDCRTParams {
-            corder,
-            original_modulus: modulus,
-            params,
+    /// Цепочка из готовых параметров башен с одинаковым порядком order
+    pub fn with_params(order: u32, params: Vec<Arc<ILParamsImpl<NativeInteger>>>) -> Self {
+        if params.iter().any(|p| p.get_cyclotomic_order() != order) {
+            panic!(
+                "ILDCRTParams: all towers must have the cyclotomic order {}",
+                order
+            );
         }
-    }
-
-    fn new_with_depth(&self, corder: u32, depth: u32, bits: u32) -> Self {
-        let params = vec![ILParamsImpl::new(corder, self.modulus, self.root_of_unity)];
-        let composite_modulus = self.modulus.clone();
-        let ciphertext_modulus = composite_modulus.clone();
-        ElemParams {
-            corder,
-            self.modulus,
-            ciphertext_modulus,
-        };
-        ILDCRTParams {
-            corder,
-            original_modulus: self.modulus,
+        let moduli: Vec<NativeInteger> = params.iter().map(|p| *p.get_modulus()).collect();
+        Self {
+            cyclotomic_order: order,
+            ring_dimension: get_totient(order as u64) as u32,
             params,
+            crt_base: RnsBaseT::new(moduli),
         }
     }
 
-    fn new_with_moduli(
-        &self,
-        corder: u32,
-        moduli: Vec<T>,
-        roots_of_unity: Vec<T>,
-    ) -> Self {
-        let limbs = moduli.len();
-        let params = Vec::with_capacity(limbs);
-        let composite_modulus = self.modulus.clone();
-        let ciphertext_modulus = composite_modulus.clone();
-        ElemParams {
-            corder,
-            self.modulus,
-            ciphertext_modulus,
-        };
-        ILDCRTParams {
-            corder,
-            original_modulus: self.modulus,
-            params,
-        }
+    pub fn get_cyclotomic_order(&self) -> u32 {
+        self.cyclotomic_order
     }
 
-    fn new_with_moduli_big(
-        &self,
-        corder: u32,
-        moduli: Vec<T>,
-        roots_of_unity: Vec<T>,
-        moduli_big: Vec<T>,
-        roots_of_unity_big: Vec<T>,
-        input_original_modulus: T,
-    ) -> Self {
-        let limbs = moduli.len();
-        let params = Vec::with_capacity(limbs);
-        let composite_modulus = self.modulus.clone();
-        let ciphertext_modulus = composite_modulus.clone();
-        ElemParams {
-            corder,
-            self.modulus,
-            ciphertext_modulus,
-        };
-        ILDCRTParams {
-            corder,
-            original_modulus: self.modulus,
-            params,
-        }
+    pub fn get_ring_dimension(&self) -> u32 {
+        self.ring_dimension
     }
 
-    fn new_with_moduli_input_original_modulus(
-        &self,
-        corder: u32,
-        moduli: Vec<T>,
-        input_original_modulus: T,
-    ) -> Self {
-        let limbs = moduli.len();
-        let params = Vec::with_capacity(limbs);
-        let composite_modulus = self.modulus.clone();
-        let ciphertext_modulus = composite_modulus.clone();
-        ElemParams {
-            corder,
-            self.modulus,
-            ciphertext_modulus,
-        };
-        ILDCRTParams {
-            corder,
-            original_modulus: self.modulus,
-            params,
-        }
+    pub fn get_params(&self) -> &[Arc<ILParamsImpl<NativeInteger>>] {
+        &self.params
     }
 
-    fn new_with_params_input_original_modulus(
+    /// Параметры башен с номерами start..=end
+    pub fn get_param_partition(
         &self,
-        corder: u32,
-        params: Vec<ILParamsImpl<T>>,
-        input_original_modulus: T,
-    ) -> Self {
-        let limbs = params.len();
-        let composite_modulus = self.modulus.clone();
-        let ciphertext_modulus = composite_modulus.clone();
-        ElemParams {
-            corder,
-            self.modulus,
-            ciphertext_modulus,
-        };
-        ILDCRTParams {
-            corder,
-            original_modulus: self.modulus,
-            params,
+        start: usize,
+        end: usize,
+    ) -> Vec<Arc<ILParamsImpl<NativeInteger>>> {
+        if start > end || end >= self.params.len() {
+            panic!("ILDCRTParams: incorrect tower range {}..={}", start, end);
         }
+        self.params[start..=end].to_vec()
     }
 
-    fn get_params(&self) -> &Vec<ILParamsImpl<T>> {
-        &self.params
+    /// Q - произведение модулей башен
+    pub fn get_modulus(&self) -> &IntType {
+        self.crt_base.get_modulus()
     }
 
-    fn get_param_partition(&self, start: u32, end: u32) -> Vec<ILParamsImpl<T>> {
-        self.params[start as usize..=end as usize].to_vec()
+    pub fn get_moduli(&self) -> &[NativeInteger] {
+        self.crt_base.get_moduli()
     }
 
-    fn get_original_modulus(&self) -> &T {
-        &self.original_modulus
+    /// Таблицы CRT модулей башен
+    pub fn get_crt_base(&self) -> &RnsBaseT<IntType> {
+        &self.crt_base
     }
 
-    fn set_original_modulus(&mut self, input_original_modulus: T) {
-        self.original_modulus = input_original_modulus;
+    /// Число башен
+    pub fn len(&self) -> usize {
+        self.params.len()
     }
 
-    fn pop_last_param(&mut self) {
-        self.ciphertext_modulus /= self.params.last().unwrap().modulus.clone();
-        self.params.pop();
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
     }
 
-    fn pop_first_param(&mut self) {
-        self.ciphertext_modulus /= self.params[0].modulus.clone();
-        self.params.remove(0);
-    }
-}
-
-impl<T: Display> Display for ILDCRTParams<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "corder: {}, original_modulus: {}, params: {:?}",
-            self.corder, self.original_modulus, self.params
-        )
-    }
-}
-
-impl<T: Debug> Debug for ILDCRTParams<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "corder: {:?}, original_modulus: {:?}, params: {:?}",
-            self.corder, self.original_modulus, self.params
-        )
-    }
-}
-
-impl<T: PartialEq> PartialEq<ElemParams<T>> for ILDCRTParams<T> {
-    fn eq(&self, other: &ElemParams<T>) -> bool {
-        if let Some(dcrt_params) = other.downcast_ref::<ILDCRTParams<T>>() {
-            if self.corder != dcrt_params.corder {
-                return false;
-            }
-            if self.params.len() != dcrt_params.params.len() {
-                return false;
-            }
-            for i in 0..self.params.len() {
-                if self.params[i] != dcrt_params.params[i] {
-                    return false;
-                }
-            }
-            return self.original_modulus == dcrt_params.original_modulus;
+    /// Параметры без последней башни
+    pub fn pop_last_param(&self) -> Self {
+        let end = self.params.len() - 1;
+        if end == 0 {
+            panic!("ILDCRTParams: cannot drop the last remaining tower");
         }
-        false
+        Self::with_params(self.cyclotomic_order, self.params[..end].to_vec())
     }
-}
 
-impl<T> ILDCRTParams<T> {
-    fn recalculate_modulus(&mut self) {
-        self.ciphertext_modulus = T::new(1);
-        for i in 0..self.params.len() {
-            self.ciphertext_modulus *= self.params[i].modulus.clone();
+    /// Параметры без первой башни
+    pub fn pop_first_param(&self) -> Self {
+        if self.params.len() < 2 {
+            panic!("ILDCRTParams: cannot drop the last remaining tower");
         }
+        Self::with_params(self.cyclotomic_order, self.params[1..].to_vec())
     }
 
-    fn recalculate_big_modulus(&mut self) {
-        self.big_ciphertext_modulus = T::new(1);
-        for i in 0..self.params.len() {
-            self.big_ciphertext_modulus *= self.params[i].big_modulus.clone();
-        }
+    /// Параметры, в которых башня index заменена на tower
+    pub fn with_tower_at_index(
+        &self,
+        index: usize,
+        tower: Arc<ILParamsImpl<NativeInteger>>,
+    ) -> Self {
+        let mut params = self.params.clone();
+        params[index] = tower;
+        Self::with_params(self.cyclotomic_order, params)
     }
 }
 
-impl<T: Display> Display for ILDCRTParams<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "corder: {}, original_modulus: {}, params: {:?}",
-            self.corder, self.original_modulus, self.params
-        )
+impl<IntType: BigIntegerInterface> PartialEq for ILDCRTParams<IntType> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cyclotomic_order == other.cyclotomic_order && self.params == other.params
     }
 }
 
-impl<T: Debug> Debug for ILDCRTParams<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl<IntType: BigIntegerInterface> Eq for ILDCRTParams<IntType> {}
+
+impl<IntType: BigIntegerInterface> fmt::Display for ILDCRTParams<IntType> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "corder: {:?}, original_modulus: {:?}, params: {:?}",
-            self.corder, self.original_modulus, self.params
-        )
+            "[m={} n={} q={}",
+            self.cyclotomic_order,
+            self.ring_dimension,
+            self.get_modulus()
+        )?;
+        for params in &self.params {
+            write!(f, " {}", params)?;
+        }
+        write!(f, "]")
     }
 }
-
-// fn main() {
-//     let il_params = ILParamsImpl::new(0, BasicInteger::new(0), BasicInteger::new(0));
-//     let elem_params = ElemParams::new(0, BasicInteger::new(0));
-//     let ildcrt_params = ILDCRTParams::new(0, BasicInteger::new(0), BasicInteger::new(0));
-//     println!("ILParamsImpl: {}", il_params);
-//     println!("ElemParams: {}", elem_params);
-//     println!("ILDCRTParams: {}", ildcrt_params);
-// }
-
-
@@ -1,93 +1,120 @@
+/*
+  This file contains the parameters of integer lattice elements: the cyclotomic order, the
+  ciphertext modulus and the roots of unity used by the CRT transforms
+*/
+use crate::core::math::hal::integer::IntegerInterface;
+use crate::core::math::nbtheory::get_totient;
+use crate::core::math::nbtheory_impl::{last_prime, root_of_unity};
 use std::fmt;
-use std::marker::PhantomData;
-
-// Assuming the existence of similar functionality in Rust for the C++ code functionality
-// such as LastPrime, RootOfUnity, and other mathematical operations.
 
+/// Параметры кольца Z_q[x]/Φ_m(x) одного многочлена.
+///
+/// Для степени двойки m корень root_of_unity имеет порядок m: этого хватает
+/// отрицательно-циклическому NTT. Для остальных m преобразование Блюстейна
+/// вычисляет значения в root^(2k), поэтому корень имеет порядок 2m.
+/// big_modulus и big_root_of_unity хранятся для SwitchModulus и упаковки
+/// битов и равны нулю, если не заданы.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ILParamsImpl<IntType> {
-    order: u32,
+    cyclotomic_order: u32,
+    ring_dimension: u32,
     modulus: IntType,
     root_of_unity: IntType,
-    big_modulus: Option<IntType>,
-    big_root_of_unity: Option<IntType>,
-    _marker: PhantomData<IntType>,
+    big_modulus: IntType,
+    big_root_of_unity: IntType,
 }
 
-impl<IntType> ILParamsImpl<IntType> {
+impl<IntType: IntegerInterface> ILParamsImpl<IntType> {
+    /// Параметры с наибольшим простым модулем разрядности bits, q = 1 mod 2m
+    /// (mod m для степени двойки), и наименьшим корнем из единицы
     pub fn new(order: u32, bits: u32) -> Self {
-        // Placeholder for LastPrime and RootOfUnity function calls
-        let modulus = LastPrime::<IntType>(bits, order);
-        let root_of_unity = RootOfUnity::<IntType>(order, &modulus);
-        Self {
-            order,
-            modulus,
-            root_of_unity,
-            big_modulus: None,
-            big_root_of_unity: None,
-            _marker: PhantomData,
-        }
+        let modulus = last_prime(bits, Self::root_order(order));
+        Self::with_modulus(order, modulus)
     }
 
+    /// Параметры с заданным простым модулем и наименьшим корнем из единицы
     pub fn with_modulus(order: u32, modulus: IntType) -> Self {
-        let root_of_unity = RootOfUnity::<IntType>(order, &modulus);
-        Self {
+        let root = root_of_unity(Self::root_order(order), &modulus);
+        Self::with_root_of_unity(order, modulus, root)
+    }
+
+    pub fn with_root_of_unity(order: u32, modulus: IntType, root_of_unity: IntType) -> Self {
+        Self::with_big_modulus(
             order,
             modulus,
             root_of_unity,
-            big_modulus: None,
-            big_root_of_unity: None,
-            _marker: PhantomData,
-        }
+            IntType::zero(),
+            IntType::zero(),
+        )
     }
 
-    pub fn with_all(order: u32, modulus: IntType, root_of_unity: IntType, big_modulus: IntType, big_root_of_unity: IntType) -> Self {
+    pub fn with_big_modulus(
+        order: u32,
+        modulus: IntType,
+        root_of_unity: IntType,
+        big_modulus: IntType,
+        big_root_of_unity: IntType,
+    ) -> Self {
         Self {
-            order,
+            cyclotomic_order: order,
+            ring_dimension: get_totient(order as u64) as u32,
             modulus,
             root_of_unity,
-            big_modulus: Some(big_modulus),
-            big_root_of_unity: Some(big_root_of_unity),
-            _marker: PhantomData,
+            big_modulus,
+            big_root_of_unity,
         }
     }
-}
 
-impl<IntType> Clone for ILParamsImpl<IntType> where IntType: Clone {
-    fn clone(&self) -> Self {
-        Self {
-            order: self.order,
-            modulus: self.modulus.clone(),
-            root_of_unity: self.root_of_unity.clone(),
-            big_modulus: self.big_modulus.clone(),
-            big_root_of_unity: self.big_root_of_unity.clone(),
-            _marker: PhantomData,
-        }
+    pub fn get_cyclotomic_order(&self) -> u32 {
+        self.cyclotomic_order
     }
-}
 
-impl<IntType> PartialEq for ILParamsImpl<IntType> where IntType: PartialEq {
-    fn eq(&self, other: &Self) -> bool {
-        self.order == other.order &&
-        self.modulus == other.modulus &&
-        self.root_of_unity == other.root_of_unity &&
-        self.big_modulus == other.big_modulus &&
-        self.big_root_of_unity == other.big_root_of_unity
+    pub fn get_ring_dimension(&self) -> u32 {
+        self.ring_dimension
     }
-}
 
-impl<IntType> fmt::Display for ILParamsImpl<IntType> where IntType: fmt::Display {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "ILParams order: {}, modulus: {}, root_of_unity: {}", self.order, self.modulus, self.root_of_unity)?;
-        if let Some(ref big_modulus) = self.big_modulus {
-            write!(f, ", big_modulus: {}", big_modulus)?;
-        }
-        if let Some(ref big_root_of_unity) = self.big_root_of_unity {
-            write!(f, ", big_root_of_unity: {}", big_root_of_unity)?;
-        }
-        Ok(())
+    pub fn get_modulus(&self) -> &IntType {
+        &self.modulus
+    }
+
+    pub fn get_root_of_unity(&self) -> &IntType {
+        &self.root_of_unity
+    }
+
+    pub fn get_big_modulus(&self) -> &IntType {
+        &self.big_modulus
+    }
+
+    pub fn get_big_root_of_unity(&self) -> &IntType {
+        &self.big_root_of_unity
     }
-}
 
-// Placeholder for serialization/deserialization logic
+    /// Кольцо задано степенью двойки: x^n + 1, n = m / 2
+    pub fn is_power_of_two(&self) -> bool {
+        self.cyclotomic_order.is_power_of_two()
+    }
 
+    /// Порядок корня из единицы, который нужен преобразованию для порядка m
+    pub(super) fn root_order(order: u32) -> u64 {
+        if order.is_power_of_two() {
+            order as u64
+        } else {
+            2 * order as u64
+        }
+    }
+}
 
+impl<IntType: fmt::Display> fmt::Display for ILParamsImpl<IntType> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[m={} n={} q={} ru={} bigq={} bigru={}]",
+            self.cyclotomic_order,
+            self.ring_dimension,
+            self.modulus,
+            self.root_of_unity,
+            self.big_modulus,
+            self.big_root_of_unity
+        )
+    }
+}
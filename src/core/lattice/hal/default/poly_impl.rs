@@ -1,537 +1,653 @@
-use std::cmp::Ordering;
-use std::convert::TryInto;
-use std::fmt;
-use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign};
-use std::str::FromStr;
-use std::vec::Vec;
-
-#[derive(Clone, Debug, PartialEq, Eq)]
-struct Integer {
-    value: i64,
-}
-
-impl Integer {
-    fn new(value: i64) -> Self {
-        Integer { value }
+/*
+  This file contains the implementation of the polynomial over an integer lattice: construction from
+  the distribution generators, ring arithmetic, CRT format switching, automorphisms and digit
+  decomposition
+*/
+use super::ilparams::ILParamsImpl;
+use super::poly::{Format, PolyImpl};
+use crate::core::math::binaryuniformgenerator::BinaryUniformGeneratorImpl;
+use crate::core::math::discretegaussiangenerator::DiscreteGaussianGenerator;
+use crate::core::math::discreteuniformgenerator::DiscreteUniformGeneratorImpl;
+use crate::core::math::distributiongenerator::{with_prng, PRNG};
+use crate::core::math::hal::integer::{BigIntegerInterface, IntegerInterface};
+use crate::core::math::hal::transform::TransformInterface;
+use crate::core::math::hal::vector::BigVectorInterface;
+use crate::core::math::nbtheory::{get_totient_list, precompute_auto_map};
+use crate::core::math::ternaryuniformgenerator::TernaryUniformGenerator;
+use fhe_core::lattice::hal::element::ILElement;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::sync::Arc;
+
+type Params<V> = Arc<ILParamsImpl<<V as BigVectorInterface>::Integer>>;
+
+/* Конструкторы */
+impl<V: TransformInterface> PolyImpl<V> {
+    /// Многочлен с параметрами params; без initialize_element_to_zero значения
+    /// не выделяются
+    pub fn new(params: Params<V>, format: Format, initialize_element_to_zero: bool) -> Self {
+        let values = initialize_element_to_zero.then(|| {
+            V::new(
+                params.get_ring_dimension() as usize,
+                params.get_modulus().clone(),
+            )
+        });
+        Self {
+            format,
+            params,
+            values,
+        }
     }
-}
 
-impl From<i64> for Integer {
-    fn from(value: i64) -> Self {
-        Integer::new(value)
+    /// Многочлен с готовыми значениями; длина и модуль values должны совпадать
+    /// с параметрами
+    pub fn with_values(params: Params<V>, format: Format, values: V) -> Self {
+        if values.get_length() != params.get_ring_dimension() as usize {
+            panic!("PolyImpl: the vector length does not match the ring dimension");
+        }
+        if values.get_modulus() != params.get_modulus() {
+            panic!("PolyImpl: the vector modulus does not match the parameters");
+        }
+        Self {
+            format,
+            params,
+            values: Some(values),
+        }
     }
-}
 
-impl From<Integer> for i64 {
-    fn from(integer: Integer) -> Self {
-        integer.value
+    /// Многочлен с гауссовскими коэффициентами; шум выбирается в COEFFICIENT и
+    /// при необходимости переводится в format
+    pub fn from_dgg(
+        dgg: &DiscreteGaussianGenerator<V>,
+        params: Params<V>,
+        format: Format,
+        prng: &mut PRNG,
+    ) -> Self {
+        let noise = dgg.generate_int_vector(params.get_ring_dimension(), prng);
+        Self::from_signed(&noise, params, format)
+    }
+
+    /// Многочлен с равномерно распределёнными значениями; равномерное
+    /// распределение одинаково в обоих форматах, поэтому формат не переключается
+    pub fn from_dug(
+        dug: &mut DiscreteUniformGeneratorImpl<V>,
+        params: Params<V>,
+        format: Format,
+        prng: &mut PRNG,
+    ) -> Self {
+        dug.set_modulus(params.get_modulus().clone());
+        let values = dug.generate_vector(params.get_ring_dimension() as usize, prng);
+        Self::with_values(params, format, values)
+    }
+
+    /// Многочлен с коэффициентами из {0, 1}
+    pub fn from_bug(
+        bug: &BinaryUniformGeneratorImpl<V>,
+        params: Params<V>,
+        format: Format,
+        prng: &mut PRNG,
+    ) -> Self {
+        let values = bug.generate_vector(
+            params.get_ring_dimension() as usize,
+            params.get_modulus(),
+            prng,
+        );
+        let mut result = Self::with_values(params, Format::COEFFICIENT, values);
+        result.set_format(format);
+        result
     }
-}
 
-impl fmt::Display for Integer {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.value)
+    /// Многочлен с коэффициентами из {-1, 0, 1}; при h > 0 ровно h коэффициентов
+    /// отличны от нуля
+    pub fn from_tug(params: Params<V>, format: Format, h: usize, prng: &mut PRNG) -> Self {
+        let values = TernaryUniformGenerator::generate_int_vector(
+            params.get_ring_dimension() as usize,
+            h,
+            prng,
+        );
+        Self::from_signed(&values, params, format)
     }
-}
-
-impl Add for Integer {
-    type Output = Integer;
 
-    fn add(self, other: Integer) -> Integer {
-        Integer::new(self.value + other.value)
+    /// Многочлен с коэффициентами values, которые приводятся по модулю q
+    pub(super) fn from_signed(values: &[i64], params: Params<V>, format: Format) -> Self {
+        let q = params.get_modulus();
+        let residues: Vec<V::Integer> = values
+            .iter()
+            .map(|&x| {
+                let r = V::Integer::from_u64(x.unsigned_abs()).modulo(q);
+                if x < 0 {
+                    V::Integer::zero().mod_sub(&r, q)
+                } else {
+                    r
+                }
+            })
+            .collect();
+        let values = V::from_values(&residues, q.clone());
+        let mut result = Self::with_values(params, Format::COEFFICIENT, values);
+        result.set_format(format);
+        result
     }
 }
 
-impl AddAssign for Integer {
-    fn add_assign(&mut self, other: Integer) {
-        self.value += other.value;
+/* Доступ к данным */
+impl<V: TransformInterface> PolyImpl<V> {
+    pub fn get_params(&self) -> &Params<V> {
+        &self.params
     }
-}
-
-impl Sub for Integer {
-    type Output = Integer;
 
-    fn sub(self, other: Integer) -> Integer {
-        Integer::new(self.value - other.value)
+    pub fn get_ring_dimension(&self) -> usize {
+        self.params.get_ring_dimension() as usize
     }
-}
 
-impl SubAssign for Integer {
-    fn sub_assign(&mut self, other: Integer) {
-        self.value -= other.value;
+    pub fn get_root_of_unity(&self) -> &V::Integer {
+        self.params.get_root_of_unity()
     }
-}
 
-impl Mul for Integer {
-    type Output = Integer;
-
-    fn mul(self, other: Integer) -> Integer {
-        Integer::new(self.value * other.value)
+    /// Заменяет значения и формат; длина и модуль проверяются как в with_values
+    pub fn set_values(&mut self, values: V, format: Format) {
+        *self = Self::with_values(self.params.clone(), format, values);
     }
-}
 
-impl MulAssign for Integer {
-    fn mul_assign(&mut self, other: Integer) {
-        self.value *= other.value;
+    pub fn set_values_to_zero(&mut self) {
+        self.values = Some(V::new(
+            self.get_ring_dimension(),
+            self.params.get_modulus().clone(),
+        ));
     }
-}
 
-impl Div for Integer {
-    type Output = Integer;
-
-    fn div(self, other: Integer) -> Integer {
-        Integer::new(self.value / other.value)
+    /// Все значения равны q - 1
+    pub fn set_values_to_max(&mut self) {
+        let q = self.params.get_modulus();
+        let max = q.clone() - V::Integer::one();
+        let values = vec![max; self.get_ring_dimension()];
+        self.values = Some(V::from_values(&values, q.clone()));
     }
-}
 
-impl DivAssign for Integer {
-    fn div_assign(&mut self, other: Integer) {
-        self.value /= other.value;
+    fn values(&self) -> &V {
+        self.values
+            .as_ref()
+            .expect("No values in PolyImpl: the element is empty")
     }
-}
 
-impl Rem for Integer {
-    type Output = Integer;
+    fn values_mut(&mut self) -> &mut V {
+        self.values
+            .as_mut()
+            .expect("No values in PolyImpl: the element is empty")
+    }
 
-    fn rem(self, other: Integer) -> Integer {
-        Integer::new(self.value % other.value)
+    /// Многочлен с теми же параметрами и форматом и значениями values
+    fn with_same_params(&self, values: V) -> Self {
+        Self {
+            format: self.format,
+            params: self.params.clone(),
+            values: Some(values),
+        }
     }
-}
 
-impl RemAssign for Integer {
-    fn rem_assign(&mut self, other: Integer) {
-        self.value %= other.value;
+    fn check_compatible(&self, rhs: &Self) {
+        if self.params != rhs.params {
+            panic!("PolyImpl: operands have different parameters");
+        }
+        if self.format != rhs.format {
+            panic!("PolyImpl: operands are in different formats");
+        }
     }
 }
 
-impl Neg for Integer {
-    type Output = Integer;
-
-    fn neg(self) -> Integer {
-        Integer::new(-self.value)
+/* Арифметика */
+impl<V: TransformInterface> PolyImpl<V> {
+    /// Прибавляет скаляр: к свободному члену в COEFFICIENT, ко всем значениям
+    /// в EVALUATION
+    pub fn plus_scalar(&self, element: &V::Integer) -> Self {
+        self.map_scalar(element, |values, e| values.mod_add(e))
     }
-}
 
-impl PartialOrd for Integer {
-    fn partial_cmp(&self, other: &Integer) -> Option<Ordering> {
-        Some(self.value.cmp(&other.value))
+    /// Вычитает скаляр: из свободного члена в COEFFICIENT, из всех значений в
+    /// EVALUATION
+    pub fn minus_scalar(&self, element: &V::Integer) -> Self {
+        self.map_scalar(element, |values, e| values.mod_sub(e))
     }
-}
 
-impl FromStr for Integer {
-    type Err = ();
+    pub fn times_scalar(&self, element: &V::Integer) -> Self {
+        let q = self.params.get_modulus();
+        self.with_same_params(self.values().mod_mul(&element.modulo(q)))
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.parse::<i64>() {
-            Ok(value) => Ok(Integer::new(value)),
-            Err(_) => Err(()),
+    /// Умножение на знаковое целое
+    pub fn times_signed(&self, element: i64) -> Self {
+        let q = self.params.get_modulus();
+        let factor = V::Integer::from_u64(element.unsigned_abs()).modulo(q);
+        if element < 0 {
+            self.times_scalar(&V::Integer::zero().mod_sub(&factor, q))
+        } else {
+            self.times_scalar(&factor)
         }
     }
-}
-
-#[derive(Clone, Debug, PartialEq, Eq)]
-struct VecType {
-    values: Vec<Integer>,
-}
 
-impl VecType {
-    fn new(values: Vec<Integer>) -> Self {
-        VecType { values }
+    pub fn plus(&self, rhs: &Self) -> Self {
+        self.check_compatible(rhs);
+        self.with_same_params(self.values().mod_add_vec(rhs.values()))
     }
-}
 
-impl From<Vec<Integer>> for VecType {
-    fn from(values: Vec<Integer>) -> Self {
-        VecType::new(values)
+    pub fn minus(&self, rhs: &Self) -> Self {
+        self.check_compatible(rhs);
+        self.with_same_params(self.values().mod_sub_vec(rhs.values()))
     }
-}
 
-impl From<VecType> for Vec<Integer> {
-    fn from(vec_type: VecType) -> Self {
-        vec_type.values
+    /// Произведение в кольце. В EVALUATION оно поэлементное; в COEFFICIENT
+    /// оба множителя временно переводятся в EVALUATION
+    pub fn times(&self, rhs: &Self) -> Self {
+        self.check_compatible(rhs);
+        if self.format == Format::EVALUATION {
+            return self.with_same_params(self.values().mod_mul_vec(rhs.values()));
+        }
+        let (mut a, mut b) = (self.clone(), rhs.clone());
+        a.switch_format();
+        b.switch_format();
+        let mut result = a.times(&b);
+        result.switch_format();
+        result
     }
-}
 
-impl fmt::Display for VecType {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let values: Vec<String> = self.values.iter().map(|x| x.to_string()).collect();
-        write!(f, "{:?}", values)
+    pub fn negate(&self) -> Self {
+        let q = self.params.get_modulus();
+        let zero = V::new(self.get_length(), q.clone());
+        self.with_same_params(zero.mod_sub_vec(self.values()))
     }
-}
-
-impl Add for VecType {
-    type Output = VecType;
 
-    fn add(self, other: VecType) -> VecType {
-        let values: Vec<Integer> = self
-            .values
-            .iter()
-            .zip(other.values.iter())
-            .map(|(x, y)| x.clone() + y.clone())
-            .collect();
-        VecType::new(values)
+    fn map_scalar(&self, element: &V::Integer, f: impl Fn(&V, &V::Integer) -> V) -> Self {
+        let q = self.params.get_modulus();
+        let element = element.modulo(q);
+        if self.format == Format::EVALUATION {
+            return self.with_same_params(f(self.values(), &element));
+        }
+        let mut values = self.values().clone();
+        let constant = V::from_values(&[values[0].clone()], q.clone());
+        values[0] = f(&constant, &element)[0].clone();
+        self.with_same_params(values)
     }
-}
 
-impl AddAssign for VecType {
-    fn add_assign(&mut self, other: VecType) {
-        self.values = self
-            .values
-            .iter()
-            .zip(other.values.iter())
-            .map(|(x, y)| x.clone() + y.clone())
-            .collect();
+    /// Значение как целое из (-q/2, q/2]: модуль и знак
+    fn centered(x: &V::Integer, q: &V::Integer, half_q: &V::Integer) -> (V::Integer, bool) {
+        if x > half_q {
+            (q.clone() - x.clone(), true)
+        } else {
+            (x.clone(), false)
+        }
     }
-}
 
-impl Sub for VecType {
-    type Output = VecType;
+    /// Применяет f к модулям центрированных значений и восстанавливает знак
+    fn map_centered(&self, f: impl Fn(&V::Integer) -> V::Integer) -> Self {
+        let q = self.params.get_modulus();
+        let half_q = q.clone() / V::Integer::from_u64(2);
+        let mut values = self.values().clone();
+        for i in 0..values.get_length() {
+            let (abs, negative) = Self::centered(&values[i], q, &half_q);
+            let r = f(&abs).modulo(q);
+            values[i] = if negative {
+                V::Integer::zero().mod_sub(&r, q)
+            } else {
+                r
+            };
+        }
+        self.with_same_params(values)
+    }
 
-    fn sub(self, other: VecType) -> VecType {
-        let values: Vec<Integer> = self
-            .values
-            .iter()
-            .zip(other.values.iter())
-            .map(|(x, y)| x.clone() - y.clone())
-            .collect();
-        VecType::new(values)
+    /// Центрированный подъём значений в модуль modulus; модуль вектора
+    /// остаётся равным q
+    fn lift_to(&self, modulus: V::Integer) -> Self {
+        let q = self.params.get_modulus();
+        let mut values = self.values().clone();
+        values.switch_modulus(modulus);
+        values.set_modulus(q.clone());
+        self.with_same_params(values)
     }
-}
 
-impl SubAssign for VecType {
-    fn sub_assign(&mut self, other: VecType) {
-        self.values = self
-            .values
-            .iter()
-            .zip(other.values.iter())
-            .map(|(x, y)| x.clone() - y.clone())
-            .collect();
+    /// Число цифр значений в системе счисления с основанием 2^base_bits
+    fn windows(&self, base_bits: usize) -> u32 {
+        if base_bits == 0 {
+            panic!("PolyImpl: the decomposition base must have at least one bit");
+        }
+        self.params
+            .get_modulus()
+            .get_msb()
+            .div_ceil(base_bits as u32)
     }
 }
 
-impl Mul for VecType {
-    type Output = VecType;
+impl<V: TransformInterface> ILElement<usize> for PolyImpl<V> {
+    type Element = Self;
+    type VecType = V;
+    type IntType = V::Integer;
 
-    fn mul(self, other: VecType) -> VecType {
-        let values: Vec<Integer> = self
-            .values
-            .iter()
-            .zip(other.values.iter())
-            .map(|(x, y)| x.clone() * y.clone())
-            .collect();
-        VecType::new(values)
+    fn clone_empty(&self) -> Self {
+        Self::new(self.params.clone(), self.format, false)
     }
-}
 
-impl MulAssign for VecType {
-    fn mul_assign(&mut self, other: VecType) {
-        self.values = self
-            .values
-            .iter()
-            .zip(other.values.iter())
-            .map(|(x, y)| x.clone() * y.clone())
-            .collect();
+    fn clone_parameters_only(&self) -> Self {
+        Self::new(self.params.clone(), self.format, false)
     }
-}
 
-impl Div for VecType {
-    type Output = VecType;
-
-    fn div(self, other: VecType) -> VecType {
-        let values: Vec<Integer> = self
-            .values
-            .iter()
-            .zip(other.values.iter())
-            .map(|(x, y)| x.clone() / y.clone())
-            .collect();
-        VecType::new(values)
+    fn clone_with_noise(&self, dgg: &DiscreteGaussianGenerator<V>, format: Format) -> Self {
+        with_prng(|prng| Self::from_dgg(dgg, self.params.clone(), format, prng))
     }
-}
 
-impl DivAssign for VecType {
-    fn div_assign(&mut self, other: VecType) {
-        self.values = self
-            .values
-            .iter()
-            .zip(other.values.iter())
-            .map(|(x, y)| x.clone() / y.clone())
-            .collect();
+    fn assign(&mut self, rhs: &Self) -> &mut Self {
+        self.clone_from(rhs);
+        self
     }
-}
-
-impl Rem for VecType {
-    type Output = VecType;
 
-    fn rem(self, other: VecType) -> VecType {
-        let values: Vec<Integer> = self
-            .values
-            .iter()
-            .zip(other.values.iter())
-            .map(|(x, y)| x.clone() % y.clone())
-            .collect();
-        VecType::new(values)
+    fn assign_move(&mut self, rhs: Self) -> &mut Self {
+        *self = rhs;
+        self
     }
-}
 
-impl RemAssign for VecType {
-    fn rem_assign(&mut self, other: VecType) {
-        self.values = self
-            .values
-            .iter()
-            .zip(other.values.iter())
-            .map(|(x, y)| x.clone() % y.clone())
-            .collect();
+    /// Первые значения берутся из rhs, остальные обнуляются
+    fn assign_initializer_list(&mut self, rhs: &[u64]) -> &mut Self {
+        let n = self.get_ring_dimension();
+        let q = self.params.get_modulus().clone();
+        let mut values = V::new(n, q.clone());
+        for (i, &x) in rhs.iter().take(n).enumerate() {
+            values[i] = V::Integer::from_u64(x).modulo(&q);
+        }
+        self.values = Some(values);
+        self
     }
-}
-
-impl Neg for VecType {
-    type Output = VecType;
 
-    fn neg(self) -> VecType {
-        let values: Vec<Integer> = self.values.iter().map(|x| -x.clone()).collect();
-        VecType::new(values)
+    fn get_format(&self) -> Format {
+        self.format
     }
-}
 
-impl PartialOrd for VecType {
-    fn partial_cmp(&self, other: &VecType) -> Option<Ordering> {
-        Some(self.values.cmp(&other.values))
+    fn get_length(&self) -> usize {
+        self.values.as_ref().map_or(0, |v| v.get_length())
     }
-}
 
-impl VecType {
-    fn mod_add_at_index(&self, index: usize, element: Integer) -> VecType {
-        let mut values = self.values.clone();
-        values[index] += element;
-        VecType::new(values)
+    fn get_modulus(&self) -> &V::Integer {
+        self.params.get_modulus()
     }
 
-    fn mod_sub(&self, element: Integer) -> VecType {
-        let values: Vec<Integer> = self.values.iter().map(|x| x.clone() - element.clone()).collect();
-        VecType::new(values)
+    fn get_values(&self) -> &V {
+        self.values()
     }
 
-    fn mod_mul(&self, element: Integer) -> VecType {
-        let values: Vec<Integer> = self.values.iter().map(|x| x.clone() * element.clone()).collect();
-        VecType::new(values)
+    fn get_cyclotomic_order(&self) -> usize {
+        self.params.get_cyclotomic_order() as usize
     }
 
-    fn mod_sub_eq(&mut self, element: Integer) {
-        self.values = self.values.iter().map(|x| x.clone() - element.clone()).collect();
+    /// Прибавляет 1 ко всем значениям
+    fn add_element_one(&mut self) {
+        let values = self.values().mod_add(&V::Integer::one());
+        self.values = Some(values);
     }
 
-    fn mod_add_eq(&mut self, element: Integer) {
-        self.values = self.values.iter().map(|x| x.clone() + element.clone()).collect();
+    /// Автоморфизм x -> x^k для k, взаимно простого с m. В EVALUATION это
+    /// перестановка значений, в COEFFICIENT (только степень двойки) -
+    /// перестановка коэффициентов со сменой знака при переходе через x^n = -1
+    fn automorphism_transform(&self, k: u32) -> Self {
+        let m = self.params.get_cyclotomic_order() as u64;
+        let n = self.get_ring_dimension();
+        if self.format == Format::EVALUATION {
+            if self.params.is_power_of_two() {
+                return self.automorphism_transform_with_vec(k, &precompute_auto_map(n, k));
+            }
+            let totients = get_totient_list(m);
+            let mut position = vec![usize::MAX; m as usize];
+            for (i, &t) in totients.iter().enumerate() {
+                position[t as usize] = i;
+            }
+            let map: Vec<u32> = totients
+                .iter()
+                .map(|&t| position[(t * k as u64 % m) as usize])
+                .map(|i| {
+                    if i == usize::MAX {
+                        panic!("Automorphism index is not coprime with the cyclotomic order");
+                    }
+                    i as u32
+                })
+                .collect();
+            return self.automorphism_transform_with_vec(k, &map);
+        }
+        if !self.params.is_power_of_two() {
+            panic!(
+                "Automorphism in COEFFICIENT format is supported only for power-of-two cyclotomics"
+            );
+        }
+        if k.is_multiple_of(2) {
+            panic!("Automorphism index not odd");
+        }
+        let q = self.params.get_modulus();
+        let source = self.values();
+        let mut values = V::new(n, q.clone());
+        for j in 0..n as u64 {
+            let jk = j * k as u64 % m;
+            let value = &source[j as usize];
+            if jk < n as u64 {
+                values[jk as usize] = value.clone();
+            } else {
+                values[(jk - n as u64) as usize] = V::Integer::zero().mod_sub(value, q);
+            }
+        }
+        self.with_same_params(values)
     }
 
-    fn mod_mul_eq(&mut self, element: Integer) {
-        self.values = self.values.iter().map(|x| x.clone() * element.clone()).collect();
+    /// Автоморфизм в EVALUATION с готовой перестановкой:
+    /// result[j] = values[vec[j]]
+    fn automorphism_transform_with_vec(&self, _k: u32, vec: &[u32]) -> Self {
+        if self.format != Format::EVALUATION {
+            panic!("Precomputed automorphism is supported only in EVALUATION format");
+        }
+        let source = self.values();
+        let mut values = source.clone();
+        for (j, &i) in vec.iter().enumerate() {
+            values[j] = source[i as usize].clone();
+        }
+        self.with_same_params(values)
     }
 
-    fn mod_inverse(&self) -> VecType {
-        let values: Vec<Integer> = self.values.iter().map(|x| Integer::new(1) / x.clone()).collect();
-        VecType::new(values)
+    /// Автоморфизм x -> x^(m-1); определён только в EVALUATION
+    fn transpose(&self) -> Self {
+        if self.format == Format::COEFFICIENT {
+            panic!("PolyImpl element Transpose is not supported in COEFFICIENT format");
+        }
+        self.automorphism_transform(self.params.get_cyclotomic_order() - 1)
     }
 
-    fn mod_by_two(&self) -> VecType {
-        let values: Vec<Integer> = self.values.iter().map(|x| x.clone() % Integer::new(2)).collect();
-        VecType::new(values)
+    /// Разложение коэффициентов по основанию 2^base_bits: sum_i 2^(i base_bits) d_i
+    /// равна исходному многочлену, коэффициенты d_i меньше 2^base_bits
+    fn base_decompose(&self, base_bits: usize, eval_mode_answer: bool) -> Vec<Self> {
+        let windows = self.windows(base_bits);
+        let mut x = self.clone();
+        x.set_format(Format::COEFFICIENT);
+        (0..windows)
+            .map(|i| {
+                let digits = x.values().get_digit_at_index_for_base(i, base_bits as u32);
+                let mut digit = x.with_same_params(digits);
+                if eval_mode_answer {
+                    digit.switch_format();
+                }
+                digit
+            })
+            .collect()
     }
 
-    fn mod(&self, modulus: Integer) -> VecType {
-        let values: Vec<Integer> = self.values.iter().map(|x| x.clone() % modulus.clone()).collect();
-        VecType::new(values)
+    fn divide_and_round(&self, q: &V::Integer) -> Self {
+        self.map_centered(|x| x.divide_and_round(q))
     }
 
-    fn switch_modulus(&mut self, modulus: Integer) {
-        self.values = self.values.iter().map(|x| x.clone() % modulus.clone()).collect();
+    fn inverse_exists(&self) -> bool {
+        let values = self.values();
+        (0..values.get_length()).all(|i| !values[i].is_zero())
     }
-}
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-enum Format {
-    Coefficient,
-    Evaluation,
-}
+    /// Норма бесконечность центрированных коэффициентов
+    fn norm(&self) -> f64 {
+        let q = self.params.get_modulus();
+        let half_q = q.clone() / V::Integer::from_u64(2);
+        let values = self.values();
+        (0..values.get_length())
+            .map(|i| Self::centered(&values[i], q, &half_q).0.convert_to_double())
+            .fold(0.0, f64::max)
+    }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-struct Params {
-    ring_dimension: usize,
-    modulus: Integer,
-    root_of_unity: Integer,
-}
+    fn is_empty(&self) -> bool {
+        self.values.is_none()
+    }
 
-impl Params {
-    fn new(ring_dimension: usize, modulus: Integer, root_of_unity: Integer) -> Self {
-        Params {
-            ring_dimension,
-            modulus,
-            root_of_unity,
+    /// Обнуляет значения с номерами, не кратными w_factor
+    fn make_sparse(&mut self, w_factor: u32) {
+        let values = self.values_mut();
+        for i in 0..values.get_length() {
+            if i % w_factor as usize != 0 {
+                values[i] = V::Integer::zero();
+            }
         }
     }
 
-    fn get_ring_dimension(&self) -> usize {
-        self.ring_dimension
+    fn mod_by_two(&self) -> Self {
+        self.lift_to(V::Integer::from_u64(2))
     }
 
-    fn get_modulus(&self) -> Integer {
-        self.modulus.clone()
+    fn multiplicative_inverse(&self) -> Self {
+        if !self.inverse_exists() {
+            panic!("PolyImpl has no inverse");
+        }
+        self.with_same_params(self.values().mod_inverse())
     }
 
-    fn get_root_of_unity(&self) -> Integer {
-        self.root_of_unity.clone()
+    fn multiply_and_round(&self, p: &V::Integer, q: &V::Integer) -> Self {
+        self.map_centered(|x| x.multiply_and_round(p, q))
     }
-}
-
-#[derive(Clone, Debug, PartialEq, Eq)]
-struct PolyImpl {
-    format: Format,
-    params: Params,
-    values: VecType,
-}
 
-impl PolyImpl {
-    fn new(format: Format, params: Params, values: VecType) -> Self {
-        PolyImpl {
-            format,
-            params,
-            values,
+    /// Степени основания: self * 2^(i base_bits), i < числа цифр base_decompose
+    fn powers_of_base(&self, base_bits: usize) -> Vec<Self> {
+        let q = self.params.get_modulus();
+        let step = (0..base_bits).fold(V::Integer::one(), |acc, _| acc.mod_add(&acc, q));
+        let mut power = V::Integer::one();
+        (0..self.windows(base_bits))
+            .map(|_| {
+                let result = self.times_scalar(&power);
+                power = power.mod_mul(&step, q);
+                result
+            })
+            .collect()
+    }
+
+    /// Центрированные представители по модулю modulus
+    fn modulo(&self, modulus: &V::Integer) -> Self {
+        self.lift_to(modulus.clone())
+    }
+
+    /// Переводит коэффициенты в модуль modulus центрированным подъёмом и
+    /// заменяет параметры
+    fn switch_modulus(
+        &mut self,
+        modulus: &V::Integer,
+        root_of_unity: &V::Integer,
+        modulus_arb: &V::Integer,
+        root_of_unity_arb: &V::Integer,
+    ) {
+        if self.format != Format::COEFFICIENT {
+            panic!("SwitchModulus is supported only in COEFFICIENT format");
+        }
+        if let Some(values) = self.values.as_mut() {
+            values.switch_modulus(modulus.clone());
         }
+        self.params = Arc::new(ILParamsImpl::with_big_modulus(
+            self.params.get_cyclotomic_order(),
+            modulus.clone(),
+            root_of_unity.clone(),
+            modulus_arb.clone(),
+            root_of_unity_arb.clone(),
+        ));
+    }
+
+    /// COEFFICIENT <-> EVALUATION: NTT для степени двойки, преобразование
+    /// Блюстейна для остальных m
+    fn switch_format(&mut self) {
+        let m = self.params.get_cyclotomic_order() as usize;
+        let root = self.params.get_root_of_unity().clone();
+        let power_of_two = self.params.is_power_of_two();
+        let to_evaluation = self.format == Format::COEFFICIENT;
+        let values = self.values_mut();
+        match (power_of_two, to_evaluation) {
+            (true, true) => V::forward_transform_to_bit_reverse_in_place(&root, m, values),
+            (true, false) => V::inverse_transform_from_bit_reverse_in_place(&root, m, values),
+            (false, true) => *values = V::forward_transform_arb(values, &root, m),
+            (false, false) => *values = V::inverse_transform_arb(values, &root, m),
+        }
+        self.format = if to_evaluation {
+            Format::EVALUATION
+        } else {
+            Format::COEFFICIENT
+        };
     }
 
     fn set_format(&mut self, format: Format) {
-        self.format = format;
+        if self.format != format {
+            self.switch_format();
+        }
     }
+}
 
-    fn set_values(&mut self, values: VecType, format: Format) {
-        self.values = values;
-        self.format = format;
-    }
+impl<V: TransformInterface> Index<usize> for PolyImpl<V> {
+    type Output = V::Integer;
 
-    fn get_values(&self) -> VecType {
-        self.values.clone()
-    }
-
-    fn get_root_of_unity(&self) -> Integer {
-        self.params.get_root_of_unity()
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.values()[index]
     }
+}
 
-    fn plus(&self, element: Integer) -> PolyImpl {
-        let values = self.values.mod_add_at_index(0, element);
-        PolyImpl::new(self.format.clone(), self.params.clone(), values)
+impl<V: TransformInterface> IndexMut<usize> for PolyImpl<V> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.values_mut()[index]
     }
+}
 
-    fn minus(&self, element: Integer) -> PolyImpl {
-        let values = self.values.mod_sub(element);
-        PolyImpl::new(self.format.clone(), self.params.clone(), values)
-    }
+impl<V: TransformInterface> Neg for PolyImpl<V> {
+    type Output = Self;
 
-    fn times(&self, element: Integer) -> PolyImpl {
-        let values = self.values.mod_mul(element);
-        PolyImpl::new(self.format.clone(), self.params.clone(), values)
+    fn neg(self) -> Self {
+        self.negate()
     }
+}
 
-    fn minus_poly(&self, rhs: PolyImpl) -> PolyImpl {
-        let values = self.values - rhs.values;
-        PolyImpl::new(self.format.clone(), self.params.clone(), values)
-    }
+impl<V: TransformInterface> Add for PolyImpl<V> {
+    type Output = Self;
 
-    fn multiply_and_round(&self, p: Integer, q: Integer) -> PolyImpl {
-        let values = self.values * p * q;
-        PolyImpl::new(self.format.clone(), self.params.clone(), values)
+    fn add(self, rhs: Self) -> Self {
+        self.plus(&rhs)
     }
+}
 
-    fn divide_and_round(&self, q: Integer) -> PolyImpl {
-        let values = self.values / q;
-        PolyImpl::new(self.format.clone(), self.params.clone(), values)
-    }
+impl<V: TransformInterface> Sub for PolyImpl<V> {
+    type Output = Self;
 
-    fn negate(&self) -> PolyImpl {
-        let values = -self.values.clone();
-        PolyImpl::new(self.format.clone(), self.params.clone(), values)
+    fn sub(self, rhs: Self) -> Self {
+        self.minus(&rhs)
     }
+}
 
-    fn automorphism_transform(&self, k: u32) -> PolyImpl {
-        let n = self.params.get_ring_dimension();
-        let m = self.params.get_root_of_unity();
-        let bp = n == m / 2;
-        let bf = self.format == Format::Evaluation;
-
-        if !bp {
-            panic!("Automorphism Poly Format not EVALUATION or not power-of-two");
-        }
-
-        if k % 2 == 0 {
-            panic!("Automorphism index not odd");
-        }
-
-        let mut result = PolyImpl::new(self.format.clone(), self.params.clone(), self.values.clone());
-
-        let logm = (m as f64).log2() as u32 - 1;
-        let logn = logm - 1;
-        let mask = (1 << logn) - 1;
-
-        if bf {
-            for j in 0..n {
-                let jrev = j.reverse_bits() >> (32 - logn);
-                let idxrev = ((k >> 1) & mask).reverse_bits() >> (32 - logn);
-                result.values.values[jrev as usize] = self.values.values[idxrev as usize].clone();
-            }
-            return result;
-        }
+impl<V: TransformInterface> Mul for PolyImpl<V> {
+    type Output = Self;
 
-        let q = self.params.get_modulus();
-        for j in 0..n {
-            let jk = j * k;
-            result.values.values[(jk & mask) as usize] = if ((jk >> logn) & 0x1) != 0 {
-                q - self.values.values[j as usize].clone()
-            } else {
-                self.values.values[j as usize].clone()
-            };
-        }
-        result
+    fn mul(self, rhs: Self) -> Self {
+        self.times(&rhs)
     }
+}
 
-    fn arbitrary_switch_format(&mut self) {
-        let lr = self.params.get_root_of_unity();
-        let bm = self.params.get_modulus();
-        let br = self.params.get_root_of_unity();
-        let co = self.params.get_ring_dimension();
-
-        if self.format == Format::Coefficient {
-            self.format = Format::Evaluation;
-            let values = self
-                .values
-                .values
-                .iter()
-                .map(|x| Integer::from_str(&x.to_string()).unwrap())
-                .collect();
-            self.values = VecType::new(values);
-        } else {
-            self.format = Format::Coefficient;
-            let values = self
-                .values
-                .values
-                .iter()
-                .map(|x| Integer::from_str(&x.to_string()).unwrap())
-                .collect();
-            self.values = VecType::new(values);
-        }
+impl<V: TransformInterface> AddAssign for PolyImpl<V> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = self.plus(&rhs);
     }
 }
 
-impl fmt::Display for PolyImpl {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.values)
+impl<V: TransformInterface> SubAssign for PolyImpl<V> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = self.minus(&rhs);
     }
 }
 
-// fn main() {
-//     let values = vec![Integer::new(1), Integer::new(2), Integer::new(3)];
-//     let vec_type = VecType::new(values);
-//     let params = Params::new(3, Integer::new(10), Integer::new(5));
-//     let poly_impl = PolyImpl::new(Format::Coefficient, params, vec_type);
-//     println!("{}", poly_impl);
-// }
-
-
+impl<V: TransformInterface> MulAssign for PolyImpl<V> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = self.times(&rhs);
+    }
+}
@@ -1,4 +1,7 @@
-pub mod default;
-pub mod elemparams;
 pub mod dcrtpoly_interface;
-pub mod lat_backend;
\ No newline at end of file
+pub mod default;
+pub mod lat_backend;
+
+// Параметры элементов задаются ILParamsImpl и ILDCRTParams; перевод
+// ElemParams из OpenFHE не собирается и исключён из дерева модулей
+// pub mod elemparams;
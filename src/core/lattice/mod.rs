@@ -1,13 +1,18 @@
 pub mod hal;
 pub mod constants_lattice;
-pub mod dg_sampling_impl;
-pub mod dg_sampling;
-pub mod field2n_impl;
-pub mod field2n;
-pub mod il_element;
 pub mod lat_hal;
 pub mod matrix_lattice_impl;
-pub mod std_lattice_params;
-pub mod trap_door_impl;
-pub mod trap_door;
-pub mod trap_door_parameters;
\ No newline at end of file
+
+// Переводы из OpenFHE, которые ещё не перенесены на интерфейсы решётчатого
+// слоя и не собираются: выборка по Гауссу на решётках, ловушки, поле
+// Field2n и таблицы стандартных параметров решёток. Модули вернутся в
+// дерево вместе с переносом
+// pub mod dg_sampling_impl;
+// pub mod dg_sampling;
+// pub mod field2n_impl;
+// pub mod field2n;
+// pub mod il_element;
+// pub mod trap_door_impl;
+// pub mod trap_door;
+// pub mod std_lattice_params;
+// pub mod trap_door_parameters;
@@ -1,34 +1,42 @@
 /*
- This code provides generation of a uniform distribution of binary values (modulus 2). Discrete uniform generator
- relies on the built-in C++ generator for 32-bit unsigned integers defined in <random>
+ This code provides generation of a uniform distribution of binary values (modulus 2) for every
+ integer backend
 */
+use super::distributiongenerator::PRNG;
+use super::hal::integer::IntegerInterface;
+use super::hal::vector::BigVectorInterface;
+use rand::distributions::{Bernoulli, Distribution};
+use std::marker::PhantomData;
 
-
-use std::rand::Rng;
-
-pub struct BinaryUniformGeneratorImpl<VecType> {
-    m_distribution: std::rand::distributions::Bernoulli,
+/// Генератор равномерно распределённых битов для векторов V
+#[derive(Clone, Debug)]
+pub struct BinaryUniformGeneratorImpl<V: BigVectorInterface> {
+    distribution: Bernoulli,
+    _marker: PhantomData<V>,
 }
 
-impl<VecType> BinaryUniformGeneratorImpl<VecType> {
+impl<V: BigVectorInterface> BinaryUniformGeneratorImpl<V> {
     pub fn new() -> Self {
-        BinaryUniformGeneratorImpl {
-            m_distribution: std::rand::distributions::Bernoulli::new(0.5),
+        Self {
+            distribution: Bernoulli::new(0.5).unwrap(),
+            _marker: PhantomData,
         }
     }
 
-    pub fn generate_integer(&self) -> VecType::Integer {
-        // Generate a random boolean value and convert it to the appropriate integer type
-        let value: bool = self.m_distribution.sample(&mut rand::thread_rng());
-        VecType::Integer::from(value as u64)
+    /// 0 или 1 с вероятностью 1/2
+    pub fn generate_integer(&self, prng: &mut PRNG) -> V::Integer {
+        V::Integer::from_u64(self.distribution.sample(prng) as u64)
     }
 
-    pub fn generate_vector(&self, size: usize, modulus: &VecType::Integer) -> VecType {
-        let mut rng = rand::thread_rng();
-        let mut result = VecType::new(size);
-        for i in 0..size {
-            result[i] = self.generate_integer();
-        }
-        result
+    /// Вектор длины size из случайных битов с модулем modulus
+    pub fn generate_vector(&self, size: usize, modulus: &V::Integer, prng: &mut PRNG) -> V {
+        let values: Vec<V::Integer> = (0..size).map(|_| self.generate_integer(prng)).collect();
+        V::from_values(&values, modulus.clone())
+    }
+}
+
+impl<V: BigVectorInterface> Default for BinaryUniformGeneratorImpl<V> {
+    fn default() -> Self {
+        Self::new()
     }
 }
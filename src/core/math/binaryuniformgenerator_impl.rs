@@ -2,6 +2,7 @@
   This code provides generation of uniform distribution of binary values (modulus 2). Discrete uniform generator relies on
   the built-in C++ generator for 32-bit unsigned integers defined in <random>
 */
+use super::distributiongenerator::PRNG;
 use rand::distributions::Bernoulli;
 use rand::Rng;
 
@@ -18,19 +19,18 @@ impl<VecType> BinaryUniformGeneratorImpl<VecType> {
         }
     }
 
-    pub fn generate_integer(&self) -> usize {
-        let mut rng = rand::thread_rng();
-        if rng.sample(&self.distribution) {
+    pub fn generate_integer(&self, prng: &mut PRNG) -> usize {
+        if prng.sample(&self.distribution) {
             1
         } else {
             0
         }
     }
 
-    pub fn generate_vector(&self, size: usize, modulus: usize) -> Vec<usize> {
+    pub fn generate_vector(&self, size: usize, modulus: usize, prng: &mut PRNG) -> Vec<usize> {
         let mut v = Vec::with_capacity(size);
         for _ in 0..size {
-            v.push(self.generate_integer());
+            v.push(self.generate_integer(prng));
         }
         v
     }
@@ -1,23 +1,242 @@
 /*
- * This code provides Chebyshev approximation utilities.
+  This code provides Chebyshev approximation utilities: interpolation coefficients, Clenshaw
+  evaluation, error measurement, degree selection and the Paterson-Stockmeyer evaluation plan
+*/
+use std::f64::consts::PI;
 
-Чебышев - необходим для аппроксимации многочленов
- Многочлены Чебышева первого рода
+/// Число точек равномерной сетки, на которой измеряется погрешность при выборе степени
+pub const ERROR_GRID_SIZE: usize = 1 << 12;
 
-*/
-use std::f64;
-use std::vec::Vec;
+/// Коэффициенты c_0..c_degree интерполяции func в degree + 1 узлах Чебышева
+/// на [a, b] (дискретное косинус-преобразование):
+/// func(x) ≈ c_0 / 2 + sum_k c_k T_k(y), y = (2x - a - b) / (b - a).
+pub fn eval_chebyshev_coefficients<F: Fn(f64) -> f64>(
+    func: F,
+    a: f64,
+    b: f64,
+    degree: u32,
+) -> Vec<f64> {
+    assert!(a < b, "The interval [a, b] must not be empty");
+    let n = degree as usize + 1;
+    let (half_width, center) = ((b - a) / 2.0, (a + b) / 2.0);
+    let node = |j: usize| PI * (j as f64 + 0.5) / n as f64;
+    let values: Vec<f64> = (0..n)
+        .map(|j| func(half_width * f64::cos(node(j)) + center))
+        .collect();
+
+    let mult_factor = 2.0 / n as f64;
+    (0..n)
+        .map(|k| {
+            let sum: f64 = values
+                .iter()
+                .enumerate()
+                .map(|(j, y)| y * f64::cos(k as f64 * node(j)))
+                .sum();
+            mult_factor * sum
+        })
+        .collect()
+}
+
+/// Значение ряда c_0 / 2 + sum_k c_k T_k(y) в точке x из [a, b] по схеме Кленшоу
+pub fn eval_chebyshev_series(coefficients: &[f64], a: f64, b: f64, x: f64) -> f64 {
+    if coefficients.is_empty() {
+        return 0.0;
+    }
+    let y = (2.0 * x - a - b) / (b - a);
+    let (mut b1, mut b2) = (0.0, 0.0);
+    for &c in coefficients[1..].iter().rev() {
+        let b0 = 2.0 * y * b1 - b2 + c;
+        b2 = b1;
+        b1 = b0;
+    }
+    y * b1 - b2 + coefficients[0] / 2.0
+}
+
+/// Наибольшее отклонение ряда от func на равномерной сетке из samples точек [a, b]
+pub fn eval_chebyshev_error<F: Fn(f64) -> f64>(
+    func: F,
+    coefficients: &[f64],
+    a: f64,
+    b: f64,
+    samples: usize,
+) -> f64 {
+    assert!(samples >= 2, "The error grid needs at least two points");
+    (0..samples)
+        .map(|i| {
+            let x = a + (b - a) * i as f64 / (samples - 1) as f64;
+            (func(x) - eval_chebyshev_series(coefficients, a, b, x)).abs()
+        })
+        .fold(0.0, f64::max)
+}
+
+/// Наименьшая степень не больше max_degree, при которой погрешность интерполяции
+/// на сетке из ERROR_GRID_SIZE точек не превышает target_error; None, если такой нет.
+///
+/// Для гладких функций погрешность убывает с ростом степени, поэтому граница
+/// ищется удвоением степени, а затем уточняется двоичным поиском.
+pub fn eval_chebyshev_degree<F: Fn(f64) -> f64>(
+    func: F,
+    a: f64,
+    b: f64,
+    target_error: f64,
+    max_degree: u32,
+) -> Option<u32> {
+    let fits = |degree: u32| {
+        let coefficients = eval_chebyshev_coefficients(&func, a, b, degree);
+        eval_chebyshev_error(&func, &coefficients, a, b, ERROR_GRID_SIZE) <= target_error
+    };
+    if fits(0) {
+        return Some(0);
+    }
+    let (mut lo, mut hi) = (0, 1.min(max_degree));
+    while !fits(hi) {
+        if hi == max_degree {
+            return None;
+        }
+        lo = hi;
+        hi = (2 * hi).min(max_degree);
+    }
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if fits(mid) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    Some(hi)
+}
 
-pub fn eval_chebyshev_coefficients(func: fn(f64) -> f64, a: f64, b: f64, degree: u32) -> Vec<f64> {
-    let mut coefficients: Vec<f64> = Vec::new();
-    for i in 0..=degree {
-        let x = ((b - a) / 2.0)
-            * f64::cos(((2 * i + 1) as f64 / (2 * degree + 2) as f64) * f64::consts::PI)
-            + ((a + b) / 2.0);
-        let y = func(x);
-        coefficients.push(y);
+/// Узел плана Патерсона-Стокмейера
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChebyshevPsNode {
+    /// sum_i c_i T_i(y), i < k: линейная комбинация baby steps, c_0 - свободный член
+    Leaf(Vec<f64>),
+    /// quotient * T_giant(y) + remainder, T_giant - один из giant steps
+    Split {
+        giant: usize,
+        quotient: Box<ChebyshevPsNode>,
+        remainder: Box<ChebyshevPsNode>,
+    },
+}
+
+/// План вычисления ряда Чебышева по схеме Патерсона-Стокмейера для
+/// гомоморфного вычислителя.
+///
+/// Вычислитель переводит x в y = (2x - a - b) / (b - a), строит baby steps
+/// T_1..T_(k-1) и giant steps T_k, T_2k, ..., T_(k 2^(m-1)) по формулам
+/// T_(i+j) = 2 T_i T_j - T_(i-j) и T_2i = 2 T_i^2 - 1, после чего обходит
+/// дерево root: листья - линейные комбинации baby steps, в узлах Split частное
+/// умножается на giant step и складывается с остатком. Параметры k = 2^l и m
+/// выбираются с l + m = ceil(log2(degree + 1)) так, чтобы число нескалярных
+/// умножений k + m + 2^m было наименьшим.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChebyshevPsPlan {
+    pub a: f64,
+    pub b: f64,
+    pub degree: usize,
+    pub k: usize,
+    pub m: usize,
+    pub root: ChebyshevPsNode,
+}
+
+impl ChebyshevPsPlan {
+    /// План для ряда c_0 / 2 + sum_k c_k T_k(y) на [a, b]
+    pub fn new(coefficients: &[f64], a: f64, b: f64) -> Self {
+        assert!(
+            !coefficients.is_empty(),
+            "The Chebyshev series must have at least one coefficient"
+        );
+        let degree = coefficients.len() - 1;
+        let depth = usize::BITS - degree.leading_zeros();
+        let cost = |l: u32| (1usize << l) + (depth - l) as usize + (1usize << (depth - l));
+        let l = (0..=depth).min_by_key(|&l| cost(l)).unwrap();
+        let (k, m) = (1 << l, (depth - l) as usize);
+
+        let mut series = coefficients.to_vec();
+        series[0] /= 2.0;
+        Self {
+            a,
+            b,
+            degree,
+            k,
+            m,
+            root: Self::build(series, k, m),
+        }
+    }
+
+    /// Степени giant steps: k, 2k, ..., k 2^(m-1)
+    pub fn giant_steps(&self) -> Vec<usize> {
+        (0..self.m).map(|j| self.k << j).collect()
+    }
+
+    /// Значение ряда в точке x, вычисленное в порядке, предписанном планом
+    pub fn evaluate(&self, x: f64) -> f64 {
+        let y = (2.0 * x - self.a - self.b) / (self.b - self.a);
+        let mut powers = vec![1.0, y];
+        while powers.len() <= self.k {
+            let i = powers.len();
+            powers.push(2.0 * y * powers[i - 1] - powers[i - 2]);
+        }
+        let mut giant = vec![powers[self.k]];
+        for j in 1..self.m {
+            giant.push(2.0 * giant[j - 1] * giant[j - 1] - 1.0);
+        }
+        self.evaluate_node(&self.root, &powers, &giant)
+    }
+
+    fn evaluate_node(&self, node: &ChebyshevPsNode, powers: &[f64], giant: &[f64]) -> f64 {
+        match node {
+            ChebyshevPsNode::Leaf(c) => c.iter().zip(powers).map(|(c, t)| c * t).sum(),
+            ChebyshevPsNode::Split {
+                giant: degree,
+                quotient,
+                remainder,
+            } => {
+                let j = (degree / self.k).trailing_zeros() as usize;
+                self.evaluate_node(quotient, powers, giant) * giant[j]
+                    + self.evaluate_node(remainder, powers, giant)
+            }
+        }
+    }
+
+    /// Делит ряд sum_i p_i T_i степени меньше k 2^m на giant steps, начиная со старшего
+    fn build(mut series: Vec<f64>, k: usize, m: usize) -> ChebyshevPsNode {
+        while series.len() > 1 && series.last() == Some(&0.0) {
+            series.pop();
+        }
+        if m == 0 {
+            return ChebyshevPsNode::Leaf(series);
+        }
+        let giant = k << (m - 1);
+        if series.len() <= giant {
+            return Self::build(series, k, m - 1);
+        }
+        let (quotient, remainder) = long_division_chebyshev(series, giant);
+        ChebyshevPsNode::Split {
+            giant,
+            quotient: Box::new(Self::build(quotient, k, m - 1)),
+            remainder: Box::new(Self::build(remainder, k, m - 1)),
+        }
     }
-    coefficients
 }
 
-// Матрицы не делаем
\ No newline at end of file
+/// Деление ряда sum_i p_i T_i на T_g: p = q T_g + r, deg r < g.
+/// Использует T_i T_g = (T_(i+g) + T_|i-g|) / 2.
+fn long_division_chebyshev(series: Vec<f64>, g: usize) -> (Vec<f64>, Vec<f64>) {
+    let n = series.len() - 1;
+    let mut remainder = series;
+    let mut quotient = vec![0.0; n - g + 1];
+    for i in (0..=n - g).rev() {
+        let lead = remainder[i + g];
+        if i == 0 {
+            quotient[0] = lead;
+        } else {
+            quotient[i] = 2.0 * lead;
+            remainder[i.abs_diff(g)] -= lead;
+        }
+        remainder[i + g] = 0.0;
+    }
+    remainder.truncate(g);
+    (quotient, remainder)
+}
@@ -1,72 +1,252 @@
 /*
  This code contains the discrete fourier transform definitions
 */
+use lazy_static::lazy_static;
+use num::Complex;
 use std::collections::HashMap;
 use std::f64::consts::PI;
+use std::sync::{Arc, RwLock};
 
+/// Быстрое преобразование Фурье над комплексными числами.
+///
+/// Кроме обычного БПФ по основанию 2 здесь реализовано "специальное" БПФ
+/// CKKS: значения многочлена вычисляются в корнях ξ^(5^j) из единицы
+/// порядка m, упорядоченных по группе вращений 5^j mod m. Таблицы корней
+/// кэшируются глобально (по размеру и по порядку круговой группы) и
+/// доступны из нескольких потоков одновременно.
 pub struct DiscreteFourierTransform;
 
 impl DiscreteFourierTransform {
-    pub fn fft_forward_transform(a: &mut [num::Complex<f64>]) -> Vec<num::Complex<f64>> {
-        // Implementation of FFTForwardTransform
+    /// Прямое БПФ: B_k = sum_j A_j e^(-2 pi i jk / n), n - степень двойки
+    pub fn fft_forward_transform(a: &[Complex<f64>]) -> Vec<Complex<f64>> {
+        let m = a.len();
+        assert!(m.is_power_of_two(), "FFT size must be a power of 2");
+        let table = DiscreteFourierTransform::root_of_unity_table(m);
+
+        let mut b = a.to_vec();
+        DiscreteFourierTransform::bit_reverse(&mut b);
+
+        let mut size = 2;
+        while size <= m {
+            let halfsize = size / 2;
+            let tablestep = m / size;
+            for i in (0..m).step_by(size) {
+                for (j, k) in (i..i + halfsize).zip((0..).step_by(tablestep)) {
+                    let temp = b[j + halfsize] * table[k];
+                    b[j + halfsize] = b[j] - temp;
+                    b[j] += temp;
+                }
+            }
+            size *= 2;
+        }
+
+        b
     }
 
-    pub fn fft_inverse_transform(a: &mut [num::Complex<f64>]) -> Vec<num::Complex<f64>> {
-        // Implementation of FFTInverseTransform
+    /// Обратное БПФ: A_j = 1/n sum_k B_k e^(2 pi i jk / n)
+    pub fn fft_inverse_transform(a: &[Complex<f64>]) -> Vec<Complex<f64>> {
+        let conj: Vec<Complex<f64>> = a.iter().map(|x| x.conj()).collect();
+        let n = a.len() as f64;
+        DiscreteFourierTransform::fft_forward_transform(&conj)
+            .iter()
+            .map(|x| x.conj() / n)
+            .collect()
     }
 
-    pub fn forward_transform(a: &mut [num::Complex<f64>]) -> Vec<num::Complex<f64>> {
-        // Implementation of ForwardTransform
+    /// Значения многочлена A степени меньше n в нечётных степенях
+    /// ζ = e^(2 pi i / 2n), то есть в корнях X^n + 1:
+    /// результат k равен A(ζ^(2k+1)).
+    pub fn forward_transform(a: &[Complex<f64>]) -> Vec<Complex<f64>> {
+        let n = a.len();
+        let mut padded = a.to_vec();
+        padded.resize(2 * n, Complex::new(0.0, 0.0));
+
+        // Индекс 2n - (2k+1) прямого БПФ длины 2n соответствует ζ^(2k+1)
+        let dft = DiscreteFourierTransform::fft_forward_transform(&padded);
+        (0..n).map(|k| dft[2 * n - 1 - 2 * k]).collect()
     }
 
-    pub fn inverse_transform(a: &mut [num::Complex<f64>]) -> Vec<num::Complex<f64>> {
-        // Implementation of InverseTransform
+    /// Обратное к forward_transform: коэффициенты многочлена по его
+    /// значениям в корнях X^n + 1.
+    pub fn inverse_transform(a: &[Complex<f64>]) -> Vec<Complex<f64>> {
+        let n = a.len();
+        let mut dft = vec![Complex::new(0.0, 0.0); 2 * n];
+        for (k, &x) in a.iter().enumerate() {
+            dft[2 * n - 1 - 2 * k] = x;
+        }
+
+        // Чётные частоты отброшены, поэтому результат умножается на 2
+        DiscreteFourierTransform::fft_inverse_transform(&dft)
+            .iter()
+            .take(n)
+            .map(|x| x * 2.0)
+            .collect()
     }
 
-    pub fn fft_special_inv(vals: &mut [num::Complex<f64>], cycl_order: u32) {
-        // Implementation of FFTSpecialInv
+    /// Обратное специальное БПФ CKKS: по значениям в слотах восстанавливает
+    /// коэффициенты. Обращает fft_special для того же порядка cycl_order.
+    pub fn fft_special_inv(vals: &mut [Complex<f64>], cycl_order: u32) {
+        let slots = vals.len();
+        let pre = DiscreteFourierTransform::precomputed_values(cycl_order, slots);
+        let m = pre.m as usize;
+
+        let mut len = slots;
+        while len >= 1 {
+            let lenh = len >> 1;
+            let lenq = len << 2;
+            let gap = m / lenq;
+            for i in (0..slots).step_by(len) {
+                for j in 0..lenh {
+                    let idx = (lenq - (pre.rot_group[j] as usize % lenq)) * gap;
+                    let u = vals[i + j] + vals[i + j + lenh];
+                    let v = (vals[i + j] - vals[i + j + lenh]) * pre.ksi_pows[idx];
+                    vals[i + j] = u;
+                    vals[i + j + lenh] = v;
+                }
+            }
+            len >>= 1;
+        }
+
+        DiscreteFourierTransform::bit_reverse(vals);
+        let n = slots as f64;
+        vals.iter_mut().for_each(|x| *x /= n);
     }
 
-    pub fn fft_special(vals: &mut [num::Complex<f64>], cycl_order: u32) {
-        // Implementation of FFTSpecial
+    /// Специальное БПФ CKKS порядка m = cycl_order для slots = vals.len()
+    /// слотов (slots - степень двойки, не больше m/4):
+    /// vals'_k = sum_j vals_j ξ^(j * 5^k * m / (4 * slots)), ξ = e^(2 pi i / m).
+    pub fn fft_special(vals: &mut [Complex<f64>], cycl_order: u32) {
+        let slots = vals.len();
+        let pre = DiscreteFourierTransform::precomputed_values(cycl_order, slots);
+        let m = pre.m as usize;
+
+        DiscreteFourierTransform::bit_reverse(vals);
+
+        let mut len = 2;
+        while len <= slots {
+            let lenh = len >> 1;
+            let lenq = len << 2;
+            let gap = m / lenq;
+            for i in (0..slots).step_by(len) {
+                for j in 0..lenh {
+                    let idx = (pre.rot_group[j] as usize % lenq) * gap;
+                    let u = vals[i + j];
+                    let v = vals[i + j + lenh] * pre.ksi_pows[idx];
+                    vals[i + j] = u + v;
+                    vals[i + j + lenh] = u - v;
+                }
+            }
+            len <<= 1;
+        }
     }
 
+    /// Очищает все кэшированные таблицы
     pub fn reset() {
-        // Implementation of Reset
+        ROOT_OF_UNITY_TABLE.write().unwrap().clear();
+        PRECOMPUTED_VALUES.write().unwrap().clear();
     }
 
+    /// Заранее вычисляет таблицу корней для БПФ размера s
     pub fn pre_compute_table(s: u32) {
-        // Implementation of PreComputeTable
+        DiscreteFourierTransform::root_of_unity_table(s as usize);
     }
 
+    /// Заранее вычисляет таблицы специального БПФ порядка m для nh слотов
     pub fn initialize(m: u32, nh: u32) {
-        // Implementation of Initialize
+        DiscreteFourierTransform::precomputed_values(m, nh as usize);
+    }
+
+    /// Перестановка элементов в порядке обращения битов индекса
+    fn bit_reverse(vals: &mut [Complex<f64>]) {
+        let size = vals.len();
+        let mut j = 0;
+        for i in 1..size {
+            let mut bit = size >> 1;
+            while j >= bit {
+                j -= bit;
+                bit >>= 1;
+            }
+            j += bit;
+            if i < j {
+                vals.swap(i, j);
+            }
+        }
     }
 
-    fn bit_reverse(vals: &mut [num::Complex<f64>]) {
-        // Implementation of BitReverse
+    fn root_of_unity_table(s: usize) -> Arc<Vec<Complex<f64>>> {
+        if let Some(table) = ROOT_OF_UNITY_TABLE.read().unwrap().get(&s) {
+            return table.clone();
+        }
+        let table: Vec<Complex<f64>> = (0..s)
+            .map(|j| Complex::from_polar(1.0, -2.0 * PI * j as f64 / s as f64))
+            .collect();
+        ROOT_OF_UNITY_TABLE
+            .write()
+            .unwrap()
+            .entry(s)
+            .or_insert_with(|| Arc::new(table))
+            .clone()
+    }
+
+    fn precomputed_values(m: u32, slots: usize) -> Arc<PrecomputedValues> {
+        // Проверка до построения таблиц: неверный порядок не должен попасть в кэш
+        assert!(
+            m >= 4 && m.is_power_of_two() && slots.is_power_of_two() && slots <= m as usize / 4,
+            "The cyclotomic order and the number of slots must be powers of 2, slots <= m/4"
+        );
+        if let Some(pre) = PRECOMPUTED_VALUES.read().unwrap().get(&m) {
+            return pre.clone();
+        }
+        let pre = PrecomputedValues::new(m, m / 4);
+        PRECOMPUTED_VALUES
+            .write()
+            .unwrap()
+            .entry(m)
+            .or_insert_with(|| Arc::new(pre))
+            .clone()
     }
 }
 
+/// Таблицы специального БПФ для порядка круговой группы m
 struct PrecomputedValues {
-    m_m: u32,
-    m_nh: u32,
-    m_rot_group: Vec<u32>,
-    m_ksi_pows: Vec<num::Complex<f64>>,
+    /// Порядок круговой группы m = 2N
+    m: u32,
+    /// Группа вращений: rot_group[j] = 5^j mod m, j < N/2
+    rot_group: Vec<u32>,
+    /// Степени корня из единицы: ksi_pows[j] = e^(2 pi i j / m), j = 0..m
+    ksi_pows: Vec<Complex<f64>>,
 }
 
 impl PrecomputedValues {
     fn new(m: u32, nh: u32) -> Self {
-        // Implementation of PrecomputedValues constructor
+        let mut rot_group = Vec::with_capacity(nh as usize);
+        let mut five_pows = 1u64;
+        for _ in 0..nh {
+            rot_group.push(five_pows as u32);
+            five_pows = five_pows * 5 % m as u64;
+        }
+
+        let mut ksi_pows: Vec<Complex<f64>> = (0..m)
+            .map(|j| Complex::from_polar(1.0, 2.0 * PI * j as f64 / m as f64))
+            .collect();
+        ksi_pows.push(ksi_pows[0]);
+
+        debug_assert_eq!(rot_group.len(), nh as usize);
+        debug_assert_eq!(ksi_pows.len(), m as usize + 1);
+        PrecomputedValues {
+            m,
+            rot_group,
+            ksi_pows,
+        }
     }
 }
 
 lazy_static! {
-    static ref ROOT_OF_UNITY_TABLE: Vec<num::Complex<f64>> = {
-        // Implementation of rootOfUnityTable initialization
-    };
+    /// Таблицы корней для БПФ по размеру преобразования
+    static ref ROOT_OF_UNITY_TABLE: RwLock<HashMap<usize, Arc<Vec<Complex<f64>>>>> =
+        RwLock::new(HashMap::new());
 
-    static ref PRECOMPUTED_VALUES: HashMap<u32, PrecomputedValues> = {
-        // Implementation of precomputedValues initialization
-    };
+    /// Таблицы специального БПФ по порядку круговой группы
+    static ref PRECOMPUTED_VALUES: RwLock<HashMap<u32, Arc<PrecomputedValues>>> =
+        RwLock::new(HashMap::new());
 }
@@ -1,139 +1,5 @@
 /*
-  This code provides generation of gaussian distributions of discrete values. Discrete uniform generator
-  relies on the built-in C++ generator for 32-bit unsigned integers defined in <random>
+  This code provides generation of gaussian distributions of discrete values. The generator lives in
+  the core crate, where the lattice element interface refers to it
 */
-
-/**
- * This is the header file for DiscreteGaussianGenerator class, which contains 3
- * different sampling methods.
- *
- * First sampling method implemented is the rejection sampling defined in
- * section 4.1 of https://eprint.iacr.org/2007/432.pdf. It is usable for
- * arbitrary centers and standard deviations, and does not require any form of
- * precomputation. However, it has high rejection rates and is prone to timing
- * attacks. It is not used anywhere in the library at the moment and is here for
- * historical reasons.
- *
- * Second sampling method implemented is Karney's method defined in Algorithm D
- * from https://arxiv.org/pdf/1303.6257.pdf, which is an improved method based
- * on rejection sampling. It also works for arbitrary centers and standard
- * deviations without any precomputation. Its rejection rate is smaller than in
- * the rejection sampling method but it may still be vulnerable to timing
- * attacks.
- *
- *
- * Final sampling method defined in this class is the Peikert's inversion method
- * discussed in section 4.1 of https://eprint.iacr.org/2010/088.pdf and
- * summarized in section 3.2.2 of
- * https://link.springer.com/content/pdf/10.1007%2Fs00200-014-0218-3.pdf. It
- * requires CDF tables of probabilities centered around single center to be
- * kept, which are precalculated in constructor. The method is not prone to
- * timing attacks but it is usable for single center, single deviation only.
- * It should be also noted that the memory requirement grows with the standard
- * deviation, therefore it is advised to use it with smaller deviations.   */
-
-const KARNEY_THRESHOLD: f64 = 300.0;
-
-struct DiscreteGaussianGeneratorImpl<VecType> {
-    m_std: f64,
-    m_a: f64,
-    m_vals: Vec<f64>,
-    peikert: bool,
-}
-
-impl<VecType> DiscreteGaussianGeneratorImpl<VecType> {
-    fn new(std: f64) -> Self {
-        Self {
-            m_std: std,
-            m_a: 0.0,
-            m_vals: Vec::new(),
-            peikert: false,
-        }
-    }
-
-    fn is_initialized(&self) -> bool {
-        // implementation
-    }
-
-    fn initialize(&mut self) {
-        // implementation
-    }
-
-    fn get_std(&self) -> f64 {
-        self.m_std
-    }
-
-    fn set_std(&mut self, std: f64) {
-        self.m_std = std;
-    }
-
-    fn generate_int(&self) -> i32 {
-        // implementation
-    }
-
-    fn generate_int_vector(&self, size: u32) -> Option<Box<i64>> {
-        // implementation
-    }
-
-    fn generate_integer(&self, modulus: &VecType::Integer) -> VecType::Integer {
-        // implementation
-    }
-
-    fn generate_vector(&self, size: u32, modulus: &VecType::Integer) -> VecType {
-        // implementation
-    }
-
-    fn generate_integer_with_mean_stddev(
-        &self,
-        mean: f64,
-        stddev: f64,
-        n: usize,
-        modulus: &VecType::Integer,
-    ) -> VecType::Integer {
-        // implementation
-    }
-
-    fn generate_integer_with_mean_stddev_n(&self, mean: f64, stddev: f64, n: usize) -> i32 {
-        // implementation
-    }
-
-    fn generate_integer_karney(mean: f64, stddev: f64) -> i64 {
-        // implementation
-    }
-
-    fn find_in_vector(&self, s: &Vec<f64>, search: f64) -> u32 {
-        // implementation
-    }
-
-    fn unnormalized_gaussian_pdf(mean: f64, sigma: f64, x: i32) -> f64 {
-        // implementation
-    }
-
-    fn unnormalized_gaussian_pdf_optimized(mean: f64, sigma_factor: f64, x: i32) -> f64 {
-        // implementation
-    }
-
-    fn algorithm_p(g: &PRNG, n: i32) -> bool {
-        // implementation
-    }
-
-    fn algorithm_g(g: &PRNG) -> i32 {
-        // implementation
-    }
-
-    fn algorithm_h(g: &PRNG) -> bool {
-        // implementation
-    }
-
-    fn algorithm_h_double(g: &PRNG) -> bool {
-        // implementation
-    }
-
-    fn algorithm_b(g: &PRNG, k: i32, x: f64) -> bool {
-        // implementation
-    }
-
-    fn algorithm_b_double(g: &PRNG, k: i32, x: f64) -> bool {
-        // implementation
-    }
-}
+pub use fhe_core::math::discrete_gaussian_generator::*;
@@ -2,6 +2,7 @@
   This code provides generation of gaussian distributions of discrete values. Discrete uniform generator relies on
   the built-in C++ generator for 32-bit unsigned integers defined in <random>
 */
+use super::distributiongenerator::PRNG;
 use rand::Rng;
 use std::cmp::Ordering;
 use std::f64::consts::E;
@@ -61,8 +62,8 @@ impl DiscreteGaussianGeneratorImpl {
         }
     }
 
-    pub fn generate_int(&self) -> i32 {
-        let seed = rand::thread_rng().gen_range(0.0, 1.0) - 0.5;
+    pub fn generate_int(&self, prng: &mut PRNG) -> i32 {
+        let seed = prng.gen_range(0.0, 1.0) - 0.5;
         let tmp = seed.abs() - self.m_a / 2.0;
         if tmp <= 0.0 {
             return 0;
@@ -72,16 +73,16 @@ impl DiscreteGaussianGeneratorImpl {
         val
     }
 
-    pub fn generate_int_vector(&self, size: u32) -> Vec<i64> {
+    pub fn generate_int_vector(&self, size: u32, prng: &mut PRNG) -> Vec<i64> {
         let mut ans = Vec::with_capacity(size as usize);
         if !peikert {
             for _ in 0..size {
-                ans.push(generate_integer_karney(0, self.m_std));
+                ans.push(generate_integer_karney(0, self.m_std, prng));
             }
             return ans;
         }
         for _ in 0..size {
-            let seed = rand::thread_rng().gen_range(0.0, 1.0) - 0.5;
+            let seed = prng.gen_range(0.0, 1.0) - 0.5;
             let tmp = seed.abs() - self.m_a / 2.0;
             let val = if tmp > 0.0 {
                 index = self.find_in_vector(tmp);
@@ -107,8 +108,8 @@ impl DiscreteGaussianGeneratorImpl {
         }
     }
 
-    pub fn generate_integer(&self, modulus: i64) -> i64 {
-        let seed = rand::thread_rng().gen_range(0.0, 1.0) - 0.5;
+    pub fn generate_integer(&self, modulus: i64, prng: &mut PRNG) -> i64 {
+        let seed = prng.gen_range(0.0, 1.0) - 0.5;
         let tmp = seed.abs() - self.m_a / 2.0;
         if tmp <= 0.0 {
             return 0;
@@ -122,8 +123,8 @@ impl DiscreteGaussianGeneratorImpl {
         }
     }
 
-    pub fn generate_vector(&self, size: u32, modulus: i64) -> Vec<i64> {
-        let result = self.generate_int_vector(size);
+    pub fn generate_vector(&self, size: u32, modulus: i64, prng: &mut PRNG) -> Vec<i64> {
+        let result = self.generate_int_vector(size, prng);
         let mut ans = Vec::with_capacity(size as usize);
         for i in 0..size {
             let v = result[i as usize];
@@ -133,14 +134,14 @@ impl DiscreteGaussianGeneratorImpl {
         ans
     }
 
-    pub fn generate_integer(mean: f64, stddev: f64, n: usize, modulus: i64) -> i64 {
+    pub fn generate_integer(mean: f64, stddev: f64, n: usize, modulus: i64, prng: &mut PRNG) -> i64 {
         let t = (n as f64).log2() * stddev;
         let uniform_int = rand::distributions::Uniform::from(mean - t..=mean + t);
         let uniform_real = rand::distributions::Uniform::from(0.0..=1.0);
         let mut x;
         loop {
-            x = uniform_int.sample(&mut rand::thread_rng());
-            if uniform_real.sample(&mut rand::thread_rng())
+            x = uniform_int.sample(prng);
+            if uniform_real.sample(prng)
                 > unnormalized_gaussian_pdf(mean, stddev, x)
             {
                 break;
@@ -153,7 +154,7 @@ impl DiscreteGaussianGeneratorImpl {
         }
     }
 
-    pub fn generate_integer(mean: f64, stddev: f64, n: usize) -> i32 {
+    pub fn generate_integer(mean: f64, stddev: f64, n: usize, prng: &mut PRNG) -> i32 {
         if mean.is_infinite() {
             panic!("DiscreteGaussianGeneratorImpl called with mean == +-inf");
         }
@@ -169,8 +170,8 @@ impl DiscreteGaussianGeneratorImpl {
         let mut x;
         let mut flag_success = false;
         while !flag_success {
-            x = uniform_int.sample(&mut rand::thread_rng());
-            let dice = uniform_real.sample(&mut rand::thread_rng());
+            x = uniform_int.sample(prng);
+            let dice = uniform_real.sample(prng);
             flag_success = dice <= unnormalized_gaussian_pdf_optimized(mean, sigma_factor, x);
             count += 1;
             if count > LIMIT {
@@ -180,30 +181,29 @@ impl DiscreteGaussianGeneratorImpl {
         x
     }
 
-    pub fn generate_integer_karney(mean: f64, stddev: f64) -> i64 {
+    pub fn generate_integer_karney(mean: f64, stddev: f64, prng: &mut PRNG) -> i64 {
         let mut result;
         let uniform_sign = rand::distributions::Uniform::from(0..=1);
         let uniform_j = rand::distributions::Uniform::from(0..=(stddev.ceil() as i64 - 1));
-        let mut g = rand::thread_rng();
         let mut flag_success = false;
         let mut k;
         while !flag_success {
-            k = algorithm_g(&mut g);
-            if !algorithm_p(&mut g, k * (k - 1)) {
+            k = algorithm_g(prng);
+            if !algorithm_p(prng, k * (k - 1)) {
                 continue;
             }
-            let s = uniform_sign.sample(&mut g);
+            let s = uniform_sign.sample(prng);
             let s = if s == 0 { -1 } else { 1 };
             let di0 = stddev * k as f64 + s as f64 * mean;
             let i0 = di0.ceil() as i64;
             let x0 = (i0 as f64 - di0) / stddev;
-            let j = uniform_j.sample(&mut g);
+            let j = uniform_j.sample(prng);
             let x = x0 + j as f64 / stddev;
             if !(x < 1.0) || (x == 0.0 && s < 0 && k == 0) {
                 continue;
             }
             let mut h = k + 1;
-            while h > 0 && algorithm_b(&mut g, k, x) {
+            while h > 0 && algorithm_b(prng, k, x) {
                 h -= 1;
             }
             if h < 0 {
@@ -214,7 +214,7 @@ impl DiscreteGaussianGeneratorImpl {
         result
     }
 
-    pub fn algorithm_p(&mut self, g: &mut rand::rngs::ThreadRng, n: i32) -> bool {
+    pub fn algorithm_p(&mut self, g: &mut PRNG, n: i32) -> bool {
         let mut n = n;
         while n > 0 && algorithm_h(g) {
             n -= 1;
@@ -222,7 +222,7 @@ impl DiscreteGaussianGeneratorImpl {
         n < 0
     }
 
-    pub fn algorithm_g(&mut self, g: &mut rand::rngs::ThreadRng) -> i32 {
+    pub fn algorithm_g(&mut self, g: &mut PRNG) -> i32 {
         let mut n = 0;
         while algorithm_h(g) {
             n += 1;
@@ -230,7 +230,7 @@ impl DiscreteGaussianGeneratorImpl {
         n
     }
 
-    pub fn algorithm_h(&mut self, g: &mut rand::rngs::ThreadRng) -> bool {
+    pub fn algorithm_h(&mut self, g: &mut PRNG) -> bool {
         let dist = rand::distributions::Uniform::from(0.0..=1.0);
         let h_a = dist.sample(g);
         if h_a > 0.5 {
@@ -257,7 +257,7 @@ impl DiscreteGaussianGeneratorImpl {
         }
     }
 
-    pub fn algorithm_h_double(&mut self, g: &mut rand::rngs::ThreadRng) -> bool {
+    pub fn algorithm_h_double(&mut self, g: &mut PRNG) -> bool {
         let dist = rand::distributions::Uniform::from(0.0..=1.0);
         let h_a = dist.sample(g);
         if !(h_a < 0.5) {
@@ -276,7 +276,7 @@ impl DiscreteGaussianGeneratorImpl {
         }
     }
 
-    pub fn algorithm_b(&mut self, g: &mut rand::rngs::ThreadRng, k: i32, x: f64) -> bool {
+    pub fn algorithm_b(&mut self, g: &mut PRNG, k: i32, x: f64) -> bool {
         let dist = rand::distributions::Uniform::from(0.0..=1.0);
         let y = x;
         let mut n = 0;
@@ -305,7 +305,7 @@ impl DiscreteGaussianGeneratorImpl {
         n % 2 == 0
     }
 
-    pub fn algorithm_b_double(&mut self, g: &mut rand::rngs::ThreadRng, k: i32, x: f64) -> bool {
+    pub fn algorithm_b_double(&mut self, g: &mut PRNG, k: i32, x: f64) -> bool {
         let dist = rand::distributions::Uniform::from(0.0..=1.0);
         let y = x;
         let mut n = 0;
@@ -65,6 +65,7 @@
  *
 * */
 
+use super::distributiongenerator::PRNG;
 use rand::Rng;
 
 const MAX_LEVELS: usize = 4;
@@ -87,9 +88,9 @@ impl BitGenerator {
         }
     }
 
-    fn generate(&mut self) -> i16 {
+    fn generate(&mut self, prng: &mut PRNG) -> i16 {
         if self.counter == 0 {
-            self.sequence = rand::thread_rng().gen();
+            self.sequence = prng.gen();
             self.counter = 32;
         }
         let bit = (self.sequence >> (self.counter - 1)) & 0x1;
@@ -136,8 +137,8 @@ impl BaseSampler {
         0
     }
 
-    fn random_bit(&mut self) -> i16 {
-        self.bg.generate()
+    fn random_bit(&mut self, prng: &mut PRNG) -> i16 {
+        self.bg.generate(prng)
     }
 
     fn find_in_vector(&self, s: &[f64], search: f64) -> u32 {
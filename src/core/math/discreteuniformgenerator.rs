@@ -1,53 +1,79 @@
 /*
   This code provides generation of uniform distributions of discrete values. Discrete uniform generator
-  relies on the built-in C++ generator for 32-bit unsigned integers defined in <random>
+  relies on the ChaCha20 PRNG producing 32-bit unsigned integers
 */
-use rand::Rng;
-use std::cmp::min;
-
-const DUG_CHUNK_MIN: u32 = 0;
-const DUG_CHUNK_WIDTH: u32 = std::u32::BITS;
-const DUG_CHUNK_MAX: u32 = std::u32::MAX;
-
-pub struct DiscreteUniformGeneratorImpl<VecType> {
-    modulus: VecType::Integer,
-    chunks_per_value: u32,
-    shift_chunk: u32,
-    bound: std::ops::Range<u32>,
+use super::distributiongenerator::PRNG;
+use super::hal::integer::IntegerInterface;
+use super::hal::vector::BigVectorInterface;
+use rand::RngCore;
+
+/// Ширина случайного блока в битах
+pub const DUG_CHUNK_WIDTH: u32 = 32;
+
+/// Генератор равномерно распределённых вычетов по модулю q.
+///
+/// Число собирается из 32-битных блоков, начиная со старшего; старший блок
+/// обрезается до разрядности q, а значения, не меньшие q, отбрасываются,
+/// поэтому в среднем нужно меньше двух попыток.
+#[derive(Clone, Debug)]
+pub struct DiscreteUniformGeneratorImpl<V: BigVectorInterface> {
+    modulus: V::Integer,
 }
 
-impl<VecType> DiscreteUniformGeneratorImpl<VecType> {
+impl<V: BigVectorInterface> DiscreteUniformGeneratorImpl<V> {
+    /// Генератор без модуля: его задаёт set_modulus или многочлен, который
+    /// строится из генератора
     pub fn new() -> Self {
         Self {
-            modulus: VecType::Integer::default(),
-            chunks_per_value: 0,
-            shift_chunk: 0,
-            bound: DUG_CHUNK_MIN..DUG_CHUNK_MAX,
+            modulus: V::Integer::zero(),
         }
     }
 
-    pub fn set_modulus(&mut self, modulus: VecType::Integer) {
-        self.modulus = modulus;
+    pub fn with_modulus(modulus: V::Integer) -> Self {
+        Self { modulus }
     }
 
-    pub fn generate_integer(&self) -> VecType::Integer {
-        let mut rng = rand::thread_rng();
-        rng.gen_range(self.bound.start, self.bound.end)
+    pub fn get_modulus(&self) -> &V::Integer {
+        &self.modulus
     }
 
-    pub fn generate_vector(&self, size: u32) -> VecType {
-        let mut vec = VecType::new();
-        for _ in 0..size {
-            vec.push(self.generate_integer());
-        }
-        vec
+    pub fn set_modulus(&mut self, modulus: V::Integer) {
+        self.modulus = modulus;
     }
 
-    pub fn generate_vector_with_modulus(&self, size: u32, modulus: VecType::Integer) -> VecType {
-        let mut vec = VecType::new();
-        for _ in 0..size {
-            vec.push(self.generate_integer() % modulus);
+    /// Равномерно распределённый вычет из [0, q)
+    pub fn generate_integer(&self, prng: &mut PRNG) -> V::Integer {
+        if self.modulus.is_zero() {
+            panic!("0 modulus?");
+        }
+        let bits = self.modulus.get_msb();
+        let chunks = bits.div_ceil(DUG_CHUNK_WIDTH);
+        let top_bits = bits - (chunks - 1) * DUG_CHUNK_WIDTH;
+        let base = V::Integer::from_u64(1 << DUG_CHUNK_WIDTH);
+        loop {
+            let mut result = V::Integer::zero();
+            for i in 0..chunks {
+                let mut chunk = prng.next_u32() as u64;
+                if i == 0 {
+                    chunk &= (1 << top_bits) - 1;
+                }
+                result = result * base.clone() + V::Integer::from_u64(chunk);
+            }
+            if result < self.modulus {
+                return result;
+            }
         }
-        vec
+    }
+
+    /// Вектор длины size из независимых равномерно распределённых вычетов
+    pub fn generate_vector(&self, size: usize, prng: &mut PRNG) -> V {
+        let values: Vec<V::Integer> = (0..size).map(|_| self.generate_integer(prng)).collect();
+        V::from_values(&values, self.modulus.clone())
+    }
+}
+
+impl<V: BigVectorInterface> Default for DiscreteUniformGeneratorImpl<V> {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -2,6 +2,7 @@
   This code provides generation of uniform distributions of discrete values. Discrete uniform generator relies on
   the built-in C++ generator for 32-bit unsigned integers defined in <random>
 */
+use super::distributiongenerator::PRNG;
 use rand::Rng;
 
 pub struct DiscreteUniformGeneratorImpl<VecType> {
@@ -31,30 +32,29 @@ impl<VecType> DiscreteUniformGeneratorImpl<VecType> {
         );
     }
 
-    pub fn generate_integer(&self) -> VecType::Integer {
+    pub fn generate_integer(&self, prng: &mut PRNG) -> VecType::Integer {
         if self.modulus == VecType::Integer::from(0) {
             panic!("0 modulus?");
         }
-        let mut rng = rand::thread_rng();
         let dist = rand::distributions::Uniform::new(DUG_CHUNK_MIN, DUG_CHUNK_MAX);
         loop {
             let mut result = VecType::Integer::from(0);
             let mut shift = 0;
             for _ in 0..self.chunks_per_value {
-                result += VecType::Integer::from(rng.sample(dist)) << shift;
+                result += VecType::Integer::from(prng.sample(dist)) << shift;
                 shift += DUG_CHUNK_WIDTH;
             }
-            result += VecType::Integer::from(rng.sample(dist)) << self.shift_chunk;
+            result += VecType::Integer::from(prng.sample(dist)) << self.shift_chunk;
             if result < self.modulus {
                 return result;
             }
         }
     }
 
-    pub fn generate_vector(&self, size: u32) -> VecType {
+    pub fn generate_vector(&self, size: u32, prng: &mut PRNG) -> VecType {
         let mut v = VecType::new(size, self.modulus);
         for i in 0..size {
-            v[i as usize] = self.generate_integer();
+            v[i as usize] = self.generate_integer(prng);
         }
         v
     }
@@ -63,11 +63,12 @@ impl<VecType> DiscreteUniformGeneratorImpl<VecType> {
         &mut self,
         size: u32,
         modulus: VecType::Integer,
+        prng: &mut PRNG,
     ) -> VecType {
         self.set_modulus(modulus);
         let mut v = VecType::new(size, self.modulus);
         for i in 0..size {
-            v[i as usize] = self.generate_integer();
+            v[i as usize] = self.generate_integer(prng);
         }
         v
     }
@@ -1,51 +1,19 @@
-#[cfg(target_pointer_width = "128")]
-const MAX_MODULUS_SIZE: u32 = 121;
-type BasicInteger = u128;
-type DoubleNativeInt = u128;
-type uint128_t = u128;
-type int128_t = i128;
+/*
+  This file contains the basic native integer types of the current platform
+*/
 
+/// Максимальная разрядность модуля машинного бэкенда: запас в несколько бит
+/// нужен редукции Барретта и умножению Шоупа
 #[cfg(target_pointer_width = "64")]
-#[cfg(feature = "int128")]
-const MAX_MODULUS_SIZE: u32 = 60;
+pub const MAX_MODULUS_SIZE: u32 = 60;
 #[cfg(target_pointer_width = "64")]
-#[cfg(feature = "int128")]
-type BasicInteger = u64;
+pub type BasicInteger = u64;
 #[cfg(target_pointer_width = "64")]
-#[cfg(feature = "int128")]
-type DoubleNativeInt = u128;
-#[cfg(target_pointer_width = "64")]
-#[cfg(feature = "int128")]
-type uint128_t = u128;
-#[cfg(target_pointer_width = "64")]
-#[cfg(feature = "int128")]
-type int128_t = i128;
-
-#[cfg(target_pointer_width = "64")]
-#[cfg(not(feature = "int128"))]
-const MAX_MODULUS_SIZE: u32 = 57;
-#[cfg(target_pointer_width = "64")]
-#[cfg(not(feature = "int128"))]
-type BasicInteger = u64;
-#[cfg(target_pointer_width = "64")]
-#[cfg(not(feature = "int128"))]
-type DoubleNativeInt = u64;
-#[cfg(target_pointer_width = "64")]
-#[cfg(not(feature = "int128"))]
-type uint128_t = u64;
-#[cfg(target_pointer_width = "64")]
-#[cfg(not(feature = "int128"))]
-type int128_t = i64;
+pub type DoubleNativeInt = u128;
 
 #[cfg(target_pointer_width = "32")]
-const MAX_MODULUS_SIZE: u32 = 28;
-#[cfg(target_pointer_width = "32")]
-type BasicInteger = u32;
+pub const MAX_MODULUS_SIZE: u32 = 28;
 #[cfg(target_pointer_width = "32")]
-type DoubleNativeInt = u64;
+pub type BasicInteger = u32;
 #[cfg(target_pointer_width = "32")]
-type uint128_t = u64;
-#[cfg(target_pointer_width = "32")]
-type int128_t = i64;
-
-
+pub type DoubleNativeInt = u64;
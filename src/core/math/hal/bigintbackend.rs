@@ -0,0 +1,27 @@
+/*
+  This file selects the default big integer backend with Cargo features
+*/
+
+/* Все бэкенды собираются всегда и доступны под именами M2/M4/M6. Признак
+ * Cargo только выбирает, чем будут BigInteger и BigVector: be2 -
+ * фиксированная разрядность, be4 (по умолчанию) - динамическая, ntl - на
+ * num-bigint. Признаки складываются, поэтому при нескольких включённых
+ * приоритет у ntl, затем у be2. */
+pub use super::bigintdyn::backenddyn::{M4Integer, M4Vector};
+pub use super::bigintfxd::backendfxd::{M2Integer, M2Vector};
+pub use super::bigintntl::backendntl::{M6Integer, M6Vector};
+
+#[cfg(feature = "ntl")]
+pub type BigInteger = M6Integer;
+#[cfg(feature = "ntl")]
+pub type BigVector = M6Vector;
+
+#[cfg(all(feature = "be2", not(feature = "ntl")))]
+pub type BigInteger = M2Integer;
+#[cfg(all(feature = "be2", not(feature = "ntl")))]
+pub type BigVector = M2Vector;
+
+#[cfg(not(any(feature = "be2", feature = "ntl")))]
+pub type BigInteger = M4Integer;
+#[cfg(not(any(feature = "be2", feature = "ntl")))]
+pub type BigVector = M4Vector;
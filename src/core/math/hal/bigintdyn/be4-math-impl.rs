@@ -1,257 +1,58 @@
 /*
- This file contains template instantiations for all math classes & functions using math be4
+ This file contains instantiations of the number theory functions for math be4
 */
+use super::backenddyn::{M4Integer, M4Vector};
+use crate::core::math::nbtheory_impl;
+use std::collections::BTreeSet;
 
-
-#[cfg(feature = "WITH_BE4")]
-mod math {
-    use crate::config_core::M4Vector;
-
-    pub struct DiscreteGaussianGeneratorImpl<T>(std::marker::PhantomData<T>);
-    pub struct BinaryUniformGeneratorImpl<T>(std::marker::PhantomData<T>);
-    pub struct TernaryUniformGeneratorImpl<T>(std::marker::PhantomData<T>);
-    pub struct DiscreteUniformGeneratorImpl<T>(std::marker::PhantomData<T>);
-
-    pub fn RootOfUnity<M4Integer>(m: usint, modulo: &M4Integer) -> M4Integer {
-        // implementation here
-    }
-
-    pub fn RootsOfUnity<M4Integer>(m: usint, moduli: &Vec<M4Integer>) -> Vec<M4Integer> {
-        // implementation here
-    }
-
-    pub fn GreatestCommonDivisor(a: &M4Integer, b: &M4Integer) -> M4Integer {
-        // implementation here
-    }
-
-    pub fn MillerRabinPrimalityTest(p: &M4Integer, niter: usint) -> bool {
-        // implementation here
-    }
-
-    pub fn PollardRhoFactorization(n: &M4Integer) -> M4Integer {
-        // implementation here
-    }
-
-    pub fn PrimeFactorize(n: M4Integer, primeFactors: &mut std::collections::HashSet<M4Integer>) {
-        // implementation here
-    }
-
-    pub fn FirstPrime(nBits: u32, m: u32) -> M4Integer {
-        // implementation here
-    }
-
-    pub fn LastPrime(nBits: u32, m: u32) -> M4Integer {
-        // implementation here
-    }
-
-    pub fn NextPrime(q: &M4Integer, m: u32) -> M4Integer {
-        // implementation here
-    }
-
-    pub fn PreviousPrime(q: &M4Integer, m: u32) -> M4Integer {
-        // implementation here
-    }
-
-    pub fn GetTotientList(n: &M4Integer) -> Vec<M4Integer> {
-        // implementation here
-    }
-
-    pub fn PolyMod(dividend: &M4Vector, divisor: &M4Vector, modulus: &M4Integer) -> M4Vector {
-        // implementation here
-    }
-
-    pub fn PolynomialMultiplication(a: &M4Vector, b: &M4Vector) -> M4Vector {
-        // implementation here
-    }
-
-    pub fn GetCyclotomicPolynomial(m: usint, modulus: &M4Integer) -> M4Vector {
-        // implementation here
-    }
-
-    pub fn SyntheticRemainder(
-        dividend: &M4Vector,
-        a: &M4Integer,
-        modulus: &M4Integer,
-    ) -> M4Integer {
-        // implementation here
-    }
-
-    pub fn SyntheticPolyRemainder(
-        dividend: &M4Vector,
-        aList: &M4Vector,
-        modulus: &M4Integer,
-    ) -> M4Vector {
-        // implementation here
-    }
-
-    pub fn PolynomialPower<M4Vector>(input: &M4Vector, power: usint) -> M4Vector {
-        // implementation here
-    }
-
-    pub fn SyntheticPolynomialDivision(
-        dividend: &M4Vector,
-        a: &M4Integer,
-        modulus: &M4Integer,
-    ) -> M4Vector {
-        // implementation here
-    }
-
-    pub fn FindGeneratorCyclic(modulo: &M4Integer) -> M4Integer {
-        // implementation here
-    }
-
-    pub fn IsGenerator(g: &M4Integer, modulo: &M4Integer) -> bool {
-        // implementation here
-    }
-
-    pub fn GetDigits(u: &M4Integer, base: u64, k: u32) -> std::shared_ptr<Vec<i64>> {
-        // implementation here
-    }
-
-    pub struct Matrix<T>(std::marker::PhantomData<T>);
+/// Первообразный корень степени m из единицы по модулю простого q
+pub fn root_of_unity(m: u64, modulo: &M4Integer) -> M4Integer {
+    nbtheory_impl::root_of_unity(m, modulo)
 }
 
-#[cfg(feature = "WITH_BE4")]
-mod lbcrypto {
-    use crate::config_core::M4Integer;
-    use crate::math::{
-        BinaryUniformGeneratorImpl, DiscreteGaussianGeneratorImpl, DiscreteUniformGeneratorImpl,
-        Matrix, TernaryUniformGeneratorImpl,
-    };
-
-    impl DiscreteGaussianGeneratorImpl<M4Vector> {}
-    impl BinaryUniformGeneratorImpl<M4Vector> {}
-    impl TernaryUniformGeneratorImpl<M4Vector> {}
-    impl DiscreteUniformGeneratorImpl<M4Vector> {}
-
-    impl M4Integer {
-        pub fn RootOfUnity(m: usint, modulo: &M4Integer) -> M4Integer {
-            // implementation here
-        }
-
-        pub fn RootsOfUnity(m: usint, moduli: &Vec<M4Integer>) -> Vec<M4Integer> {
-            // implementation here
-        }
-
-        pub fn GreatestCommonDivisor(a: &M4Integer, b: &M4Integer) -> M4Integer {
-            // implementation here
-        }
-
-        pub fn MillerRabinPrimalityTest(p: &M4Integer, niter: usint) -> bool {
-            // implementation here
-        }
-
-        pub fn PollardRhoFactorization(n: &M4Integer) -> M4Integer {
-            // implementation here
-        }
-
-        pub fn PrimeFactorize(
-            n: M4Integer,
-            primeFactors: &mut std::collections::HashSet<M4Integer>,
-        ) {
-            // implementation here
-        }
-
-        pub fn FirstPrime(nBits: u32, m: u32) -> M4Integer {
-            // implementation here
-        }
-
-        pub fn LastPrime(nBits: u32, m: u32) -> M4Integer {
-            // implementation here
-        }
-
-        pub fn NextPrime(q: &M4Integer, m: u32) -> M4Integer {
-            // implementation here
-        }
-
-        pub fn PreviousPrime(q: &M4Integer, m: u32) -> M4Integer {
-            // implementation here
-        }
-
-        pub fn GetTotientList(n: &M4Integer) -> Vec<M4Integer> {
-            // implementation here
-        }
-
-        pub fn PolyMod(dividend: &M4Vector, divisor: &M4Vector, modulus: &M4Integer) -> M4Vector {
-            // implementation here
-        }
-
-        pub fn PolynomialMultiplication(a: &M4Vector, b: &M4Vector) -> M4Vector {
-            // implementation here
-        }
-
-        pub fn GetCyclotomicPolynomial(m: usint, modulus: &M4Integer) -> M4Vector {
-            // implementation here
-        }
-
-        pub fn SyntheticRemainder(
-            dividend: &M4Vector,
-            a: &M4Integer,
-            modulus: &M4Integer,
-        ) -> M4Integer {
-            // implementation here
-        }
-
-        pub fn SyntheticPolyRemainder(
-            dividend: &M4Vector,
-            aList: &M4Vector,
-            modulus: &M4Integer,
-        ) -> M4Vector {
-            // implementation here
-        }
-
-        pub fn PolynomialPower<M4Vector>(input: &M4Vector, power: usint) -> M4Vector {
-            // implementation here
-        }
+/// Корни степени m для каждого модуля из moduli
+pub fn roots_of_unity(m: u64, moduli: &[M4Integer]) -> Vec<M4Integer> {
+    moduli.iter().map(|q| root_of_unity(m, q)).collect()
+}
 
-        pub fn SyntheticPolynomialDivision(
-            dividend: &M4Vector,
-            a: &M4Integer,
-            modulus: &M4Integer,
-        ) -> M4Vector {
-            // implementation here
-        }
+pub fn greatest_common_divisor(a: &M4Integer, b: &M4Integer) -> M4Integer {
+    nbtheory_impl::greatest_common_divisor(a, b)
+}
 
-        pub fn FindGeneratorCyclic(modulo: &M4Integer) -> M4Integer {
-            // implementation here
-        }
+pub fn miller_rabin_primality_test(p: &M4Integer) -> bool {
+    nbtheory_impl::miller_rabin_primality_test(p)
+}
 
-        pub fn IsGenerator(g: &M4Integer, modulo: &M4Integer) -> bool {
-            // implementation here
-        }
+pub fn pollard_rho_factorization(n: &M4Integer) -> M4Integer {
+    nbtheory_impl::pollard_rho_factorization(n)
+}
 
-        pub fn GetDigits(u: &M4Integer, base: u64, k: u32) -> std::shared_ptr<Vec<i64>> {
-            // implementation here
-        }
-    }
+pub fn prime_factorize(n: &M4Integer) -> BTreeSet<M4Integer> {
+    nbtheory_impl::prime_factorize(n)
+}
 
-    impl Matrix<M4Integer> {}
-    impl Matrix<M4Vector> {}
+pub fn first_prime(n_bits: u32, m: u64) -> M4Integer {
+    nbtheory_impl::first_prime(n_bits, m)
 }
 
-#[cfg(feature = "WITH_BE4")]
-fn main() {
-    // implementation here
+pub fn last_prime(n_bits: u32, m: u64) -> M4Integer {
+    nbtheory_impl::last_prime(n_bits, m)
 }
 
-#[cfg(not(feature = "WITH_BE4"))]
-fn main() {
-    // implementation here
+pub fn next_prime(q: &M4Integer, m: u64) -> M4Integer {
+    nbtheory_impl::next_prime(q, m)
 }
 
-#[cfg(feature = "WITH_BE4")]
-mod cereal {
-    use crate::config_core::{M4Integer, M4Vector};
+pub fn previous_prime(q: &M4Integer, m: u64) -> M4Integer {
+    nbtheory_impl::previous_prime(q, m)
+}
 
-    impl M4Integer {
-        pub const fn SerializedVersion() -> u32 {
-            // implementation here
-        }
-    }
+pub fn find_generator(q: &M4Integer) -> M4Integer {
+    nbtheory_impl::find_generator(q)
+}
 
-    impl M4Vector {
-        pub const fn SerializedVersion() -> u32 {
-            // implementation here
-        }
-    }
+/// Многочлен деления круга Φ_m с коэффициентами по модулю modulus
+pub fn get_cyclotomic_polynomial(m: u64, modulus: &M4Integer) -> M4Vector {
+    let coefficients = nbtheory_impl::get_cyclotomic_polynomial(m, modulus);
+    M4Vector::from_values(&coefficients, modulus.clone())
 }
@@ -1,8 +1,16 @@
+/// MATHBACKEND 4 type definitions
+pub mod backenddyn;
+
+/// number theory instantiations for MATHBACKEND 4
+#[path = "be4-math-impl.rs"]
+pub mod be4_math_impl;
+
 /// rings of ubints
 pub mod mubintvecdyn;
 
 /// dynamically sized unsigned big integers or ubints
 pub mod ubintdyn;
 
-/// transforms for dynamic
-pub mod transformdyn;
+// NTT для ubint реализована обобщённо в transform; перевод transforms
+// из OpenFHE не собирается и исключён из дерева модулей
+// pub mod transformdyn;
@@ -1,735 +1,352 @@
 /*
-  This file contains mubintvecdyn, a <vector> of buintdyn, with associated modulus and modulo math operators
+  This file contains mubintvecdyn, a vector of ubints with associated modulus and modulo math operators
 */
-
- // Assuming the functionality of the included headers needs to be replicated in Rust,
-// the following Rust code includes necessary crates and modules.
-// Note: Rust does not have a direct equivalent of C++'s header guards or conditional compilation based on macros in the same way.
-// Conditional compilation in Rust is handled through features in Cargo.toml and cfg attributes.
-
-// In Rust, external crates are added in Cargo.toml, not in the source files.
-// Assuming the functionality provided by the C++ headers, you might need crates like `num-bigint`, `serde` for serialization, and others.
-// Here is an example of how you might start translating the includes and guards to Rust, focusing on functionality.
-
-// Cargo.toml dependencies (example):
-// [dependencies]
-// num-bigint = "0.4"
-// serde = { version = "1.0", features = ["derive"] }
-// thiserror = "1.0"
-
-// Rust does not use include guards or conditional compilation in the same way as C++.
-// Features in Rust are enabled in Cargo.toml and checked with cfg attribute.
-
-// Example Rust code starting point:
-
-// main.rs or lib.rs
-extern crate num_bigint;
-extern crate serde;
-extern crate thiserror;
-
-use num_bigint::BigUint;
-use serde::{Serialize, Deserialize};
-use thiserror::Error;
-use std::collections::VecDeque;
+use super::ubintdyn::Ubint;
+use crate::core::math::hal::intnat::ubintnat::NativeInt;
+use crate::core::math::hal::vector::BigVectorInterface;
 use std::fmt;
-use std::io;
-
-
-
-extern crate num_bigint;
-extern crate num_traits;
-
-use num_bigint::BigUint;
-use num_traits::Zero;
-use std::vec::Vec;
-
-struct BigVector {
-    data: Vec<BigUint>,
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+/// Вектор длинных целых динамической разрядности по модулю q.
+///
+/// Тот же интерфейс, что у Mubintvec, но модуль не ограничен
+/// разрядностью: элементы растут вместе с ним.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Mubintvec<T: NativeInt> {
+    m_modulus: Ubint<T>,
+    m_data: Vec<Ubint<T>>,
 }
 
-impl BigVector {
-    fn new() -> BigVector {
-        BigVector { data: Vec::new() }
-    }
+/// Вектор длинных целых динамической разрядности по умолчанию
+pub type BigVector = Mubintvec<u64>;
 
-    // Additional methods to manipulate BigVector can be added here
-}
-
-
-use std::ops::{Index, IndexMut};
-
-#[derive(Clone, Debug)]
-enum State {
-    GARBAGE,
-    INITIALIZED,
-}
-
-#[derive(Clone, Debug)]
-struct mubintvec<ubint_el_t> {
-    m_modulus: ubint_el_t,
-    m_modulus_state: State,
-    m_data: Vec<ubint_el_t>,
-}
-
-impl<ubint_el_t> mubintvec<ubint_el_t> {
-    fn new() -> Self {
-        Self {
-            m_modulus: Default::default(),
-            m_modulus_state: State::GARBAGE,
-            m_data: Vec::new(),
-        }
-    }
-
-    fn single(val: ubint_el_t, modulus: ubint_el_t) -> Self {
-        let mut vec = Self::new();
-        vec.m_data.push(val);
-        vec.m_modulus = modulus;
-        vec
+impl<T: NativeInt> Mubintvec<T> {
+    /// Нулевой вектор длины length с модулем modulus
+    pub fn new(length: usize, modulus: Ubint<T>) -> Self {
+        Self::with_value(length, modulus, Ubint::default())
     }
 
-    fn with_length(length: usize) -> Self {
-        Self {
-            m_modulus: Default::default(),
-            m_modulus_state: State::GARBAGE,
-            m_data: vec![Default::default(); length],
-        }
+    /// Вектор длины length, все элементы которого равны val mod modulus
+    pub fn with_value(length: usize, modulus: Ubint<T>, val: Ubint<T>) -> Self {
+        let mut res = Self {
+            m_modulus: Ubint::default(),
+            m_data: vec![],
+        };
+        res.m_data = vec![val.modulo(&modulus); length];
+        res.set_modulus(modulus);
+        res
     }
 
-    fn with_length_and_modulus(length: usize, modulus: ubint_el_t) -> Self {
-        Self {
-            m_modulus: modulus,
-            m_modulus_state: State::INITIALIZED,
-            m_data: vec![Default::default(); length],
-        }
+    /// Вектор из значений values, приведённых по модулю modulus
+    pub fn from_values(values: &[Ubint<T>], modulus: Ubint<T>) -> Self {
+        let data = values.iter().map(|x| x.modulo(&modulus)).collect();
+        let mut res = Self::new(0, modulus);
+        res.m_data = data;
+        res
     }
 
-    fn with_length_modulus_and_value(length: usize, modulus: ubint_el_t, val: ubint_el_t) -> Self {
-        Self {
-            m_modulus: modulus,
-            m_modulus_state: State::INITIALIZED,
-            m_data: vec![val; length],
-        }
-    }
-
-    fn with_length_modulus_and_string(length: usize, modulus: ubint_el_t, s: &str) -> Self {
-        Self {
-            m_modulus: ubint_el_t::from_str(s).unwrap(),
-            m_modulus_state: State::INITIALIZED,
-            m_data: vec![Default::default(); length],
-        }
-    }
-
-    fn with_vector_string_and_modulus(s: &[String], modulus: ubint_el_t) -> Self {
-        Self {
-            m_modulus: modulus,
-            m_modulus_state: State::INITIALIZED,
-            m_data: s.iter().map(|x| ubint_el_t::from_str(x).unwrap()).collect(),
-        }
+    pub fn get_modulus(&self) -> &Ubint<T> {
+        &self.m_modulus
     }
 
-    fn with_vector_string_and_string(s: &[String], modulus: &str) -> Self {
-        Self {
-            m_modulus: ubint_el_t::from_str(modulus).unwrap(),
-            m_modulus_state: State::INITIALIZED,
-            m_data: s.iter().map(|x| ubint_el_t::from_str(x).unwrap()).collect(),
+    /// Меняет модуль без пересчёта элементов
+    pub fn set_modulus(&mut self, value: Ubint<T>) {
+        if value == Ubint::default() {
+            panic!("Mubintvec: the modulus must be positive")
         }
-    }
-
-    fn set_modulus(&mut self, value: ubint_el_t) {
         self.m_modulus = value;
-        self.m_modulus_state = State::INITIALIZED;
-    }
-
-    fn set_modulus_string(&mut self, value: &str) {
-        self.m_modulus = ubint_el_t::from_str(value).unwrap();
-        self.m_modulus_state = State::INITIALIZED;
-    }
-
-    fn set_modulus_mubintvec(&mut self, value: &mubintvec<ubint_el_t>) {
-        self.m_modulus = value.get_modulus();
-        self.m_modulus_state = State::INITIALIZED;
     }
 
-    fn switch_modulus(&mut self, value: ubint_el_t) {
-        // TODO: Implement switch_modulus
-    }
-
-    fn get_length(&self) -> usize {
+    pub fn get_length(&self) -> usize {
         self.m_data.len()
     }
 
-    fn at(&self, i: usize) -> &ubint_el_t {
-        if !self.index_check(i) {
-            panic!("index out of range");
-        }
-        &self.m_data[i]
-    }
-
-    fn at_mut(&mut self, i: usize) -> &mut ubint_el_t {
-        if !self.index_check(i) {
-            panic!("index out of range");
-        }
-        &mut self.m_data[i]
-    }
-
-    fn index_check(&self, length: usize) -> bool {
-        length < self.m_data.len()
-    }
-
-    fn is_modulus_set(&self) -> bool {
-        self.m_modulus_state == State::INITIALIZED
-    }
-
-    fn get_modulus(&self) -> &ubint_el_t {
-        if self.m_modulus_state != State::INITIALIZED {
-            panic!("GetModulus() on uninitialized mubintvec");
-        }
-        &self.m_modulus
-    }
-
-    fn mod_eq(&mut self, modulus: ubint_el_t) -> Self {
-        // TODO: Implement mod_eq
-        Self::new()
-    }
-
-    fn mod_add(&self, b: ubint_el_t) -> Self {
-        // TODO: Implement mod_add
-        Self::new()
-    }
-
-    fn mod_add_at_index(&self, i: usize, b: ubint_el_t) -> Self {
-        // TODO: Implement mod_add_at_index
-        Self::new()
-    }
-
-    fn mod_add_no_check_eq(&mut self, b: &mubintvec<ubint_el_t>) -> Self {
-        // TODO: Implement mod_add_no_check_eq
-        Self::new()
-    }
-
-    fn mod_sub(&self, b: ubint_el_t) -> Self {
-        // TODO: Implement mod_sub
-        Self::new()
-    }
-
-    fn mod_sub_eq(&mut self, b: &mubintvec<ubint_el_t>) -> Self {
-        // TODO: Implement mod_sub_eq
-        Self::new()
-    }
-
-    fn mod_mul(&self, b: ubint_el_t) -> Self {
-        // TODO: Implement mod_mul
-        Self::new()
-    }
-
-    fn mod_mul_eq(&mut self, b: &mubintvec<ubint_el_t>) -> Self {
-        // TODO: Implement mod_mul_eq
-        Self::new()
-    }
-
-    fn mod_mul_no_check_eq(&mut self, b: &mubintvec<ubint_el_t>) -> Self {
-        // TODO: Implement mod_mul_no_check_eq
-        Self::new()
-    }
-
-    fn mod_exp(&self, b: ubint_el_t) -> Self {
-        // TODO: Implement mod_exp
-        Self::new()
-    }
-
-    fn mod_exp_eq(&mut self, b: &mubintvec<ubint_el_t>) -> Self {
-        // TODO: Implement mod_exp_eq
-        Self::new()
-    }
-
-    fn mod_inverse(&self) -> Self {
-        // TODO: Implement mod_inverse
-        Self::new()
-    }
-
-    fn mod_inverse_eq(&mut self) -> Self {
-        // TODO: Implement mod_inverse_eq
-        Self::new()
-    }
-
-    fn mod_by_two(&self) -> Self {
-        // TODO: Implement mod_by_two
-        Self::new()
-    }
-
-    fn mod_by_two_eq(&mut self) -> Self {
-        // TODO: Implement mod_by_two_eq
-        Self::new()
-    }
-
-    fn multiply_and_round(&self, p: ubint_el_t, q: ubint_el_t) -> Self {
-        // TODO: Implement multiply_and_round
-        Self::new()
-    }
-
-    fn multiply_and_round_eq(&mut self, p: ubint_el_t, q: ubint_el_t) -> Self {
-        // TODO: Implement multiply_and_round_eq
-        Self::new()
-    }
-
-    fn divide_and_round(&self, q: ubint_el_t) -> Self {
-        // TODO: Implement divide_and_round
-        Self::new()
+    pub fn len(&self) -> usize {
+        self.m_data.len()
     }
 
-    fn divide_and_round_eq(&mut self, q: ubint_el_t) -> Self {
-        // TODO: Implement divide_and_round_eq
-        Self::new()
+    pub fn is_empty(&self) -> bool {
+        self.m_data.is_empty()
     }
 
-    fn get_digit_at_index_for_base(&self, index: usize, base: usize) -> Self {
-        // TODO: Implement get_digit_at_index_for_base
-        Self::new()
+    pub fn iter(&self) -> std::slice::Iter<'_, Ubint<T>> {
+        self.m_data.iter()
     }
-}
 
-impl<ubint_el_t> Index<usize> for mubintvec<ubint_el_t> {
-    type Output = ubint_el_t;
-
-    fn index(&self, i: usize) -> &Self::Output {
-        self.at(i)
+    pub fn as_slice(&self) -> &[Ubint<T>] {
+        &self.m_data
     }
-}
-
-impl<ubint_el_t> IndexMut<usize> for mubintvec<ubint_el_t> {
-    fn index_mut(&mut self, i: usize) -> &mut Self::Output {
-        self.at_mut(i)
-    }
-}
 
-impl<ubint_el_t> std::fmt::Display for mubintvec<ubint_el_t> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let len = self.m_data.len();
-        write!(f, "[")?;
-        for (i, val) in self.m_data.iter().enumerate() {
-            write!(f, "{}", val)?;
-            if i == len - 1 {
-                write!(f, "]")?;
+    /// Переводит элементы в модуль new_modulus через центрированный подъём:
+    /// элемент x > q/2 считается отрицательным числом x - q.
+    pub fn switch_modulus(&mut self, new_modulus: Ubint<T>) {
+        let q = self.m_modulus.clone();
+        let half_q = &q >> 1;
+        for x in self.m_data.iter_mut() {
+            *x = if *x > half_q {
+                (&new_modulus - &(&q - &*x).modulo(&new_modulus)).modulo(&new_modulus)
             } else {
-                write!(f, " ")?;
-            }
+                x.modulo(&new_modulus)
+            };
         }
-        write!(f, " modulus: {}", self.m_modulus)
-    }
-}
-
-impl<ubint_el_t> lbcrypto::BigVectorInterface<mubintvec<ubint_el_t>, ubint_el_t> for mubintvec<ubint_el_t> {}
-
-impl<ubint_el_t> lbcrypto::Serializable for mubintvec<ubint_el_t> {
-    fn save(&self, ar: &mut dyn lbcrypto::CerealizeArchive) {
-        ar.write_named_value("d", &self.m_data);
-        ar.write_named_value("m", &self.m_modulus);
-        ar.write_named_value("ms", &self.m_modulus_state);
+        self.set_modulus(new_modulus);
     }
 
-    fn load(&mut self, ar: &mut dyn lbcrypto::CerealizeArchive) {
-        let version = ar.get_version();
-        if version > Self::serialized_version() {
-            panic!(
-                "serialized object version {} is from a later version of the library",
-                version
-            );
+    /// Цифры всех элементов с номером index в системе счисления с основанием 2^bits
+    pub fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> Self {
+        let mut res = self.clone();
+        for x in res.m_data.iter_mut() {
+            *x = Ubint::from(x.get_digit_at_index_for_base(index, bits)).modulo(&self.m_modulus);
         }
-        self.m_data = ar.read_named_value("d").unwrap();
-        self.m_modulus = ar.read_named_value("m").unwrap();
-        self.m_modulus_state = ar.read_named_value("ms").unwrap();
-    }
-
-    fn serialized_object_name(&self) -> String {
-        "ExpVector".to_string()
+        res
     }
-
-    fn serialized_version() -> u32 {
-        1
-    }
-}
-
-use std::ops::{Index, IndexMut};
-use std::string::String;
-use std::vec::Vec;
-
-pub struct BigInteger {
-    // implementation details
-}
-
-pub struct mubintvec<T> {
-    m_data: Vec<T>,
-    m_modulus: T,
-    m_modulus_state: State,
 }
 
-pub type xmubintvec = mubintvec<BigInteger>;
-pub type BigVector = xmubintvec;
-
-impl<T> mubintvec<T> {
-    pub fn new() -> Self {
-        mubintvec {
-            m_data: Vec::new(),
-            m_modulus: T::default(),
-            m_modulus_state: State::UNINITIALIZED,
-        }
-    }
-
-    pub fn single(val: T, modulus: T) -> Self {
-        let mut vec = mubintvec::new();
-        vec.m_data.push(val);
-        vec.m_modulus = modulus;
-        vec.m_modulus_state = State::INITIALIZED;
-        vec
+/* Операции со скаляром */
+impl<T: NativeInt> Mubintvec<T> {
+    pub fn mod_add(&self, b: &Ubint<T>) -> Self {
+        let mut res = self.clone();
+        res.mod_add_eq(b);
+        res
     }
 
-    pub fn with_length(length: usize) -> Self {
-        mubintvec {
-            m_data: Vec::with_capacity(length),
-            m_modulus: T::default(),
-            m_modulus_state: State::UNINITIALIZED,
-        }
-    }
-
-    pub fn with_length_and_modulus(length: usize, modulus: T) -> Self {
-        mubintvec {
-            m_data: Vec::with_capacity(length),
-            m_modulus,
-            m_modulus_state: State::INITIALIZED,
-        }
-    }
-
-    pub fn with_length_modulus_and_value(length: usize, modulus: T, val: T) -> Self {
-        mubintvec {
-            m_data: vec![val; length],
-            m_modulus,
-            m_modulus_state: State::INITIALIZED,
-        }
-    }
-
-    pub fn with_length_and_modulus_string(length: usize, modulus: String) -> Self {
-        mubintvec {
-            m_data: Vec::with_capacity(length),
-            m_modulus: T::from(modulus),
-            m_modulus_state: State::INITIALIZED,
-        }
-    }
-
-    pub fn with_strings(s: Vec<String>, modulus: T) -> Self {
-        let mut vec = mubintvec::with_length(s.len());
-        for i in 0..s.len() {
-            vec.m_data[i] = T::from(s[i].clone());
-        }
-        vec.m_modulus = modulus;
-        vec.m_modulus_state = State::INITIALIZED;
-        vec
+    pub fn mod_add_eq(&mut self, b: &Ubint<T>) -> &mut Self {
+        let q = self.m_modulus.clone();
+        let b = b.modulo(&q);
+        self.m_data
+            .iter_mut()
+            .for_each(|x| *x = x.mod_add_fast(&b, &q));
+        self
     }
 
-    pub fn with_strings_and_modulus_string(s: Vec<String>, modulus: String) -> Self {
-        let mut vec = mubintvec::with_length(s.len());
-        for i in 0..s.len() {
-            vec.m_data[i] = T::from(s[i].clone());
-        }
-        vec.m_modulus = T::from(modulus);
-        vec.m_modulus_state = State::INITIALIZED;
-        vec
+    /// Прибавляет b только к элементу с номером i
+    pub fn mod_add_at_index_eq(&mut self, i: usize, b: &Ubint<T>) -> &mut Self {
+        let q = self.m_modulus.clone();
+        self.m_data[i] = self.m_data[i].mod_add(b, &q);
+        self
     }
 
-    pub fn set_modulus(&mut self, value: T) {
-        self.m_modulus = value;
-        self.m_modulus_state = State::INITIALIZED;
+    pub fn mod_sub(&self, b: &Ubint<T>) -> Self {
+        let mut res = self.clone();
+        res.mod_sub_eq(b);
+        res
     }
 
-    pub fn set_modulus_string(&mut self, value: String) {
-        self.m_modulus = T::from(value);
-        self.m_modulus_state = State::INITIALIZED;
+    pub fn mod_sub_eq(&mut self, b: &Ubint<T>) -> &mut Self {
+        let q = self.m_modulus.clone();
+        let b = b.modulo(&q);
+        self.m_data
+            .iter_mut()
+            .for_each(|x| *x = x.mod_sub_fast(&b, &q));
+        self
     }
 
-    pub fn set_modulus_from_vec(&mut self, value: &mubintvec<T>) {
-        self.m_modulus = value.m_modulus.clone();
-        self.m_modulus_state = State::INITIALIZED;
+    /// Умножение на скаляр: константа Барретта вычисляется один раз на весь вектор
+    pub fn mod_mul(&self, b: &Ubint<T>) -> Self {
+        let mut res = self.clone();
+        res.mod_mul_eq(b);
+        res
     }
 
-    pub fn length(&self) -> usize {
-        self.m_data.len()
-    }
-
-    pub fn at(&self, i: usize) -> &T {
-        if !self.index_check(i) {
-            panic!("index out of range");
-        }
-        &self.m_data[i]
+    pub fn mod_mul_eq(&mut self, b: &Ubint<T>) -> &mut Self {
+        let q = self.m_modulus.clone();
+        let mu = q.compute_mu();
+        let b = b.modulo(&q);
+        self.m_data
+            .iter_mut()
+            .for_each(|x| *x = x.mod_mul_fast(&b, &q, &mu));
+        self
     }
 
-    pub fn at_mut(&mut self, i: usize) -> &mut T {
-        if !self.index_check(i) {
-            panic!("index out of range");
-        }
-        &mut self.m_data[i]
+    /// Возведение всех элементов в степень b
+    pub fn mod_exp(&self, b: &Ubint<T>) -> Self {
+        let mut res = self.clone();
+        let q = self.m_modulus.clone();
+        res.m_data.iter_mut().for_each(|x| *x = x.mod_exp(b, &q));
+        res
     }
-}
-
-impl<T> Index<usize> for mubintvec<T> {
-    type Output = T;
 
-    fn index(&self, i: usize) -> &Self::Output {
-        &self.m_data[i]
+    /// Обратные ко всем элементам; паникует, если какой-то элемент необратим
+    pub fn mod_inverse(&self) -> Self {
+        let mut res = self.clone();
+        let q = self.m_modulus.clone();
+        res.m_data.iter_mut().for_each(|x| *x = x.mod_inverse(&q));
+        res
     }
 }
 
-impl<T> IndexMut<usize> for mubintvec<T> {
-    fn index_mut(&mut self, i: usize) -> &mut Self::Output {
-        &mut self.m_data[i]
+/* Поэлементные операции с вектором */
+impl<T: NativeInt> Mubintvec<T> {
+    pub fn mod_add_vec(&self, b: &Self) -> Self {
+        let mut res = self.clone();
+        res.mod_add_vec_eq(b);
+        res
     }
-}
-
-enum State {
-    UNINITIALIZED,
-    INITIALIZED,
-}
-
-use std::fmt;
-use std::ops::{Add, Mul, Sub};
-
-#[derive(Clone, Debug)]
-struct ubint_el_t {
-    // Define the structure of ubint_el_t here
-}
 
-#[derive(Clone, Debug)]
-enum State {
-    GARBAGE,
-    INITIALIZED,
-}
-
-#[derive(Clone, Debug)]
-struct mubintvec {
-    m_modulus: ubint_el_t,
-    m_modulus_state: State,
-    m_data: Vec<ubint_el_t>,
-}
-
-impl mubintvec {
-    fn new() -> Self {
-        mubintvec {
-            m_modulus: ubint_el_t::default(),
-            m_modulus_state: State::GARBAGE,
-            m_data: Vec::new(),
+    pub fn mod_add_vec_eq(&mut self, b: &Self) -> &mut Self {
+        self.check(b, "ModAdd");
+        let q = self.m_modulus.clone();
+        for (x, y) in self.m_data.iter_mut().zip(b.m_data.iter()) {
+            *x = x.mod_add_fast(y, &q);
         }
+        self
     }
 
-    fn switch_modulus(&self, value: &ubint_el_t) {
-        // Implement SwitchModulus function here
+    pub fn mod_sub_vec(&self, b: &Self) -> Self {
+        let mut res = self.clone();
+        res.mod_sub_vec_eq(b);
+        res
     }
 
-    fn get_modulus(&self) -> Result<&ubint_el_t, String> {
-        if self.m_modulus_state != State::INITIALIZED {
-            Err("GetModulus() on uninitialized mubintvec".to_string())
-        } else {
-            Ok(&self.m_modulus)
+    pub fn mod_sub_vec_eq(&mut self, b: &Self) -> &mut Self {
+        self.check(b, "ModSub");
+        let q = self.m_modulus.clone();
+        for (x, y) in self.m_data.iter_mut().zip(b.m_data.iter()) {
+            *x = x.mod_sub_fast(y, &q);
         }
-    }
-
-    fn modulus(&self, modulus: &ubint_el_t) -> mubintvec {
-        // Implement Mod function here
-        mubintvec::new()
-    }
-
-    fn modulus_eq(&mut self, modulus: &ubint_el_t) -> &mut mubintvec {
-        // Implement ModEq function here
         self
     }
 
-    fn modulus_add(&self, b: &ubint_el_t) -> mubintvec {
-        // Implement ModAdd function here
-        mubintvec::new()
+    /// Поэлементное произведение с редукцией Барретта
+    pub fn mod_mul_vec(&self, b: &Self) -> Self {
+        let mut res = self.clone();
+        res.mod_mul_vec_eq(b);
+        res
     }
 
-    fn modulus_add_eq(&mut self, b: &ubint_el_t) -> &mut mubintvec {
-        // Implement ModAddEq function here
+    pub fn mod_mul_vec_eq(&mut self, b: &Self) -> &mut Self {
+        self.check(b, "ModMul");
+        let q = self.m_modulus.clone();
+        let mu = q.compute_mu();
+        for (x, y) in self.m_data.iter_mut().zip(b.m_data.iter()) {
+            *x = x.mod_mul_fast(y, &q, &mu);
+        }
         self
     }
 
-    fn modulus_add_at_index(&self, i: usize, b: &ubint_el_t) -> mubintvec {
-        // Implement ModAddAtIndex function here
-        mubintvec::new()
-    }
-
-    fn modulus_add_at_index_eq(&mut self, i: usize, b: &ubint_el_t) -> &mut mubintvec {
-        // Implement ModAddAtIndexEq function here
-        self
+    fn check(&self, b: &Self, op: &str) {
+        if self.m_modulus != b.m_modulus {
+            panic!("{} called on Mubintvec's with different parameters.", op)
+        }
+        if self.m_data.len() != b.m_data.len() {
+            panic!("{} called on Mubintvec's of different lengths.", op)
+        }
     }
+}
 
-    fn modulus_add_vec(&self, b: &mubintvec) -> mubintvec {
-        // Implement ModAdd function here
-        mubintvec::new()
-    }
+impl<T: NativeInt> BigVectorInterface for Mubintvec<T> {
+    type Integer = Ubint<T>;
 
-    fn modulus_add_vec_eq(&mut self, b: &mubintvec) -> &mut mubintvec {
-        // Implement ModAddEq function here
-        self
+    fn new(length: usize, modulus: Self::Integer) -> Self {
+        Mubintvec::new(length, modulus)
     }
 
-    fn modulus_add_no_check_eq(&mut self, b: &mubintvec) -> &mut mubintvec {
-        // Implement ModAddNoCheckEq function here
-        self
+    fn from_values(values: &[Self::Integer], modulus: Self::Integer) -> Self {
+        Mubintvec::from_values(values, modulus)
     }
 
-    fn modulus_sub(&self, b: &ubint_el_t) -> mubintvec {
-        // Implement ModSub function here
-        mubintvec::new()
+    fn get_modulus(&self) -> &Self::Integer {
+        Mubintvec::get_modulus(self)
     }
 
-    fn modulus_sub_eq(&mut self, b: &ubint_el_t) -> &mut mubintvec {
-        // Implement ModSubEq function here
-        self
+    fn set_modulus(&mut self, value: Self::Integer) {
+        Mubintvec::set_modulus(self, value)
     }
 
-    fn modulus_sub_vec(&self, b: &mubintvec) -> mubintvec {
-        // Implement ModSub function here
-        mubintvec::new()
+    fn get_length(&self) -> usize {
+        Mubintvec::get_length(self)
     }
 
-    fn modulus_sub_vec_eq(&mut self, b: &mubintvec) -> &mut mubintvec {
-        // Implement ModSubEq function here
-        self
+    fn switch_modulus(&mut self, new_modulus: Self::Integer) {
+        Mubintvec::switch_modulus(self, new_modulus)
     }
 
-    fn modulus_mul(&self, b: &ubint_el_t) -> mubintvec {
-        // Implement ModMul function here
-        mubintvec::new()
+    fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> Self {
+        Mubintvec::get_digit_at_index_for_base(self, index, bits)
     }
 
-    fn modulus_mul_eq(&mut self, b: &ubint_el_t) -> &mut mubintvec {
-        // Implement ModMulEq function here
-        self
+    fn mod_add(&self, b: &Self::Integer) -> Self {
+        Mubintvec::mod_add(self, b)
     }
 
-    fn modulus_mul_vec(&self, b: &mubintvec) -> mubintvec {
-        // Implement ModMul function here
-        mubintvec::new()
+    fn mod_sub(&self, b: &Self::Integer) -> Self {
+        Mubintvec::mod_sub(self, b)
     }
 
-    fn modulus_mul_vec_eq(&mut self, b: &mubintvec) -> &mut mubintvec {
-        // Implement ModMulEq function here
-        self
+    fn mod_mul(&self, b: &Self::Integer) -> Self {
+        Mubintvec::mod_mul(self, b)
     }
 
-    fn modulus_mul_no_check_eq(&mut self, b: &mubintvec) -> &mut mubintvec {
-        // Implement ModMulNoCheckEq function here
-        self
+    fn mod_exp(&self, b: &Self::Integer) -> Self {
+        Mubintvec::mod_exp(self, b)
     }
 
-    fn modulus_exp(&self, b: &ubint_el_t) -> mubintvec {
-        // Implement ModExp function here
-        mubintvec::new()
+    fn mod_inverse(&self) -> Self {
+        Mubintvec::mod_inverse(self)
     }
 
-    fn modulus_exp_eq(&mut self, b: &ubint_el_t) -> &mut mubintvec {
-        // Implement ModExpEq function here
-        self
+    fn mod_add_vec(&self, b: &Self) -> Self {
+        Mubintvec::mod_add_vec(self, b)
     }
 
-    fn modulus_inverse(&self) -> mubintvec {
-        // Implement ModInverse function here
-        mubintvec::new()
+    fn mod_sub_vec(&self, b: &Self) -> Self {
+        Mubintvec::mod_sub_vec(self, b)
     }
 
-    fn modulus_inverse_eq(&mut self) -> &mut mubintvec {
-        // Implement ModInverseEq function here
-        self
+    fn mod_mul_vec(&self, b: &Self) -> Self {
+        Mubintvec::mod_mul_vec(self, b)
     }
+}
 
-    fn modulus_by_two(&self) -> mubintvec {
-        // Implement ModByTwo function here
-        mubintvec::new()
-    }
+impl<T: NativeInt> Index<usize> for Mubintvec<T> {
+    type Output = Ubint<T>;
 
-    fn modulus_by_two_eq(&mut self) -> &mut mubintvec {
-        // Implement ModByTwoEq function here
-        self
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.m_data[idx]
     }
+}
 
-    fn multiply_and_round(&self, p: &ubint_el_t, q: &ubint_el_t) -> mubintvec {
-        // Implement MultiplyAndRound function here
-        mubintvec::new()
+/// Запись через индекс не приводит значение по модулю: записывайте только
+/// значения меньше модуля
+impl<T: NativeInt> IndexMut<usize> for Mubintvec<T> {
+    fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
+        &mut self.m_data[idx]
     }
+}
 
-    fn multiply_and_round_eq(&mut self, p: &ubint_el_t, q: &ubint_el_t) -> &mut mubintvec {
-        // Implement MultiplyAndRoundEq function here
+impl<T: NativeInt> Add for Mubintvec<T> {
+    type Output = Self;
+    fn add(mut self, rhs: Self) -> Self {
+        self.mod_add_vec_eq(&rhs);
         self
     }
+}
 
-    fn divide_and_round(&self, q: &ubint_el_t) -> mubintvec {
-        // Implement DivideAndRound function here
-        mubintvec::new()
-    }
-
-    fn divide_and_round_eq(&mut self, q: &ubint_el_t) -> &mut mubintvec {
-        // Implement DivideAndRoundEq function here
+impl<T: NativeInt> Sub for Mubintvec<T> {
+    type Output = Self;
+    fn sub(mut self, rhs: Self) -> Self {
+        self.mod_sub_vec_eq(&rhs);
         self
     }
+}
 
-    fn get_digit_at_index_for_base(&self, index: usize, base: usize) -> mubintvec {
-        // Implement GetDigitAtIndexForBase function here
-        mubintvec::new()
+impl<T: NativeInt> Mul for Mubintvec<T> {
+    type Output = Self;
+    fn mul(mut self, rhs: Self) -> Self {
+        self.mod_mul_vec_eq(&rhs);
+        self
     }
 }
 
-impl fmt::Display for mubintvec {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let len = self.m_data.len();
+impl<T: NativeInt> fmt::Display for Mubintvec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "[")?;
-        for (i, val) in self.m_data.iter().enumerate() {
-            write!(f, "{}", val)?;
-            if i == len - 1 {
-                write!(f, "]")?;
-            } else {
+        for (i, x) in self.m_data.iter().enumerate() {
+            if i > 0 {
                 write!(f, " ")?;
             }
+            write!(f, "{}", x)?;
         }
-        write!(f, " modulus: {}", self.m_modulus)
-    }
-}
-
-impl Add for mubintvec {
-    type Output = mubintvec;
-
-    fn add(self, other: mubintvec) -> mubintvec {
-        // Implement Add trait for mubintvec here
-        mubintvec::new()
-    }
-}
-
-impl Sub for mubintvec {
-    type Output = mubintvec;
-
-    fn sub(self, other: mubintvec) -> mubintvec {
-        // Implement Sub trait for mubintvec here
-        mubintvec::new()
+        write!(f, "] modulus: {}", self.m_modulus)
     }
 }
-
-impl Mul for mubintvec {
-    type Output = mubintvec;
-
-    fn mul(self, other: mubintvec) -> mubintvec {
-        // Implement Mul trait for mubintvec here
-        mubintvec::new()
-    }
-}
-
-impl Default for ubint_el_t {
-    fn default() -> Self {
-        // Implement default value for ubint_el_t here
-        ubint_el_t {}
-    }
-}
-
-impl Default for mubintvec {
-    fn default() -> Self {
-        // Implement default value for mubintvec here
-        mubintvec::new()
-    }
-}
-
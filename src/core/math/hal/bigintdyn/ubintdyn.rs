@@ -1,1670 +1,838 @@
 /*
   This file contains the main class for unsigned big integers: ubint. Big integers are
-  represented as arrays of machine native unsigned integers. The native integer type is
-  supplied as a template parameter. Currently implementation based on uint32_t and uint64_t is
-  supported. a native double the base integer size is also needed.
+  represented as vectors of machine native unsigned limbs. The limb type (u32 or u64) is
+  supplied as a generic parameter; the vector grows as needed, so the size of an integer is
+  limited only by memory
 */
-
- use std::collections::HashMap;
-use std::fmt;
-use std::ops::{Add, Sub, Mul, Div};
-use num_bigint::{BigInt, Sign};
-use num_traits::{Zero, One};
-use num_integer::Integer;
-
-struct Ubint<LimbT> {
-    msb: usize,
-    value: Vec<LimbT>,
+use crate::core::math::hal::integer::{BigIntegerInterface, IntegerInterface};
+use crate::core::math::hal::intnat::ubintnat::NativeInt;
+use num::traits::NumCast;
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign,
+    Sub, SubAssign,
+};
+use std::str::FromStr;
+
+/// Начиная с этой длины множителей (в словах) умножение выполняется по Карацубе
+const KARATSUBA_THRESHOLD: usize = 32;
+
+/// Целое без знака произвольной длины (аналог ubint в OpenFHE).
+///
+/// Слова типа T (u32 или u64) хранятся от младшего к старшему без старших
+/// нулей, ноль - пустой вектор. Обычная арифметика не ограничена по
+/// разрядности, вычитание большего из меньшего - ошибка. Модульные операции
+/// с Барреттом работают с модулем любой длины.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Ubint<T: NativeInt> {
+    m_value: Vec<T>,
 }
 
-impl<LimbT> Ubint<LimbT> 
-where
-    LimbT: Integer + Clone + From<u8>,
-{
-    fn new() -> Self {
-        Ubint {
-            msb: 0,
-            value: vec![LimbT::zero()],
-        }
-    }
-
-    fn from_vec(v: Vec<LimbT>) -> Self {
-        let mut ubint = Ubint {
-            msb: 0, // This should be calculated based on the input vector `v`
-            value: v,
-        };
-        ubint.normalize_limbs();
-        ubint
-    }
+/// Длинное целое динамической разрядности по умолчанию
+pub type BigInteger = Ubint<u64>;
 
-    fn normalize_limbs(&mut self) {
-        // Implementation for normalizing limbs goes here
+impl<T: NativeInt> Ubint<T> {
+    pub fn from_limbs(limbs: Vec<T>) -> Self {
+        let mut res = Self { m_value: limbs };
+        res.normalize_limbs();
+        res
     }
 
-    fn set_value(&mut self, strval: &str) {
-        // Implementation for setting value from a string goes here
+    /// Слова числа от младшего к старшему без старших нулей
+    pub fn limbs(&self) -> &[T] {
+        &self.m_value
     }
-}
 
-impl<LimbT> From<&str> for Ubint<LimbT> 
-where
-    LimbT: Integer + Clone + From<u8>,
-{
-    fn from(strval: &str) -> Self {
-        let mut ubint = Ubint::new();
-        ubint.set_value(strval);
-        ubint
+    pub fn set_identity(&mut self) {
+        self.m_value = vec![T::one()];
     }
-}
 
-impl<LimbT> Clone for Ubint<LimbT> 
-where
-    LimbT: Clone,
-{
-    fn clone(&self) -> Self {
-        Ubint {
-            msb: self.msb,
-            value: self.value.clone(),
+    /// Значение в другом целочисленном типе; паникует, если не помещается
+    pub fn convert_to_int<I: NumCast>(&self) -> I {
+        if self.get_msb() > 128 {
+            panic!("The value does not fit into the requested type")
         }
-    }
-}
-
-impl<LimbT> From<u8> for Ubint<LimbT> 
-where
-    LimbT: From<u8>,
-{
-    fn from(val: u8) -> Self {
-        Ubint {
-            msb: 0, // This should be calculated based on `val`
-            value: vec![LimbT::from(val)],
-        }
-    }
-}
-
-// Implementations for Add, Sub, Mul, Div traits could go here, depending on LimbT capabilities
-
-// Log2 and DataTypes structures would be replaced by Rust's type system and traits
-
-
-use std::ops::{Add, Sub, Mul, Div, Rem, AddAssign, SubAssign, MulAssign, DivAssign, RemAssign, Neg};
-
-impl Ubint {
-    fn set_value(&mut self, val: &Ubint) {
-        self.m_MSB = val.m_MSB;
-        self.m_value = val.m_value.clone();
+        let value = self
+            .m_value
+            .iter()
+            .rev()
+            .fold(0u128, |acc, x| (acc << T::BITS) | x.to_u128().unwrap());
+        I::from(value).expect("The value does not fit into the requested type")
     }
 
-    fn set_identity(&mut self) {
-        self.m_MSB = 1;
-        self.m_value = vec![1];
+    pub fn convert_to_double(&self) -> f64 {
+        let base = 2f64.powi(T::BITS as i32);
+        self.m_value
+            .iter()
+            .rev()
+            .fold(0f64, |acc, x| acc * base + x.to_f64().unwrap())
     }
 
-    fn add(&self, b: &Ubint) -> Ubint {
-        // Implementation omitted
+    /// Номер старшего ненулевого бита, считая с 1 (0 для нуля)
+    pub fn get_msb(&self) -> u32 {
+        match self.m_value.last() {
+            None => 0,
+            Some(top) => T::BITS * self.m_value.len() as u32 - top.leading_zeros(),
+        }
     }
 
-    fn add_eq(&mut self, b: &Ubint) {
-        // Implementation omitted
+    /// Количество слов
+    pub fn get_number_of_limbs(&self) -> usize {
+        self.m_value.len()
     }
 
-    fn sub(&self, b: &Ubint) -> Ubint {
-        // Implementation omitted
+    /// Бит с номером index, младший бит имеет номер 0
+    pub fn get_bit_at_index(&self, index: u32) -> bool {
+        match self.m_value.get((index / T::BITS) as usize) {
+            None => false,
+            Some(&x) => (x >> (index % T::BITS) as usize) & T::one() == T::one(),
+        }
+    }
+
+    /// Цифра с номером index (с 0) в системе счисления с основанием 2^bits, bits <= 64
+    pub fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> u64 {
+        assert!(
+            bits > 0 && bits <= 64,
+            "The base must be 2^bits with 0 < bits <= 64"
+        );
+        let shifted = self >> (index * bits);
+        let low = &shifted.m_value[..shifted.m_value.len().min((64 / T::BITS) as usize)];
+        let digit =
+            low.iter()
+                .rev()
+                .fold(0u128, |acc, x| (acc << T::BITS) | x.to_u128().unwrap()) as u64;
+        if bits == 64 {
+            digit
+        } else {
+            digit & ((1 << bits) - 1)
+        }
     }
 
-    fn sub_eq(&mut self, b: &Ubint) {
-        // Implementation omitted
+    /// Частное и остаток от деления на b
+    pub fn div_rem(&self, b: &Self) -> (Self, Self) {
+        if b.m_value.is_empty() {
+            panic!("Ubint: Divide by zero");
+        }
+        let (quot, rem) = div_rem_limbs(&self.m_value, &b.m_value);
+        (Self::from_limbs(quot), Self::from_limbs(rem))
     }
 
-    fn mul(&self, b: &Ubint) -> Ubint {
-        // Implementation omitted
+    /// self^p
+    pub fn exp(&self, p: u32) -> Self {
+        let mut res = Self::from(1u64);
+        for i in (0..u32::BITS - p.leading_zeros()).rev() {
+            res = &res * &res;
+            if (p >> i) & 1 == 1 {
+                res = &res * self;
+            }
+        }
+        res
     }
 
-    fn mul_eq(&mut self, b: &Ubint) {
-        *self = self.mul(b);
+    /// round(self * p / q) без потери точности
+    pub fn multiply_and_round(&self, p: &Self, q: &Self) -> Self {
+        (self * p).divide_and_round(q)
     }
 
-    fn divided_by(&self, b: &Ubint) -> Ubint {
-        // Implementation omitted
+    /// round(self / q)
+    pub fn divide_and_round(&self, q: &Self) -> Self {
+        let (quot, rem) = self.div_rem(q);
+        if rem > q >> 1 {
+            quot + Self::from(1u64)
+        } else {
+            quot
+        }
     }
 
-    fn divided_by_eq(&mut self, b: &Ubint) {
-        // Implementation omitted
+    /// Убирает старшие нулевые слова
+    fn normalize_limbs(&mut self) {
+        while self.m_value.last().is_some_and(|x| x.is_zero()) {
+            self.m_value.pop();
+        }
     }
+}
 
-    fn exp(&self, p: usize) -> Ubint {
-        // Implementation omitted
+/* Модульная арифметика */
+impl<T: NativeInt> Ubint<T> {
+    /// self mod modulus
+    pub fn modulo(&self, modulus: &Self) -> Self {
+        self.div_rem(modulus).1
     }
 
-    fn exp_eq(&mut self, p: usize) {
-        *self = self.exp(p);
+    /// Константа Барретта mu = floor(2^(2n) / q), где n - разрядность модуля q = self
+    pub fn compute_mu(&self) -> Self {
+        if self.m_value.is_empty() {
+            panic!("Ubint ComputeMu: Divide by zero");
+        }
+        (Self::from(1u64) << (2 * self.get_msb())) / self.clone()
     }
 
-    fn multiply_and_round(&self, p: &Ubint, q: &Ubint) -> Ubint {
-        // Implementation omitted
+    /// self mod modulus редукцией Барретта, mu = modulus.compute_mu()
+    pub fn modulo_with_mu(&self, modulus: &Self, mu: &Self) -> Self {
+        if self.get_msb() > 2 * modulus.get_msb() {
+            return self.modulo(modulus);
+        }
+        Self::barrett(self, modulus, mu)
     }
 
-    fn multiply_and_round_eq(&mut self, p: &Ubint, q: &Ubint) {
-        *self = self.multiply_and_round(p, q);
+    /// (self + b) mod modulus
+    pub fn mod_add(&self, b: &Self, modulus: &Self) -> Self {
+        self.modulo(modulus)
+            .mod_add_fast(&b.modulo(modulus), modulus)
     }
 
-    fn divide_and_round(&self, q: &Ubint) -> Ubint {
-        // Implementation omitted
+    /// (self + b) mod modulus для self, b < modulus
+    pub fn mod_add_fast(&self, b: &Self, modulus: &Self) -> Self {
+        let sum = self + b;
+        if sum >= *modulus {
+            sum - modulus
+        } else {
+            sum
+        }
     }
 
-    fn divide_and_round_eq(&mut self, q: &Ubint) {
-        *self = self.divide_and_round(q);
+    pub fn mod_add_eq(&mut self, b: &Self, modulus: &Self) -> &mut Self {
+        *self = self.mod_add(b, modulus);
+        self
     }
 
-    fn mod_(&self, modulus: &Ubint) -> Ubint {
-        // Implementation omitted
+    /// (self - b) mod modulus
+    pub fn mod_sub(&self, b: &Self, modulus: &Self) -> Self {
+        self.modulo(modulus)
+            .mod_sub_fast(&b.modulo(modulus), modulus)
     }
 
-    fn mod_eq(&mut self, modulus: &Ubint) {
-        // Implementation omitted
+    /// (self - b) mod modulus для self, b < modulus
+    pub fn mod_sub_fast(&self, b: &Self, modulus: &Self) -> Self {
+        if self >= b {
+            self - b
+        } else {
+            self + &(modulus - b)
+        }
     }
 
-    fn compute_mu(&self) -> Ubint {
-        // Implementation omitted
+    pub fn mod_sub_eq(&mut self, b: &Self, modulus: &Self) -> &mut Self {
+        *self = self.mod_sub(b, modulus);
+        self
     }
 
-    fn mod_add(&self, b: &Ubint, modulus: &Ubint) -> Ubint {
-        // Implementation omitted
+    /// (self * b) mod modulus делением произведения
+    pub fn mod_mul(&self, b: &Self, modulus: &Self) -> Self {
+        (&self.modulo(modulus) * &b.modulo(modulus)).modulo(modulus)
     }
 
-    fn mod_add_eq(&mut self, b: &Ubint, modulus: &Ubint) {
-        // Implementation omitted
+    pub fn mod_mul_eq(&mut self, b: &Self, modulus: &Self) -> &mut Self {
+        *self = self.mod_mul(b, modulus);
+        self
     }
 
-    fn mod_add_fast(&self, b: &Ubint, modulus: &Ubint) -> Ubint {
-        // Implementation omitted
+    /// (self * b) mod modulus редукцией Барретта для self, b < modulus
+    pub fn mod_mul_fast(&self, b: &Self, modulus: &Self, mu: &Self) -> Self {
+        Self::barrett(&(self * b), modulus, mu)
     }
 
-    fn mod_add_fast_eq(&mut self, b: &Ubint, modulus: &Ubint) {
-        // Implementation omitted
+    pub fn mod_mul_fast_eq(&mut self, b: &Self, modulus: &Self, mu: &Self) -> &mut Self {
+        *self = self.mod_mul_fast(b, modulus, mu);
+        self
     }
-}
-
 
-use num_bigint::BigUint;
-use num_traits::One;
-use num_traits::Zero;
-
-fn mod_add<T: Into<BigUint>>(b: T, modulus: T, mu: T) -> BigUint {
-    let b = b.into();
-    let modulus = modulus.into();
-    let mu = mu.into();
-    b.mod_add(&modulus)
-}
-
-fn mod_add_eq<T: Into<BigUint>>(b: T, modulus: T, mu: T) -> BigUint {
-    let mut this = self.clone();
-    let b = b.into();
-    let modulus = modulus.into();
-    let mu = mu.into();
-    this = b.mod_add(&modulus);
-    this
-}
-
-fn mod_sub<T: Into<BigUint>>(b: T, modulus: T) -> BigUint {
-    let b = b.into();
-    let modulus = modulus.into();
-    self.mod_sub(&b, &modulus)
-}
-
-fn mod_sub_eq<T: Into<BigUint>>(b: T, modulus: T) -> BigUint {
-    let mut this = self.clone();
-    let b = b.into();
-    let modulus = modulus.into();
-    this = this.mod_sub(&b, &modulus);
-    this
-}
-
-fn mod_sub_fast<T: Into<BigUint>>(b: T, modulus: T) -> BigUint {
-    let b = b.into();
-    let modulus = modulus.into();
-    self.mod_sub_fast(&b, &modulus)
-}
-
-fn mod_sub_fast_eq<T: Into<BigUint>>(b: T, modulus: T) -> BigUint {
-    let mut this = self.clone();
-    let b = b.into();
-    let modulus = modulus.into();
-    this = this.mod_sub_fast(&b, &modulus);
-    this
-}
-
-fn mod_mul<T: Into<BigUint>>(b: T, modulus: T) -> BigUint {
-    let b = b.into();
-    let modulus = modulus.into();
-    self.mod_mul(&b, &modulus)
-}
-
-fn mod_mul_eq<T: Into<BigUint>>(b: T, modulus: T) -> BigUint {
-    let mut this = self.clone();
-    let b = b.into();
-    let modulus = modulus.into();
-    this = this.mod_mul(&b, &modulus);
-    this
-}
-
-fn mod_mul_fast<T: Into<BigUint>>(b: T, modulus: T) -> BigUint {
-    let b = b.into();
-    let modulus = modulus.into();
-    self.mod_mul_fast(&b, &modulus)
-}
-
-fn mod_mul_fast_eq<T: Into<BigUint>>(b: T, modulus: T) -> BigUint {
-    let mut this = self.clone();
-    let b = b.into();
-    let modulus = modulus.into();
-    this = this.mod_mul_fast(&b, &modulus);
-    this
-}
-
-fn mod_exp<T: Into<BigUint>>(b: T, modulus: T) -> BigUint {
-    let b = b.into();
-    let modulus = modulus.into();
-    self.mod_exp(&b, &modulus)
-}
-
-fn mod_exp_eq<T: Into<BigUint>>(b: T, modulus: T) -> BigUint {
-    let mut this = self.clone();
-    let b = b.into();
-    let modulus = modulus.into();
-    this = this.mod_exp(&b, &modulus);
-    this
-}
-
-fn mod_inverse<T: Into<BigUint>>(modulus: T) -> BigUint {
-    let modulus = modulus.into();
-    self.mod_inverse(&modulus)
-}
-
-fn mod_inverse_eq<T: Into<BigUint>>(modulus: T) -> BigUint {
-    let mut this = self.clone();
-    let modulus = modulus.into();
-    this = this.mod_inverse(&modulus);
-    this
-}
-
-fn l_shift(shift: u16) -> BigUint {
-    self.lsh(shift)
-}
-
-fn l_shift_eq(shift: u16) -> BigUint {
-    let mut this = self.clone();
-    this = this.lsh(shift);
-    this
-}
-
-fn r_shift(shift: u16) -> BigUint {
-    self.rsh(shift)
-}
-
-fn r_shift_eq(shift: u16) -> BigUint {
-    let mut this = self.clone();
-    this = this.rsh(shift);
-    this
-}
-
-
-
-use std::fmt;
-use std::ops;
-
-struct ubint {
-    m_value: Vec<limb_t>,
-    m_MSB: usint,
-    m_limbBitLength: usint,
-}
-
-impl ubint {
-    fn compare(&self, a: &ubint) -> i32 {
-        if self.m_MSB < a.m_MSB {
-            return -1;
-        }
-        if self.m_MSB > a.m_MSB {
-            return 1;
-        }
-        for i in (0..self.m_value.len()).rev() {
-            if self.m_value[i] < a.m_value[i] {
-                return -1;
-            }
-            if self.m_value[i] > a.m_value[i] {
-                return 1;
+    /// self^b mod modulus
+    pub fn mod_exp(&self, b: &Self, modulus: &Self) -> Self {
+        let mu = modulus.compute_mu();
+        let mut res = Self::from(1u64).modulo(modulus);
+        let base = self.modulo(modulus);
+        for i in (0..b.get_msb()).rev() {
+            res = res.mod_mul_fast(&res, modulus, &mu);
+            if b.get_bit_at_index(i) {
+                res = res.mod_mul_fast(&base, modulus, &mu);
             }
         }
-        return 0;
+        res
     }
 
-    fn convert_to_int<T>(&self) -> T
-    where
-        T: From<limb_t> + ops::BitOrAssign + ops::ShlAssign,
-    {
-        let limblen: usint = std::mem::size_of::<T>() * 8;
-        if self.m_limbBitLength >= limblen {
-            return self.m_value[0].into();
+    /// self^-1 mod modulus; паникует, если self и modulus не взаимно просты
+    pub fn mod_inverse(&self, modulus: &Self) -> Self {
+        let (mut r0, mut r1) = (modulus.clone(), self.modulo(modulus));
+        // Коэффициенты Безу хранятся по модулю modulus, чтобы обойтись без знака
+        let (mut t0, mut t1) = (Self::default(), Self::from(1u64));
+        while !r1.m_value.is_empty() {
+            let (quot, r) = r0.div_rem(&r1);
+            let t = t0.mod_sub_fast(&quot.mod_mul(&t1, modulus), modulus);
+            r0 = r1;
+            r1 = r;
+            t0 = t1;
+            t1 = t;
         }
-        if self.m_limbBitLength < limblen {
-            let ceilInt = self.MSBToLimbs(limblen.max(self.m_MSB));
-            let mut result: T = self.m_value[0].into();
-            for i in 1..ceilInt {
-                result |= (self.m_value[i].into() << (i * self.m_limbBitLength));
-            }
-            return result;
+        if r0 != Self::from(1u64) {
+            panic!(
+                "Ubint ModInverse: {} is not invertible modulo {}",
+                self, modulus
+            )
         }
+        t0.modulo(modulus)
     }
 
-    fn convert_to_float(&self) -> f32 {
-        // implementation goes here
-    }
-
-    fn convert_to_double(&self) -> f64 {
-        // implementation goes here
-    }
-
-    fn convert_to_long_double(&self) -> f128 {
-        // implementation goes here
-    }
-
-    fn from_binary_string(bitString: &str) -> ubint {
-        // implementation goes here
-    }
-
-    fn get_MSB(&self) -> usint {
-        self.m_MSB
-    }
-
-    fn get_number_of_limbs(&self) -> usize {
-        self.m_value.len()
+    /// Редукция Барретта x < 2^(2n) по модулю q < 2^n (HAC 14.42)
+    fn barrett(x: &Self, q: &Self, mu: &Self) -> Self {
+        let n = q.get_msb();
+        // q3 = floor(floor(x / 2^(n-1)) * mu / 2^(n+1)) отличается от floor(x / q) не больше чем на 2
+        let q3 = (&(x >> (n - 1)) * mu) >> (n + 1);
+        let mut r = x - &(&q3 * q);
+        while r >= *q {
+            r -= q;
+        }
+        r
     }
+}
 
-    fn get_length_for_base(&self, base: usint) -> usint {
-        self.get_MSB()
+impl<T: NativeInt> Ord for Ubint<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_limbs(&self.m_value, &other.m_value)
     }
+}
 
-    fn get_digit_at_index_for_base(&self, index: usint, base: usint) -> usint {
-        // implementation goes here
+impl<T: NativeInt> PartialOrd for Ubint<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
+}
 
-    fn get_bit_at_index(&self, index: usint) -> uschar {
-        // implementation goes here
+impl<T: NativeInt> From<u64> for Ubint<T> {
+    fn from(value: u64) -> Self {
+        Self::from(value as u128)
     }
+}
 
-    fn allocator() -> ubint {
-        ubint {
-            m_value: Vec::new(),
-            m_MSB: 0,
-            m_limbBitLength: 0,
+impl<T: NativeInt> From<u128> for Ubint<T> {
+    fn from(mut value: u128) -> Self {
+        let mut limbs = vec![];
+        while value != 0 {
+            limbs.push(limb(value));
+            value = value.checked_shr(T::BITS).unwrap_or(0);
         }
+        Self::from_limbs(limbs)
     }
+}
 
-    fn to_string(&self) -> String {
-        // implementation goes here
-    }
-
-    fn integer_type_name() -> String {
-        if std::mem::size_of::<limb_t>() == std::mem::size_of::<u32>() {
-            return String::from("UBDYNINT_32");
-        }
-        if std::mem::size_of::<limb_t>() == std::mem::size_of::<u64>() {
-            return String::from("UBDYNINT_64");
-        }
-        if std::mem::size_of::<limb_t>() == std::mem::size_of::<u128>() {
-            return String::from("UBDYNINT_128");
+/// Десятичная запись или шестнадцатеричная с префиксом 0x
+impl<T: NativeInt> FromStr for Ubint<T> {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || format!("{} is not a valid unsigned integer", s);
+        let (digits, radix) = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            Some(hex) => (hex, 16),
+            None => (s, 10),
+        };
+        if digits.is_empty() {
+            return Err(err());
         }
-        panic!("Configuration Error: ubintdyn.h");
-    }
 
-    fn get_internal_representation(&self) -> String {
-        let mut ret = String::new();
-        for (i, value) in self.m_value.iter().enumerate() {
-            ret += &value.to_string();
-            if i < (self.m_value.len() - 1) {
-                ret += " ";
-            }
+        let mut limbs = vec![];
+        for c in digits.chars() {
+            let digit = c.to_digit(radix).ok_or_else(err)?;
+            mul_add_small(&mut limbs, limb(radix as u128), limb(digit as u128));
         }
-        return ret;
-    }
-
-    fn print_integer_constants() {
-        println!("sizeof UINT8_C  {}", std::mem::size_of::<u8>());
-        println!("sizeof UINT16_C {}", std::mem::size_of::<u16>());
-        println!("sizeof UINT32_C {}", std::mem::size_of::<u32>());
-        println!("sizeof UINT64_C {}", std::mem::size_of::<u64>());
-        println!("sizeof uint8_t  {}", std::mem::size_of::<u8>());
-        println!("sizeof uint16_t {}", std::mem::size_of::<u16>());
-        println!("sizeof uint32_t {}", std::mem::size_of::<u32>());
-        println!("sizeof uint64_t {}", std::mem::size_of::<u64>());
-        #[cfg(feature = "HAVE_INT128")]
-        println!("sizeof uint128_t {}", std::mem::size_of::<u128>());
-    }
-
-    fn save(&self, ar: &mut dyn Archive, version: u32) {
-        ar.serialize_field("v", &self.m_value);
-        ar.serialize_field("m", &self.m_MSB);
+        Ok(Self::from_limbs(limbs))
     }
+}
 
-    fn load(&mut self, ar: &mut dyn Archive, version: u32) {
-        if version > self.serialized_version() {
-            panic!("serialized object version {} is from a later version of the library", version);
+impl<T: NativeInt> Display for Ubint<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Число делится на наибольшую степень десяти, помещающуюся в слово
+        let ten = limb::<T>(10);
+        let (mut chunk, mut width) = (T::one(), 0);
+        while let Some(next) = chunk.checked_mul(&ten) {
+            chunk = next;
+            width += 1;
         }
-        self.m_value = ar.deserialize_field("v").unwrap();
-        self.m_MSB = ar.deserialize_field("m").unwrap();
-    }
 
-    fn serialized_object_name(&self) -> String {
-        String::from("DYNInteger")
-    }
-
-    fn serialized_version() -> u32 {
-        1
-    }
-
-    fn set_MSB(&mut self) {
-        self.m_MSB = self.m_limbBitLength * (self.m_value.len() - 1) as usint;
-        self.m_MSB += lbcrypto::GetMSB(self.m_value.last().unwrap());
-    }
-
-    fn normalize_limbs(&mut self) {
-        let mut size = self.m_value.len() - 1;
-        while size > 0 && self.m_value[size] == 0 {
-            self.m_value.pop();
-            size -= 1;
+        let mut chunks = vec![];
+        let mut x = self.m_value.clone();
+        loop {
+            chunks.push(div_rem_small(&mut x, chunk));
+            while x.last().is_some_and(|l| l.is_zero()) {
+                x.pop();
+            }
+            if x.is_empty() {
+                break;
+            }
         }
-        self.m_MSB = self.m_limbBitLength * (self.m_value.len() - 1) as usint;
-        self.m_MSB += lbcrypto::GetMSB(self.m_value.last().unwrap());
-    }
-
-    fn divqr_vect(&self, q: &mut ubint, r: &mut ubint, u: &ubint, v: &ubint) {
-        // implementation goes here
-    }
-
-    fn divq_vect(&self, q: &mut ubint, u: &ubint, v: &ubint) {
-        // implementation goes here
-    }
-
-    fn divr_vect(&self, r: &mut ubint, u: &ubint, v: &ubint) {
-        // implementation goes here
-    }
-
-    fn MSBToLimbs(msb: usint) -> usint {
-        const mask: usint = self.m_limbBitLength - 1;
-        if msb == 0 {
-            return 1;
+        let mut s = chunks.pop().unwrap().to_string();
+        for c in chunks.iter().rev() {
+            s += &format!("{:0width$}", c, width = width);
         }
-        return (msb >> self.m_log2LimbBitLength) + ((msb & mask) != 0);
+        f.pad_integral(true, "", &s)
     }
 }
 
-impl fmt::Display for ubint {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.to_string())
+impl<T: NativeInt> fmt::LowerHex for Ubint<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = (T::BITS / 4) as usize;
+        let mut s = match self.m_value.last() {
+            None => "0".to_string(),
+            Some(top) => format!("{:x}", top.to_u64().unwrap()),
+        };
+        for x in self.m_value.iter().rev().skip(1) {
+            s += &format!("{:0width$x}", x.to_u64().unwrap(), width = width);
+        }
+        f.pad_integral(true, "0x", &s)
     }
 }
 
-impl<T: Archive> Archive for ubint {
-    fn save(&self, ar: &mut T, version: u32) {
-        self.save(ar, version);
-    }
-
-    fn load(&mut self, ar: &mut T, version: u32) {
-        self.load(ar, version);
+/* Обычная арифметика над ссылками; варианты со значениями сводятся к ним */
+impl<T: NativeInt> Add<&Ubint<T>> for &Ubint<T> {
+    type Output = Ubint<T>;
+    fn add(self, rhs: &Ubint<T>) -> Ubint<T> {
+        Ubint::from_limbs(add_limbs(&self.m_value, &rhs.m_value))
     }
 }
 
-impl<T: Archive> Serialize for ubint {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let mut ar = CerealArchive::new(serializer);
-        self.save(&mut ar, self.serialized_version());
-        ar.into_inner()
+impl<T: NativeInt> Sub<&Ubint<T>> for &Ubint<T> {
+    type Output = Ubint<T>;
+    fn sub(self, rhs: &Ubint<T>) -> Ubint<T> {
+        let (res, borrow) = sub_limbs(&self.m_value, &rhs.m_value);
+        if borrow {
+            panic!("Ubint: attempt to subtract with overflow")
+        }
+        Ubint::from_limbs(res)
     }
 }
 
-impl<'de, T: Archive> Deserialize<'de> for ubint {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let ar = CerealArchive::new(deserializer);
-        let mut obj = Self::allocator();
-        obj.load(&mut ar, obj.serialized_version());
-        Ok(obj)
+impl<T: NativeInt> Mul<&Ubint<T>> for &Ubint<T> {
+    type Output = Ubint<T>;
+    fn mul(self, rhs: &Ubint<T>) -> Ubint<T> {
+        Ubint::from_limbs(mul_limbs(&self.m_value, &rhs.m_value))
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_compare() {
-        let a = ubint {
-            m_value: vec![1, 2, 3],
-            m_MSB: 10,
-            m_limbBitLength: 32,
-        };
-        let b = ubint {
-            m_value: vec![1, 2, 4],
-            m_MSB: 10,
-            m_limbBitLength: 32,
-        };
-        assert_eq!(a.compare(&b), -1);
+impl<T: NativeInt> Div<&Ubint<T>> for &Ubint<T> {
+    type Output = Ubint<T>;
+    fn div(self, rhs: &Ubint<T>) -> Ubint<T> {
+        self.div_rem(rhs).0
     }
 }
 
-
-
-/*
-  This file contains the main class for unsigned big integers: ubint. Big integers are represented as arrays of
-  machine native unsigned integers. The native integer type is supplied as a template parameter.
-  Currently implementation based on uint32_t and uint64_t is supported. a native double the base integer size is also needed.
-*/
-use std::vec::Vec;
-
-pub struct ubint<limb_t> {
-    m_value: Vec<limb_t>,
-    m_MSB: u32,
-    m_limbBitLength: u32,
-    m_MaxLimb: u32,
+impl<T: NativeInt> Rem<&Ubint<T>> for &Ubint<T> {
+    type Output = Ubint<T>;
+    fn rem(self, rhs: &Ubint<T>) -> Ubint<T> {
+        self.div_rem(rhs).1
+    }
 }
 
-impl<limb_t> ubint<limb_t> {
-    pub fn Add(&self, b: &ubint<limb_t>) -> ubint<limb_t> {
-        let A = self;
-        let sizeA = self.m_value.len();
-        let B = b;
-        let sizeB = b.m_value.len();
-        if sizeA < sizeB {
-            let temp = A;
-            A = B;
-            B = temp;
-            let tempSize = sizeA;
-            sizeA = sizeB;
-            sizeB = tempSize;
-        }
-        if B.m_MSB == 0 {
-            return A.clone();
-        }
-        let mut r = Vec::with_capacity(sizeA + 1);
-        let mut c: Dlimb_t = 0;
-        for i in 0..sizeA {
-            let av = A.m_value[i] as Dlimb_t;
-            let bv = if i < sizeB { B.m_value[i] as Dlimb_t } else { 0 };
-            r[i] = (c += av + bv) as limb_t;
-            c >>= m_limbBitLength;
-        }
-        r[sizeA] = c as limb_t;
-        return ubint { m_value: r };
-    }
-
-    pub fn AddEq(&mut self, b: &ubint<limb_t>) -> &ubint<limb_t> {
-        let A = self;
-        let sizeA = self.m_value.len();
-        let B = b;
-        let sizeB = B.m_value.len();
-        if sizeA < sizeB {
-            let temp = A;
-            A = B;
-            B = temp;
-            let tempSize = sizeA;
-            sizeA = sizeB;
-            sizeB = tempSize;
-        }
-        if B.m_MSB == 0 {
-            return self = A.clone();
-        }
-        let mut r = Vec::with_capacity(sizeA + 1);
-        let mut c: Dlimb_t = 0;
-        for i in 0..sizeA {
-            let av = A.m_value[i] as Dlimb_t;
-            let bv = if i < sizeB { B.m_value[i] as Dlimb_t } else { 0 };
-            r[i] = (c += av + bv) as limb_t;
-            c >>= m_limbBitLength;
+macro_rules! ubint_op_by_value {
+    ($op:ident, $method:ident, $op_assign:ident, $method_assign:ident) => {
+        impl<T: NativeInt> $op for Ubint<T> {
+            type Output = Self;
+            fn $method(self, rhs: Self) -> Self {
+                $op::$method(&self, &rhs)
+            }
         }
-        r[sizeA] = c as limb_t;
-        self.m_value = r;
-        ubint<limb_t>::NormalizeLimbs();
-        return self;
-    }
 
-    pub fn Sub(&self, b: &ubint<limb_t>) -> ubint<limb_t> {
-        if *self <= *b {
-            return ubint();
-        }
-        let mut result = self.clone();
-        for i in 0..b.m_value.len() {
-            if result.m_value[i] < b.m_value[i] {
-                let mut cntr = i;
-                result.m_value[cntr] += (m_MaxLimb - b.m_value[cntr]) + 1;
-                while 0 == result.m_value[++cntr] {
-                    result.m_value[cntr] = m_MaxLimb;
-                }
-                result.m_value[cntr]--;
-            } else {
-                result.m_value[i] -= b.m_value[i];
+        impl<T: NativeInt> $op<&Ubint<T>> for Ubint<T> {
+            type Output = Self;
+            fn $method(self, rhs: &Self) -> Self {
+                $op::$method(&self, rhs)
             }
         }
-        result.NormalizeLimbs();
-        return result;
-    }
 
-    pub fn SubEq(&mut self, b: &ubint<limb_t>) -> &ubint<limb_t> {
-        if *self <= *b {
-            self.m_MSB = 0;
-            self.m_value[0] = 0;
-            self.m_value.resize(1);
-            return self;
-        }
-        for i in 0..b.m_value.len() {
-            if self.m_value[i] < b.m_value[i] {
-                let mut cntr = i;
-                self.m_value[cntr] += (m_MaxLimb - b.m_value[cntr]) + 1;
-                while 0 == self.m_value[++cntr] {
-                    self.m_value[cntr] = m_MaxLimb;
-                }
-                self.m_value[cntr]--;
-            } else {
-                self.m_value[i] -= b.m_value[i];
+        impl<T: NativeInt> $op_assign<&Ubint<T>> for Ubint<T> {
+            fn $method_assign(&mut self, rhs: &Self) {
+                *self = $op::$method(&*self, rhs);
             }
         }
-        ubint<limb_t>::NormalizeLimbs();
-        return self;
-    }
 
-    pub fn Mul(&self, b: &ubint<limb_t>) -> ubint<limb_t> {
-        if self.m_MSB == 0 || b.m_MSB == 0 {
-            return ubint();
-        }
-        if b.m_MSB == 1 {
-            return self.clone();
-        }
-        if self.m_MSB == 1 {
-            return b.clone();
-        }
-        let A = self;
-        let aSize = self.m_value.len();
-        let B = b;
-        let bSize = b.m_value.len();
-        if aSize < bSize {
-            let temp = A;
-            A = B;
-            B = temp;
-            let tempSize = aSize;
-            aSize = bSize;
-            bSize = tempSize;
-        }
-        let mut ans = ubint();
-        for i in 0..bSize {
-            let mut c = Vec::with_capacity(i + aSize + 1);
-            let limbb = B.m_value[i] as Dlimb_t;
-            let mut ofl: Dlimb_t = 0;
-            for j in 0..aSize {
-                c[i + j] = (ofl += limbb * A.m_value[j] as Dlimb_t) as limb_t;
-                ofl >>= m_limbBitLength;
+        impl<T: NativeInt> $op_assign for Ubint<T> {
+            fn $method_assign(&mut self, rhs: Self) {
+                *self = $op::$method(&*self, &rhs);
             }
-            c[i + aSize] = ofl as limb_t;
-            ans = ans.Add(ubint { m_value: c });
         }
-        return ans;
-    }
+    };
+}
 
-    pub fn DividedBy(&self, b: &ubint<limb_t>) -> ubint<limb_t> {
-        if b.m_MSB == 0 {
-            OPENFHE_THROW(lbcrypto::math_error, "Divisor is zero");
-        }
-        if b.m_MSB > self.m_MSB {
-            return ubint();
-        }
-        if b.m_MSB == self.m_MSB && b.m_value.back() == self.m_value.back() {
-            return ubint(1);
-        }
-        let mut ans = ubint();
-        divq_vect(ans, self, b);
-        return ans;
-    }
+ubint_op_by_value!(Add, add, AddAssign, add_assign);
+ubint_op_by_value!(Sub, sub, SubAssign, sub_assign);
+ubint_op_by_value!(Mul, mul, MulAssign, mul_assign);
+ubint_op_by_value!(Div, div, DivAssign, div_assign);
+ubint_op_by_value!(Rem, rem, RemAssign, rem_assign);
 
-    pub fn DividedByEq(&mut self, b: &ubint<limb_t>) -> &ubint<limb_t> {
-        if b.m_MSB == 0 {
-            OPENFHE_THROW(lbcrypto::math_error, "Divisor is zero");
-        }
-        if b.m_MSB > self.m_MSB {
-            self.m_MSB = 0;
-            self.m_value.resize(1);
-            self.m_value[0] = 0;
-            return self;
-        }
-        if b.m_MSB == self.m_MSB && b.m_value.back() == self.m_value.back() {
-            self.m_MSB = 1;
-            self.m_value.resize(1);
-            self.m_value[0] = 1;
-            return self;
-        }
-        let mut ans = ubint();
-        divq_vect(ans, self, b);
-        return self = ans;
+impl<T: NativeInt> Shl<u32> for &Ubint<T> {
+    type Output = Ubint<T>;
+    fn shl(self, shift: u32) -> Ubint<T> {
+        Ubint::from_limbs(shl_limbs(&self.m_value, shift))
     }
+}
 
-    pub fn Exp(&self, p: usint) -> ubint<limb_t> {
-        if p == 0 {
-            return ubint(1);
-        }
-        if p == 1 {
-            return self.clone();
-        }
-        let mut tmp = ubint<limb_t>::Exp(p >> 1);
-        tmp = tmp.Mul(tmp);
-        if p & 0x1 {
-            return tmp.Mul(self);
-        }
-        return tmp;
+impl<T: NativeInt> Shl<u32> for Ubint<T> {
+    type Output = Self;
+    fn shl(self, shift: u32) -> Self {
+        &self << shift
     }
+}
 
-    pub fn MultiplyAndRound(&self, p: &ubint<limb_t>, q: &ubint<limb_t>) -> ubint<limb_t> {
-        if q.m_MSB == 0 {
-            OPENFHE_THROW(lbcrypto::math_error, "MultiplyAndRound() Divisor is zero");
-        }
-        let t = self.Mul(p);
-        let halfQ = q >> 1;
-        if t <= halfQ {
-            return ubint();
-        }
-        if (t.m_MSB == halfQ.m_MSB) || ((t.m_MSB == q.m_MSB) && (t.m_value.back() < q.m_value.back())) {
-            return ubint(1);
-        }
-        let mut ans = ubint();
-        let mut rv = ubint();
-        divqr_vect(ans, rv, t, q);
-        if rv > halfQ {
-            return ans.Add(ubint(1));
-        }
-        return ans;
+impl<T: NativeInt> ShlAssign<u32> for Ubint<T> {
+    fn shl_assign(&mut self, shift: u32) {
+        *self = &*self << shift;
     }
+}
 
-    pub fn DivideAndRound(&self, q: &ubint<limb_t>) -> ubint<limb_t> {
-        if q.m_MSB == 0 {
-            OPENFHE_THROW(lbcrypto::math_error, "DivideAndRound() Divisor is zero");
-        }
-        let halfQ = q >> 1;
-        if *self <= halfQ {
-            return ubint();
-        }
-        if (self.m_MSB == halfQ.m_MSB) || ((self.m_MSB == q.m_MSB) && (self.m_value.back() < q.m_value.back())) {
-            return ubint(1);
-        }
-        let mut ans = ubint();
-        let mut rv = ubint();
-        divqr_vect(ans, rv, self, q);
-        if rv > halfQ {
-            return ans.Add(ubint(1));
-        }
-        return ans;
+impl<T: NativeInt> Shr<u32> for &Ubint<T> {
+    type Output = Ubint<T>;
+    fn shr(self, shift: u32) -> Ubint<T> {
+        Ubint::from_limbs(shr_limbs(&self.m_value, shift))
     }
+}
 
-    pub fn Mod(&self, modulus: &ubint<limb_t>) -> ubint<limb_t> {
-        if modulus.m_MSB == 0 {
-            OPENFHE_THROW(lbcrypto::math_error, "Mod() using zero modulus");
-        }
-        if *self < *modulus {
-            return self.clone();
-        }
-        if modulus.m_MSB == 2 && modulus.m_value[0] == 2 {
-            return ubint(self.m_value[0] & 0x1);
-        }
-        let mut ans = ubint();
-        divr_vect(ans, self, modulus);
-        return ans;
+impl<T: NativeInt> Shr<u32> for Ubint<T> {
+    type Output = Self;
+    fn shr(self, shift: u32) -> Self {
+        &self >> shift
     }
+}
 
-    pub fn ModEq(&mut self, modulus: &ubint<limb_t>) -> &ubint<limb_t> {
-        if modulus.m_MSB == 0 {
-            OPENFHE_THROW(lbcrypto::math_error, "Mod() using zero modulus");
-        }
-        if *self < *modulus {
-            self.m_value.resize(1);
-            self.m_value[0] &= 0x1;
-            self.m_MSB = self.m_value[0];
-            return self;
-        }
-        let mut ans = ubint();
-        divr_vect(ans, self, modulus);
-        return self = ans;
+impl<T: NativeInt> ShrAssign<u32> for Ubint<T> {
+    fn shr_assign(&mut self, shift: u32) {
+        *self = &*self >> shift;
     }
+}
 
-    pub fn ModAdd(&self, b: &ubint<limb_t>, modulus: &ubint<limb_t>) -> ubint<limb_t> {
-        let mut bv = b.clone();
-        if bv >= *modulus {
-            bv.ModEq(modulus);
-        }
-        let mut av = self.clone();
-        if av >= *modulus {
-            av.ModEq(modulus);
-        }
-        av = av.Add(bv);
-        if av >= *modulus {
-            return av.Sub(modulus);
-        }
-        return av;
+impl<T: NativeInt> IntegerInterface for Ubint<T> {
+    fn from_u64(value: u64) -> Self {
+        Self::from(value)
     }
 
-    pub fn ModAddEq(&mut self, b: &ubint<limb_t>, modulus: &ubint<limb_t>) -> &ubint<limb_t> {
-        let mut bv = b.clone();
-        if bv >= *modulus {
-            bv.ModEq(modulus);
-        }
-        if *self >= *modulus {
-            ubint<limb_t>::ModEq(modulus);
-        }
-        *self = bv.Add(*self);
-        if *self >= *modulus {
-            return ubint<limb_t>::SubEq(modulus);
+    fn to_u64(&self) -> Option<u64> {
+        if self.get_msb() <= 64 {
+            Some(self.convert_to_int())
+        } else {
+            None
         }
-        return self;
     }
 
-    pub fn ModAddFast(&self, b: &ubint<limb_t>, modulus: &ubint<limb_t>) -> ubint<limb_t> {
-        let ans = b.Add(*self);
-        if ans >= *modulus {
-            return ans.Sub(modulus);
-        }
-        return ans;
+    fn get_msb(&self) -> u32 {
+        Ubint::get_msb(self)
     }
 
-    pub fn ModAddFastEq(&mut self, b: &ubint<limb_t>, modulus: &ubint<limb_t>) -> &ubint<limb_t> {
-        *self = b.Add(*self);
-        if *self >= *modulus {
-            return ubint<limb_t>::SubEq(modulus);
-        }
-        return self;
+    fn get_bit_at_index(&self, index: u32) -> bool {
+        Ubint::get_bit_at_index(self, index)
     }
 
-    pub fn ModSub(&self, b: &ubint<limb_t>, modulus: &ubint<limb_t>) -> ubint<limb_t> {
-        let mut av = self.clone();
-        let mut bv = b.clone();
-        if bv >= *modulus {
-            bv.ModEq(modulus);
-        }
-        if av >= *modulus {
-            av.ModEq(modulus);
-        }
-        if av < bv {
-            av = modulus.Add(av);
-        }
-        return av.Sub(bv);
+    fn mod_mul(&self, b: &Self, modulus: &Self) -> Self {
+        Ubint::mod_mul(self, b, modulus)
     }
 
-    pub fn ModSubEq(&mut self, b: &ubint<limb_t>, modulus: &ubint<limb_t>) -> &ubint<limb_t> {
-        let mut bv = b.clone();
-        if bv >= *modulus {
-            bv.ModEq(modulus);
-        }
-        if *self >= *modulus {
-            ubint<limb_t>::ModEq(modulus);
-        }
-        if *self < bv {
-            *self = modulus.Add(*self);
-        }
-        return ubint<limb_t>::SubEq(bv);
+    fn mod_add(&self, b: &Self, modulus: &Self) -> Self {
+        Ubint::mod_add(self, b, modulus)
     }
 
-    pub fn ModSubFast(&self, b: &ubint<limb_t>, modulus: &ubint<limb_t>) -> ubint<limb_t> {
-        if *self < *b {
-            return modulus.Add(*self).Sub(b);
-        }
-        return ubint<limb_t>::Sub(b);
+    fn mod_sub(&self, b: &Self, modulus: &Self) -> Self {
+        Ubint::mod_sub(self, b, modulus)
     }
 
-    pub fn ModSubFastEq(&mut self, b: &ubint<limb_t>, modulus: &ubint<limb_t>) -> &ubint<limb_t> {
-        if *self < *b {
-            return *self = std::move(modulus.Add(*self).Sub(b));
-        }
-        return ubint<limb_t>::SubEq(b);
+    fn mod_exp(&self, e: &Self, modulus: &Self) -> Self {
+        Ubint::mod_exp(self, e, modulus)
     }
 }
 
-
-use std::cmp::Ordering;
-use std::ops::{Add, Sub, Mul, Div, Rem, Shl, Shr};
-
-#[derive(Clone, Debug, PartialEq, Eq)]
-struct ubint<T> {
-    m_value: Vec<T>,
-    m_MSB: usize,
-    m_limbBitLength: usize,
-    m_log2LimbBitLength: usize,
-}
-
-impl<T> ubint<T> {
-    fn new() -> Self {
-        ubint {
-            m_value: Vec::new(),
-            m_MSB: 0,
-            m_limbBitLength: 0,
-            m_log2LimbBitLength: 0,
-        }
+impl<T: NativeInt> BigIntegerInterface for Ubint<T> {
+    fn modulo(&self, modulus: &Self) -> Self {
+        Ubint::modulo(self, modulus)
     }
 
-    fn set_MSB(&mut self) {
-        self.m_MSB = self.m_value.len() * self.m_limbBitLength;
-        if let Some(&last) = self.m_value.last() {
-            let mut mask = 1 << (self.m_limbBitLength - 1);
-            while mask > 0 && last & mask == 0 {
-                self.m_MSB -= 1;
-                mask >>= 1;
-            }
-        }
+    fn compute_mu(&self) -> Self {
+        Ubint::compute_mu(self)
     }
 
-    fn add(&self, other: &ubint<T>) -> ubint<T> {
-        let mut ans = ubint::new();
-        let mut carry = 0;
-        let mut i = 0;
-        while i < self.m_value.len() || i < other.m_value.len() || carry > 0 {
-            let mut sum = carry;
-            if i < self.m_value.len() {
-                sum += self.m_value[i];
-            }
-            if i < other.m_value.len() {
-                sum += other.m_value[i];
-            }
-            ans.m_value.push(sum % (1 << self.m_limbBitLength));
-            carry = sum / (1 << self.m_limbBitLength);
-            i += 1;
-        }
-        ans.set_MSB();
-        ans
+    fn modulo_with_mu(&self, modulus: &Self, mu: &Self) -> Self {
+        Ubint::modulo_with_mu(self, modulus, mu)
     }
 
-    fn mod_add(&self, other: &ubint<T>, modulus: &ubint<T>) -> ubint<T> {
-        let ans = self.add(other);
-        if ans >= *modulus {
-            ans.modulus(modulus)
-        } else {
-            ans
-        }
+    fn mod_add_fast(&self, b: &Self, modulus: &Self) -> Self {
+        Ubint::mod_add_fast(self, b, modulus)
     }
 
-    fn modulus(&self, modulus: &ubint<T>) -> ubint<T> {
-        let mut ans = self.clone();
-        while ans >= *modulus {
-            ans = ans.sub(modulus);
-        }
-        ans
+    fn mod_sub_fast(&self, b: &Self, modulus: &Self) -> Self {
+        Ubint::mod_sub_fast(self, b, modulus)
     }
 
-    fn sub(&self, other: &ubint<T>) -> ubint<T> {
-        let mut ans = ubint::new();
-        let mut borrow = 0;
-        let mut i = 0;
-        while i < self.m_value.len() || i < other.m_value.len() || borrow > 0 {
-            let mut diff = borrow;
-            if i < self.m_value.len() {
-                diff += self.m_value[i];
-            }
-            if i < other.m_value.len() {
-                diff -= other.m_value[i];
-            }
-            if diff < 0 {
-                diff += 1 << self.m_limbBitLength;
-                borrow = -1;
-            } else {
-                borrow = 0;
-            }
-            ans.m_value.push(diff);
-            i += 1;
-        }
-        ans.set_MSB();
-        ans
+    fn mod_mul_fast(&self, b: &Self, modulus: &Self, mu: &Self) -> Self {
+        Ubint::mod_mul_fast(self, b, modulus, mu)
     }
 
-    fn mod_sub(&self, other: &ubint<T>, modulus: &ubint<T>) -> ubint<T> {
-        let ans = self.sub(other);
-        if ans < ubint::new() {
-            ans.add(modulus)
-        } else {
-            ans
-        }
+    fn mod_inverse(&self, modulus: &Self) -> Self {
+        Ubint::mod_inverse(self, modulus)
     }
 
-    fn mul(&self, other: &ubint<T>) -> ubint<T> {
-        let mut ans = ubint::new();
-        ans.m_value.resize(self.m_value.len() + other.m_value.len(), 0);
-        for i in 0..self.m_value.len() {
-            let mut carry = 0;
-            for j in 0..other.m_value.len() {
-                let product = self.m_value[i] * other.m_value[j] + ans.m_value[i + j] + carry;
-                ans.m_value[i + j] = product % (1 << self.m_limbBitLength);
-                carry = product / (1 << self.m_limbBitLength);
-            }
-            ans.m_value[i + other.m_value.len()] = carry;
-        }
-        ans.set_MSB();
-        ans
-    }
-
-    fn mod_mul(&self, other: &ubint<T>, modulus: &ubint<T>) -> ubint<T> {
-        let ans = self.mul(other);
-        ans.modulus(modulus)
-    }
-
-    fn div(&self, other: &ubint<T>) -> ubint<T> {
-        let mut ans = ubint::new();
-        let mut remainder = self.clone();
-        let mut quotient = ubint::new();
-        while remainder >= *other {
-            let mut shift = remainder.m_MSB - other.m_MSB;
-            let mut divisor = other.clone();
-            divisor <<= shift;
-            if remainder < divisor {
-                shift -= 1;
-                divisor >>= 1;
-            }
-            remainder = remainder.sub(&divisor);
-            quotient.m_value.push(1 << shift);
-        }
-        quotient.set_MSB();
-        quotient
-    }
-
-    fn mod_div(&self, other: &ubint<T>, modulus: &ubint<T>) -> ubint<T> {
-        let ans = self.div(other);
-        ans.modulus(modulus)
-    }
-
-    fn shl(&self, shift: usize) -> ubint<T> {
-        let mut ans = self.clone();
-        ans.m_MSB += shift;
-        let shift_by_limb = shift >> self.m_log2LimbBitLength;
-        let shift = shift % self.m_limbBitLength;
-        if shift > 0 {
-            let mut carry = 0;
-            for v in ans.m_value.iter_mut() {
-                let new_carry = *v >> (self.m_limbBitLength - shift);
-                *v = (*v << shift) + carry;
-                carry = new_carry;
-            }
-            if carry > 0 {
-                ans.m_value.push(carry);
-            }
-        }
-        if shift_by_limb > 0 {
-            let j = ans.m_value.len();
-            let i = j + shift_by_limb;
-            ans.m_value.resize(i, 0);
-            while i > 0 {
-                ans.m_value[i - 1] = if j > 0 { ans.m_value[j - 1] } else { 0 };
-                i -= 1;
-                j -= 1;
-            }
-        }
-        ans
+    fn multiply_and_round(&self, p: &Self, q: &Self) -> Self {
+        Ubint::multiply_and_round(self, p, q)
     }
 
-    fn shr(&self, shift: usize) -> ubint<T> {
-        if self.m_MSB <= shift {
-            return ubint::new();
-        }
-        let mut ans = self.clone();
-        ans.m_MSB -= shift;
-        let shift_by_limb = shift >> self.m_log2LimbBitLength;
-        let shift = shift % self.m_limbBitLength;
-        let mut tmp = ans.m_value[shift_by_limb] >> shift;
-        let lshift = self.m_limbBitLength - shift;
-        let size = ans.m_value.len() - shift_by_limb;
-        for i in 0..size {
-            tmp |= (ans.m_value[i + shift_by_limb] as T) << lshift;
-            ans.m_value[i] = tmp % (1 << self.m_limbBitLength);
-            tmp /= 1 << self.m_limbBitLength;
-        }
-        ans.m_value.resize(size, 0);
-        if tmp > 0 {
-            ans.m_value.push(tmp);
-        }
-        ans
-    }
-
-    fn mod_exp(&self, b: &ubint<T>, modulus: &ubint<T>) -> ubint<T> {
-        let mut t = self.modulus(modulus);
-        let mut p = b.clone();
-        let mut r = ubint::new();
-        if p.m_value[0] & 0x1 > 0 {
-            r = r.mod_mul(&t, modulus);
-        }
-        while p >>= 1 > 0 {
-            t = t.mod_mul(&t, modulus);
-            if p.m_value[0] & 0x1 > 0 {
-                r = r.mod_mul(&t, modulus);
-            }
-        }
-        r
+    fn divide_and_round(&self, q: &Self) -> Self {
+        Ubint::divide_and_round(self, q)
     }
 
-    fn from_binary_string(vin: &str) -> ubint<T> {
-        let mut v = vin.to_string();
-        v = v.trim_start_matches('0').to_string();
-        if v.is_empty() {
-            return ubint::new();
-        }
-        let mut value = ubint::new();
-        value.m_value.clear();
-        let len = v.len();
-        let cntr = (len as f64 / value.m_limbBitLength as f64).ceil() as usize;
-        let mut partial_value = 0;
-        for i in 0..cntr {
-            let start = len - (i + 1) * value.m_limbBitLength;
-            let end = if len > (i + 1) * value.m_limbBitLength {
-                len - (i + 1) * value.m_limbBitLength
-            } else {
-                0
-            };
-            let val = &v[end..start];
-            for j in 0..val.len() {
-                partial_value += val[j..j + 1].parse::<T>().unwrap();
-                partial_value <<= 1;
-            }
-            partial_value >>= 1;
-            value.m_value.push(partial_value);
-            partial_value = 0;
-        }
-        value.set_MSB();
-        value
-    }
-
-    fn to_string(&self) -> String {
-        let mut val = Vec::new();
-        val.reserve(self.m_MSB >> 1);
-        for i in (1..=self.m_MSB).rev() {
-            let mut ofl = self.get_bit_at_index(i);
-            for a in &mut val {
-                *a = (*a << 1) + ofl;
-                if *a > 9 {
-                    *a -= 10;
-                    ofl = 1;
-                } else {
-                    ofl = 0;
-                }
-            }
-            if ofl > 0 {
-                val.push(1);
-            }
-        }
-        val.iter()
-            .rev()
-            .map(|a| (a + '0' as T) as char)
-            .collect::<String>()
+    fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> u64 {
+        Ubint::get_digit_at_index_for_base(self, index, bits)
     }
 
-    fn get_bit_at_index(&self, index: usize) -> T {
-        let digit_len = (self.m_limbBitLength as f64).log2().ceil() as usize;
-        let mut digit = 0;
-        let new_index = 1 + (index - 1) * digit_len;
-        for i in 1..self.m_limbBitLength {
-            digit += self.get_bit_at_index(new_index + i) * (1 << i);
-        }
-        digit
+    fn convert_to_double(&self) -> f64 {
+        Ubint::convert_to_double(self)
     }
 }
 
-impl<T: Add<Output = T> + Copy> Add for ubint<T> {
-    type Output = Self;
+/* Операции над словами. Числа хранятся от младшего слова к старшему, результаты
+ * могут содержать старшие нули. */
 
-    fn add(self, other: Self) -> Self {
-        self.add(&other)
-    }
+/// Младшее слово значения
+fn limb<T: NativeInt>(value: u128) -> T {
+    let mask = u128::MAX.checked_shr(128 - T::BITS).unwrap_or(u128::MAX);
+    <T as NumCast>::from(value & mask).unwrap()
 }
 
-impl<T: Sub<Output = T> + Copy> Sub for ubint<T> {
-    type Output = Self;
-
-    fn sub(self, other: Self) -> Self {
-        self.sub(&other)
+/// a + b + carry с переносом
+fn add_carry<T: NativeInt>(a: T, b: T, carry: bool) -> (T, bool) {
+    let s = a.wrapping_add(&b);
+    let c = s < a;
+    if carry {
+        let s2 = s.wrapping_add(&T::one());
+        (s2, c || s2.is_zero())
+    } else {
+        (s, c)
     }
 }
 
-impl<T: Mul<Output = T> + Copy> Mul for ubint<T> {
-    type Output = Self;
-
-    fn mul(self, other: Self) -> Self {
-        self.mul(&other)
+/// a - b - borrow с заёмом
+fn sub_borrow<T: NativeInt>(a: T, b: T, borrow: bool) -> (T, bool) {
+    let d = a.wrapping_sub(&b);
+    let c = a < b;
+    if borrow {
+        (d.wrapping_sub(&T::one()), c || d.is_zero())
+    } else {
+        (d, c)
     }
 }
 
-impl<T: Div<Output = T> + Copy> Div for ubint<T> {
-    type Output = Self;
-
-    fn div(self, other: Self) -> Self {
-        self.div(&other)
-    }
+fn cmp_limbs<T: NativeInt>(a: &[T], b: &[T]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
 }
 
-impl<T: Rem<Output = T> + Copy> Rem for ubint<T> {
-    type Output = Self;
-
-    fn rem(self, other: Self) -> Self {
-        self.mod_div(&other, &ubint::new())
+fn add_limbs<T: NativeInt>(a: &[T], b: &[T]) -> Vec<T> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut res = Vec::with_capacity(long.len() + 1);
+    let mut carry = false;
+    for (i, &x) in long.iter().enumerate() {
+        let (s, c) = add_carry(x, short.get(i).copied().unwrap_or_else(T::zero), carry);
+        res.push(s);
+        carry = c;
     }
-}
-
-impl<T: Shl<usize, Output = T> + Copy> Shl<usize> for ubint<T> {
-    type Output = Self;
-
-    fn shl(self, shift: usize) -> Self {
-        self.shl(shift)
+    if carry {
+        res.push(T::one());
     }
+    res
 }
 
-impl<T: Shr<usize, Output = T> + Copy> Shr<usize> for ubint<T> {
-    type Output = Self;
-
-    fn shr(self, shift: usize) -> Self {
-        self.shr(shift)
+/// a - b и признак того, что b > a
+fn sub_limbs<T: NativeInt>(a: &[T], b: &[T]) -> (Vec<T>, bool) {
+    let mut res = Vec::with_capacity(a.len());
+    let mut borrow = false;
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).copied().unwrap_or_else(T::zero);
+        let (d, c) = sub_borrow(x, b.get(i).copied().unwrap_or_else(T::zero), borrow);
+        res.push(d);
+        borrow = c;
     }
+    (res, borrow)
 }
 
-impl<T: PartialOrd> PartialOrd for ubint<T> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+/// res += x * B^shift
+fn add_shifted<T: NativeInt>(res: &mut Vec<T>, x: &[T], shift: usize) {
+    if res.len() < shift + x.len() {
+        res.resize(shift + x.len(), T::zero());
     }
-}
-
-impl<T: Ord> Ord for ubint<T> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        if self.m_MSB > other.m_MSB {
-            Ordering::Greater
-        } else if self.m_MSB < other.m_MSB {
-            Ordering::Less
-        } else {
-            for i in (0..self.m_value.len()).rev() {
-                if self.m_value[i] > other.m_value[i] {
-                    return Ordering::Greater;
-                } else if self.m_value[i] < other.m_value[i] {
-                    return Ordering::Less;
-                }
-            }
-            Ordering::Equal
+    let mut carry = false;
+    let mut i = 0;
+    while i < x.len() || carry {
+        if shift + i == res.len() {
+            res.push(T::zero());
         }
+        let y = x.get(i).copied().unwrap_or_else(T::zero);
+        let (s, c) = add_carry(res[shift + i], y, carry);
+        res[shift + i] = s;
+        carry = c;
+        i += 1;
     }
 }
 
-/* q[0], r[0], u[0], and v[0] contain the LEAST significant words.
- (The sequence is in little-endian order).
-
- This is a fairly precise implementation of Knuth's Algorithm D, for a
- binary computer with base b = 2**(32|64). The caller supplies:
- 1. Space q for the quotient, m - n + 1 words (at least one).
- 2. Space r for the remainder (optional), n words.
- 3. The dividend u, m words, m >= 1.
- 4. The divisor v, n words, n >= 2.
- The most significant digit of the divisor, v[n-1], must be nonzero.  The
- dividend u may have leading zeros; this just makes the algorithm take
- longer and makes the quotient contain more leading zeros.  A value of
- nullptr may be given for the address of the remainder to signify that the
- caller does not want the remainder.
- The program does not alter the input parameters u and v.
- The quotient and remainder returned may have leading zeros.  The
- function itself returns a value of 0 for success and 1 for invalid
- parameters (e.g., division by 0).
- For now, we must have m >= n.  Knuth's Algorithm D also requires
- that the dividend be at least as long as the divisor.  (In his terms,
- m >= 0 (unstated).  Therefore m+n >= n.) */
-
- fn divqr_vect<T: Copy + Default + PartialOrd + Div<Output = T> + Rem<Output = T>>(
-    qin: &mut ubint<T>,
-    rin: &mut ubint<T>,
-    uin: &ubint<T>,
-    vin: &ubint<T>,
-) {
-    let u = &uin.m_value;
-    let m = u.len();
-    let v = &vin.m_value;
-    let n = v.len();
-    let q = &mut qin.m_value;
-    q.resize(m - n + 1, Default::default());
-    let r = &mut rin.m_value;
-    let mut ofl: T = Default::default();
-    if n == 1 {
-        for i in (0..m).rev() {
-            ofl = (ofl << m_limbBitLength) | u[i];
-            q[i] = ofl / v[0];
-            ofl %= v[0];
-        }
-        qin.NormalizeLimbs();
-        r.resize(1, Default::default());
-        r[0] = ofl;
-        rin.m_MSB = lbcrypto::GetMSB(r[0]);
-        return;
-    }
-    let sl = m_limbBitLength - lbcrypto::GetMSB(v[v.len() - 1]);
-    let mut vn = Vec::with_capacity(n);
-    ofl = Default::default();
-    for i in 0..n {
-        ofl |= (v[i] as Dlimb_t) << sl;
-        vn.push(ofl as limb_t);
-        ofl >>= m_limbBitLength;
-    }
-    let mut un = Vec::with_capacity(m + 1);
-    ofl = Default::default();
-    for i in 0..m {
-        ofl |= (u[i] as Dlimb_t) << sl;
-        un.push(ofl as limb_t);
-        ofl >>= m_limbBitLength;
-    }
-    un.push(ofl as limb_t);
-    let mut qhat: Dlimb_t;
-    let mut rhat: Dlimb_t;
-    let mut p: Dlimb_t;
-    for j in (0..=m - n).rev() {
-        ofl = (un[j + n] as Dlimb_t) << m_limbBitLength | un[j + n - 1] as Dlimb_t;
-        qhat = ofl / vn[n - 1] as Dlimb_t;
-        rhat = ofl % vn[n - 1] as Dlimb_t;
-        while qhat >> m_limbBitLength != 0
-            || qhat * vn[n - 2] as Dlimb_t > (rhat << m_limbBitLength) | un[j + n - 2] as Dlimb_t
-        {
-            qhat -= 1;
-            rhat += vn[n - 1] as Dlimb_t;
-            if rhat >> m_limbBitLength != 0 {
-                break;
-            }
-        }
-        let mut k: SDlimb_t = 0;
-        let mut t: SDlimb_t;
-        for i in 0..n {
-            p = qhat * vn[i] as Dlimb_t;
-            t = un[i + j] as SDlimb_t - k - (p & m_MaxLimb as Dlimb_t) as SDlimb_t;
-            un[i + j] = t as limb_t;
-            k = (p >> m_limbBitLength) as SDlimb_t - (t >> m_limbBitLength) as SDlimb_t;
-        }
-        t = un[j + n] as SDlimb_t - k;
-        un[j + n] = t as limb_t;
-        q[j] = qhat as limb_t;
-        if t < 0 {
-            q[j] -= 1;
-            k = 0;
-            for i in 0..n {
-                t = un[i + j] as Dlimb_t + vn[i] as Dlimb_t + k as Dlimb_t;
-                un[i + j] = t as limb_t;
-                k = (t >> m_limbBitLength) as SDlimb_t;
-            }
-            un[j + n] = (un[j + n] as Dlimb_t + k as Dlimb_t) as limb_t;
-        }
+/// a = a * m + c
+fn mul_add_small<T: NativeInt>(a: &mut Vec<T>, m: T, c: T) {
+    let mut carry = c;
+    for x in a.iter_mut() {
+        let (h, l) = T::mul_wide(*x, m);
+        let (l, c) = add_carry(l, carry, false);
+        *x = l;
+        carry = if c { h + T::one() } else { h };
+    }
+    if !carry.is_zero() {
+        a.push(carry);
     }
-    qin.NormalizeLimbs();
-    ofl = (un[0] as Dlimb_t) >> sl;
-    let sr = m_limbBitLength - sl;
-    r.resize(n, Default::default());
-    for i in 0..n {
-        ofl |= (un[i + 1] as Dlimb_t) << sr;
-        r[i] = ofl as limb_t;
-        ofl >>= m_limbBitLength;
-    }
-    r[n] = (un[n] as Dlimb_t) >> sl;
-    rin.NormalizeLimbs();
 }
 
-fn divq_vect<T: Copy + Default + PartialOrd + Div<Output = T> + Rem<Output = T>>(
-    qin: &mut ubint<T>,
-    uin: &ubint<T>,
-    vin: &ubint<T>,
-) {
-    let u = &uin.m_value;
-    let m = u.len();
-    let v = &vin.m_value;
-    let n = v.len();
-    let q = &mut qin.m_value;
-    q.resize(m - n + 1, Default::default());
-    let mut ofl: T = Default::default();
-    if n == 1 {
-        for i in (0..m).rev() {
-            ofl = (ofl << m_limbBitLength) | u[i];
-            q[i] = ofl / v[0];
-            ofl %= v[0];
-        }
-        qin.NormalizeLimbs();
-        return;
-    }
-    let sl = m_limbBitLength - lbcrypto::GetMSB(v[v.len() - 1]);
-    let mut vn = Vec::with_capacity(n);
-    ofl = Default::default();
-    for i in 0..n {
-        ofl |= (v[i] as Dlimb_t) << sl;
-        vn.push(ofl as limb_t);
-        ofl >>= m_limbBitLength;
-    }
-    let mut un = Vec::with_capacity(m + 1);
-    ofl = Default::default();
-    for i in 0..m {
-        ofl |= (u[i] as Dlimb_t) << sl;
-        un.push(ofl as limb_t);
-        ofl >>= m_limbBitLength;
-    }
-    un.push(ofl as limb_t);
-    let mut qhat: Dlimb_t;
-    let mut rhat: Dlimb_t;
-    let mut p: Dlimb_t;
-    for j in (0..=m - n).rev() {
-        ofl = (un[j + n] as Dlimb_t) << m_limbBitLength | un[j + n - 1] as Dlimb_t;
-        qhat = ofl / vn[n - 1] as Dlimb_t;
-        rhat = ofl % vn[n - 1] as Dlimb_t;
-        while qhat >> m_limbBitLength != 0
-            || qhat * vn[n - 2] as Dlimb_t > (rhat << m_limbBitLength) | un[j + n - 2] as Dlimb_t
-        {
-            qhat -= 1;
-            rhat += vn[n - 1] as Dlimb_t;
-            if rhat >> m_limbBitLength != 0 {
-                break;
-            }
-        }
-        let mut k: SDlimb_t = 0;
-        let mut t: SDlimb_t;
-        for i in 0..n {
-            p = qhat * vn[i] as Dlimb_t;
-            t = un[i + j] as SDlimb_t - k - (p & m_MaxLimb as Dlimb_t) as SDlimb_t;
-            un[i + j] = t as limb_t;
-            k = (p >> m_limbBitLength) as SDlimb_t - (t >> m_limbBitLength) as SDlimb_t;
-        }
-        t = un[j + n] as SDlimb_t - k;
-        un[j + n] = t as limb_t;
-        q[j] = qhat as limb_t;
-        if t < 0 {
-            q[j] -= 1;
-            k = 0;
-            for i in 0..n {
-                t = un[i + j] as Dlimb_t + vn[i] as Dlimb_t + k as Dlimb_t;
-                un[i + j] = t as limb_t;
-                k = (t >> m_limbBitLength) as SDlimb_t;
-            }
-            un[j + n] = (un[j + n] as Dlimb_t + k as Dlimb_t) as limb_t;
-        }
+/// a = a / d, возвращает остаток
+fn div_rem_small<T: NativeInt>(a: &mut [T], d: T) -> T {
+    let mut rem = T::zero();
+    for x in a.iter_mut().rev() {
+        let (q, r) = T::div_rem_wide(rem, *x, d);
+        *x = q;
+        rem = r;
     }
-    qin.NormalizeLimbs();
+    rem
 }
-
-fn divr_vect<T: Copy + Default + PartialOrd + Div<Output = T> + Rem<Output = T>>(
-    rin: &mut ubint<T>,
-    uin: &ubint<T>,
-    vin: &ubint<T>,
-) {
-    let u = &uin.m_value;
-    let m = u.len();
-    let v = &vin.m_value;
-    let n = v.len();
-    let r = &mut rin.m_value;
-    let mut ofl: T = Default::default();
-    if n == 1 {
-        let mut q = Vec::with_capacity(m - n + 1);
-        for i in (0..m).rev() {
-            ofl = (ofl << m_limbBitLength) | u[i];
-            q.push(ofl / v[0]);
-            ofl %= v[0];
-        }
-        r[0] = ofl;
-        rin.m_MSB = lbcrypto::GetMSB(r[0]);
-        return;
-    }
-    let sl = m_limbBitLength - lbcrypto::GetMSB(v[v.len() - 1]);
-    let mut vn = Vec::with_capacity(n);
-    ofl = Default::default();
-    for i in 0..n {
-        ofl |= (v[i] as Dlimb_t) << sl;
-        vn.push(ofl as limb_t);
-        ofl >>= m_limbBitLength;
-    }
-    let mut un = Vec::with_capacity(m + 1);
-    ofl = Default::default();
-    for i in 0..m {
-        ofl |= (u[i] as Dlimb_t) << sl;
-        un.push(ofl as limb_t);
-        ofl >>= m_limbBitLength;
-    }
-    un.push(ofl as limb_t);
-    let mut qhat: Dlimb_t;
-    let mut rhat: Dlimb_t;
-    let mut p: Dlimb_t;
-    for j in (0..=m - n).rev() {
-        ofl = (un[j + n] as Dlimb_t) << m_limbBitLength | un[j + n - 1] as Dlimb_t;
-        qhat = ofl / vn[n - 1] as Dlimb_t;
-        rhat = ofl % vn[n - 1] as Dlimb_t;
-        while qhat >> m_limbBitLength != 0
-            || qhat * vn[n - 2] as Dlimb_t > (rhat << m_limbBitLength) | un[j + n - 2] as Dlimb_t
-        {
-            qhat -= 1;
-            rhat += vn[n - 1] as Dlimb_t;
-            if rhat >> m_limbBitLength != 0 {
-                break;
-            }
-        }
-        let mut k: SDlimb_t = 0;
-        let mut t: SDlimb_t;
-        for i in 0..n {
-            p = qhat * vn[i] as Dlimb_t;
-            t = un[i + j] as SDlimb_t - k - (p & m_MaxLimb as Dlimb_t) as SDlimb_t;
-            un[i + j] = t as limb_t;
-            k = (p >> m_limbBitLength) as SDlimb_t - (t >> m_limbBitLength) as SDlimb_t;
-        }
-        t = un[j + n] as SDlimb_t - k;
-        un[j + n] = t as limb_t;
-        if t < 0 {
-            k = 0;
-            for i in 0..n {
-                t = un[i + j] as Dlimb_t + vn[i] as Dlimb_t + k as Dlimb_t;
-                un[i + j] = t as limb_t;
-                k = (t >> m_limbBitLength) as SDlimb_t;
-            }
-            un[j + n] = (un[j + n] as Dlimb_t + k as Dlimb_t) as limb_t;
+
+fn mul_schoolbook<T: NativeInt>(a: &[T], b: &[T]) -> Vec<T> {
+    let mut out = vec![T::zero(); a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = T::zero();
+        for (j, &y) in b.iter().enumerate() {
+            let (h, l) = T::mul_wide(x, y);
+            let (l, c1) = add_carry(l, out[i + j], false);
+            let (l, c2) = add_carry(l, carry, false);
+            out[i + j] = l;
+            // x * y + out + carry < B^2, поэтому старшее слово не переполняется
+            carry =
+                h + if c1 { T::one() } else { T::zero() } + if c2 { T::one() } else { T::zero() };
         }
+        out[i + b.len()] = carry;
     }
-    r.resize(n, Default::default());
-    ofl = (un[0] as Dlimb_t) >> sl;
-    let sr = m_limbBitLength - sl;
-    for i in 0..n {
-        ofl |= (un[i + 1] as Dlimb_t) << sr;
-        r[i] = ofl as limb_t;
-        ofl >>= m_limbBitLength;
-    }
-    r[n] = (un[n] as Dlimb_t) >> sl;
-    rin.NormalizeLimbs();
+    out
 }
 
+/// Произведение в столбик для коротких множителей и по Карацубе для длинных
+fn mul_limbs<T: NativeInt>(a: &[T], b: &[T]) -> Vec<T> {
+    if a.len().min(b.len()) < KARATSUBA_THRESHOLD {
+        return mul_schoolbook(a, b);
+    }
+
+    // a = a1 * B^k + a0, b = b1 * B^k + b0
+    let k = a.len().max(b.len()) / 2;
+    let (a0, a1) = a.split_at(k.min(a.len()));
+    let (b0, b1) = b.split_at(k.min(b.len()));
+    let z0 = mul_limbs(a0, b0);
+    let z2 = mul_limbs(a1, b1);
+    // z1 = (a0 + a1)(b0 + b1) - z0 - z2
+    let z1 = mul_limbs(&add_limbs(a0, a1), &add_limbs(b0, b1));
+    let z1 = sub_limbs(&sub_limbs(&z1, &z0).0, &z2).0;
 
-
-// Initializes the vector of limbs from the string equivalent of ubint
-// Algorithm used is repeated division by 2
-// Reference:http://pctechtips.org/convert-from-decimal-to-binary-with-recursion-in-java/
-
-use std::convert::TryInto;
-
-pub struct Ubint<T> {
-    m_value: Vec<T>,
-    m_limb_bit_length: usize,
-    m_MSB: usize,
+    let mut res = z0;
+    add_shifted(&mut res, &z1, k);
+    add_shifted(&mut res, &z2, 2 * k);
+    res
 }
 
-impl<T> Ubint<T> {
-    pub fn set_value(&mut self, vin: &str) {
-        let mut v = vin.to_string();
-        
-        v = v.trim_start_matches('0').to_string();
-        if v.is_empty() {
-            v = "0".to_string();
-        }
-        let arr_size = v.len() - 1;
-        let v: Vec<u8> = v.chars().map(|c| c.to_digit(10).unwrap().try_into().unwrap()).collect();
-        self.m_value.clear();
-        
-        let mut cnt = 0;
-        let mut val: T = 0.try_into().unwrap();
-        let mut zptr = 0;
-        while zptr <= arr_size {
-            val |= (v[arr_size] & 0x1).try_into().unwrap() << cnt;
-            for i in zptr..arr_size {
-                v[i + 1] += (v[i] & 0x1) * 10;
-                v[i] >>= 1;
-            }
-            v[arr_size] >>= 1;
-            if v[zptr] == 0 {
-                zptr += 1;
-            }
-            if cnt == self.m_limb_bit_length || zptr > arr_size {
-                self.m_value.push(val);
-                cnt = 0;
-                val = 0.try_into().unwrap();
-            }
-        }
-        self.normalize_limbs();
+/// Сдвиг влево на shift бит
+fn shl_limbs<T: NativeInt>(a: &[T], shift: u32) -> Vec<T> {
+    let (words, bits) = ((shift / T::BITS) as usize, shift % T::BITS);
+    let mut res = vec![T::zero(); words];
+    if bits == 0 {
+        res.extend_from_slice(a);
+        return res;
+    }
+    let mut carry = T::zero();
+    for &x in a {
+        res.push((x << bits as usize) | carry);
+        carry = x >> (T::BITS - bits) as usize;
     }
-
-    pub fn get_bit_at_index(&self, index: usize) -> u8 {
-        let mask = self.m_limb_bit_length - 1;
-        if index > self.m_MSB {
-            return 0;
-        }
-        let idx = self.MSB_to_limbs(index) - 1;
-        let index = index & mask;
-        return ((self.m_value[idx] >> (if index != 0 { index - 1 } else { mask })) & 0x1).try_into().unwrap();
+    res.push(carry);
+    res
+}
+
+/// Сдвиг вправо на shift бит
+fn shr_limbs<T: NativeInt>(a: &[T], shift: u32) -> Vec<T> {
+    let (words, bits) = ((shift / T::BITS) as usize, shift % T::BITS);
+    if words >= a.len() {
+        return vec![];
+    }
+    let a = &a[words..];
+    if bits == 0 {
+        return a.to_vec();
     }
-
-    fn normalize_limbs(&mut self) {
-        // implementation of NormalizeLimbs function
-    }
-
-    fn MSB_to_limbs(&self, index: usize) -> usize {
-        // implementation of MSBToLimbs function
+    (0..a.len())
+        .map(|i| {
+            let high = a
+                .get(i + 1)
+                .map_or(T::zero(), |&x| x << (T::BITS - bits) as usize);
+            (a[i] >> bits as usize) | high
+        })
+        .collect()
+}
+
+/// Частное и остаток от деления u на v (v без старших нулей) по алгоритму D
+/// Кнута (TAOCP, 4.3.1)
+fn div_rem_limbs<T: NativeInt>(u: &[T], v: &[T]) -> (Vec<T>, Vec<T>) {
+    if cmp_limbs(u, v) == Ordering::Less {
+        return (vec![], u.to_vec());
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_set_value() {
-        let mut ubint: Ubint<u32> = Ubint {
-            m_value: vec![],
-            m_limb_bit_length: 32,
-            m_MSB: 0,
+    let n = v.len();
+    if n == 1 {
+        let mut quot = u.to_vec();
+        let rem = div_rem_small(&mut quot, v[0]);
+        return (quot, vec![rem]);
+    }
+
+    // Нормализация: старший бит делителя равен 1, у делимого есть лишнее старшее слово
+    let s = v[n - 1].leading_zeros();
+    let vn = &shl_limbs(v, s)[..n];
+    let mut un = shl_limbs(u, s);
+    un.resize(u.len() + 1, T::zero());
+
+    let m = u.len() - n;
+    let mut quot = vec![T::zero(); m + 1];
+    let (v1, v2) = (vn[n - 1], vn[n - 2]);
+    for j in (0..=m).rev() {
+        // Оценка очередной цифры частного по двум старшим словам, ошибается не больше чем на 2
+        let (hi, lo) = (un[j + n], un[j + n - 1]);
+        let (mut qhat, mut rhat) = if hi >= v1 {
+            (T::max_value(), lo.checked_add(&v1))
+        } else {
+            let (q, r) = T::div_rem_wide(hi, lo, v1);
+            (q, Some(r))
         };
-        ubint.set_value("12345");
-        assert_eq!(ubint.m_value, vec![12345]);
-    }
+        while let Some(r) = rhat {
+            if T::mul_wide(qhat, v2) <= (r, un[j + n - 2]) {
+                break;
+            }
+            qhat = qhat - T::one();
+            rhat = r.checked_add(&v1);
+        }
 
-    #[test]
-    fn test_get_bit_at_index() {
-        let ubint: Ubint<u32> = Ubint {
-            m_value: vec![12345],
-            m_limb_bit_length: 32,
-            m_MSB: 0,
-        };
-        assert_eq!(ubint.get_bit_at_index(0), 1);
-        assert_eq!(ubint.get_bit_at_index(1), 0);
-        assert_eq!(ubint.get_bit_at_index(2), 1);
-        assert_eq!(ubint.get_bit_at_index(3), 0);
-        assert_eq!(ubint.get_bit_at_index(4), 1);
+        // un[j..j+n+1] -= qhat * v
+        let mut carry = T::zero();
+        let mut borrow = false;
+        for i in 0..n {
+            let (h, l) = T::mul_wide(qhat, vn[i]);
+            let (l, c) = add_carry(l, carry, false);
+            carry = if c { h + T::one() } else { h };
+            let (d, b) = sub_borrow(un[i + j], l, borrow);
+            un[i + j] = d;
+            borrow = b;
+        }
+        let (d, negative) = sub_borrow(un[j + n], carry, borrow);
+        un[j + n] = d;
+
+        // Оценка оказалась на единицу больше: возвращаем v
+        if negative {
+            qhat = qhat - T::one();
+            let mut c = false;
+            for i in 0..n {
+                let (s, cc) = add_carry(un[i + j], vn[i], c);
+                un[i + j] = s;
+                c = cc;
+            }
+            un[j + n] = un[j + n].wrapping_add(&if c { T::one() } else { T::zero() });
+        }
+        quot[j] = qhat;
     }
+    un.truncate(n);
+    (quot, shr_limbs(&un, s))
 }
-
-
-
-
@@ -1 +1,3 @@
-
+pub mod backendfxd;
+pub mod mubintvecfxd;
+pub mod ubintfxd;
@@ -1,212 +1,367 @@
 /*
-  This file contains the vector manipulation functionality
- */
-
- use std::fmt::Debug;
-use std::ops::{Add, Sub, Mul, AddAssign, SubAssign, MulAssign};
-use serde::{Serialize, Deserialize};
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct BigVectorFixedT<IntegerType> {
-    data: Vec<IntegerType>,
-    modulus: IntegerType,
+  This file contains the vector manipulation functionality for fixed-width big integers
+*/
+use super::ubintfxd::{BigIntegerFixed, BIG_INTEGER_LIMBS};
+use crate::core::math::hal::vector::BigVectorInterface;
+use std::fmt;
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+/// Вектор длинных целых фиксированной разрядности по модулю q.
+///
+/// Как и NativeVectorT, хранит модуль вместе с данными, все элементы
+/// приведены в [0, q). Нужен для модулей больше машинного слова, например
+/// для многочлена по модулю произведения всех башен при CRT-интерполяции.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigVectorFixedT<const LIMBS: usize> {
+    m_modulus: BigIntegerFixed<LIMBS>,
+    m_data: Vec<BigIntegerFixed<LIMBS>>,
 }
 
-impl<IntegerType> BigVectorFixedT<IntegerType>
-where
-    IntegerType: Clone + From<u64> + for<'a> Add<&'a IntegerType, Output = IntegerType> + for<'a> Sub<&'a IntegerType, Output = IntegerType> + for<'a> Mul<&'a IntegerType, Output = IntegerType> + PartialEq + Debug + Serialize + for<'de> Deserialize<'de>,
-{
-    pub fn new(length: usize, modulus: IntegerType) -> Self {
-        BigVectorFixedT {
-            data: vec![IntegerType::from(0); length],
-            modulus,
+/// Вектор длинных целых фиксированной разрядности по умолчанию
+pub type BigVector = BigVectorFixedT<BIG_INTEGER_LIMBS>;
+
+impl<const LIMBS: usize> BigVectorFixedT<LIMBS> {
+    /// Нулевой вектор длины length с модулем modulus
+    pub fn new(length: usize, modulus: BigIntegerFixed<LIMBS>) -> Self {
+        Self::with_value(length, modulus, BigIntegerFixed::default())
+    }
+
+    /// Вектор длины length, все элементы которого равны val mod modulus
+    pub fn with_value(
+        length: usize,
+        modulus: BigIntegerFixed<LIMBS>,
+        val: BigIntegerFixed<LIMBS>,
+    ) -> Self {
+        let mut res = Self {
+            m_modulus: BigIntegerFixed::default(),
+            m_data: vec![],
+        };
+        res.set_modulus(modulus);
+        res.m_data = vec![val.modulo(&modulus); length];
+        res
+    }
+
+    /// Вектор из значений values, приведённых по модулю modulus
+    pub fn from_values(values: &[BigIntegerFixed<LIMBS>], modulus: BigIntegerFixed<LIMBS>) -> Self {
+        let mut res = Self::new(0, modulus);
+        res.m_data = values.iter().map(|x| x.modulo(&modulus)).collect();
+        res
+    }
+
+    pub fn get_modulus(&self) -> &BigIntegerFixed<LIMBS> {
+        &self.m_modulus
+    }
+
+    /// Меняет модуль без пересчёта элементов
+    pub fn set_modulus(&mut self, value: BigIntegerFixed<LIMBS>) {
+        let max_size = BigIntegerFixed::<LIMBS>::max_bits() - 2;
+        if value.get_msb() > max_size {
+            panic!(
+                "Requested modulus' size {} is not supported. BigVectorFixedT supports only modulus size <= {}",
+                value.get_msb(),
+                max_size
+            )
+        }
+        if value == BigIntegerFixed::default() {
+            panic!("BigVectorFixedT: the modulus must be positive")
         }
+        self.m_modulus = value;
     }
 
-    pub fn single(val: IntegerType, modulus: IntegerType) -> Self {
-        let mut vec = Self::new(1, modulus);
-        vec.data[0] = val;
-        vec
+    pub fn get_length(&self) -> usize {
+        self.m_data.len()
     }
 
-    pub fn with_value(length: usize, modulus: IntegerType, value: IntegerType) -> Self {
-        BigVectorFixedT {
-            data: vec![value; length],
-            modulus,
-        }
+    pub fn len(&self) -> usize {
+        self.m_data.len()
     }
 
-    pub fn set_modulus(&mut self, value: IntegerType) {
-        self.modulus = value;
+    pub fn is_empty(&self) -> bool {
+        self.m_data.is_empty()
     }
 
-    pub fn get_modulus(&self) -> &IntegerType {
-        &self.modulus
+    pub fn iter(&self) -> std::slice::Iter<'_, BigIntegerFixed<LIMBS>> {
+        self.m_data.iter()
     }
 
-    pub fn get_length(&self) -> usize {
-        self.data.len()
+    pub fn as_slice(&self) -> &[BigIntegerFixed<LIMBS>] {
+        &self.m_data
     }
 
-    pub fn mod_eq(&mut self, modulus: IntegerType) {
-        self.modulus = modulus;
-        for item in &mut self.data {
-            *item = item.clone() % self.modulus.clone();
+    /// Переводит элементы в модуль new_modulus через центрированный подъём:
+    /// элемент x > q/2 считается отрицательным числом x - q.
+    pub fn switch_modulus(&mut self, new_modulus: BigIntegerFixed<LIMBS>) {
+        let q = self.m_modulus;
+        let half_q = q >> 1;
+        for x in self.m_data.iter_mut() {
+            *x = if *x > half_q {
+                (new_modulus - (q - *x).modulo(&new_modulus)).modulo(&new_modulus)
+            } else {
+                x.modulo(&new_modulus)
+            };
         }
+        self.set_modulus(new_modulus);
     }
 
-    pub fn mod_add(&self, other: &IntegerType) -> Self {
-        let mut result = self.clone();
-        for item in &mut result.data {
-            *item = item.clone() + other.clone();
+    /// Цифры всех элементов с номером index в системе счисления с основанием 2^bits
+    pub fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> Self {
+        let mut res = self.clone();
+        for x in res.m_data.iter_mut() {
+            *x = BigIntegerFixed::from(x.get_digit_at_index_for_base(index, bits))
+                .modulo(&self.m_modulus);
         }
-        result
+        res
     }
+}
 
-    pub fn mod_add_eq(&mut self, other: &IntegerType) {
-        for item in &mut self.data {
-            *item = item.clone() + other.clone();
-        }
+/* Операции со скаляром */
+impl<const LIMBS: usize> BigVectorFixedT<LIMBS> {
+    pub fn mod_add(&self, b: &BigIntegerFixed<LIMBS>) -> Self {
+        let mut res = self.clone();
+        res.mod_add_eq(b);
+        res
+    }
+
+    pub fn mod_add_eq(&mut self, b: &BigIntegerFixed<LIMBS>) -> &mut Self {
+        let q = self.m_modulus;
+        let b = b.modulo(&q);
+        self.m_data
+            .iter_mut()
+            .for_each(|x| *x = x.mod_add_fast(&b, &q));
+        self
     }
 
-    pub fn mod_sub(&self, other: &IntegerType) -> Self {
-        let mut result = self.clone();
-        for item in &mut result.data {
-            *item = item.clone() - other.clone();
+    /// Прибавляет b только к элементу с номером i
+    pub fn mod_add_at_index_eq(&mut self, i: usize, b: &BigIntegerFixed<LIMBS>) -> &mut Self {
+        let q = self.m_modulus;
+        self.m_data[i] = self.m_data[i].mod_add(b, &q);
+        self
+    }
+
+    pub fn mod_sub(&self, b: &BigIntegerFixed<LIMBS>) -> Self {
+        let mut res = self.clone();
+        res.mod_sub_eq(b);
+        res
+    }
+
+    pub fn mod_sub_eq(&mut self, b: &BigIntegerFixed<LIMBS>) -> &mut Self {
+        let q = self.m_modulus;
+        let b = b.modulo(&q);
+        self.m_data
+            .iter_mut()
+            .for_each(|x| *x = x.mod_sub_fast(&b, &q));
+        self
+    }
+
+    /// Умножение на скаляр: константа Барретта вычисляется один раз на весь вектор
+    pub fn mod_mul(&self, b: &BigIntegerFixed<LIMBS>) -> Self {
+        let mut res = self.clone();
+        res.mod_mul_eq(b);
+        res
+    }
+
+    pub fn mod_mul_eq(&mut self, b: &BigIntegerFixed<LIMBS>) -> &mut Self {
+        let q = self.m_modulus;
+        let mu = q.compute_mu();
+        let b = b.modulo(&q);
+        self.m_data
+            .iter_mut()
+            .for_each(|x| *x = x.mod_mul_fast(&b, &q, &mu));
+        self
+    }
+
+    /// Возведение всех элементов в степень b
+    pub fn mod_exp(&self, b: &BigIntegerFixed<LIMBS>) -> Self {
+        let mut res = self.clone();
+        let q = self.m_modulus;
+        res.m_data.iter_mut().for_each(|x| *x = x.mod_exp(b, &q));
+        res
+    }
+
+    /// Обратные ко всем элементам; паникует, если какой-то элемент необратим
+    pub fn mod_inverse(&self) -> Self {
+        let mut res = self.clone();
+        let q = self.m_modulus;
+        res.m_data.iter_mut().for_each(|x| *x = x.mod_inverse(&q));
+        res
+    }
+}
+
+/* Поэлементные операции с вектором */
+impl<const LIMBS: usize> BigVectorFixedT<LIMBS> {
+    pub fn mod_add_vec(&self, b: &Self) -> Self {
+        let mut res = self.clone();
+        res.mod_add_vec_eq(b);
+        res
+    }
+
+    pub fn mod_add_vec_eq(&mut self, b: &Self) -> &mut Self {
+        self.check(b, "ModAdd");
+        let q = self.m_modulus;
+        for (x, y) in self.m_data.iter_mut().zip(b.m_data.iter()) {
+            *x = x.mod_add_fast(y, &q);
         }
-        result
+        self
+    }
+
+    pub fn mod_sub_vec(&self, b: &Self) -> Self {
+        let mut res = self.clone();
+        res.mod_sub_vec_eq(b);
+        res
     }
 
-    pub fn mod_sub_eq(&mut self, other: &IntegerType) {
-        for item in &mut self.data {
-            *item = item.clone() - other.clone();
+    pub fn mod_sub_vec_eq(&mut self, b: &Self) -> &mut Self {
+        self.check(b, "ModSub");
+        let q = self.m_modulus;
+        for (x, y) in self.m_data.iter_mut().zip(b.m_data.iter()) {
+            *x = x.mod_sub_fast(y, &q);
         }
+        self
     }
 
-    pub fn mod_mul(&self, other: &IntegerType) -> Self {
-        let mut result = self.clone();
-        for item in &mut result.data {
-            *item = item.clone() * other.clone();
+    /// Поэлементное произведение с редукцией Барретта
+    pub fn mod_mul_vec(&self, b: &Self) -> Self {
+        let mut res = self.clone();
+        res.mod_mul_vec_eq(b);
+        res
+    }
+
+    pub fn mod_mul_vec_eq(&mut self, b: &Self) -> &mut Self {
+        self.check(b, "ModMul");
+        let q = self.m_modulus;
+        let mu = q.compute_mu();
+        for (x, y) in self.m_data.iter_mut().zip(b.m_data.iter()) {
+            *x = x.mod_mul_fast(y, &q, &mu);
         }
-        result
+        self
     }
 
-    pub fn mod_mul_eq(&mut self, other: &IntegerType) {
-        for item in &mut self.data {
-            *item = item.clone() * other.clone();
+    fn check(&self, b: &Self, op: &str) {
+        if self.m_modulus != b.m_modulus {
+            panic!(
+                "{} called on BigVectorFixedT's with different parameters.",
+                op
+            )
+        }
+        if self.m_data.len() != b.m_data.len() {
+            panic!("{} called on BigVectorFixedT's of different lengths.", op)
         }
     }
 }
 
-use serde::{Serialize, Deserialize};
-use serde::ser::SerializeStruct;
-use serde::de::{self, Deserialize, Deserializer, Visitor, SeqAccess, MapAccess};
-use std::fmt;
-use std::marker::PhantomData;
-
-#[derive(Serialize, Deserialize)]
-struct BigVectorFixedT<IntegerType> {
-    #[serde(skip_serializing, skip_deserializing)]
-    m_data: Vec<IntegerType>,
-    m_length: usize,
-    m_modulus: IntegerType,
-}
+impl<const LIMBS: usize> BigVectorInterface for BigVectorFixedT<LIMBS> {
+    type Integer = BigIntegerFixed<LIMBS>;
 
-impl<IntegerType> BigVectorFixedT<IntegerType>
-where
-    IntegerType: Serialize + Deserialize<'static> + Clone + Default,
-{
-    fn mod_mul(&self, b: &Self) -> Self {
-        unimplemented!()
+    fn new(length: usize, modulus: Self::Integer) -> Self {
+        BigVectorFixedT::new(length, modulus)
     }
 
-    fn mod_mul_eq(&mut self, b: &Self) -> &mut Self {
-        unimplemented!()
+    fn from_values(values: &[Self::Integer], modulus: Self::Integer) -> Self {
+        BigVectorFixedT::from_values(values, modulus)
     }
 
-    fn mod_mul_no_check_eq(&mut self, b: &Self) -> &mut Self {
-        unimplemented!()
+    fn get_modulus(&self) -> &Self::Integer {
+        BigVectorFixedT::get_modulus(self)
     }
 
-    fn mod_exp(&self, b: &IntegerType) -> Self {
-        unimplemented!()
+    fn set_modulus(&mut self, value: Self::Integer) {
+        BigVectorFixedT::set_modulus(self, value)
     }
 
-    fn mod_exp_eq(&mut self, b: &IntegerType) -> &mut Self {
-        unimplemented!()
+    fn get_length(&self) -> usize {
+        BigVectorFixedT::get_length(self)
     }
 
-    fn mod_inverse(&self) -> Self {
-        unimplemented!()
+    fn switch_modulus(&mut self, new_modulus: Self::Integer) {
+        BigVectorFixedT::switch_modulus(self, new_modulus)
     }
 
-    fn mod_inverse_eq(&mut self) -> &mut Self {
-        unimplemented!()
+    fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> Self {
+        BigVectorFixedT::get_digit_at_index_for_base(self, index, bits)
     }
 
-    fn mod_by_two(&self) -> Self {
-        unimplemented!()
+    fn mod_add(&self, b: &Self::Integer) -> Self {
+        BigVectorFixedT::mod_add(self, b)
     }
 
-    fn mod_by_two_eq(&mut self) -> &mut Self {
-        unimplemented!()
+    fn mod_sub(&self, b: &Self::Integer) -> Self {
+        BigVectorFixedT::mod_sub(self, b)
     }
 
-    fn mult_with_out_mod(&self, b: &Self) -> Self {
-        unimplemented!()
+    fn mod_mul(&self, b: &Self::Integer) -> Self {
+        BigVectorFixedT::mod_mul(self, b)
     }
 
-    fn mult_with_out_mod_eq(&mut self, b: &Self) -> &mut Self {
-        unimplemented!()
+    fn mod_exp(&self, b: &Self::Integer) -> Self {
+        BigVectorFixedT::mod_exp(self, b)
     }
 
-    fn multiply_and_round(&self, p: &IntegerType, q: &IntegerType) -> Self {
-        unimplemented!()
+    fn mod_inverse(&self) -> Self {
+        BigVectorFixedT::mod_inverse(self)
     }
 
-    fn multiply_and_round_eq(&mut self, p: &IntegerType, q: &IntegerType) -> &mut Self {
-        unimplemented!()
+    fn mod_add_vec(&self, b: &Self) -> Self {
+        BigVectorFixedT::mod_add_vec(self, b)
     }
 
-    fn divide_and_round(&self, q: &IntegerType) -> Self {
-        unimplemented!()
+    fn mod_sub_vec(&self, b: &Self) -> Self {
+        BigVectorFixedT::mod_sub_vec(self, b)
     }
 
-    fn divide_and_round_eq(&mut self, q: &IntegerType) -> &mut Self {
-        unimplemented!()
+    fn mod_mul_vec(&self, b: &Self) -> Self {
+        BigVectorFixedT::mod_mul_vec(self, b)
     }
+}
+
+impl<const LIMBS: usize> Index<usize> for BigVectorFixedT<LIMBS> {
+    type Output = BigIntegerFixed<LIMBS>;
 
-    fn get_digit_at_index_for_base(&self, index: usize, base: usize) -> Self {
-        unimplemented!()
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.m_data[idx]
     }
 }
 
-impl<IntegerType> fmt::Display for BigVectorFixedT<IntegerType>
-where
-    IntegerType: fmt::Display + Serialize + Deserialize<'static> + Clone + Default,
-{
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "[")?;
-        for (i, val) in self.m_data.iter().enumerate() {
-            if i > 0 { write!(f, " ")?; }
-            write!(f, "{}", val)?;
-        }
-        write!(f, "] modulus: {}", self.m_modulus)
+/// Запись через индекс не приводит значение по модулю: записывайте только
+/// значения меньше модуля
+impl<const LIMBS: usize> IndexMut<usize> for BigVectorFixedT<LIMBS> {
+    fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
+        &mut self.m_data[idx]
     }
 }
 
-impl<IntegerType> BigVectorFixedT<IntegerType>
-where
-    IntegerType: Serialize + Deserialize<'static> + Clone + Default,
-{
-    fn serialized_object_name() -> &'static str {
-        "FXDInteger"
+impl<const LIMBS: usize> Add for BigVectorFixedT<LIMBS> {
+    type Output = Self;
+    fn add(mut self, rhs: Self) -> Self {
+        self.mod_add_vec_eq(&rhs);
+        self
     }
+}
 
-    fn serialized_version() -> u32 {
-        1
+impl<const LIMBS: usize> Sub for BigVectorFixedT<LIMBS> {
+    type Output = Self;
+    fn sub(mut self, rhs: Self) -> Self {
+        self.mod_sub_vec_eq(&rhs);
+        self
     }
 }
 
+impl<const LIMBS: usize> Mul for BigVectorFixedT<LIMBS> {
+    type Output = Self;
+    fn mul(mut self, rhs: Self) -> Self {
+        self.mod_mul_vec_eq(&rhs);
+        self
+    }
+}
 
-
+impl<const LIMBS: usize> fmt::Display for BigVectorFixedT<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, x) in self.m_data.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", x)?;
+        }
+        write!(f, "] modulus: {}", self.m_modulus)
+    }
+}
@@ -1,504 +1,904 @@
 /*
- * This file contains the vector manipulation functionality.
- * This file contains the main class for big integers: BigIntegerFixedT. Big integers
- * are represented as arrays of native usigned integers. The native integer type
- * is supplied as a template parameter. Currently implementations based on
- * uint8_t, uint16_t, and uint32_t are supported. The second template parameter
- * is the maximum bitwidth for the big integer.
- */
-
- use std::marker::PhantomData;
-
-const NATIVEINT: usize = std::mem::size_of::<usize>() * 8;
-
-#[cfg(not(defined(BigIntegerBitLength)))]
-const BigIntegerBitLength: usize = if NATIVEINT < 128 { 3500 } else { 8000 };
-
-#[cfg(BigIntegerBitLength < 600)]
-compile_error!("BigIntegerBitLength is too small");
-
-mod bigintfxd {
-    use super::*;
+  This file contains the main class for fixed-width big integers: BigIntegerFixed. Big integers
+  are represented as arrays of 64-bit limbs, the number of limbs is a const generic parameter,
+  so the integers live on the stack and never allocate
+*/
+use crate::core::math::hal::integer::{BigIntegerInterface, IntegerInterface};
+use num::traits::NumCast;
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::ops::{
+    Add, AddAssign, BitAnd, BitOr, BitXor, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Shl,
+    ShlAssign, Shr, ShrAssign, Sub, SubAssign,
+};
+use std::str::FromStr;
+
+/// Наибольшая разрядность BigInteger по умолчанию (BigIntegerBitLength в OpenFHE)
+pub const BIG_INTEGER_BIT_LENGTH: usize = 3500;
+
+/// Количество 64-битных слов BigInteger по умолчанию
+pub const BIG_INTEGER_LIMBS: usize = BIG_INTEGER_BIT_LENGTH.div_ceil(64);
+
+/// Начиная с этой длины множителей (в словах) умножение выполняется по Карацубе
+const KARATSUBA_THRESHOLD: usize = 24;
+
+/// Целое без знака фиксированной разрядности 64 * LIMBS бит
+/// (аналог BigIntegerFixedT в OpenFHE).
+///
+/// Слова хранятся от младшего к старшему. Обычная арифметика паникует при
+/// переполнении, как машинные целые. Модульные операции с Барреттом
+/// принимают модуль разрядностью не больше 64 * LIMBS - 2 бит, произведения
+/// вычисляются в двойной ширине на стеке.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BigIntegerFixed<const LIMBS: usize> {
+    m_value: [u64; LIMBS],
+}
 
-    type U64BITS = u64;
-    #[cfg(HAVE_INT128)]
-    type U128BITS = u128;
+/// Длинное целое фиксированной разрядности по умолчанию
+pub type BigInteger = BigIntegerFixed<BIG_INTEGER_LIMBS>;
 
-    pub struct BigIntegerFixedT<uint_type, const BITLENGTH: usize> {
-        _marker: PhantomData<uint_type>,
+impl<const LIMBS: usize> BigIntegerFixed<LIMBS> {
+    pub fn from_limbs(limbs: [u64; LIMBS]) -> Self {
+        Self { m_value: limbs }
     }
 
-    pub type BigInteger = BigIntegerFixedT<u32, BigIntegerBitLength>;
-
-    pub struct Log2<const N: usize>;
-
-    impl<const N: usize> Log2<N> {
-        pub const VALUE: usize = 1 + Log2<{N / 2}>::VALUE;
+    /// Слова числа от младшего к старшему
+    pub fn limbs(&self) -> &[u64; LIMBS] {
+        &self.m_value
     }
 
-    impl Log2<2> {
-        pub const VALUE: usize = 1;
+    /// Разрядность числа
+    pub fn max_bits() -> u32 {
+        64 * LIMBS as u32
     }
 
-    pub struct LogDtype<U> {
-        _marker: PhantomData<U>,
+    pub fn max() -> Self {
+        Self::from_limbs([u64::MAX; LIMBS])
     }
 
-    impl<U> LogDtype<U> {
-        pub const VALUE: usize = Log2<{8 * std::mem::size_of::<U>()}>::VALUE;
+    pub fn set_identity(&mut self) {
+        *self = Self::from(1u64);
     }
 
-    pub struct DataTypeChecker<Dtype> {
-        _marker: PhantomData<Dtype>,
+    /// Значение в другом целочисленном типе; паникует, если не помещается
+    pub fn convert_to_int<I: NumCast>(&self) -> I {
+        if self.used_limbs() > 2 {
+            panic!("The value does not fit into the requested type")
+        }
+        let value = self
+            .m_value
+            .iter()
+            .take(2)
+            .rev()
+            .fold(0u128, |acc, &x| (acc << 64) | x as u128);
+        I::from(value).expect("The value does not fit into the requested type")
+    }
+
+    pub fn convert_to_double(&self) -> f64 {
+        self.m_value
+            .iter()
+            .rev()
+            .fold(0f64, |acc, &x| acc * 18446744073709551616f64 + x as f64)
+    }
+
+    /// Номер старшего ненулевого бита, считая с 1 (0 для нуля)
+    pub fn get_msb(&self) -> u32 {
+        match self.used_limbs() {
+            0 => 0,
+            n => 64 * n as u32 - self.m_value[n - 1].leading_zeros(),
+        }
     }
 
-    impl DataTypeChecker<u8> {
-        pub const VALUE: bool = true;
+    /// Бит с номером index, младший бит имеет номер 0
+    pub fn get_bit_at_index(&self, index: u32) -> bool {
+        let limb = index as usize / 64;
+        limb < LIMBS && (self.m_value[limb] >> (index % 64)) & 1 == 1
     }
 
-    impl DataTypeChecker<u16> {
-        pub const VALUE: bool = true;
+    /// Цифра с номером index (с 0) в системе счисления с основанием 2^bits, bits <= 64
+    pub fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> u64 {
+        assert!(
+            bits > 0 && bits <= 64,
+            "The base must be 2^bits with 0 < bits <= 64"
+        );
+        let digit = (*self >> (index * bits)).m_value[0];
+        if bits == 64 {
+            digit
+        } else {
+            digit & ((1 << bits) - 1)
+        }
     }
 
-    impl DataTypeChecker<u32> {
-        pub const VALUE: bool = true;
+    /// Частное и остаток от деления на b
+    pub fn div_rem(&self, b: &Self) -> (Self, Self) {
+        let (quot, rem) = Self::div_rem_limbs(&self.m_value, b);
+        (Self::from_limbs(quot[0]), rem)
+    }
+
+    /// Полное произведение self * b как пара (hi, lo)
+    pub fn mul_wide(&self, b: &Self) -> (Self, Self) {
+        let mut prod = [[0u64; LIMBS]; 2];
+        let (na, nb) = (self.used_limbs(), b.used_limbs());
+        let n = na.max(nb);
+        if n < KARATSUBA_THRESHOLD {
+            mul_schoolbook(
+                &self.m_value[..na],
+                &b.m_value[..nb],
+                &mut prod.as_flattened_mut()[..na + nb],
+            );
+        } else {
+            let mut scratch = [[0u64; LIMBS]; 6];
+            mul_karatsuba(
+                &self.m_value[..n],
+                &b.m_value[..n],
+                &mut prod.as_flattened_mut()[..2 * n],
+                scratch.as_flattened_mut(),
+            );
+        }
+        (Self::from_limbs(prod[1]), Self::from_limbs(prod[0]))
+    }
+
+    /// Частное и остаток от деления (hi * 2^(64 * LIMBS) + lo) на d;
+    /// паникует, если частное не помещается в число
+    pub fn div_rem_wide(hi: &Self, lo: &Self, d: &Self) -> (Self, Self) {
+        let (quot, rem) = Self::div_rem_limbs([lo.m_value, hi.m_value].as_flattened(), d);
+        if quot[1].iter().any(|&x| x != 0) {
+            panic!(
+                "BigIntegerFixed: the quotient does not fit into {} bits",
+                Self::max_bits()
+            )
+        }
+        (Self::from_limbs(quot[0]), rem)
     }
 
-    impl DataTypeChecker<u64> {
-        pub const VALUE: bool = true;
+    /// round(self * p / q) без потери точности
+    pub fn multiply_and_round(&self, p: &Self, q: &Self) -> Self {
+        let (hi, lo) = self.mul_wide(p);
+        let (quot, rem) = Self::div_rem_wide(&hi, &lo, q);
+        if rem > *q >> 1 {
+            quot + Self::from(1u64)
+        } else {
+            quot
+        }
     }
 
-    pub struct UIntBitWidth<uint_type> {
-        _marker: PhantomData<uint_type>,
+    /// round(self / q)
+    pub fn divide_and_round(&self, q: &Self) -> Self {
+        let (quot, rem) = self.div_rem(q);
+        if rem > *q >> 1 {
+            quot + Self::from(1u64)
+        } else {
+            quot
+        }
     }
 
-    impl<uint_type> UIntBitWidth<uint_type> {
-        pub const VALUE: usize = 8 * std::mem::size_of::<uint_type>();
+    /// Количество значащих слов
+    fn used_limbs(&self) -> usize {
+        LIMBS - self.m_value.iter().rev().take_while(|&&x| x == 0).count()
     }
 
-    pub struct DoubleDataType<utype> {
-        _marker: PhantomData<utype>,
+    fn overflowing_add(&self, b: &Self) -> (Self, bool) {
+        let mut res = *self;
+        let carry = add_limbs(&mut res.m_value, &b.m_value);
+        (res, carry)
     }
 
-    impl DoubleDataType<u8> {
-        pub type T = u16;
+    fn overflowing_sub(&self, b: &Self) -> (Self, bool) {
+        let mut res = *self;
+        let borrow = sub_limbs(&mut res.m_value, &b.m_value);
+        (res, borrow)
     }
 
-    impl DoubleDataType<u16> {
-        pub type T = u32;
-    }
+    /// Частное (в двойной ширине) и остаток от деления числа u из не более
+    /// чем 2 * LIMBS слов на d
+    fn div_rem_limbs(u: &[u64], d: &Self) -> ([[u64; LIMBS]; 2], Self) {
+        let n = d.used_limbs();
+        if n == 0 {
+            panic!("BigIntegerFixed: Divide by zero");
+        }
+        let un = u.len() - u.iter().rev().take_while(|&&x| x == 0).count();
+        let mut quot = [[0u64; LIMBS]; 2];
+        let mut rem = Self::default();
+        if un < n {
+            rem.m_value[..un].copy_from_slice(&u[..un]);
+            return (quot, rem);
+        }
 
-    impl DoubleDataType<u32> {
-        pub type T = u64;
-    }
+        if n == 1 {
+            let d = d.m_value[0] as u128;
+            let mut r = 0u128;
+            let q = quot.as_flattened_mut();
+            for i in (0..un).rev() {
+                let cur = (r << 64) | u[i] as u128;
+                q[i] = (cur / d) as u64;
+                r = cur % d;
+            }
+            rem.m_value[0] = r as u64;
+            return (quot, rem);
+        }
 
-    impl DoubleDataType<u64> {
-        #[cfg(HAVE_INT128)]
-        pub type T = u128;
-        #[cfg(not(HAVE_INT128))]
-        pub type T = u64;
+        // Лишнее старшее слово делимого принимает биты нормализации
+        let mut work = [[0u64; LIMBS]; 3];
+        let w = work.as_flattened_mut();
+        w[..un].copy_from_slice(&u[..un]);
+        let mut v = d.m_value;
+        div_rem_knuth(&mut w[..un + 1], &mut v[..n], quot.as_flattened_mut());
+        rem.m_value[..n].copy_from_slice(&w[..n]);
+        (quot, rem)
+    }
+
+    /// Младшие LIMBS слов числа (hi, lo), сдвинутого вправо на shift бит
+    fn shr_wide(hi: &Self, lo: &Self, shift: u32) -> Self {
+        let wide = [lo.m_value, hi.m_value];
+        let wide = wide.as_flattened();
+        let (words, bits) = (shift as usize / 64, shift % 64);
+        let mut res = Self::default();
+        for (i, x) in res.m_value.iter_mut().enumerate() {
+            let low = wide.get(i + words).copied().unwrap_or(0);
+            let high = wide.get(i + words + 1).copied().unwrap_or(0);
+            *x = if bits == 0 {
+                low
+            } else {
+                (low >> bits) | (high << (64 - bits))
+            };
+        }
+        res
     }
 }
 
+/* Модульная арифметика */
+impl<const LIMBS: usize> BigIntegerFixed<LIMBS> {
+    /// self mod modulus
+    pub fn modulo(&self, modulus: &Self) -> Self {
+        self.div_rem(modulus).1
+    }
 
-const LOG2_10: f64 = 3.32192809;
-
-use std::convert::TryInto;
-use std::marker::PhantomData;
-
-pub struct BigIntegerFixedT<uint_type, const BITLENGTH: usize> {
-    phantom: PhantomData<uint_type>,
-}
-
-impl<uint_type, const BITLENGTH: usize> BigIntegerFixedT<uint_type, BITLENGTH> {
-    pub fn new() -> Self {
-        Self {
-            phantom: PhantomData,
+    /// Константа Барретта mu = floor(2^(2n) / q), где n - разрядность модуля q = self
+    pub fn compute_mu(&self) -> Self {
+        if *self == Self::default() {
+            panic!("BigIntegerFixed ComputeMu: Divide by zero");
         }
+        let n = self.checked_modulus_bits();
+        let mut power = [[0u64; LIMBS]; 2];
+        power.as_flattened_mut()[(2 * n / 64) as usize] = 1 << (2 * n % 64);
+        Self::from_limbs(Self::div_rem_limbs(power.as_flattened(), self).0[0])
     }
 
-    pub fn from_strval(strval: &str) -> Self {
-        Self::new() // Placeholder for actual conversion logic
+    /// self mod modulus редукцией Барретта, mu = modulus.compute_mu()
+    pub fn modulo_with_mu(&self, modulus: &Self, mu: &Self) -> Self {
+        if self.get_msb() > 2 * modulus.get_msb() {
+            return self.modulo(modulus);
+        }
+        Self::barrett(&Self::default(), self, modulus, mu)
     }
 
-    pub fn from_u64(val: u64) -> Self {
-        Self::new() // Placeholder for actual conversion logic
+    /// (self + b) mod modulus
+    pub fn mod_add(&self, b: &Self, modulus: &Self) -> Self {
+        self.modulo(modulus)
+            .mod_add_fast(&b.modulo(modulus), modulus)
     }
 
-    pub fn set_value(&mut self, strval: &str) {
-        // Placeholder for actual logic
+    /// (self + b) mod modulus для self, b < modulus
+    pub fn mod_add_fast(&self, b: &Self, modulus: &Self) -> Self {
+        // Сравнение с дополнением вместо суммы: сумма может не поместиться в число
+        let complement = *modulus - *b;
+        if *self >= complement {
+            *self - complement
+        } else {
+            *self + *b
+        }
     }
 
-    pub fn set_identity(&mut self) {
-        // Placeholder for actual logic
+    pub fn mod_add_eq(&mut self, b: &Self, modulus: &Self) -> &mut Self {
+        *self = self.mod_add(b, modulus);
+        self
     }
 
-    pub fn set_int_at_index(&mut self, idx: usize, value: uint_type) {
-        // Placeholder for actual logic
+    /// (self - b) mod modulus
+    pub fn mod_sub(&self, b: &Self, modulus: &Self) -> Self {
+        self.modulo(modulus)
+            .mod_sub_fast(&b.modulo(modulus), modulus)
     }
 
-    pub fn add(&self, b: &Self) -> Self {
-        Self::new() // Placeholder for actual logic
+    /// (self - b) mod modulus для self, b < modulus
+    pub fn mod_sub_fast(&self, b: &Self, modulus: &Self) -> Self {
+        if *self >= *b {
+            *self - *b
+        } else {
+            *self + (*modulus - *b)
+        }
     }
 
-    pub fn add_eq(&mut self, b: &Self) {
-        // Placeholder for actual logic
+    pub fn mod_sub_eq(&mut self, b: &Self, modulus: &Self) -> &mut Self {
+        *self = self.mod_sub(b, modulus);
+        self
     }
 
-    pub fn sub(&self, b: &Self) -> Self {
-        Self::new() // Placeholder for actual logic
+    /// (self * b) mod modulus делением полного произведения, для любого модуля
+    pub fn mod_mul(&self, b: &Self, modulus: &Self) -> Self {
+        let (hi, lo) = self.modulo(modulus).mul_wide(&b.modulo(modulus));
+        Self::div_rem_limbs([lo.m_value, hi.m_value].as_flattened(), modulus).1
     }
 
-    pub fn sub_eq(&mut self, b: &Self) {
-        // Placeholder for actual logic
+    pub fn mod_mul_eq(&mut self, b: &Self, modulus: &Self) -> &mut Self {
+        *self = self.mod_mul(b, modulus);
+        self
     }
 
-    pub fn neg(&self) -> Self {
-        Self::new() // Placeholder for actual logic
+    /// (self * b) mod modulus редукцией Барретта для self, b < modulus
+    pub fn mod_mul_fast(&self, b: &Self, modulus: &Self, mu: &Self) -> Self {
+        let (hi, lo) = self.mul_wide(b);
+        Self::barrett(&hi, &lo, modulus, mu)
     }
 
-    pub fn mul(&self, b: &Self) -> Self {
-        Self::new() // Placeholder for actual logic
+    pub fn mod_mul_fast_eq(&mut self, b: &Self, modulus: &Self, mu: &Self) -> &mut Self {
+        *self = self.mod_mul_fast(b, modulus, mu);
+        self
     }
 
-    pub fn mul_eq(&mut self, b: &Self) {
-        // Placeholder for actual logic
+    /// self^b mod modulus
+    pub fn mod_exp(&self, b: &Self, modulus: &Self) -> Self {
+        let fast = modulus.get_msb() <= Self::max_bits() - 2;
+        let mu = if fast {
+            modulus.compute_mu()
+        } else {
+            Self::default()
+        };
+        let mul = |x: &Self, y: &Self| {
+            if fast {
+                x.mod_mul_fast(y, modulus, &mu)
+            } else {
+                x.mod_mul(y, modulus)
+            }
+        };
+
+        let mut res = Self::from(1u64).modulo(modulus);
+        let base = self.modulo(modulus);
+        for i in (0..b.get_msb()).rev() {
+            res = mul(&res, &res);
+            if b.get_bit_at_index(i) {
+                res = mul(&res, &base);
+            }
+        }
+        res
+    }
+
+    /// self^-1 mod modulus; паникует, если self и modulus не взаимно просты
+    pub fn mod_inverse(&self, modulus: &Self) -> Self {
+        let (mut r0, mut r1) = (*modulus, self.modulo(modulus));
+        // Коэффициенты Безу хранятся по модулю modulus, чтобы обойтись без знака
+        let (mut t0, mut t1) = (Self::default(), Self::from(1u64));
+        while r1 != Self::default() {
+            let (quot, r) = r0.div_rem(&r1);
+            let t = t0.mod_sub_fast(&quot.mod_mul(&t1, modulus), modulus);
+            r0 = r1;
+            r1 = r;
+            t0 = t1;
+            t1 = t;
+        }
+        if r0 != Self::from(1u64) {
+            panic!(
+                "BigIntegerFixed ModInverse: {} is not invertible modulo {}",
+                self, modulus
+            )
+        }
+        t0.modulo(modulus)
+    }
+
+    /// Разрядность модуля с проверкой запаса для Барретта
+    fn checked_modulus_bits(&self) -> u32 {
+        let n = self.get_msb();
+        if n > Self::max_bits() - 2 {
+            panic!(
+                "BigIntegerFixed: the modulus {} has {} bits, at most {} are supported",
+                self,
+                n,
+                Self::max_bits() - 2
+            )
+        }
+        n
+    }
+
+    /// Редукция Барретта x = (hi, lo) < 2^(2n) по модулю q < 2^n (HAC 14.42)
+    fn barrett(hi: &Self, lo: &Self, q: &Self, mu: &Self) -> Self {
+        let n = q.get_msb();
+        // q1 = floor(x / 2^(n-1)) занимает не больше n + 1 бит
+        let q1 = Self::shr_wide(hi, lo, n - 1);
+        // q3 = floor(q1 * mu / 2^(n+1)) отличается от floor(x / q) не больше чем на 2
+        let (h2, l2) = q1.mul_wide(mu);
+        let q3 = Self::shr_wide(&h2, &l2, n + 1);
+        // x - q3 * q < 3q помещается в число, поэтому считается по модулю 2^(64 * LIMBS)
+        let (_, l3) = q3.mul_wide(q);
+        let mut r = lo.overflowing_sub(&l3).0;
+        while r >= *q {
+            r -= *q;
+        }
+        r
     }
+}
 
-    pub fn divided_by(&self, b: &Self) -> Self {
-        Self::new() // Placeholder for actual logic
+impl<const LIMBS: usize> Default for BigIntegerFixed<LIMBS> {
+    fn default() -> Self {
+        Self::from_limbs([0; LIMBS])
     }
+}
 
-    pub fn divided_by_eq(&mut self, b: &Self) {
-        // Placeholder for actual logic
+impl<const LIMBS: usize> Ord for BigIntegerFixed<LIMBS> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.m_value.iter().rev().cmp(other.m_value.iter().rev())
     }
+}
 
-    pub fn exp(&self, p: usize) -> Self {
-        Self::new() // Placeholder for actual logic
+impl<const LIMBS: usize> PartialOrd for BigIntegerFixed<LIMBS> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
+}
 
-    pub fn exp_eq(&mut self, p: usize) {
-        // Placeholder for actual logic
+impl<const LIMBS: usize> From<u64> for BigIntegerFixed<LIMBS> {
+    fn from(value: u64) -> Self {
+        let mut res = Self::default();
+        res.m_value[0] = value;
+        res
     }
+}
 
-    pub fn multiply_and_round(&self, p: &Self, q: &Self) -> Self {
-        Self::new() // Placeholder for actual logic
+impl<const LIMBS: usize> From<u128> for BigIntegerFixed<LIMBS> {
+    fn from(value: u128) -> Self {
+        let mut res = Self::from(value as u64);
+        if value >> 64 != 0 {
+            if LIMBS < 2 {
+                panic!("BigIntegerFixed: {} does not fit into 64 bits", value)
+            }
+            res.m_value[1] = (value >> 64) as u64;
+        }
+        res
     }
+}
 
-    pub fn multiply_and_round_eq(&mut self, p: &Self, q: &Self) {
-        // Placeholder for actual logic
-    }
+/// Десятичная запись или шестнадцатеричная с префиксом 0x
+impl<const LIMBS: usize> FromStr for BigIntegerFixed<LIMBS> {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || {
+            format!(
+                "{} is not a valid {}-bit fixed-width integer",
+                s,
+                Self::max_bits()
+            )
+        };
+        let (digits, radix) = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            Some(hex) => (hex, 16),
+            None => (s, 10),
+        };
+        if digits.is_empty() {
+            return Err(err());
+        }
 
-    pub fn divide_and_round(&self, q: &Self) -> Self {
-        Self::new() // Placeholder for actual logic
+        let mut res = Self::default();
+        for c in digits.chars() {
+            let digit = c.to_digit(radix).ok_or_else(err)?;
+            let carry = mul_add_limbs(&mut res.m_value, radix as u64, digit as u64);
+            if carry != 0 {
+                return Err(err());
+            }
+        }
+        Ok(res)
     }
+}
 
-    pub fn divide_and_round_eq(&mut self, q: &Self) {
-        // Placeholder for actual logic
+impl<const LIMBS: usize> Display for BigIntegerFixed<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Число делится на 10^19 - наибольшую степень десяти в слове
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+        let mut chunks = vec![];
+        let mut x = self.m_value;
+        loop {
+            let rem = div_rem_small(&mut x, CHUNK);
+            chunks.push(rem);
+            if x.iter().all(|&l| l == 0) {
+                break;
+            }
+        }
+        let mut s = chunks.pop().unwrap().to_string();
+        for chunk in chunks.iter().rev() {
+            s += &format!("{:019}", chunk);
+        }
+        f.pad_integral(true, "", &s)
     }
+}
 
-    pub fn mod_(&self, modulus: &Self) -> Self {
-        Self::new() // Placeholder for actual logic
+impl<const LIMBS: usize> fmt::LowerHex for BigIntegerFixed<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let n = self.used_limbs().max(1);
+        let mut s = format!("{:x}", self.m_value[n - 1]);
+        for x in self.m_value[..n - 1].iter().rev() {
+            s += &format!("{:016x}", x);
+        }
+        f.pad_integral(true, "0x", &s)
     }
+}
 
-    pub fn mod_eq(&mut self, modulus: &Self) {
-        // Placeholder for actual logic
+/* Обычная арифметика без приведения по модулю: переполнение - ошибка, как у
+ * машинных целых */
+impl<const LIMBS: usize> Add for BigIntegerFixed<LIMBS> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        match self.overflowing_add(&rhs) {
+            (res, false) => res,
+            _ => panic!("BigIntegerFixed: attempt to add with overflow"),
+        }
     }
+}
 
-    pub fn compute_mu(&self) -> Self {
-        Self::new() // Placeholder for actual logic
+impl<const LIMBS: usize> Sub for BigIntegerFixed<LIMBS> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        match self.overflowing_sub(&rhs) {
+            (res, false) => res,
+            _ => panic!("BigIntegerFixed: attempt to subtract with overflow"),
+        }
     }
+}
 
-    pub fn mod_add(&self, b: &Self, modulus: &Self) -> Self {
-        Self::new() // Placeholder for actual logic
+impl<const LIMBS: usize> Mul for BigIntegerFixed<LIMBS> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let (hi, lo) = self.mul_wide(&rhs);
+        if hi != Self::default() {
+            panic!("BigIntegerFixed: attempt to multiply with overflow")
+        }
+        lo
     }
+}
 
-    pub fn mod_add_eq(&mut self, b: &Self, modulus: &Self) {
-        // Placeholder for actual logic
-    }
-
-    pub fn mod_add_fast(&self, b: &Self, modulus: &Self) -> Self {
-        Self::new() // Placeholder for actual logic
+impl<const LIMBS: usize> Div for BigIntegerFixed<LIMBS> {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        self.div_rem(&rhs).0
     }
+}
 
-    pub fn mod_add_fast_eq(&mut self, b: &Self, modulus: &Self) {
-        // Placeholder for actual logic
+impl<const LIMBS: usize> Rem for BigIntegerFixed<LIMBS> {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self {
+        self.div_rem(&rhs).1
     }
 }
 
-use num_bigint::BigUint;
-use num_traits::{One, Zero};
-use std::ops::{AddAssign, MulAssign, SubAssign};
-
-struct BigIntegerFixedT {
-    value: BigUint,
+macro_rules! big_integer_fixed_op_assign {
+    ($op:ident, $method:ident, $op_assign:ident, $method_assign:ident) => {
+        impl<const LIMBS: usize> $op_assign for BigIntegerFixed<LIMBS> {
+            fn $method_assign(&mut self, rhs: Self) {
+                *self = $op::$method(*self, rhs);
+            }
+        }
+    };
 }
 
-impl BigIntegerFixedT {
-    fn mod_add(&self, b: &Self, modulus: &Self, _mu: &Self) -> Self {
-        Self {
-            value: (&self.value + &b.value) % &modulus.value,
+big_integer_fixed_op_assign!(Add, add, AddAssign, add_assign);
+big_integer_fixed_op_assign!(Sub, sub, SubAssign, sub_assign);
+big_integer_fixed_op_assign!(Mul, mul, MulAssign, mul_assign);
+big_integer_fixed_op_assign!(Div, div, DivAssign, div_assign);
+big_integer_fixed_op_assign!(Rem, rem, RemAssign, rem_assign);
+
+macro_rules! big_integer_fixed_bit_op {
+    ($op:ident, $method:ident, $op_assign:tt) => {
+        impl<const LIMBS: usize> $op for BigIntegerFixed<LIMBS> {
+            type Output = Self;
+            fn $method(mut self, rhs: Self) -> Self {
+                for (x, y) in self.m_value.iter_mut().zip(rhs.m_value.iter()) {
+                    *x $op_assign *y;
+                }
+                self
+            }
         }
-    }
-
-    fn mod_add_eq(&mut self, b: &Self, modulus: &Self, _mu: &Self) {
-        self.value = (&self.value + &b.value) % &modulus.value;
-    }
+    };
+}
 
-    fn mod_sub(&self, b: &Self, modulus: &Self) -> Self {
-        Self {
-            value: (&self.value + modulus.value - &b.value) % &modulus.value,
+big_integer_fixed_bit_op!(BitAnd, bitand, &=);
+big_integer_fixed_bit_op!(BitOr, bitor, |=);
+big_integer_fixed_bit_op!(BitXor, bitxor, ^=);
+
+/// Сдвиг влево; вышедшие за разрядность биты отбрасываются
+impl<const LIMBS: usize> Shl<u32> for BigIntegerFixed<LIMBS> {
+    type Output = Self;
+    fn shl(self, shift: u32) -> Self {
+        let (words, bits) = (shift as usize / 64, shift % 64);
+        let mut res = Self::default();
+        for i in words..LIMBS {
+            let low = self.m_value[i - words];
+            res.m_value[i] = low << bits;
+            if bits != 0 && i > words {
+                res.m_value[i] |= self.m_value[i - words - 1] >> (64 - bits);
+            }
         }
+        res
     }
+}
 
-    fn mod_sub_eq(&mut self, b: &Self, modulus: &Self) {
-        self.value = (&self.value + &modulus.value - &b.value) % &modulus.value;
-    }
-
-    fn mod_sub_fast(&self, b: &Self, modulus: &Self) -> Self {
-        self.mod_sub(b, modulus)
-    }
-
-    fn mod_sub_fast_eq(&mut self, b: &Self, modulus: &Self) {
-        self.mod_sub_eq(b, modulus);
-    }
-
-    fn mod_mul(&self, b: &Self, modulus: &Self) -> Self {
-        Self {
-            value: (&self.value * &b.value) % &modulus.value,
-        }
+impl<const LIMBS: usize> ShlAssign<u32> for BigIntegerFixed<LIMBS> {
+    fn shl_assign(&mut self, shift: u32) {
+        *self = *self << shift;
     }
+}
 
-    fn mod_mul_eq(&mut self, b: &Self, modulus: &Self) {
-        self.value = (&self.value * &b.value) % &modulus.value;
+impl<const LIMBS: usize> Shr<u32> for BigIntegerFixed<LIMBS> {
+    type Output = Self;
+    fn shr(self, shift: u32) -> Self {
+        Self::shr_wide(&Self::default(), &self, shift)
     }
+}
 
-    fn mod_mul_fast(&self, b: &Self, modulus: &Self) -> Self {
-        self.mod_mul(b, modulus)
+impl<const LIMBS: usize> ShrAssign<u32> for BigIntegerFixed<LIMBS> {
+    fn shr_assign(&mut self, shift: u32) {
+        *self = *self >> shift;
     }
+}
 
-    fn mod_mul_fast_eq(&mut self, b: &Self, modulus: &Self) {
-        self.mod_mul_eq(b, modulus);
+impl<const LIMBS: usize> IntegerInterface for BigIntegerFixed<LIMBS> {
+    fn from_u64(value: u64) -> Self {
+        Self::from(value)
     }
 
-    fn mod_exp(&self, b: &Self, modulus: &Self) -> Self {
-        Self {
-            value: self.value.modpow(&b.value, &modulus.value),
+    fn to_u64(&self) -> Option<u64> {
+        if self.used_limbs() <= 1 {
+            Some(self.m_value[0])
+        } else {
+            None
         }
     }
 
-    fn mod_exp_eq(&mut self, b: &Self, modulus: &Self) {
-        self.value = self.value.modpow(&b.value, &modulus.value);
+    fn get_msb(&self) -> u32 {
+        BigIntegerFixed::get_msb(self)
     }
 
-    fn mod_inverse(&self, modulus: &Self) -> Self {
-        Self {
-            value: self.value.modpow(&(&modulus.value - BigUint::one()), &modulus.value),
-        }
+    fn get_bit_at_index(&self, index: u32) -> bool {
+        BigIntegerFixed::get_bit_at_index(self, index)
     }
 
-    fn mod_inverse_eq(&mut self, modulus: &Self) {
-        self.value = self.value.modpow(&(&modulus.value - BigUint::one()), &modulus.value);
-    }
-
-    fn l_shift(&self, shift: usize) -> Self {
-        Self {
-            value: &self.value << shift,
-        }
+    fn mod_mul(&self, b: &Self, modulus: &Self) -> Self {
+        BigIntegerFixed::mod_mul(self, b, modulus)
     }
 
-    fn l_shift_eq(&mut self, shift: usize) {
-        self.value <<= shift;
+    fn mod_add(&self, b: &Self, modulus: &Self) -> Self {
+        BigIntegerFixed::mod_add(self, b, modulus)
     }
 
-    fn r_shift(&self, shift: usize) -> Self {
-        Self {
-            value: &self.value >> shift,
-        }
+    fn mod_sub(&self, b: &Self, modulus: &Self) -> Self {
+        BigIntegerFixed::mod_sub(self, b, modulus)
     }
 
-    fn r_shift_eq(&mut self, shift: usize) {
-        self.value >>= shift;
+    fn mod_exp(&self, e: &Self, modulus: &Self) -> Self {
+        BigIntegerFixed::mod_exp(self, e, modulus)
     }
+}
 
-    fn compare(&self, a: &Self) -> std::cmp::Ordering {
-        self.value.cmp(&a.value)
+impl<const LIMBS: usize> BigIntegerInterface for BigIntegerFixed<LIMBS> {
+    fn modulo(&self, modulus: &Self) -> Self {
+        BigIntegerFixed::modulo(self, modulus)
     }
 
-    fn convert_to_int<T>(&self) -> T
-    where
-        T: From<u64> + Zero + AddAssign + MulAssign,
-    {
-        let bits = 64; // Assuming T can hold at least 64 bits
-        let mut result = T::zero();
-        let num = bits / 64; // Assuming each segment of BigUint is 64 bits
-
-        for i in 0..num {
-            if let Some(segment) = self.value.to_u64_digits().get(i) {
-                result += T::from(*segment);
-                if i < num - 1 {
-                    result *= T::from(0x1_0000_0000_0000_0000u64);
-                }
-            }
-        }
-
-        result
+    fn compute_mu(&self) -> Self {
+        BigIntegerFixed::compute_mu(self)
     }
 
-    fn convert_to_double(&self) -> f64 {
-        self.value.to_f64().unwrap_or(0.0)
+    fn modulo_with_mu(&self, modulus: &Self, mu: &Self) -> Self {
+        BigIntegerFixed::modulo_with_mu(self, modulus, mu)
     }
 
-    fn int_to_big_integer(m: usize) -> Self {
-        Self {
-            value: BigUint::from(m),
-        }
+    fn mod_add_fast(&self, b: &Self, modulus: &Self) -> Self {
+        BigIntegerFixed::mod_add_fast(self, b, modulus)
     }
 
-    fn from_binary_string(bit_string: &str) -> Self {
-        Self {
-            value: BigUint::parse_bytes(bit_string.as_bytes(), 2).unwrap_or_else(Zero::zero),
-        }
+    fn mod_sub_fast(&self, b: &Self, modulus: &Self) -> Self {
+        BigIntegerFixed::mod_sub_fast(self, b, modulus)
     }
 
-    fn get_msb(&self) -> usize {
-        self.value.bits() as usize
+    fn mod_mul_fast(&self, b: &Self, modulus: &Self, mu: &Self) -> Self {
+        BigIntegerFixed::mod_mul_fast(self, b, modulus, mu)
     }
 
-    fn get_length_for_base(&self, _base: usize) -> usize {
-        self.get_msb()
+    fn mod_inverse(&self, modulus: &Self) -> Self {
+        BigIntegerFixed::mod_inverse(self, modulus)
     }
 
-    fn get_digit_at_index_for_base(&self, index: usize, base: usize) -> u32 {
-        if base == 2 {
-            if let Some(bit) = self.value.to_bytes_be().get(index / 8) {
-                return ((bit >> (7 - index % 8)) & 1) as u32;
-            }
-        }
-        0
+    fn multiply_and_round(&self, p: &Self, q: &Self) -> Self {
+        BigIntegerFixed::multiply_and_round(self, p, q)
     }
 
-    fn check_if_power_of_two(&self) -> bool {
-        self.value.is_power_of_two()
+    fn divide_and_round(&self, q: &Self) -> Self {
+        BigIntegerFixed::divide_and_round(self, q)
     }
 
-    fn get_bit_at_index(&self, index: usize) -> u8 {
-        self.get_digit_at_index_for_base(index, 2) as u8
+    fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> u64 {
+        BigIntegerFixed::get_digit_at_index_for_base(self, index, bits)
     }
 
-    fn allocator() -> Self {
-        Self { value: BigUint::zero() }
+    fn convert_to_double(&self) -> f64 {
+        BigIntegerFixed::convert_to_double(self)
     }
+}
 
-    fn to_string(&self) -> String {
-        self.value.to_str_radix(10)
-    }
+/* Операции над словами. Числа хранятся от младшего слова к старшему. */
 
-    fn integer_type_name() -> &'static str {
-        "UBFIXINT"
+/// a += b при a.len() >= b.len(); возвращает перенос из старшего слова a
+fn add_limbs(a: &mut [u64], b: &[u64]) -> bool {
+    let mut carry = false;
+    for (i, x) in a.iter_mut().enumerate() {
+        let y = b.get(i).copied().unwrap_or(0);
+        if i >= b.len() && !carry {
+            break;
+        }
+        let (s, c1) = x.overflowing_add(y);
+        let (s, c2) = s.overflowing_add(carry as u64);
+        *x = s;
+        carry = c1 || c2;
     }
+    carry
+}
 
-    fn get_internal_representation(&self) -> String {
-        format!("{:?}", self.value.to_u64_digits())
+/// a -= b при a.len() >= b.len(); возвращает заём из старшего слова a
+fn sub_limbs(a: &mut [u64], b: &[u64]) -> bool {
+    let mut borrow = false;
+    for (i, x) in a.iter_mut().enumerate() {
+        let y = b.get(i).copied().unwrap_or(0);
+        if i >= b.len() && !borrow {
+            break;
+        }
+        let (s, b1) = x.overflowing_sub(y);
+        let (s, b2) = s.overflowing_sub(borrow as u64);
+        *x = s;
+        borrow = b1 || b2;
     }
+    borrow
 }
 
-use serde::{Deserialize, Serialize};
-use serde::ser::{SerializeStruct, Serializer};
-use serde::de::{self, DeserializeOwned, Deserializer, Visitor};
-use std::fmt;
-use std::marker::PhantomData;
-
-struct BigIntegerFixedT<uint_type_c, const BITLENGTH_c: usize> {
-    value: [uint_type_c; (BITLENGTH_c + 8 * std::mem::size_of::<uint_type_c>() - 1) / (8 * std::mem::size_of::<uint_type_c>())],
-    msb: u16,
-    // Other associated constants and types as needed
+/// a = a * m + c; возвращает перенос из старшего слова
+fn mul_add_limbs(a: &mut [u64], m: u64, c: u64) -> u64 {
+    let mut carry = c as u128;
+    for x in a.iter_mut() {
+        let p = *x as u128 * m as u128 + carry;
+        *x = p as u64;
+        carry = p >> 64;
+    }
+    carry as u64
 }
 
-impl<uint_type_c, const BITLENGTH_c: usize> fmt::Display for BigIntegerFixedT<uint_type_c, BITLENGTH_c> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // Similar logic for printing the object
-        unimplemented!() // Placeholder for actual implementation
+/// a = a / d, возвращает остаток
+fn div_rem_small(a: &mut [u64], d: u64) -> u64 {
+    let mut rem = 0u128;
+    for x in a.iter_mut().rev() {
+        let cur = (rem << 64) | *x as u128;
+        *x = (cur / d as u128) as u64;
+        rem = cur % d as u128;
     }
+    rem as u64
 }
 
-impl<uint_type_c, const BITLENGTH_c: usize> Serialize for BigIntegerFixedT<uint_type_c, BITLENGTH_c> {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let mut state = serializer.serialize_struct("BigIntegerFixedT", 2)?;
-        state.serialize_field("value", &self.value)?;
-        state.serialize_field("msb", &self.msb)?;
-        state.end()
+/// out = a * b в столбик, out.len() >= a.len() + b.len()
+fn mul_schoolbook(a: &[u64], b: &[u64], out: &mut [u64]) {
+    out.iter_mut().for_each(|x| *x = 0);
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, &y) in b.iter().enumerate() {
+            let p = x as u128 * y as u128 + out[i + j] as u128 + carry;
+            out[i + j] = p as u64;
+            carry = p >> 64;
+        }
+        out[i + b.len()] = carry as u64;
     }
 }
 
-impl<'de, uint_type_c, const BITLENGTH_c: usize> Deserialize<'de> for BigIntegerFixedT<uint_type_c, BITLENGTH_c> {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        #[derive(Deserialize)]
-        #[serde(field_identifier, rename_all = "lowercase")]
-        enum Field { Value, Msb }
-
-        struct BigIntegerFixedTVisitor<uint_type_c, const BITLENGTH_c: usize>(PhantomData<BigIntegerFixedT<uint_type_c, BITLENGTH_c>>);
-
-        impl<'de, uint_type_c, const BITLENGTH_c: usize> Visitor<'de> for BigIntegerFixedTVisitor<uint_type_c, BITLENGTH_c> {
-            type Value = BigIntegerFixedT<uint_type_c, BITLENGTH_c>;
-
-            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("struct BigIntegerFixedT")
-            }
-
-            fn visit_seq<V>(self, mut seq: V) -> Result<BigIntegerFixedT<uint_type_c, BITLENGTH_c>, V::Error>
-            where
-                V: serde::de::SeqAccess<'de>,
-            {
-                let value = seq.next_element()?
-                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
-                let msb = seq.next_element()?
-                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
-                Ok(BigIntegerFixedT { value, msb })
-            }
+/// out = a * b по Карацубе для множителей одной длины n, out.len() == 2n.
+/// scratch должен вмещать не меньше 6n слов.
+fn mul_karatsuba(a: &[u64], b: &[u64], out: &mut [u64], scratch: &mut [u64]) {
+    let n = a.len();
+    if n < KARATSUBA_THRESHOLD {
+        mul_schoolbook(a, b, out);
+        return;
+    }
+
+    // a = a1 * B^k + a0, b = b1 * B^k + b0
+    let k = n.div_ceil(2);
+    let (a0, a1) = a.split_at(k);
+    let (b0, b1) = b.split_at(k);
+    let (z0, z2) = out.split_at_mut(2 * k);
+    mul_karatsuba(a0, b0, z0, scratch);
+    mul_karatsuba(a1, b1, z2, scratch);
+
+    // z1 = (a0 + a1)(b0 + b1) - z0 - z2, суммы занимают k + 1 слово
+    let (sa, rest) = scratch.split_at_mut(k + 1);
+    let (sb, rest) = rest.split_at_mut(k + 1);
+    let (z1, rest) = rest.split_at_mut(2 * k + 2);
+    sa[..k].copy_from_slice(a0);
+    sa[k] = 0;
+    add_limbs(sa, a1);
+    sb[..k].copy_from_slice(b0);
+    sb[k] = 0;
+    add_limbs(sb, b1);
+    mul_karatsuba(sa, sb, z1, rest);
+    sub_limbs(z1, z0);
+    sub_limbs(z1, z2);
+
+    let len = z1.len() - z1.iter().rev().take_while(|&&x| x == 0).count();
+    let carry = add_limbs(&mut out[k..], &z1[..len]);
+    debug_assert!(!carry);
+}
 
-            fn visit_map<V>(self, mut map: V) -> Result<BigIntegerFixedT<uint_type_c, BITLENGTH_c>, V::Error>
-            where
-                V: serde::de::MapAccess<'de>,
-            {
-                let mut value = None;
-                let mut msb = None;
-                while let Some(key) = map.next_key()? {
-                    match key {
-                        Field::Value => {
-                            if value.is_some() {
-                                return Err(de::Error::duplicate_field("value"));
-                            }
-                            value = Some(map.next_value()?);
-                        },
-                        Field::Msb => {
-                            if msb.is_some() {
-                                return Err(de::Error::duplicate_field("msb"));
-                            }
-                            msb = Some(map.next_value()?);
-                        },
-                    }
-                }
-                let value = value.ok_or_else(|| de::Error::missing_field("value"))?;
-                let msb = msb.ok_or_else(|| de::Error::missing_field("msb"))?;
-                Ok(BigIntegerFixedT { value, msb })
+/// Деление по алгоритму D Кнута (TAOCP, 4.3.1). У делимого u есть лишнее
+/// нулевое старшее слово, у делителя v нет старших нулей и v.len() >= 2.
+/// Частное записывается в quot, остаток остаётся в u[..v.len()]; v
+/// портится нормализацией.
+fn div_rem_knuth(u: &mut [u64], v: &mut [u64], quot: &mut [u64]) {
+    let n = v.len();
+    let m = u.len() - n - 1;
+    let s = v[n - 1].leading_zeros();
+    shl_limbs(v, s);
+    shl_limbs(u, s);
+
+    let (v1, v2) = (v[n - 1] as u128, v[n - 2] as u128);
+    for j in (0..=m).rev() {
+        // Оценка очередной цифры частного по двум старшим словам, ошибается не больше чем на 2
+        let num = ((u[j + n] as u128) << 64) | u[j + n - 1] as u128;
+        let mut qhat = num / v1;
+        let mut rhat = num % v1;
+        while qhat >> 64 != 0 || qhat * v2 > ((rhat << 64) | u[j + n - 2] as u128) {
+            qhat -= 1;
+            rhat += v1;
+            if rhat >> 64 != 0 {
+                break;
             }
         }
 
-        const FIELDS: &'static [&'static str] = &["value", "msb"];
-        deserializer.deserialize_struct("BigIntegerFixedT", FIELDS, BigIntegerFixedTVisitor(PhantomData))
+        // u[j..j+n+1] -= qhat * v
+        let mut carry = 0u128;
+        let mut borrow = false;
+        for i in 0..n {
+            let p = qhat * v[i] as u128 + carry;
+            carry = p >> 64;
+            let (t, b1) = u[i + j].overflowing_sub(p as u64);
+            let (t, b2) = t.overflowing_sub(borrow as u64);
+            u[i + j] = t;
+            borrow = b1 || b2;
+        }
+        let (t, b1) = u[j + n].overflowing_sub(carry as u64);
+        let (t, b2) = t.overflowing_sub(borrow as u64);
+        u[j + n] = t;
+
+        // Оценка оказалась на единицу больше: возвращаем v
+        if b1 || b2 {
+            qhat -= 1;
+            let carry = add_limbs(&mut u[j..j + n], v);
+            u[j + n] = u[j + n].wrapping_add(carry as u64);
+        }
+        quot[j] = qhat as u64;
     }
+    shr_limbs(&mut u[..n], s);
 }
 
-// Placeholder for methods like `double_bitVal`, `add_bitVal`, etc.
-// These would need to be implemented according to their logic in Rust.
-
-
-
+/// Сдвиг влево на shift < 64 бит с потерей вышедших битов
+fn shl_limbs(a: &mut [u64], shift: u32) {
+    if shift == 0 {
+        return;
+    }
+    for i in (0..a.len()).rev() {
+        let low = if i > 0 { a[i - 1] >> (64 - shift) } else { 0 };
+        a[i] = (a[i] << shift) | low;
+    }
+}
 
+/// Сдвиг вправо на shift < 64 бит
+fn shr_limbs(a: &mut [u64], shift: u32) {
+    if shift == 0 {
+        return;
+    }
+    for i in 0..a.len() {
+        let high = if i + 1 < a.len() {
+            a[i + 1] << (64 - shift)
+        } else {
+            0
+        };
+        a[i] = (a[i] >> shift) | high;
+    }
+}
@@ -1 +1,5 @@
-
+pub mod backendntl;
+pub mod mubintvecntl;
+pub mod transformntl;
+pub mod transformntl_impl;
+pub mod ubintntl;
@@ -1,594 +1,350 @@
 /*
-  This file contains mgmpintvec, a <vector> of gmpint, with associated math operators.
-  NOTE: this has been refactored so that implied modulo (ring) aritmetic is in mbintvec
- */
-
-
-use num_bigint::BigInt;
-use num_traits::Zero;
-use std::convert::TryInto;
-use std::ops::{AddAssign, SubAssign, MulAssign};
-
-pub struct myVecP<T> {
-    vec: Vec<T>,
-    modulus_state: ModulusState,
-    modulus: T,
-}
+ This file contains myVecP, a vector of myZZ with associated modulus and modulo math operators
+*/
+use super::ubintntl::MyZZ;
+use crate::core::math::hal::vector::BigVectorInterface;
+use std::fmt;
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
 
-enum ModulusState {
-    Garbage,
-    Initialized,
+/// Вектор длинных целых бэкенда NTL по модулю q (аналог myVecP в OpenFHE).
+///
+/// Тот же интерфейс, что у Mubintvec; модуль не ограничен разрядностью.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MyVecP {
+    m_modulus: MyZZ,
+    m_data: Vec<MyZZ>,
 }
 
-impl<T> myVecP<T> {
-    pub fn new() -> Self {
-        Self {
-            vec: Vec::new(),
-            modulus_state: ModulusState::Garbage,
-            modulus: T::zero(),
-        }
-    }
-
-    pub fn single(val: T, modulus: T) -> Self {
-        let mut vec = Self::new();
-        vec.set_modulus(modulus);
-        vec.vec.push(val);
-        vec
-    }
+/// Вектор бэкенда NTL
+pub type BigVector = MyVecP;
 
-    pub fn with_length(length: usize) -> Self {
-        Self {
-            vec: Vec::with_capacity(length),
-            modulus_state: ModulusState::Garbage,
-            modulus: T::zero(),
-        }
+impl MyVecP {
+    /// Нулевой вектор длины length с модулем modulus
+    pub fn new(length: usize, modulus: MyZZ) -> Self {
+        Self::with_value(length, modulus, MyZZ::default())
     }
 
-    pub fn with_length_type(length: usize) -> Self {
-        Self {
-            vec: Vec::with_capacity(length),
-            modulus_state: ModulusState::Garbage,
-            modulus: T::zero(),
-        }
+    /// Вектор длины length, все элементы которого равны val mod modulus
+    pub fn with_value(length: usize, modulus: MyZZ, val: MyZZ) -> Self {
+        let mut res = Self {
+            m_modulus: MyZZ::default(),
+            m_data: vec![],
+        };
+        res.m_data = vec![val.modulo(&modulus); length];
+        res.set_modulus(modulus);
+        res
     }
 
-    pub fn with_value(length: usize, modulus: T, value: T) -> Self {
-        let mut vec = Self::with_length(length);
-        vec.set_modulus(modulus);
-        vec.vec.resize(length, value);
-        vec
+    /// Вектор из значений values, приведённых по модулю modulus
+    pub fn from_values(values: &[MyZZ], modulus: MyZZ) -> Self {
+        let data = values.iter().map(|x| x.modulo(&modulus)).collect();
+        let mut res = Self::new(0, modulus);
+        res.m_data = data;
+        res
     }
 
-    pub fn with_value_string(length: usize, modulus: T, rhs: Vec<String>) -> Self {
-        let mut vec = Self::with_length(length);
-        vec.set_modulus(modulus);
-        for i in 0..length {
-            vec.vec[i] = rhs[i].parse().unwrap();
-        }
-        vec
+    pub fn get_modulus(&self) -> &MyZZ {
+        &self.m_modulus
     }
 
-    pub fn with_value_uint64(length: usize, modulus: T, rhs: Vec<u64>) -> Self {
-        let mut vec = Self::with_length(length);
-        vec.set_modulus(modulus);
-        for i in 0..length {
-            vec.vec[i] = rhs[i].try_into().unwrap();
+    /// Меняет модуль без пересчёта элементов
+    pub fn set_modulus(&mut self, value: MyZZ) {
+        if value == MyZZ::default() {
+            panic!("MyVecP: the modulus must be positive")
         }
-        vec
-    }
-
-    pub fn with_modulus(a: &myVecP<T>, q: T) -> Self {
-        let mut vec = Self::new();
-        vec.vec = a.vec.clone();
-        vec.set_modulus(q);
-        vec
+        self.m_modulus = value;
     }
 
-    pub fn with_modulus_string(a: &myVecP<T>, sq: &str) -> Self {
-        let mut vec = Self::new();
-        vec.vec = a.vec.clone();
-        vec.set_modulus(sq.parse().unwrap());
-        vec
+    pub fn get_length(&self) -> usize {
+        self.m_data.len()
     }
 
-    pub fn with_modulus_uint64(a: &myVecP<T>, q: u64) -> Self {
-        let mut vec = Self::new();
-        vec.vec = a.vec.clone();
-        vec.set_modulus(q.try_into().unwrap());
-        vec
-    }
-
-    pub fn clear(&mut self) {
-        self.vec.clear();
-    }
-
-    pub fn at(&self, i: usize) -> &T {
-        &self.vec[i]
-    }
-
-    pub fn at_mut(&mut self, i: usize) -> &mut T {
-        &mut self.vec[i]
-    }
-
-    pub fn push(&mut self, a: T) {
-        self.vec.push(a);
-    }
-
-    pub fn switch_modulus(&mut self, new_modulus: T) {
-        self.set_modulus(new_modulus);
+    pub fn len(&self) -> usize {
+        self.m_data.len()
     }
 
-    pub fn is_modulus_set(&self) -> bool {
-        self.modulus_state == ModulusState::Initialized
+    pub fn is_empty(&self) -> bool {
+        self.m_data.is_empty()
     }
 
-    pub fn same_modulus(&self, a: &myVecP<T>) -> bool {
-        self.modulus_state == a.modulus_state && self.modulus == a.modulus
+    pub fn iter(&self) -> std::slice::Iter<'_, MyZZ> {
+        self.m_data.iter()
     }
 
-    pub fn set_modulus(&mut self, value: T) {
-        if value.is_zero() {
-            panic!("SetModulus cannot be zero");
-        }
-        self.modulus = value;
-        self.modulus_state = ModulusState::Initialized;
+    pub fn as_slice(&self) -> &[MyZZ] {
+        &self.m_data
     }
 
-    pub fn get_modulus(&self) -> &T {
-        if self.is_modulus_set() {
-            &self.modulus
-        } else {
-            panic!("modulus not set");
+    /// Переводит элементы в модуль new_modulus через центрированный подъём:
+    /// элемент x > q/2 считается отрицательным числом x - q.
+    pub fn switch_modulus(&mut self, new_modulus: MyZZ) {
+        let q = self.m_modulus.clone();
+        let half_q = &q >> 1;
+        for x in self.m_data.iter_mut() {
+            *x = if *x > half_q {
+                (&new_modulus - &(&q - &*x).modulo(&new_modulus)).modulo(&new_modulus)
+            } else {
+                x.modulo(&new_modulus)
+            };
         }
+        self.set_modulus(new_modulus);
     }
 
-    pub fn copy_modulus(&mut self, rhs: &myVecP<T>) -> i32 {
-        self.modulus = rhs.modulus;
-        self.modulus_state = rhs.modulus_state;
-        if self.is_modulus_set() {
-            0
-        } else {
-            self.modulus_state = ModulusState::Garbage;
-            -1
+    /// Цифры всех элементов с номером index в системе счисления с основанием 2^bits
+    pub fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> Self {
+        let mut res = self.clone();
+        for x in res.m_data.iter_mut() {
+            *x = MyZZ::from(x.get_digit_at_index_for_base(index, bits)).modulo(&self.m_modulus);
         }
+        res
     }
+}
 
-    pub fn len(&self) -> usize {
-        self.vec.len()
+/* Операции со скаляром */
+impl MyVecP {
+    pub fn mod_add(&self, b: &MyZZ) -> Self {
+        let mut res = self.clone();
+        res.mod_add_eq(b);
+        res
     }
 
-    pub fn resize(&mut self, n: usize) {
-        self.vec.resize(n, T::zero());
+    pub fn mod_add_eq(&mut self, b: &MyZZ) -> &mut Self {
+        let q = self.m_modulus.clone();
+        let b = b.modulo(&q);
+        self.m_data
+            .iter_mut()
+            .for_each(|x| *x = x.mod_add_fast(&b, &q));
+        self
     }
 
-    pub fn mod_add(&self, b: &T) -> myVecP<T> {
-        self.modulus_check("Warning: myVecP::ModAdd");
-        let mut ans = self.clone();
-        ans.mod_add_eq(b);
-        ans
+    /// Прибавляет b только к элементу с номером i
+    pub fn mod_add_at_index_eq(&mut self, i: usize, b: &MyZZ) -> &mut Self {
+        let q = self.m_modulus.clone();
+        self.m_data[i] = self.m_data[i].mod_add(b, &q);
+        self
     }
 
-    pub fn mod_add_eq(&mut self, b: &T) {
-        self.modulus_check("Warning: myVecP::ModAdd");
-        for i in 0..self.len() {
-            self.vec[i] += b;
-            self.vec[i] %= &self.modulus;
-        }
+    pub fn mod_sub(&self, b: &MyZZ) -> Self {
+        let mut res = self.clone();
+        res.mod_sub_eq(b);
+        res
     }
 
-    pub fn mod_add_at_index(&self, i: usize, b: &T) -> myVecP<T> {
-        let mut ans = self.clone();
-        ans.mod_add_at_index_eq(i, b);
-        ans
+    pub fn mod_sub_eq(&mut self, b: &MyZZ) -> &mut Self {
+        let q = self.m_modulus.clone();
+        let b = b.modulo(&q);
+        self.m_data
+            .iter_mut()
+            .for_each(|x| *x = x.mod_sub_fast(&b, &q));
+        self
     }
 
-    pub fn mod_add_at_index_eq(&mut self, i: usize, b: &T) {
-        self.modulus_check("Warning: myVecP::ModAdd");
-        self.vec[i] += b;
-        self.vec[i] %= &self.modulus;
+    /// Умножение на скаляр: константа Барретта вычисляется один раз на весь вектор
+    pub fn mod_mul(&self, b: &MyZZ) -> Self {
+        let mut res = self.clone();
+        res.mod_mul_eq(b);
+        res
     }
 
-    pub fn mod_add_vec(&self, b: &myVecP<T>) -> myVecP<T> {
-        self.arg_check_vector("myVecP ModAdd()");
-        let mut ans = self.clone();
-        ans.mod_add_vec_eq(b);
-        ans
+    pub fn mod_mul_eq(&mut self, b: &MyZZ) -> &mut Self {
+        let q = self.m_modulus.clone();
+        let mu = q.compute_mu();
+        let b = b.modulo(&q);
+        self.m_data
+            .iter_mut()
+            .for_each(|x| *x = x.mod_mul_fast(&b, &q, &mu));
+        self
     }
 
-    pub fn mod_add_vec_eq(&mut self, b: &myVecP<T>) {
-        self.arg_check_vector("myVecP ModAddEq()");
-        for i in 0..self.len() {
-            self.vec[i] += &b.vec[i];
-            self.vec[i] %= &self.modulus;
-        }
+    /// Возведение всех элементов в степень b
+    pub fn mod_exp(&self, b: &MyZZ) -> Self {
+        let mut res = self.clone();
+        let q = self.m_modulus.clone();
+        res.m_data.iter_mut().for_each(|x| *x = x.mod_exp(b, &q));
+        res
     }
 
-    pub fn mod_add_no_check_vec_eq(&mut self, b: &myVecP<T>) {
-        for i in 0..self.len() {
-            self.vec[i] += &b.vec[i];
-            self.vec[i] %= &self.modulus;
-        }
+    /// Обратные ко всем элементам; паникует, если какой-то элемент необратим
+    pub fn mod_inverse(&self) -> Self {
+        let mut res = self.clone();
+        let q = self.m_modulus.clone();
+        res.m_data.iter_mut().for_each(|x| *x = x.mod_inverse(&q));
+        res
     }
+}
 
-    pub fn mod_sub(&self, b: &T) -> myVecP<T> {
-        self.modulus_check("Warning: myVecP::ModSub");
-        let mut ans = self.clone();
-        ans.mod_sub_eq(b);
-        ans
+/* Поэлементные операции с вектором */
+impl MyVecP {
+    pub fn mod_add_vec(&self, b: &Self) -> Self {
+        let mut res = self.clone();
+        res.mod_add_vec_eq(b);
+        res
     }
 
-    pub fn mod_sub_eq(&mut self, b: &T) {
-        self.modulus_check("Warning: myVecP::ModSubEq");
-        for i in 0..self.len() {
-            self.vec[i] -= b;
-            self.vec[i] %= &self.modulus;
+    pub fn mod_add_vec_eq(&mut self, b: &Self) -> &mut Self {
+        self.check(b, "ModAdd");
+        let q = self.m_modulus.clone();
+        for (x, y) in self.m_data.iter_mut().zip(b.m_data.iter()) {
+            *x = x.mod_add_fast(y, &q);
         }
+        self
     }
 
-    pub fn mod_sub_vec(&self, b: &myVecP<T>) -> myVecP<T> {
-        self.arg_check_vector("myVecP ModSub()");
-        let mut ans = self.clone();
-        ans.mod_sub_vec_eq(b);
-        ans
+    pub fn mod_sub_vec(&self, b: &Self) -> Self {
+        let mut res = self.clone();
+        res.mod_sub_vec_eq(b);
+        res
     }
 
-    pub fn mod_sub_vec_eq(&mut self, b: &myVecP<T>) {
-        self.arg_check_vector("myVecP ModSubEq()");
-        for i in 0..self.len() {
-            self.vec[i] -= &b.vec[i];
-            self.vec[i] %= &self.modulus;
+    pub fn mod_sub_vec_eq(&mut self, b: &Self) -> &mut Self {
+        self.check(b, "ModSub");
+        let q = self.m_modulus.clone();
+        for (x, y) in self.m_data.iter_mut().zip(b.m_data.iter()) {
+            *x = x.mod_sub_fast(y, &q);
         }
+        self
     }
 
-    pub fn mod_mul(&self, b: &T) -> myVecP<T> {
-        self.modulus_check("Warning: myVecP::ModMul");
-        let mut ans = self.clone();
-        ans.mod_mul_eq(b);
-        ans
+    /// Поэлементное произведение с редукцией Барретта
+    pub fn mod_mul_vec(&self, b: &Self) -> Self {
+        let mut res = self.clone();
+        res.mod_mul_vec_eq(b);
+        res
     }
 
-    pub fn mod_mul_eq(&mut self, b: &T) {
-        self.modulus_check("Warning: myVecP::ModMul");
-        for i in 0..self.len() {
-            self.vec[i] *= b;
-            self.vec[i] %= &self.modulus;
+    pub fn mod_mul_vec_eq(&mut self, b: &Self) -> &mut Self {
+        self.check(b, "ModMul");
+        let q = self.m_modulus.clone();
+        let mu = q.compute_mu();
+        for (x, y) in self.m_data.iter_mut().zip(b.m_data.iter()) {
+            *x = x.mod_mul_fast(y, &q, &mu);
         }
+        self
     }
 
-    fn modulus_check(&self, warning: &str) {
-        if !self.is_modulus_set() {
-            panic!(warning);
+    fn check(&self, b: &Self, op: &str) {
+        if self.m_modulus != b.m_modulus {
+            panic!("{} called on MyVecP's with different parameters.", op)
         }
-    }
-
-    fn arg_check_vector(&self, warning: &str) {
-        if self.len() != b.len() {
-            panic!(warning);
+        if self.m_data.len() != b.m_data.len() {
+            panic!("{} called on MyVecP's of different lengths.", op)
         }
     }
 }
 
-impl<T: Clone> Clone for myVecP<T> {
-    fn clone(&self) -> Self {
-        Self {
-            vec: self.vec.clone(),
-            modulus_state: self.modulus_state,
-            modulus: self.modulus.clone(),
-        }
-    }
-}
+impl BigVectorInterface for MyVecP {
+    type Integer = MyZZ;
 
-impl<T: PartialEq> PartialEq for myVecP<T> {
-    fn eq(&self, other: &Self) -> bool {
-        self.vec == other.vec
-            && self.modulus_state == other.modulus_state
-            && self.modulus == other.modulus
+    fn new(length: usize, modulus: Self::Integer) -> Self {
+        MyVecP::new(length, modulus)
     }
-}
-
-impl<T: Eq> Eq for myVecP<T> {}
 
-impl<T: std::fmt::Debug> std::fmt::Debug for myVecP<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("myVecP")
-            .field("vec", &self.vec)
-            .field("modulus_state", &self.modulus_state)
-            .field("modulus", &self.modulus)
-            .finish()
+    fn from_values(values: &[Self::Integer], modulus: Self::Integer) -> Self {
+        MyVecP::from_values(values, modulus)
     }
-}
 
-impl<T: Zero + AddAssign + SubAssign + MulAssign + Clone> AddAssign<&myVecP<T>> for myVecP<T> {
-    fn add_assign(&mut self, other: &myVecP<T>) {
-        self.mod_add_vec_eq(other);
+    fn get_modulus(&self) -> &Self::Integer {
+        MyVecP::get_modulus(self)
     }
-}
 
-impl<T: Zero + AddAssign + SubAssign + MulAssign + Clone> SubAssign<&myVecP<T>> for myVecP<T> {
-    fn sub_assign(&mut self, other: &myVecP<T>) {
-        self.mod_sub_vec_eq(other);
+    fn set_modulus(&mut self, value: Self::Integer) {
+        MyVecP::set_modulus(self, value)
     }
-}
 
-impl<T: Zero + AddAssign + SubAssign + MulAssign + Clone> MulAssign<&myVecP<T>> for myVecP<T> {
-    fn mul_assign(&mut self, other: &myVecP<T>) {
-        self.mod_mul_vec_eq(other);
+    fn get_length(&self) -> usize {
+        MyVecP::get_length(self)
     }
-}
 
-impl<T: Zero + AddAssign + SubAssign + MulAssign + Clone> AddAssign<T> for myVecP<T> {
-    fn add_assign(&mut self, other: T) {
-        self.mod_add_eq(&other);
+    fn switch_modulus(&mut self, new_modulus: Self::Integer) {
+        MyVecP::switch_modulus(self, new_modulus)
     }
-}
 
-impl<T: Zero + AddAssign + SubAssign + MulAssign + Clone> SubAssign<T> for myVecP<T> {
-    fn sub_assign(&mut self, other: T) {
-        self.mod_sub_eq(&other);
+    fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> Self {
+        MyVecP::get_digit_at_index_for_base(self, index, bits)
     }
-}
 
-impl<T: Zero + AddAssign + SubAssign + MulAssign + Clone> MulAssign<T> for myVecP<T> {
-    fn mul_assign(&mut self, other: T) {
-        self.mod_mul_eq(&other);
+    fn mod_add(&self, b: &Self::Integer) -> Self {
+        MyVecP::mod_add(self, b)
     }
-}
-
 
-use std::fmt;
-use std::ops::{Index, IndexMut};
-
-struct myVecP<T> {
-    data: Vec<T>,
-    modulus: T,
-    modulus_state: ModulusState,
-}
-
-impl<T> myVecP<T> {
-    fn new(data: Vec<T>, modulus: T) -> Self {
-        Self {
-            data,
-            modulus,
-            modulus_state: ModulusState::INITIALIZED,
-        }
+    fn mod_sub(&self, b: &Self::Integer) -> Self {
+        MyVecP::mod_sub(self, b)
     }
 
-    fn modulus_check(&self, msg: &str) {
-        if self.modulus_state != ModulusState::INITIALIZED {
-            panic!(msg.to_string() + " uninitialized this->modulus");
-        }
+    fn mod_mul(&self, b: &Self::Integer) -> Self {
+        MyVecP::mod_mul(self, b)
     }
 
-    fn arg_check_vector(&self, b: &myVecP<T>, fname: &str) {
-        if self.modulus != b.modulus {
-            panic!(fname.to_string() + " modulus vector modulus vector op of different moduli");
-        } else if self.modulus_state != ModulusState::INITIALIZED {
-            panic!(fname.to_string() + " modulus vector modulus vector op  GARBAGE  moduli");
-        } else if self.data.len() != b.data.len() {
-            panic!(fname.to_string() + " vectors of different lengths");
-        }
+    fn mod_exp(&self, b: &Self::Integer) -> Self {
+        MyVecP::mod_exp(self, b)
     }
 
-    fn renormalize(&mut self) {
-        for i in 0..self.data.len() {
-            self.data[i] %= self.modulus;
-        }
+    fn mod_inverse(&self) -> Self {
+        MyVecP::mod_inverse(self)
     }
 
-    fn index_check(&self, index: usize) -> bool {
-        index < self.data.len()
-    }
-}
-
-impl<T: fmt::Display> fmt::Display for myVecP<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "[")?;
-        for (i, item) in self.data.iter().enumerate() {
-            write!(f, "{}", item)?;
-            if i != self.data.len() - 1 {
-                write!(f, " ")?;
-            }
-        }
-        write!(f, "] modulus: {}", self.modulus)
+    fn mod_add_vec(&self, b: &Self) -> Self {
+        MyVecP::mod_add_vec(self, b)
     }
-}
-
-impl<T> Index<usize> for myVecP<T> {
-    type Output = T;
 
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.data[index]
+    fn mod_sub_vec(&self, b: &Self) -> Self {
+        MyVecP::mod_sub_vec(self, b)
     }
-}
 
-impl<T> IndexMut<usize> for myVecP<T> {
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.data[index]
+    fn mod_mul_vec(&self, b: &Self) -> Self {
+        MyVecP::mod_mul_vec(self, b)
     }
 }
 
-enum ModulusState {
-    GARBAGE,
-    INITIALIZED,
-}
-
-fn mod_mul<T>(a: &myVecP<T>, b: &myVecP<T>) -> myVecP<T>
-where
-    T: std::ops::MulAssign + std::ops::RemAssign + Copy,
-{
-    a.arg_check_vector(b, "myVecP Mul()");
-    let mut ans = a.clone();
-    ans.mod_mul_eq(b);
-    ans
-}
-
-fn mod_mul_eq<T>(a: &mut myVecP<T>, b: &myVecP<T>)
-where
-    T: std::ops::MulAssign + std::ops::RemAssign + Copy,
-{
-    a.arg_check_vector(b, "myVecP Mul()");
-    for i in 0..a.data.len() {
-        a.data[i] *= b.data[i];
-        a.data[i] %= a.modulus;
-    }
-}
-
-fn mod_mul_no_check_eq<T>(a: &mut myVecP<T>, b: &myVecP<T>)
-where
-    T: std::ops::MulAssign + std::ops::RemAssign + Copy,
-{
-    for i in 0..a.data.len() {
-        a.data[i] *= b.data[i];
-        a.data[i] %= a.modulus;
-    }
-}
-
-fn mod_exp<T>(a: &myVecP<T>, b: &T) -> myVecP<T>
-where
-    T: std::ops::MulAssign + std::ops::RemAssign + Copy,
-{
-    // implementation for ModExp function
-}
-
-fn mod_exp_eq<T>(a: &mut myVecP<T>, b: &T)
-where
-    T: std::ops::MulAssign + std::ops::RemAssign + Copy,
-{
-    // implementation for ModExpEq function
-}
-
-fn mod_inverse<T>(a: &myVecP<T>) -> myVecP<T>
-where
-    T: std::ops::MulAssign + std::ops::RemAssign + Copy,
-{
-    // implementation for ModInverse function
-}
-
-fn mod_inverse_eq<T>(a: &mut myVecP<T>)
-where
-    T: std::ops::MulAssign + std::ops::RemAssign + Copy,
-{
-    // implementation for ModInverseEq function
-}
-
-fn mod_by_two<T>(a: &myVecP<T>) -> myVecP<T>
-where
-    T: std::ops::MulAssign + std::ops::RemAssign + Copy,
-{
-    // implementation for ModByTwo function
-}
-
-fn mod_by_two_eq<T>(a: &mut myVecP<T>)
-where
-    T: std::ops::MulAssign + std::ops::RemAssign + Copy,
-{
-    // implementation for ModByTwoEq function
-}
-
-fn multiply_and_round<T>(a: &myVecP<T>, p: &T, q: &T) -> myVecP<T>
-where
-    T: std::ops::MulAssign + std::ops::RemAssign + Copy,
-{
-    // implementation for MultiplyAndRound function
-}
-
-fn multiply_and_round_eq<T>(a: &mut myVecP<T>, p: &T, q: &T)
-where
-    T: std::ops::MulAssign + std::ops::RemAssign + Copy,
-{
-    // implementation for MultiplyAndRoundEq function
-}
-
-fn divide_and_round<T>(a: &myVecP<T>, q: &T) -> myVecP<T>
-where
-    T: std::ops::MulAssign + std::ops::RemAssign + Copy,
-{
-    // implementation for DivideAndRound function
-}
-
-fn divide_and_round_eq<T>(a: &mut myVecP<T>, q: &T)
-where
-    T: std::ops::MulAssign + std::ops::RemAssign + Copy,
-{
-    // implementation for DivideAndRoundEq function
-}
+impl Index<usize> for MyVecP {
+    type Output = MyZZ;
 
-fn get_digit_at_index_for_base<T>(a: &myVecP<T>, index: usize, base: usize) -> myVecP<T>
-where
-    T: std::ops::MulAssign + std::ops::RemAssign + Copy,
-{
-    // implementation for GetDigitAtIndexForBase function
-}
-
-impl<T: fmt::Display> fmt::Display for myVecP<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "[")?;
-        for (i, item) in self.data.iter().enumerate() {
-            write!(f, "{}", item)?;
-            if i != self.data.len() - 1 {
-                write!(f, " ")?;
-            }
-        }
-        write!(f, "] modulus: {}", self.modulus)
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.m_data[idx]
     }
 }
 
-impl<T: Clone> Clone for myVecP<T> {
-    fn clone(&self) -> Self {
-        Self {
-            data: self.data.clone(),
-            modulus: self.modulus,
-            modulus_state: self.modulus_state,
-        }
+/// Запись через индекс не приводит значение по модулю: записывайте только
+/// значения меньше модуля
+impl IndexMut<usize> for MyVecP {
+    fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
+        &mut self.m_data[idx]
     }
 }
 
-impl<T> myVecP<T>
-where
-    T: std::ops::MulAssign + std::ops::RemAssign + Copy,
-{
-    fn mod_mul_eq(&mut self, b: &myVecP<T>) {
-        self.arg_check_vector(b, "myVecP Mul()");
-        for i in 0..self.data.len() {
-            self.data[i] *= b.data[i];
-            self.data[i] %= self.modulus;
-        }
+impl Add for MyVecP {
+    type Output = Self;
+    fn add(mut self, rhs: Self) -> Self {
+        self.mod_add_vec_eq(&rhs);
+        self
     }
 }
 
-impl<T> Index<usize> for myVecP<T> {
-    type Output = T;
-
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.data[index]
+impl Sub for MyVecP {
+    type Output = Self;
+    fn sub(mut self, rhs: Self) -> Self {
+        self.mod_sub_vec_eq(&rhs);
+        self
     }
 }
 
-impl<T> IndexMut<usize> for myVecP<T> {
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.data[index]
+impl Mul for MyVecP {
+    type Output = Self;
+    fn mul(mut self, rhs: Self) -> Self {
+        self.mod_mul_vec_eq(&rhs);
+        self
     }
 }
 
-impl<T: fmt::Display> fmt::Display for myVecP<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl fmt::Display for MyVecP {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "[")?;
-        for (i, item) in self.data.iter().enumerate() {
-            write!(f, "{}", item)?;
-            if i != self.data.len() - 1 {
+        for (i, x) in self.m_data.iter().enumerate() {
+            if i > 0 {
                 write!(f, " ")?;
             }
+            write!(f, "{}", x)?;
         }
-        write!(f, "] modulus: {}", self.modulus)
+        write!(f, "] modulus: {}", self.m_modulus)
     }
 }
-
-fn main() {
-    // example usage of the myVecP struct and functions
-    let a = myVecP::new(vec![1, 2, 3], 5);
-    let b = myVecP::new(vec![4, 5, 6], 5);
-    let c = mod_mul(&a, &b);
-    println!("{}", c);
-}
-
-
@@ -1,6 +1,16 @@
-
 /*
-  This file contains the linear transform interface functionality for the NTL math backend
- */
+ This file contains the linear transform interface functionality for the NTL math backend
+*/
+
+/// Теоретико-числовое преобразование (NTT) векторов бэкенда NTL.
+///
+/// Таблицы корней передаются явно: root_of_unity_table[i] = w^i для
+/// итеративного варианта и w^(rev(i)) для вариантов с обращением битов.
+pub struct NumberTheoreticTransformNtl;
 
- 
\ No newline at end of file
+/// Преобразование в CRT-представление по степени двойки порядка круговой
+/// группы: NTT многочлена по модулю x^n + 1, n = m / 2.
+///
+/// Таблицы корней кэшируются глобально по модулю и пересчитываются, если
+/// порядок изменился; reset очищает кэш.
+pub struct ChineseRemainderTransformFTTNtl;
@@ -1,469 +1,318 @@
-
-// ATTENTION: this file contains implementations of the functions
-//            declared in math/bigintntl/transformntl.h and
-//            MUST be included in the end of math/bigintntl/transformntl.h ONLY
-//            and nowhere else
-
+/*
+ This file contains the implementation of the NTT and the power-of-two CRT transform for the
+ NTL math backend
+*/
+use super::backendntl::{M6Integer, M6Vector};
+use super::transformntl::{ChineseRemainderTransformFTTNtl, NumberTheoreticTransformNtl};
+use crate::core::math::nbtheory::reverse_bits;
+use lazy_static::lazy_static;
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 
-lazy_static! {
-    static ref M_CYCLOORDERINVERSE_TABLEBYMODULUS: HashMap<usize, VecType> = HashMap::new();
-    static ref M_CYCLOORDERINVERSEPRECON_TABLEBYMODULUS: HashMap<usize, VecType> = HashMap::new();
-    static ref M_ROOTOFUNITYREVERSE_TABLEBYMODULUS: HashMap<usize, VecType> = HashMap::new();
-    static ref M_ROOTOFUNITYINVERSEREVERSE_TABLEBYMODULUS: HashMap<usize, VecType> = HashMap::new();
-    static ref M_ROOTOFUNITYPRECONREVERSE_TABLEBYMODULUS: HashMap<usize, VecType> = HashMap::new();
-    static ref M_ROOTOFUNITYINVERSEPRECONREVERSE_TABLEBYMODULUS: HashMap<usize, VecType> = HashMap::new();
-    static ref M_CYCLOTOMICPOLYMAP: HashMap<usize, VecType> = HashMap::new();
-    static ref M_CYCLOTOMICPOLYREVERSE_NTTMAP: HashMap<usize, VecType> = HashMap::new();
-    static ref M_CYCLOTOMICPOLYNTTMAP: HashMap<usize, VecType> = HashMap::new();
-    static ref M_ROOTOFUNITYTABLEBYMODULUSROOT: HashMap<usize, VecType> = HashMap::new();
-    static ref M_ROOTOFUNITYINVERSETABLEBYMODULUSROOT: HashMap<usize, VecType> = HashMap::new();
-    static ref M_POWERSTABLEBYMODULUSROOT: HashMap<usize, VecType> = HashMap::new();
-    static ref M_RBTABLEBYMODULUSROOTPAIR: HashMap<usize, VecType> = HashMap::new();
-    static ref M_DEFAULTNTTMODULUSROOT: HashMap<usize, ModulusRoot<usize>> = HashMap::new();
-    static ref M_ROOTOFUNITYDIVISIONTABLEBYMODULUS: HashMap<usize, VecType> = HashMap::new();
-    static ref M_ROOTOFUNITYDIVISIONINVERSE_TABLEBYMODULUS: HashMap<usize, VecType> = HashMap::new();
-    static ref M_DIVISIONNTTMODULUS: HashMap<usize, usize> = HashMap::new();
-    static ref M_DIVISIONNTTROOTOFUNITY: HashMap<usize, usize> = HashMap::new();
-    static ref M_NTTDIVISIONDIM: HashMap<usize, usize> = HashMap::new();
-}
+impl NumberTheoreticTransformNtl {
+    /// Циклическое NTT в естественном порядке: result[k] = sum_j element[j] w^(jk),
+    /// root_of_unity_table[i] = w^i
+    pub fn forward_transform_iterative(
+        element: &M6Vector,
+        root_of_unity_table: &M6Vector,
+        result: &mut M6Vector,
+    ) {
+        let n = element.get_length();
+        if result.get_length() != n {
+            panic!("size of input element and size of output element not of same size");
+        }
+        let modulus = element.get_modulus();
+        let mu = modulus.compute_mu();
+        result.set_modulus(modulus.clone());
 
-fn forward_transform_iterative(element: &VecType, root_of_unity_table: &VecType, result: &mut VecType) {
-    let n = element.len();
-    if result.len() != n {
-        panic!("size of input element and size of output element not of same size");
-    }
-    let modulus = element.get_modulus();
-    let mu = modulus.compute_mu();
-    result.set_modulus(modulus);
-    let msb = (n - 1).leading_zeros();
-    for i in 0..n {
-        result[i] = element[reverse_bits(i, msb)];
-    }
-    let mut omega;
-    let mut omega_factor;
-    let mut odd_val;
-    let mut even_val;
-    let logm;
-    let mut i;
-    let mut j;
-    let mut index_even;
-    let mut index_odd;
-    let logn = (n - 1).leading_zeros();
-    for logm in 1..=logn {
-        let mut indexes = Vec::with_capacity(1 << (logm - 1));
-        for i in 0..(1 << (logm - 1)) {
-            indexes.push(i << (logn - logm));
+        let logn = n.trailing_zeros();
+        for i in 0..n {
+            result[i] = element[reverse_bits(i as u64, logn) as usize].clone();
         }
-        for j in (0..n).step_by(1 << logm) {
-            for i in 0..(1 << (logm - 1)) {
-                omega = root_of_unity_table[indexes[i]];
-                index_even = j + i;
-                index_odd = index_even + (1 << (logm - 1));
-                odd_val = result[index_odd];
-                omega_factor = omega.mod_mul(odd_val, modulus, mu);
-                even_val = result[index_even];
-                odd_val = even_val;
-                odd_val += omega_factor;
-                if odd_val >= modulus {
-                    odd_val -= modulus;
-                }
-                if even_val < omega_factor {
-                    even_val += modulus;
+        for logm in 1..=logn {
+            let half = 1 << (logm - 1);
+            for j in (0..n).step_by(1 << logm) {
+                for i in 0..half {
+                    let omega = &root_of_unity_table[i << (logn - logm)];
+                    let (index_even, index_odd) = (j + i, j + i + half);
+                    let omega_factor = omega.mod_mul_fast(&result[index_odd], modulus, &mu);
+                    let even_val = result[index_even].clone();
+                    result[index_even] = even_val.mod_add_fast(&omega_factor, modulus);
+                    result[index_odd] = even_val.mod_sub_fast(&omega_factor, modulus);
                 }
-                even_val -= omega_factor;
-                result[index_even] = odd_val;
-                result[index_odd] = even_val;
             }
         }
     }
-}
-
-fn inverse_transform_iterative(element: &VecType, root_of_unity_inverse_table: &VecType, result: &mut VecType) {
-    let n = element.len();
-    let modulus = element.get_modulus();
-    let mu = modulus.compute_mu();
-    forward_transform_iterative(element, root_of_unity_inverse_table, result);
-    let cyclo_order_inv = IntType::from(n).mod_inverse(modulus);
-    for i in 0..n {
-        result[i].mod_mul_eq(cyclo_order_inv, modulus, mu);
-    }
-}
 
-fn forward_transform_to_bit_reverse_in_place(root_of_unity_table: &VecType, element: &mut VecType) {
-    let n = element.len();
-    let modulus = element.get_modulus();
-    let mu = modulus.compute_mu();
-    let mut i;
-    let mut m;
-    let mut j1;
-    let mut j2;
-    let mut index_omega;
-    let mut index_lo;
-    let mut index_hi;
-    let mut omega;
-    let mut omega_factor;
-    let mut lo_val;
-    let mut hi_val;
-    let zero = IntType::from(0);
-    let mut t = n >> 1;
-    let mut logt1 = t.leading_zeros();
-    for m in 1..n {
-        for i in 0..m {
-            j1 = i << logt1;
-            j2 = j1 + t;
-            index_omega = m + i;
-            omega = root_of_unity_table[index_omega];
-            for index_lo in j1..j2 {
-                index_hi = index_lo + t;
-                lo_val = element[index_lo];
-                omega_factor = element[index_hi];
-                omega_factor.mod_mul_fast_eq(omega, modulus, mu);
-                hi_val = lo_val + omega_factor;
-                if hi_val >= modulus {
-                    hi_val -= modulus;
-                }
-                if lo_val < omega_factor {
-                    lo_val += modulus;
-                }
-                lo_val -= omega_factor;
-                element[index_lo] = hi_val;
-                element[index_hi] = lo_val;
-            }
-        }
-        t >>= 1;
-        logt1 -= 1;
+    /// Обратное циклическое NTT в естественном порядке, root_of_unity_inverse_table[i] = w^-i
+    pub fn inverse_transform_iterative(
+        element: &M6Vector,
+        root_of_unity_inverse_table: &M6Vector,
+        result: &mut M6Vector,
+    ) {
+        let n = element.get_length();
+        let modulus = element.get_modulus();
+        Self::forward_transform_iterative(element, root_of_unity_inverse_table, result);
+        let n_inv = M6Integer::from(n as u64).mod_inverse(modulus);
+        result.mod_mul_eq(&n_inv);
     }
-}
-
 
-use num::bigint::BigUint;
-use num::traits::Num;
-use num::traits::One;
-use num::traits::Zero;
-
-fn forward_transform_to_bit_reverse<T: Num + Clone + PartialOrd>(element: &Vec<T>,
-                                                                 root_of_unity_table: &Vec<T>,
-                                                                 result: &mut Vec<T>) {
-    let n = element.len();
-    if result.len() != n {
-        panic!("size of input element and size of output element not of same size");
-    }
-    let modulus = element[0].clone();
-    let mu = modulus.clone().compute_mu();
-    result.iter_mut().zip(element.iter()).for_each(|(r, e)| *r = e.clone());
-    let mut t = n >> 1;
-    let mut logt1 = get_msb64(t);
-    for m in 1..n {
-        for i in 0..m {
-            let j1 = i << logt1;
-            let j2 = j1 + t;
-            let index_omega = m + i;
-            let omega = root_of_unity_table[index_omega].clone();
-            for index_lo in j1..j2 {
-                let index_hi = index_lo + t;
-                let lo_val = result[index_lo].clone();
-                let omega_factor = result[index_hi].clone();
-                if omega_factor != T::zero() {
-                    let omega_factor = omega_factor.mod_mul_fast(omega.clone(), modulus.clone(), mu.clone());
-                    let hi_val = lo_val.clone() + omega_factor.clone();
-                    let modulus_clone = modulus.clone();
-                    if hi_val >= modulus_clone {
-                        hi_val - modulus_clone;
-                    }
-                    let modulus_clone = modulus.clone();
-                    let lo_val = lo_val.clone();
-                    if lo_val < omega_factor {
-                        lo_val + modulus_clone;
-                    }
-                    let lo_val = lo_val.clone() - omega_factor;
-                    result[index_lo] = hi_val;
-                    result[index_hi] = lo_val;
-                } else {
-                    result[index_hi] = lo_val;
-                }
-            }
-        }
-        t >>= 1;
-        logt1 -= 1;
-    }
-}
+    /// NTT Кули-Тьюки на месте: вход в естественном порядке, выход в порядке
+    /// обращения битов. root_of_unity_table[i] = w^(rev(i)), w - корень порядка 2n
+    pub fn forward_transform_to_bit_reverse_in_place(
+        root_of_unity_table: &M6Vector,
+        element: &mut M6Vector,
+    ) {
+        let n = element.get_length();
+        let modulus = element.get_modulus().clone();
+        let mu = modulus.compute_mu();
 
-fn forward_transform_to_bit_reverse_in_place<T: Num + Clone + PartialOrd>(root_of_unity_table: &Vec<T>,
-                                                                          precon_root_of_unity_table: &Vec<T>,
-                                                                          element: &mut Vec<T>) {
-    let n = element.len();
-    let modulus = element[0].clone();
-    let mut t = n >> 1;
-    let mut logt1 = get_msb64(t);
-    for m in 1..n {
-        let mut j1;
-        let mut j2;
-        for i in 0..m {
-            j1 = i << logt1;
-            j2 = j1 + t;
-            let index_omega = m + i;
-            let omega = root_of_unity_table[index_omega].clone();
-            let precon_omega = precon_root_of_unity_table[index_omega].clone();
-            for index_lo in j1..j2 {
-                let index_hi = index_lo + t;
-                let lo_val = element[index_lo].clone();
-                let omega_factor = element[index_hi].clone();
-                let omega_factor = omega_factor.mod_mul_fast_const(omega.clone(), modulus.clone(), precon_omega.clone());
-                let hi_val = lo_val.clone() + omega_factor.clone();
-                let modulus_clone = modulus.clone();
-                if hi_val >= modulus_clone {
-                    hi_val - modulus_clone;
+        let mut t = n >> 1;
+        let mut logt1 = t.trailing_zeros() + 1;
+        let mut m = 1;
+        while m < n {
+            for i in 0..m {
+                let j1 = i << logt1;
+                let j2 = j1 + t;
+                let omega = &root_of_unity_table[m + i];
+                for index_lo in j1..j2 {
+                    let index_hi = index_lo + t;
+                    let lo_val = element[index_lo].clone();
+                    let omega_factor = element[index_hi].mod_mul_fast(omega, &modulus, &mu);
+                    element[index_lo] = lo_val.mod_add_fast(&omega_factor, &modulus);
+                    element[index_hi] = lo_val.mod_sub_fast(&omega_factor, &modulus);
                 }
-                let modulus_clone = modulus.clone();
-                let lo_val = lo_val.clone();
-                if lo_val < omega_factor {
-                    lo_val + modulus_clone;
-                }
-                let lo_val = lo_val.clone() - omega_factor;
-                element[index_lo] = hi_val;
-                element[index_hi] = lo_val;
             }
+            m <<= 1;
+            t >>= 1;
+            logt1 -= 1;
         }
-        t >>= 1;
-        logt1 -= 1;
     }
-}
 
-fn inverse_transform_from_bit_reverse_in_place<T: Num + Clone + PartialOrd>(root_of_unity_inverse_table: &Vec<T>,
-                                                                            cyclo_order_inv: &T,
-                                                                            element: &mut Vec<T>) {
-    let n = element.len();
-    let modulus = element[0].clone();
-    let mu = modulus.clone().compute_mu();
-    let mut lo_val;
-    let mut hi_val;
-    let mut omega;
-    let mut omega_factor;
-    let mut i;
-    let mut m;
-    let mut j1;
-    let mut j2;
-    let mut index_omega;
-    let mut index_lo;
-    let mut index_hi;
-    let mut t = 1;
-    let mut logt1 = 1;
-    for m in (n >> 1)..=1 {
-        for i in 0..m {
-            j1 = i << logt1;
-            j2 = j1 + t;
-            index_omega = m + i;
-            omega = root_of_unity_inverse_table[index_omega].clone();
-            for index_lo in j1..j2 {
-                index_hi = index_lo + t;
-                hi_val = element[index_hi].clone();
-                lo_val = element[index_lo].clone();
-                omega_factor = lo_val.clone();
-                let modulus_clone = modulus.clone();
-                if omega_factor < hi_val {
-                    omega_factor + modulus_clone;
-                }
-                let modulus_clone = modulus.clone();
-                let hi_val = hi_val.clone();
-                omega_factor -= hi_val;
-                lo_val += hi_val;
-                if lo_val >= modulus_clone {
-                    lo_val - modulus_clone;
-                }
-                omega_factor = omega_factor.mod_mul_fast(omega.clone(), modulus.clone(), mu.clone());
-                element[index_lo] = lo_val;
-                element[index_hi] = omega_factor;
-            }
+    pub fn forward_transform_to_bit_reverse(
+        element: &M6Vector,
+        root_of_unity_table: &M6Vector,
+        result: &mut M6Vector,
+    ) {
+        if result.get_length() != element.get_length() {
+            panic!("size of input element and size of output element not of same size");
         }
-        t <<= 1;
-        logt1 += 1;
-    }
-    for i in 0..n {
-        element[i] = element[i].mod_mul_fast(cyclo_order_inv.clone(), modulus.clone(), mu.clone());
+        *result = element.clone();
+        Self::forward_transform_to_bit_reverse_in_place(root_of_unity_table, result);
     }
-}
 
-fn inverse_transform_from_bit_reverse<T: Num + Clone + PartialOrd>(element: &Vec<T>,
-                                                                  root_of_unity_inverse_table: &Vec<T>,
-                                                                  cyclo_order_inv: &T,
-                                                                  result: &mut Vec<T>) {
-    let n = element.len();
-    if result.len() != n {
-        panic!("size of input element and size of output element not of same size");
-    }
-    result.iter_mut().zip(element.iter()).for_each(|(r, e)| *r = e.clone());
-    inverse_transform_from_bit_reverse_in_place(root_of_unity_inverse_table, cyclo_order_inv, result);
-}
-
-use std::collections::HashMap;
-
-pub struct NumberTheoreticTransformNtl<VecType> {
-    // implementation details
-}
-
-impl<VecType> NumberTheoreticTransformNtl<VecType> {
+    /// Обратное NTT Джентльмена-Сенде на месте: вход в порядке обращения битов,
+    /// выход в естественном порядке. root_of_unity_inverse_table[i] = w^(-rev(i)),
+    /// cyclo_order_inv = n^-1 mod q
     pub fn inverse_transform_from_bit_reverse_in_place(
-        &self,
-        root_of_unity_inverse_table: &VecType,
-        precon_root_of_unity_inverse_table: &VecType,
-        cyclo_order_inv: &IntType,
-        precon_cyclo_order_inv: &IntType,
-        element: &mut VecType,
+        root_of_unity_inverse_table: &M6Vector,
+        cyclo_order_inv: &M6Integer,
+        element: &mut M6Vector,
     ) {
         let n = element.get_length();
-        let modulus = element.get_modulus();
-        let mut lo_val;
-        let mut hi_val;
-        let mut omega;
-        let mut omega_factor;
-        let mut precon_omega;
-        let mut i;
-        let mut m;
-        let mut j1;
-        let mut j2;
-        let mut index_omega;
-        let mut index_lo;
-        let mut index_hi;
+        let modulus = element.get_modulus().clone();
+        let mu = modulus.compute_mu();
+
         let mut t = 1;
         let mut logt1 = 1;
-        m = n >> 1;
+        let mut m = n >> 1;
         while m >= 1 {
-            i = 0;
-            while i < m {
-                j1 = i << logt1;
-                j2 = j1 + t;
-                index_omega = m + i;
-                omega = root_of_unity_inverse_table[index_omega];
-                precon_omega = precon_root_of_unity_inverse_table[index_omega];
-                index_lo = j1;
-                while index_lo < j2 {
-                    index_hi = index_lo + t;
-                    hi_val = element[index_hi];
-                    lo_val = element[index_lo];
-                    omega_factor = lo_val;
-                    if omega_factor < hi_val {
-                        omega_factor += modulus;
-                    }
-                    omega_factor -= hi_val;
-                    lo_val += hi_val;
-                    if lo_val >= modulus {
-                        lo_val -= modulus;
-                    }
-                    omega_factor.mod_mul_fast_const_eq(omega, modulus, precon_omega);
-                    element[index_lo] = lo_val;
+            for i in 0..m {
+                let j1 = i << logt1;
+                let j2 = j1 + t;
+                let omega = &root_of_unity_inverse_table[m + i];
+                for index_lo in j1..j2 {
+                    let index_hi = index_lo + t;
+                    let (lo_val, hi_val) = (&element[index_lo], &element[index_hi]);
+                    let sum = lo_val.mod_add_fast(hi_val, &modulus);
+                    let omega_factor = lo_val
+                        .mod_sub_fast(hi_val, &modulus)
+                        .mod_mul_fast(omega, &modulus, &mu);
+                    element[index_lo] = sum;
                     element[index_hi] = omega_factor;
-                    index_lo += 1;
                 }
-                i += 1;
             }
             t <<= 1;
             logt1 += 1;
             m >>= 1;
         }
-        i = 0;
-        while i < n {
-            element[i].mod_mul_fast_const_eq(cyclo_order_inv, modulus, precon_cyclo_order_inv);
-            i += 1;
+        for i in 0..n {
+            element[i] = element[i].mod_mul_fast(cyclo_order_inv, &modulus, &mu);
         }
     }
 
     pub fn inverse_transform_from_bit_reverse(
-        &self,
-        element: &VecType,
-        root_of_unity_inverse_table: &VecType,
-        precon_root_of_unity_inverse_table: &VecType,
-        cyclo_order_inv: &IntType,
-        precon_cyclo_order_inv: &IntType,
-        result: &mut VecType,
+        element: &M6Vector,
+        root_of_unity_inverse_table: &M6Vector,
+        cyclo_order_inv: &M6Integer,
+        result: &mut M6Vector,
     ) {
-        let n = element.get_length();
-        if result.get_length() != n {
+        if result.get_length() != element.get_length() {
             panic!("size of input element and size of output element not of same size");
         }
-        result.set_modulus(element.get_modulus());
-        let mut i = 0;
-        while i < n {
-            result[i] = element[i];
-            i += 1;
-        }
-        self.inverse_transform_from_bit_reverse_in_place(
+        *result = element.clone();
+        Self::inverse_transform_from_bit_reverse_in_place(
             root_of_unity_inverse_table,
-            precon_root_of_unity_inverse_table,
             cyclo_order_inv,
-            precon_cyclo_order_inv,
             result,
         );
     }
 }
 
-pub struct ChineseRemainderTransformFTTNtl<VecType> {
-    // implementation details
-}
-
-impl<VecType> ChineseRemainderTransformFTTNtl<VecType> {
+impl ChineseRemainderTransformFTTNtl {
+    /// Прямое преобразование на месте; root_of_unity - первообразный корень
+    /// порядка cyclo_order по модулю вектора
     pub fn forward_transform_to_bit_reverse_in_place(
-        &self,
-        root_of_unity: &IntType,
-        cyclo_order: usint,
-        element: &mut VecType,
+        root_of_unity: &M6Integer,
+        cyclo_order: usize,
+        element: &mut M6Vector,
     ) {
-        if *root_of_unity == IntType(1) || *root_of_unity == IntType(0) {
+        if Self::is_trivial_root(root_of_unity) {
             return;
         }
-        if !is_power_of_two(cyclo_order) {
-            panic!("CyclotomicOrder is not a power of two");
-        }
-        let cyclo_order_hf = cyclo_order >> 1;
-        if element.get_length() != cyclo_order_hf {
-            panic!("element size must be equal to CyclotomicOrder / 2");
-        }
-        let modulus = element.get_modulus();
-        let map_search = m_root_of_unity_reverse_table_by_modulus.get(&modulus);
-        if map_search.is_none() || map_search.unwrap().get_length() != cyclo_order_hf {
-            self.pre_compute(root_of_unity, cyclo_order, modulus);
-        }
-        NumberTheoreticTransformNtl<VecType>().forward_transform_to_bit_reverse_in_place(
-            m_root_of_unity_reverse_table_by_modulus[modulus],
+        Self::check_length(cyclo_order, element.get_length(), "element");
+        let tables = Self::tables(root_of_unity, cyclo_order, element.get_modulus());
+        NumberTheoreticTransformNtl::forward_transform_to_bit_reverse_in_place(
+            &tables.root_of_unity_reverse,
             element,
         );
     }
 
     pub fn forward_transform_to_bit_reverse(
-        &self,
-        element: &VecType,
-        root_of_unity: &IntType,
-        cyclo_order: usint,
-        result: &mut VecType,
+        element: &M6Vector,
+        root_of_unity: &M6Integer,
+        cyclo_order: usize,
+        result: &mut M6Vector,
     ) {
-        if *root_of_unity == IntType(1) || *root_of_unity == IntType(0) {
-            *result = element;
+        if Self::is_trivial_root(root_of_unity) {
+            *result = element.clone();
             return;
         }
-        if !is_power_of_two(cyclo_order) {
-            panic!("CyclotomicOrder is not a power of two");
-        }
-        let cyclo_order_hf = cyclo_order >> 1;
-        if result.get_length() != cyclo_order_hf {
-            panic!("result size must be equal to CyclotomicOrder / 2");
+        Self::check_length(cyclo_order, result.get_length(), "result");
+        let tables = Self::tables(root_of_unity, cyclo_order, element.get_modulus());
+        NumberTheoreticTransformNtl::forward_transform_to_bit_reverse(
+            element,
+            &tables.root_of_unity_reverse,
+            result,
+        );
+    }
+
+    /// Обратное преобразование на месте
+    pub fn inverse_transform_from_bit_reverse_in_place(
+        root_of_unity: &M6Integer,
+        cyclo_order: usize,
+        element: &mut M6Vector,
+    ) {
+        if Self::is_trivial_root(root_of_unity) {
+            return;
         }
-        let modulus = element.get_modulus();
-        let map_search = m_root_of_unity_reverse_table_by_modulus.get(&modulus);
-        if map_search.is_none() || map_search.unwrap().get_length() != cyclo_order_hf {
-            self.pre_compute(root_of_unity, cyclo_order, modulus);
+        Self::check_length(cyclo_order, element.get_length(), "element");
+        let tables = Self::tables(root_of_unity, cyclo_order, element.get_modulus());
+        NumberTheoreticTransformNtl::inverse_transform_from_bit_reverse_in_place(
+            &tables.root_of_unity_inverse_reverse,
+            &tables.cyclo_order_inv,
+            element,
+        );
+    }
+
+    pub fn inverse_transform_from_bit_reverse(
+        element: &M6Vector,
+        root_of_unity: &M6Integer,
+        cyclo_order: usize,
+        result: &mut M6Vector,
+    ) {
+        if Self::is_trivial_root(root_of_unity) {
+            *result = element.clone();
+            return;
         }
-        NumberTheoreticTransformNtl<VecType>().forward_transform_to_bit_reverse(
+        Self::check_length(cyclo_order, result.get_length(), "result");
+        let tables = Self::tables(root_of_unity, cyclo_order, element.get_modulus());
+        NumberTheoreticTransformNtl::inverse_transform_from_bit_reverse(
             element,
-            m_root_of_unity_reverse_table_by_modulus[modulus],
+            &tables.root_of_unity_inverse_reverse,
+            &tables.cyclo_order_inv,
             result,
         );
     }
-}
 
-fn is_power_of_two(n: usint) -> bool {
-    n != 0 && (n & (n - 1)) == 0
-}
+    /// Вычисляет и кэширует таблицы корней для модуля modulus
+    pub fn pre_compute(root_of_unity: &M6Integer, cyclo_order: usize, modulus: &M6Integer) {
+        let n = cyclo_order >> 1;
+        let msb = n.trailing_zeros();
+        let root_inv = root_of_unity.mod_inverse(modulus);
+        let mu = modulus.compute_mu();
 
+        let mut powers = vec![M6Integer::from(1u64)];
+        let mut powers_inv = vec![M6Integer::from(1u64)];
+        for i in 1..n {
+            powers.push(powers[i - 1].mod_mul_fast(root_of_unity, modulus, &mu));
+            powers_inv.push(powers_inv[i - 1].mod_mul_fast(&root_inv, modulus, &mu));
+        }
+        let reverse = |table: &[M6Integer]| {
+            let values: Vec<M6Integer> = (0..n)
+                .map(|i| table[reverse_bits(i as u64, msb) as usize].clone())
+                .collect();
+            M6Vector::from_values(&values, modulus.clone())
+        };
+        let tables = NttTables {
+            root_of_unity_reverse: reverse(&powers),
+            root_of_unity_inverse_reverse: reverse(&powers_inv),
+            cyclo_order_inv: M6Integer::from(n as u64).mod_inverse(modulus),
+        };
+        TABLES_BY_MODULUS
+            .write()
+            .unwrap()
+            .insert(modulus.clone(), Arc::new(tables));
+    }
 
+    /// Очищает все кэшированные таблицы
+    pub fn reset() {
+        TABLES_BY_MODULUS.write().unwrap().clear();
+    }
 
+    /// Таблицы для модуля modulus; пересчитываются, если их нет или их длина
+    /// не соответствует порядку cyclo_order
+    fn tables(
+        root_of_unity: &M6Integer,
+        cyclo_order: usize,
+        modulus: &M6Integer,
+    ) -> Arc<NttTables> {
+        let cached = TABLES_BY_MODULUS.read().unwrap().get(modulus).cloned();
+        match cached {
+            Some(tables) if tables.root_of_unity_reverse.get_length() == cyclo_order >> 1 => tables,
+            _ => {
+                Self::pre_compute(root_of_unity, cyclo_order, modulus);
+                TABLES_BY_MODULUS.read().unwrap()[modulus].clone()
+            }
+        }
+    }
 
+    fn is_trivial_root(root_of_unity: &M6Integer) -> bool {
+        *root_of_unity == M6Integer::from(1u64) || *root_of_unity == M6Integer::from(0u64)
+    }
+
+    fn check_length(cyclo_order: usize, length: usize, name: &str) {
+        if !cyclo_order.is_power_of_two() {
+            panic!("CyclotomicOrder is not a power of two");
+        }
+        if length != cyclo_order >> 1 {
+            panic!("{} size must be equal to CyclotomicOrder / 2", name);
+        }
+    }
+}
+
+/// Таблицы преобразования для одного модуля
+struct NttTables {
+    /// w^(rev(i)), w - корень порядка 2n
+    root_of_unity_reverse: M6Vector,
+    /// w^(-rev(i))
+    root_of_unity_inverse_reverse: M6Vector,
+    /// n^-1 mod q
+    cyclo_order_inv: M6Integer,
+}
+
+lazy_static! {
+    /// Таблицы преобразования по модулю
+    static ref TABLES_BY_MODULUS: RwLock<HashMap<M6Integer, Arc<NttTables>>> =
+        RwLock::new(HashMap::new());
+}
@@ -1,8 +1,473 @@
-
 /*
-  This file contains the C++ code for implementing the main class for big integers:
-  gmpint which replaces BBI and uses NTL
- */
+ This file contains the main class for big integers of the NTL backend: myZZ. OpenFHE wraps
+ NTL::ZZ here; this port wraps num-bigint instead, so the backend is written independently
+ of the two native ones and can be used to cross-check them
+*/
+use crate::core::math::hal::integer::{self, BigIntegerInterface};
+use num::bigint::BigUint;
+use num::traits::{Num, NumCast, One, ToPrimitive, Zero};
+use std::fmt::{self, Display};
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign,
+    Sub, SubAssign,
+};
+use std::str::FromStr;
+
+/// Целое без знака произвольной длины поверх num-bigint (аналог myZZ в OpenFHE).
+///
+/// Интерфейс совпадает с BigIntegerFixed и Ubint. Вычитание большего из
+/// меньшего - ошибка, как и в остальных бэкендах.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MyZZ {
+    m_value: BigUint,
+}
+
+/// Длинное целое бэкенда NTL
+pub type BigInteger = MyZZ;
+
+impl MyZZ {
+    pub fn new(value: BigUint) -> Self {
+        Self { m_value: value }
+    }
+
+    /// Значение как BigUint
+    pub fn value(&self) -> &BigUint {
+        &self.m_value
+    }
+
+    pub fn set_identity(&mut self) {
+        self.m_value = BigUint::one();
+    }
+
+    /// Значение в другом целочисленном типе; паникует, если не помещается
+    pub fn convert_to_int<I: NumCast>(&self) -> I {
+        self.m_value
+            .to_u128()
+            .and_then(I::from)
+            .expect("The value does not fit into the requested type")
+    }
+
+    pub fn convert_to_double(&self) -> f64 {
+        self.m_value.to_f64().unwrap()
+    }
+
+    /// Номер старшего ненулевого бита, считая с 1 (0 для нуля)
+    pub fn get_msb(&self) -> u32 {
+        self.m_value.bits() as u32
+    }
+
+    /// Бит с номером index, младший бит имеет номер 0
+    pub fn get_bit_at_index(&self, index: u32) -> bool {
+        !(&self.m_value >> index as usize & BigUint::one()).is_zero()
+    }
+
+    /// Цифра с номером index (с 0) в системе счисления с основанием 2^bits, bits <= 64
+    pub fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> u64 {
+        assert!(
+            bits > 0 && bits <= 64,
+            "The base must be 2^bits with 0 < bits <= 64"
+        );
+        let mask = (BigUint::one() << bits as usize) - 1u8;
+        ((&self.m_value >> (index * bits) as usize) & mask)
+            .to_u64()
+            .unwrap()
+    }
+
+    /// Частное и остаток от деления на b
+    pub fn div_rem(&self, b: &Self) -> (Self, Self) {
+        if b.m_value.is_zero() {
+            panic!("MyZZ: Divide by zero");
+        }
+        let (quot, rem) = num::Integer::div_rem(&self.m_value, &b.m_value);
+        (Self::new(quot), Self::new(rem))
+    }
+
+    /// self^p
+    pub fn exp(&self, p: u32) -> Self {
+        Self::new(num::pow(self.m_value.clone(), p as usize))
+    }
+
+    /// round(self * p / q) без потери точности
+    pub fn multiply_and_round(&self, p: &Self, q: &Self) -> Self {
+        (self * p).divide_and_round(q)
+    }
+
+    /// round(self / q)
+    pub fn divide_and_round(&self, q: &Self) -> Self {
+        let (quot, rem) = self.div_rem(q);
+        if rem.m_value > &q.m_value >> 1 {
+            quot + Self::from(1u64)
+        } else {
+            quot
+        }
+    }
+}
+
+/* Модульная арифметика */
+impl MyZZ {
+    /// self mod modulus
+    pub fn modulo(&self, modulus: &Self) -> Self {
+        self.div_rem(modulus).1
+    }
+
+    /// Константа Барретта mu = floor(2^(2n) / q), где n - разрядность модуля q = self
+    pub fn compute_mu(&self) -> Self {
+        if self.m_value.is_zero() {
+            panic!("MyZZ ComputeMu: Divide by zero");
+        }
+        Self::new((BigUint::one() << (2 * self.get_msb()) as usize) / &self.m_value)
+    }
+
+    /// self mod modulus редукцией Барретта, mu = modulus.compute_mu()
+    pub fn modulo_with_mu(&self, modulus: &Self, mu: &Self) -> Self {
+        if self.get_msb() > 2 * modulus.get_msb() {
+            return self.modulo(modulus);
+        }
+        Self::barrett(&self.m_value, modulus, mu)
+    }
+
+    /// (self + b) mod modulus
+    pub fn mod_add(&self, b: &Self, modulus: &Self) -> Self {
+        self.modulo(modulus)
+            .mod_add_fast(&b.modulo(modulus), modulus)
+    }
+
+    /// (self + b) mod modulus для self, b < modulus
+    pub fn mod_add_fast(&self, b: &Self, modulus: &Self) -> Self {
+        let sum = &self.m_value + &b.m_value;
+        if sum >= modulus.m_value {
+            Self::new(sum - &modulus.m_value)
+        } else {
+            Self::new(sum)
+        }
+    }
+
+    pub fn mod_add_eq(&mut self, b: &Self, modulus: &Self) -> &mut Self {
+        *self = self.mod_add(b, modulus);
+        self
+    }
+
+    /// (self - b) mod modulus
+    pub fn mod_sub(&self, b: &Self, modulus: &Self) -> Self {
+        self.modulo(modulus)
+            .mod_sub_fast(&b.modulo(modulus), modulus)
+    }
+
+    /// (self - b) mod modulus для self, b < modulus
+    pub fn mod_sub_fast(&self, b: &Self, modulus: &Self) -> Self {
+        if self.m_value >= b.m_value {
+            Self::new(&self.m_value - &b.m_value)
+        } else {
+            Self::new(&self.m_value + &modulus.m_value - &b.m_value)
+        }
+    }
+
+    pub fn mod_sub_eq(&mut self, b: &Self, modulus: &Self) -> &mut Self {
+        *self = self.mod_sub(b, modulus);
+        self
+    }
+
+    /// (self * b) mod modulus
+    pub fn mod_mul(&self, b: &Self, modulus: &Self) -> Self {
+        if modulus.m_value.is_zero() {
+            panic!("MyZZ: Divide by zero");
+        }
+        Self::new(&self.m_value * &b.m_value % &modulus.m_value)
+    }
+
+    pub fn mod_mul_eq(&mut self, b: &Self, modulus: &Self) -> &mut Self {
+        *self = self.mod_mul(b, modulus);
+        self
+    }
+
+    /// (self * b) mod modulus редукцией Барретта для self, b < modulus
+    pub fn mod_mul_fast(&self, b: &Self, modulus: &Self, mu: &Self) -> Self {
+        Self::barrett(&(&self.m_value * &b.m_value), modulus, mu)
+    }
+
+    pub fn mod_mul_fast_eq(&mut self, b: &Self, modulus: &Self, mu: &Self) -> &mut Self {
+        *self = self.mod_mul_fast(b, modulus, mu);
+        self
+    }
+
+    /// self^b mod modulus
+    pub fn mod_exp(&self, b: &Self, modulus: &Self) -> Self {
+        if modulus.m_value.is_zero() {
+            panic!("MyZZ: Divide by zero");
+        }
+        Self::new(self.m_value.modpow(&b.m_value, &modulus.m_value))
+    }
+
+    /// self^-1 mod modulus; паникует, если self и modulus не взаимно просты
+    pub fn mod_inverse(&self, modulus: &Self) -> Self {
+        let q = &modulus.m_value;
+        let (mut r0, mut r1) = (q.clone(), &self.m_value % q);
+        // Коэффициенты Безу хранятся по модулю q, чтобы обойтись без знака
+        let (mut t0, mut t1) = (BigUint::zero(), BigUint::one());
+        while !r1.is_zero() {
+            let (quot, r) = num::Integer::div_rem(&r0, &r1);
+            let t = (&t0 + q - quot * &t1 % q) % q;
+            r0 = std::mem::replace(&mut r1, r);
+            t0 = std::mem::replace(&mut t1, t);
+        }
+        if !r0.is_one() {
+            panic!(
+                "MyZZ ModInverse: {} is not invertible modulo {}",
+                self, modulus
+            )
+        }
+        Self::new(t0 % q)
+    }
+
+    /// Редукция Барретта x < 2^(2n) по модулю q < 2^n (HAC 14.42)
+    fn barrett(x: &BigUint, q: &Self, mu: &Self) -> Self {
+        let n = q.get_msb() as usize;
+        let q3 = ((x >> (n - 1)) * &mu.m_value) >> (n + 1);
+        let mut r = x - q3 * &q.m_value;
+        while r >= q.m_value {
+            r -= &q.m_value;
+        }
+        Self::new(r)
+    }
+}
+
+impl From<u64> for MyZZ {
+    fn from(value: u64) -> Self {
+        Self::new(BigUint::from(value))
+    }
+}
+
+impl From<u128> for MyZZ {
+    fn from(value: u128) -> Self {
+        Self::new(BigUint::from(value))
+    }
+}
+
+impl From<BigUint> for MyZZ {
+    fn from(value: BigUint) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Десятичная запись или шестнадцатеричная с префиксом 0x
+impl FromStr for MyZZ {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (digits, radix) = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            Some(hex) => (hex, 16),
+            None => (s, 10),
+        };
+        // num-bigint допускает знак и подчёркивания, здесь - только цифры
+        if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+            return Err(format!("{} is not a valid unsigned integer", s));
+        }
+        Ok(Self::new(BigUint::from_str_radix(digits, radix).unwrap()))
+    }
+}
+
+impl Display for MyZZ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.m_value, f)
+    }
+}
+
+impl fmt::LowerHex for MyZZ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.m_value, f)
+    }
+}
+
+impl Sub<&MyZZ> for &MyZZ {
+    type Output = MyZZ;
+    fn sub(self, rhs: &MyZZ) -> MyZZ {
+        if self.m_value < rhs.m_value {
+            panic!("MyZZ: attempt to subtract with overflow")
+        }
+        MyZZ::new(&self.m_value - &rhs.m_value)
+    }
+}
+
+impl Div<&MyZZ> for &MyZZ {
+    type Output = MyZZ;
+    fn div(self, rhs: &MyZZ) -> MyZZ {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem<&MyZZ> for &MyZZ {
+    type Output = MyZZ;
+    fn rem(self, rhs: &MyZZ) -> MyZZ {
+        self.div_rem(rhs).1
+    }
+}
+
+/* Сложение и умножение не могут завершиться ошибкой и передаются num-bigint напрямую */
+macro_rules! myzz_op_by_ref {
+    ($op:ident, $method:ident) => {
+        impl $op<&MyZZ> for &MyZZ {
+            type Output = MyZZ;
+            fn $method(self, rhs: &MyZZ) -> MyZZ {
+                MyZZ::new($op::$method(&self.m_value, &rhs.m_value))
+            }
+        }
+    };
+}
+
+myzz_op_by_ref!(Add, add);
+myzz_op_by_ref!(Mul, mul);
+
+macro_rules! myzz_op_by_value {
+    ($op:ident, $method:ident, $op_assign:ident, $method_assign:ident) => {
+        impl $op for MyZZ {
+            type Output = Self;
+            fn $method(self, rhs: Self) -> Self {
+                $op::$method(&self, &rhs)
+            }
+        }
+
+        impl $op<&MyZZ> for MyZZ {
+            type Output = Self;
+            fn $method(self, rhs: &Self) -> Self {
+                $op::$method(&self, rhs)
+            }
+        }
+
+        impl $op_assign<&MyZZ> for MyZZ {
+            fn $method_assign(&mut self, rhs: &Self) {
+                *self = $op::$method(&*self, rhs);
+            }
+        }
+
+        impl $op_assign for MyZZ {
+            fn $method_assign(&mut self, rhs: Self) {
+                *self = $op::$method(&*self, &rhs);
+            }
+        }
+    };
+}
+
+myzz_op_by_value!(Add, add, AddAssign, add_assign);
+myzz_op_by_value!(Sub, sub, SubAssign, sub_assign);
+myzz_op_by_value!(Mul, mul, MulAssign, mul_assign);
+myzz_op_by_value!(Div, div, DivAssign, div_assign);
+myzz_op_by_value!(Rem, rem, RemAssign, rem_assign);
+
+impl Shl<u32> for &MyZZ {
+    type Output = MyZZ;
+    fn shl(self, shift: u32) -> MyZZ {
+        MyZZ::new(&self.m_value << shift as usize)
+    }
+}
+
+impl Shl<u32> for MyZZ {
+    type Output = Self;
+    fn shl(self, shift: u32) -> Self {
+        &self << shift
+    }
+}
+
+impl ShlAssign<u32> for MyZZ {
+    fn shl_assign(&mut self, shift: u32) {
+        *self = &*self << shift;
+    }
+}
+
+impl Shr<u32> for &MyZZ {
+    type Output = MyZZ;
+    fn shr(self, shift: u32) -> MyZZ {
+        MyZZ::new(&self.m_value >> shift as usize)
+    }
+}
+
+impl Shr<u32> for MyZZ {
+    type Output = Self;
+    fn shr(self, shift: u32) -> Self {
+        &self >> shift
+    }
+}
+
+impl ShrAssign<u32> for MyZZ {
+    fn shr_assign(&mut self, shift: u32) {
+        *self = &*self >> shift;
+    }
+}
+
+impl integer::IntegerInterface for MyZZ {
+    fn from_u64(value: u64) -> Self {
+        Self::from(value)
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        ToPrimitive::to_u64(&self.m_value)
+    }
+
+    fn get_msb(&self) -> u32 {
+        MyZZ::get_msb(self)
+    }
+
+    fn get_bit_at_index(&self, index: u32) -> bool {
+        MyZZ::get_bit_at_index(self, index)
+    }
+
+    fn mod_mul(&self, b: &Self, modulus: &Self) -> Self {
+        MyZZ::mod_mul(self, b, modulus)
+    }
+
+    fn mod_add(&self, b: &Self, modulus: &Self) -> Self {
+        MyZZ::mod_add(self, b, modulus)
+    }
+
+    fn mod_sub(&self, b: &Self, modulus: &Self) -> Self {
+        MyZZ::mod_sub(self, b, modulus)
+    }
+
+    fn mod_exp(&self, e: &Self, modulus: &Self) -> Self {
+        MyZZ::mod_exp(self, e, modulus)
+    }
+}
+
+impl BigIntegerInterface for MyZZ {
+    fn modulo(&self, modulus: &Self) -> Self {
+        MyZZ::modulo(self, modulus)
+    }
+
+    fn compute_mu(&self) -> Self {
+        MyZZ::compute_mu(self)
+    }
+
+    fn modulo_with_mu(&self, modulus: &Self, mu: &Self) -> Self {
+        MyZZ::modulo_with_mu(self, modulus, mu)
+    }
+
+    fn mod_add_fast(&self, b: &Self, modulus: &Self) -> Self {
+        MyZZ::mod_add_fast(self, b, modulus)
+    }
+
+    fn mod_sub_fast(&self, b: &Self, modulus: &Self) -> Self {
+        MyZZ::mod_sub_fast(self, b, modulus)
+    }
+
+    fn mod_mul_fast(&self, b: &Self, modulus: &Self, mu: &Self) -> Self {
+        MyZZ::mod_mul_fast(self, b, modulus, mu)
+    }
+
+    fn mod_inverse(&self, modulus: &Self) -> Self {
+        MyZZ::mod_inverse(self, modulus)
+    }
+
+    fn multiply_and_round(&self, p: &Self, q: &Self) -> Self {
+        MyZZ::multiply_and_round(self, p, q)
+    }
+
+    fn divide_and_round(&self, q: &Self) -> Self {
+        MyZZ::divide_and_round(self, q)
+    }
 
+    fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> u64 {
+        MyZZ::get_digit_at_index_for_base(self, index, bits)
+    }
 
- 
\ No newline at end of file
+    fn convert_to_double(&self) -> f64 {
+        MyZZ::convert_to_double(self)
+    }
+}
@@ -0,0 +1,200 @@
+/*
+  This file contains the interface every integer backend provides to the generic math code
+*/
+use num::bigint::BigUint;
+use num::{Integer, One, ToPrimitive, Zero};
+use std::fmt::{self, Debug, Display};
+use std::hash::Hash;
+use std::ops::{Add, Div, Mul, Rem, Sub};
+use std::str::FromStr;
+
+/// Длинное целое выбранного бэкенда и машинное целое
+pub use super::bigintbackend::BigInteger;
+pub use super::intnat::ubintnat::NativeInteger;
+
+/// Общий интерфейс целых чисел всех бэкендов (аналог IntegerInterface в OpenFHE).
+///
+/// Обобщённый код (теория чисел, NTT, RNS) пишется над этим типажом и
+/// работает как с машинными словами, так и с длинной арифметикой. Операторы
+/// +, -, /, % выполняются без приведения по модулю и не должны переполняться
+/// для аргументов меньше модуля; умножение всегда выполняется по модулю,
+/// потому что его результат не помещается в слово машинного бэкенда.
+pub trait IntegerInterface:
+    Clone
+    + Debug
+    + Display
+    + Ord
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    fn from_u64(value: u64) -> Self;
+
+    /// Значение как u64, если оно помещается
+    fn to_u64(&self) -> Option<u64>;
+
+    /// Номер старшего ненулевого бита, считая с 1 (0 для нуля)
+    fn get_msb(&self) -> u32;
+
+    /// Бит с номером index, младший бит имеет номер 0
+    fn get_bit_at_index(&self, index: u32) -> bool;
+
+    /// (self * b) mod modulus
+    fn mod_mul(&self, b: &Self, modulus: &Self) -> Self;
+
+    fn zero() -> Self {
+        Self::from_u64(0)
+    }
+
+    fn one() -> Self {
+        Self::from_u64(1)
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+
+    /// (self + b) mod modulus без переполнения при модуле во всё слово
+    fn mod_add(&self, b: &Self, modulus: &Self) -> Self {
+        let a = self.clone() % modulus.clone();
+        let b = b.clone() % modulus.clone();
+        let complement = modulus.clone() - b.clone();
+        if a >= complement {
+            a - complement
+        } else {
+            a + b
+        }
+    }
+
+    /// (self - b) mod modulus
+    fn mod_sub(&self, b: &Self, modulus: &Self) -> Self {
+        let a = self.clone() % modulus.clone();
+        let b = b.clone() % modulus.clone();
+        if a >= b {
+            a - b
+        } else {
+            a + (modulus.clone() - b)
+        }
+    }
+
+    /// self^e mod modulus возведением в квадрат
+    fn mod_exp(&self, e: &Self, modulus: &Self) -> Self {
+        let mut res = Self::one() % modulus.clone();
+        let base = self.clone() % modulus.clone();
+        for i in (0..e.get_msb()).rev() {
+            res = res.mod_mul(&res, modulus);
+            if e.get_bit_at_index(i) {
+                res = res.mod_mul(&base, modulus);
+            }
+        }
+        res
+    }
+}
+
+/* Машинный бэкенд: 64-битные слова, произведение через u128 */
+impl IntegerInterface for u64 {
+    fn from_u64(value: u64) -> Self {
+        value
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        Some(*self)
+    }
+
+    fn get_msb(&self) -> u32 {
+        u64::BITS - self.leading_zeros()
+    }
+
+    fn get_bit_at_index(&self, index: u32) -> bool {
+        (self >> index) & 1 == 1
+    }
+
+    fn mod_mul(&self, b: &Self, modulus: &Self) -> Self {
+        ((*self as u128 * *b as u128) % *modulus as u128) as u64
+    }
+}
+
+/* Длинная арифметика num-bigint */
+impl IntegerInterface for BigUint {
+    fn from_u64(value: u64) -> Self {
+        BigUint::from(value)
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        ToPrimitive::to_u64(self)
+    }
+
+    fn get_msb(&self) -> u32 {
+        self.bits() as u32
+    }
+
+    fn get_bit_at_index(&self, index: u32) -> bool {
+        (self >> index as usize).is_odd()
+    }
+
+    fn mod_mul(&self, b: &Self, modulus: &Self) -> Self {
+        (self * b) % modulus
+    }
+
+    fn zero() -> Self {
+        Zero::zero()
+    }
+
+    fn one() -> Self {
+        One::one()
+    }
+
+    fn mod_exp(&self, e: &Self, modulus: &Self) -> Self {
+        self.modpow(e, modulus)
+    }
+}
+
+/// Общий интерфейс длинных целых (аналог BigIntegerInterface в OpenFHE).
+///
+/// Его реализуют все бэкенды длинной арифметики, поэтому решётчатый слой и
+/// тесты пишутся один раз и работают с любым из них. Бэкенд по умолчанию
+/// выбирается признаком Cargo, см. bigintbackend.
+pub trait BigIntegerInterface:
+    IntegerInterface
+    + Default
+    + Hash
+    + Mul<Output = Self>
+    + FromStr
+    + fmt::LowerHex
+    + Send
+    + Sync
+    + 'static
+{
+    /// self mod modulus
+    fn modulo(&self, modulus: &Self) -> Self;
+
+    /// Константа Барретта для модуля self
+    fn compute_mu(&self) -> Self;
+
+    /// self mod modulus редукцией Барретта, mu = modulus.compute_mu()
+    fn modulo_with_mu(&self, modulus: &Self, mu: &Self) -> Self;
+
+    /// (self + b) mod modulus для self, b < modulus
+    fn mod_add_fast(&self, b: &Self, modulus: &Self) -> Self;
+
+    /// (self - b) mod modulus для self, b < modulus
+    fn mod_sub_fast(&self, b: &Self, modulus: &Self) -> Self;
+
+    /// (self * b) mod modulus редукцией Барретта для self, b < modulus
+    fn mod_mul_fast(&self, b: &Self, modulus: &Self, mu: &Self) -> Self;
+
+    /// self^-1 mod modulus; паникует, если обратного нет
+    fn mod_inverse(&self, modulus: &Self) -> Self;
+
+    /// round(self * p / q)
+    fn multiply_and_round(&self, p: &Self, q: &Self) -> Self;
+
+    /// round(self / q)
+    fn divide_and_round(&self, q: &Self) -> Self;
+
+    /// Цифра с номером index в системе счисления с основанием 2^bits
+    fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> u64;
+
+    fn convert_to_double(&self) -> f64;
+}
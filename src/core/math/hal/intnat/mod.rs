@@ -1 +1,4 @@
-
+pub mod mubintvecnat;
+pub mod transformnat;
+pub mod transformnat_impl;
+pub mod ubintnat;
@@ -1,633 +1,404 @@
-const BLOCK_VECTOR_ALLOCATION: u64 = 0;
+/*
+  This file contains the vector manipulation functionality for native integers
+*/
+use super::ubintnat::{NativeInt, NativeIntegerT};
+use crate::core::math::hal::basicint::{BasicInteger, MAX_MODULUS_SIZE};
+use crate::core::math::hal::vector::BigVectorInterface;
+use std::fmt;
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+/// Вектор машинных целых по модулю q.
+///
+/// Модуль хранится вместе с данными, и все элементы всегда приведены в
+/// [0, q). Операции над двумя векторами требуют одинаковых модулей и длин.
+/// На этом типе построены многочлены PolyImpl<NativeVector> и каждая башня
+/// RNS-представления DCRTPolyImpl.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct NativeVectorT<T: NativeInt> {
+    m_modulus: NativeIntegerT<T>,
+    m_data: Vec<NativeIntegerT<T>>,
+}
+
+/// Вектор машинных целых текущей платформы
+pub type NativeVector = NativeVectorT<BasicInteger>;
 
-pub struct NativeVector<T> {
-    modulus: T,
-    data: Vec<T>,
+/// Наибольшая разрядность модуля: тот же запас до разрядности слова, что и у
+/// MAX_MODULUS_SIZE для BasicInteger
+pub fn max_modulus_size<T: NativeInt>() -> u32 {
+    T::BITS - (BasicInteger::BITS - MAX_MODULUS_SIZE)
 }
 
-impl<T> NativeVector<T> {
-    pub fn new(length: usize) -> Self {
-        NativeVector {
-            modulus: Default::default(),
-            data: vec![Default::default(); length],
-        }
+impl<T: NativeInt> NativeVectorT<T> {
+    /// Нулевой вектор длины length с модулем modulus
+    pub fn new(length: usize, modulus: NativeIntegerT<T>) -> Self {
+        Self::with_value(length, modulus, NativeIntegerT::default())
     }
 
-    pub fn single(val: T, modulus: T) -> Self {
-        NativeVector {
-            modulus,
-            data: vec![val],
-        }
+    /// Вектор длины length, все элементы которого равны val mod modulus
+    pub fn with_value(length: usize, modulus: NativeIntegerT<T>, val: NativeIntegerT<T>) -> Self {
+        let mut res = Self {
+            m_modulus: NativeIntegerT::default(),
+            m_data: vec![],
+        };
+        res.set_modulus(modulus);
+        res.m_data = vec![val.modulo(&modulus); length];
+        res
     }
 
-    pub fn with_modulus(length: usize, modulus: T) -> Self {
-        NativeVector {
-            modulus,
-            data: vec![Default::default(); length],
-        }
+    /// Вектор из значений values, приведённых по модулю modulus
+    pub fn from_values(values: &[T], modulus: NativeIntegerT<T>) -> Self {
+        let mut res = Self::new(0, modulus);
+        res.m_data = values
+            .iter()
+            .map(|&x| NativeIntegerT::new(x).modulo(&modulus))
+            .collect();
+        res
     }
 
-    pub fn with_modulus_and_value(length: usize, modulus: T, val: T) -> Self {
-        NativeVector {
-            modulus,
-            data: vec![val.modulus(modulus); length],
-        }
+    pub fn get_modulus(&self) -> &NativeIntegerT<T> {
+        &self.m_modulus
     }
 
-    pub fn set_modulus(&mut self, value: T) {
-        if value.get_msb() > MAX_MODULUS_SIZE {
-            let err_msg = format!(
+    /// Меняет модуль без пересчёта элементов
+    pub fn set_modulus(&mut self, value: NativeIntegerT<T>) {
+        if value.get_msb() > max_modulus_size::<T>() {
+            panic!(
                 "Requested modulus' size {} is not supported. NativeVectorT supports only modulus size <= {}",
                 value.get_msb(),
-                MAX_MODULUS_SIZE
-            );
-            panic!(err_msg);
+                max_modulus_size::<T>()
+            )
         }
-        self.modulus = value;
-    }
-}
-
-impl<T: Clone> Clone for NativeVector<T> {
-    fn clone(&self) -> Self {
-        NativeVector {
-            modulus: self.modulus.clone(),
-            data: self.data.clone(),
+        if value.value().is_zero() {
+            panic!("NativeVectorT: the modulus must be positive")
         }
+        self.m_modulus = value;
     }
-}
-
-impl<T> std::ops::Index<usize> for NativeVector<T> {
-    type Output = T;
 
-    fn index(&self, idx: usize) -> &Self::Output {
-        &self.data[idx]
+    pub fn get_length(&self) -> usize {
+        self.m_data.len()
     }
-}
 
-impl<T> std::ops::IndexMut<usize> for NativeVector<T> {
-    fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
-        &mut self.data[idx]
+    pub fn len(&self) -> usize {
+        self.m_data.len()
     }
-}
-
-use std::ops::{Add, Sub, Mul};
 
-fn switch_modulus<T: IntegerType>(value: &T) {}
+    pub fn is_empty(&self) -> bool {
+        self.m_data.is_empty()
+    }
 
-impl<T: IntegerType> NativeVectorT<T> {
-    fn get_modulus(&self) -> &T {
-        &self.m_modulus
+    pub fn iter(&self) -> std::slice::Iter<'_, NativeIntegerT<T>> {
+        self.m_data.iter()
     }
-    
-    fn get_length(&self) -> usize {
-        self.m_data.len()
+
+    pub fn as_slice(&self) -> &[NativeIntegerT<T>] {
+        &self.m_data
     }
-    
-    fn mod_eq(&mut self, modulus: &T) -> &mut Self {
-        self.m_modulus = modulus.clone();
-        self
+
+    /// Элементы для изменения на месте; приведение по модулю остаётся на вызывающем
+    pub fn as_mut_slice(&mut self) -> &mut [NativeIntegerT<T>] {
+        &mut self.m_data
     }
-    
-    fn mod_add(&self, b: &T) -> Self {
-        let mut result = self.clone();
-        for i in 0..self.m_data.len() {
-            result.m_data[i] += b;
-            result.m_data[i] %= self.m_modulus;
+
+    /// Переводит элементы в модуль new_modulus через центрированный подъём:
+    /// элемент x > q/2 считается отрицательным числом x - q.
+    pub fn switch_modulus(&mut self, new_modulus: NativeIntegerT<T>) {
+        let q = self.m_modulus.value();
+        let half_q = q >> 1;
+        let new_q = new_modulus.value();
+        for x in self.m_data.iter_mut() {
+            let v = x.value();
+            *x = if v > half_q {
+                NativeIntegerT::new(new_q - (q - v) % new_q).modulo(&new_modulus)
+            } else {
+                NativeIntegerT::new(v % new_q)
+            };
         }
-        result
+        self.set_modulus(new_modulus);
     }
-    
-    fn mod_add_eq(&mut self, b: &T) -> &mut Self {
-        for i in 0..self.m_data.len() {
-            self.m_data[i] += b;
-            self.m_data[i] %= self.m_modulus;
+
+    /// Цифры всех элементов с номером index в системе счисления с основанием 2^bits
+    pub fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> Self {
+        let mut res = self.clone();
+        for x in res.m_data.iter_mut() {
+            *x = NativeIntegerT::new(x.get_digit_at_index_for_base(index, bits))
+                .modulo(&self.m_modulus);
         }
-        self
-    }
-    
-    fn mod_add_at_index(&self, i: usize, b: &T) -> Self {
-        let mut result = self.clone();
-        result.m_data[i] += b;
-        result.m_data[i] %= self.m_modulus;
-        result
-    }
-    
-    fn mod_add_at_index_eq(&mut self, i: usize, b: &T) -> &mut Self {
-        self.m_data[i] += b;
-        self.m_data[i] %= self.m_modulus;
-        self
+        res
     }
-    
-    fn mod_add_vec(&self, b: &Self) -> Self {
-        let mut result = self.clone();
-        for i in 0..self.m_data.len() {
-            result.m_data[i] += b.m_data[i];
-            result.m_data[i] %= self.m_modulus;
-        }
-        result
+
+    /// Вектор по модулю 2 центрированных представителей: x mod q -> (x - q) mod 2 при x > q/2
+    pub fn mod_by_two(&self) -> Self {
+        let mut res = self.clone();
+        res.switch_modulus(NativeIntegerT::new(T::one() + T::one()));
+        res
     }
-    
-    fn mod_add_vec_eq(&mut self, b: &Self) -> &mut Self {
-        for i in 0..self.m_data.len() {
-            self.m_data[i] += b.m_data[i];
-            self.m_data[i] %= self.m_modulus;
-        }
-        self
+}
+
+/* Операции со скаляром */
+impl<T: NativeInt> NativeVectorT<T> {
+    pub fn mod_add(&self, b: &NativeIntegerT<T>) -> Self {
+        let mut res = self.clone();
+        res.mod_add_eq(b);
+        res
     }
-    
-    fn mod_add_no_check_eq(&mut self, b: &Self) -> &mut Self {
-        let size = self.m_data.len();
-        let mv = self.m_modulus.clone();
-        for i in 0..size {
-            self.m_data[i].mod_add_fast_eq(&b.m_data[i], &mv);
-        }
+
+    pub fn mod_add_eq(&mut self, b: &NativeIntegerT<T>) -> &mut Self {
+        let q = self.m_modulus;
+        let b = b.modulo(&q);
+        self.m_data
+            .iter_mut()
+            .for_each(|x| *x = x.mod_add_fast(&b, &q));
         self
     }
-    
-    fn mod_sub(&self, b: &T) -> Self {
-        let mut result = self.clone();
-        for i in 0..self.m_data.len() {
-            result.m_data[i] -= b;
-            result.m_data[i] %= self.m_modulus;
-        }
-        result
-    }
-    
-    fn mod_sub_eq(&mut self, b: &T) -> &mut Self {
-        for i in 0..self.m_data.len() {
-            self.m_data[i] -= b;
-            self.m_data[i] %= self.m_modulus;
-        }
+
+    /// Прибавляет b только к элементу с номером i
+    pub fn mod_add_at_index_eq(&mut self, i: usize, b: &NativeIntegerT<T>) -> &mut Self {
+        let q = self.m_modulus;
+        self.m_data[i] = self.m_data[i].mod_add(b, &q);
         self
     }
-    
-    fn mod_sub_vec(&self, b: &Self) -> Self {
-        let mut result = self.clone();
-        for i in 0..self.m_data.len() {
-            result.m_data[i] -= b.m_data[i];
-            result.m_data[i] %= self.m_modulus;
-        }
-        result
+
+    pub fn mod_sub(&self, b: &NativeIntegerT<T>) -> Self {
+        let mut res = self.clone();
+        res.mod_sub_eq(b);
+        res
     }
-    
-    fn mod_sub_vec_eq(&mut self, b: &Self) -> &mut Self {
-        for i in 0..self.m_data.len() {
-            self.m_data[i] -= b.m_data[i];
-            self.m_data[i] %= self.m_modulus;
-        }
+
+    pub fn mod_sub_eq(&mut self, b: &NativeIntegerT<T>) -> &mut Self {
+        let q = self.m_modulus;
+        let b = b.modulo(&q);
+        self.m_data
+            .iter_mut()
+            .for_each(|x| *x = x.mod_sub_fast(&b, &q));
         self
     }
-    
-    fn mod_mul(&self, b: &T) -> Self {
-        let mut result = self.clone();
-        for i in 0..self.m_data.len() {
-            result.m_data[i] *= b;
-            result.m_data[i] %= self.m_modulus;
-        }
-        result
+
+    /// Умножение на скаляр: константа Шоупа вычисляется один раз на весь вектор
+    pub fn mod_mul(&self, b: &NativeIntegerT<T>) -> Self {
+        let mut res = self.clone();
+        res.mod_mul_eq(b);
+        res
     }
-    
-    fn mod_mul_eq(&mut self, b: &T) -> &mut Self {
-        for i in 0..self.m_data.len() {
-            self.m_data[i] *= b;
-            self.m_data[i] %= self.m_modulus;
-        }
-        self
+
+    pub fn mod_mul_eq(&mut self, b: &NativeIntegerT<T>) -> &mut Self {
+        let b = b.modulo(&self.m_modulus);
+        let b_inv = b.prep_mod_mul_const(&self.m_modulus);
+        self.mod_mul_fast_const_eq(&b, &b_inv)
     }
-    
-    fn mod_mul_vec(&self, b: &Self) -> Self {
-        let mut result = self.clone();
-        for i in 0..self.m_data.len() {
-            result.m_data[i] *= b.m_data[i];
-            result.m_data[i] %= self.m_modulus;
-        }
-        result
+
+    /// Умножение на скаляр b < q с заранее вычисленной константой Шоупа
+    /// b_inv = b.prep_mod_mul_const(q)
+    pub fn mod_mul_fast_const(&self, b: &NativeIntegerT<T>, b_inv: &NativeIntegerT<T>) -> Self {
+        let mut res = self.clone();
+        res.mod_mul_fast_const_eq(b, b_inv);
+        res
     }
-    
-    fn mod_mul_vec_eq(&mut self, b: &Self) -> &mut Self {
-        for i in 0..self.m_data.len() {
-            self.m_data[i] *= b.m_data[i];
-            self.m_data[i] %= self.m_modulus;
-        }
+
+    pub fn mod_mul_fast_const_eq(
+        &mut self,
+        b: &NativeIntegerT<T>,
+        b_inv: &NativeIntegerT<T>,
+    ) -> &mut Self {
+        let q = self.m_modulus;
+        self.m_data
+            .iter_mut()
+            .for_each(|x| *x = x.mod_mul_fast_const(b, &q, b_inv));
         self
     }
-    
-    fn mod_mul_no_check_eq(&mut self, b: &Self) -> &mut Self {
-        let size = self.m_data.len();
-        let mv = self.m_modulus.clone();
-        for i in 0..size {
-            self.m_data[i].mod_mul_fast_eq(&b.m_data[i], &mv);
-        }
-        self
+
+    /// Возведение всех элементов в степень b
+    pub fn mod_exp(&self, b: &NativeIntegerT<T>) -> Self {
+        let mut res = self.clone();
+        let q = self.m_modulus;
+        res.m_data.iter_mut().for_each(|x| *x = x.mod_exp(b, &q));
+        res
     }
-    
-    fn mult_without_mod(&self, b: &Self) -> Self {
-        let mut result = self.clone();
-        for i in 0..self.m_data.len() {
-            result.m_data[i] *= b.m_data[i];
-        }
-        result
+
+    /// Обратные ко всем элементам; паникует, если какой-то элемент необратим
+    pub fn mod_inverse(&self) -> Self {
+        let mut res = self.clone();
+        let q = self.m_modulus;
+        res.m_data.iter_mut().for_each(|x| *x = x.mod_inverse(&q));
+        res
     }
-    
-    fn mod_exp(&self, b: &T) -> Self {
-        let mut result = self.clone();
-        for i in 0..self.m_data.len() {
-            result.m_data[i] = result.m_data[i].mod_exp(b, &self.m_modulus);
-        }
-        result
+}
+
+/* Поэлементные операции с вектором */
+impl<T: NativeInt> NativeVectorT<T> {
+    pub fn mod_add_vec(&self, b: &Self) -> Self {
+        let mut res = self.clone();
+        res.mod_add_vec_eq(b);
+        res
     }
-    
-    fn mod_exp_eq(&mut self, b: &T) -> &mut Self {
-        for i in 0..self.m_data.len() {
-            self.m_data[i] = self.m_data[i].mod_exp(b, &self.m_modulus);
+
+    pub fn mod_add_vec_eq(&mut self, b: &Self) -> &mut Self {
+        self.check(b, "ModAdd");
+        let q = self.m_modulus;
+        for (x, y) in self.m_data.iter_mut().zip(b.m_data.iter()) {
+            *x = x.mod_add_fast(y, &q);
         }
         self
     }
-    
-    fn mod_inverse(&self) -> Self {
-        let size = self.m_data.len();
-        let mv = self.m_modulus.clone();
-        let mut ans = Self::new(size, mv);
-        for i in 0..size {
-            ans.m_data[i] = self.m_data[i].mod_inverse(&mv);
-        }
-        ans
-    }
-}
-
 
+    pub fn mod_sub_vec(&self, b: &Self) -> Self {
+        let mut res = self.clone();
+        res.mod_sub_vec_eq(b);
+        res
+    }
 
-use std::ops::Div;
-use std::ops::Mul;
-use std::ops::Rem;
-use std::ops::Shr;
-use std::ops::Sub;
-use std::ops::BitAnd;
-use std::ops::BitOr;
-use std::ops::BitXor;
-use std::ops::Not;
-use std::ops::Add;
-use std::ops::BitAndAssign;
-use std::ops::BitOrAssign;
-use std::ops::BitXorAssign;
-use std::ops::ShrAssign;
-use std::ops::SubAssign;
-use std::ops::AddAssign;
-use std::ops::MulAssign;
-use std::ops::RemAssign;
-use std::ops::NotAssign;
-use std::cmp::PartialEq;
-use std::cmp::Eq;
-use std::cmp::PartialOrd;
-use std::cmp::Ord;
-use std::fmt::Debug;
-use std::fmt::Display;
-use std::fmt::Formatter;
-use std::fmt::Result;
-use std::hash::Hash;
-use std::hash::Hasher;
-use std::marker::PhantomData;
-use std::mem::size_of;
-use std::mem::transmute;
-use std::num::Wrapping;
-use std::ops::Deref;
-use std::ops::DerefMut;
-use std::ptr::copy_nonoverlapping;
-use std::ptr::NonNull;
-use std::slice::from_raw_parts;
-use std::slice::from_raw_parts_mut;
-use std::slice::Iter;
-use std::slice::IterMut;
-use std::slice::Split;
-use std::slice::SplitMut;
-use std::slice::SplitN;
-use std::slice::SplitNMut;
-use std::slice::Windows;
-use std::vec::IntoIter;
-use std::vec::Vec;
-
-pub struct NativeVectorT<T> {
-    m_data: Vec<T>,
-    m_modulus: T,
-}
-
-impl<T> NativeVectorT<T> {
-    pub fn new() -> Self {
-        NativeVectorT {
-            m_data: Vec::new(),
-            m_modulus: T::default(),
+    pub fn mod_sub_vec_eq(&mut self, b: &Self) -> &mut Self {
+        self.check(b, "ModSub");
+        let q = self.m_modulus;
+        for (x, y) in self.m_data.iter_mut().zip(b.m_data.iter()) {
+            *x = x.mod_sub_fast(y, &q);
         }
+        self
     }
 
-    pub fn ModInverse(&self, mv: T) -> T {
-        unimplemented!()
+    /// Поэлементное произведение с редукцией Барретта
+    pub fn mod_mul_vec(&self, b: &Self) -> Self {
+        let mut res = self.clone();
+        res.mod_mul_vec_eq(b);
+        res
     }
 
-    pub fn ModInverseEq(&mut self) -> &mut Self {
-        let size = self.m_data.len();
-        let mv = self.m_modulus;
-        for i in 0..size {
-            self.m_data[i] = self.m_data[i].ModInverse(mv);
+    pub fn mod_mul_vec_eq(&mut self, b: &Self) -> &mut Self {
+        self.check(b, "ModMul");
+        let q = self.m_modulus;
+        let mu = q.compute_mu();
+        for (x, y) in self.m_data.iter_mut().zip(b.m_data.iter()) {
+            *x = x.mod_mul_fast(y, &q, &mu);
         }
         self
     }
 
-    pub fn ModByTwo(&self) -> NativeVectorT<T> {
-        unimplemented!()
+    fn check(&self, b: &Self, op: &str) {
+        if self.m_modulus != b.m_modulus {
+            panic!(
+                "{} called on NativeVectorT's with different parameters.",
+                op
+            )
+        }
+        if self.m_data.len() != b.m_data.len() {
+            panic!("{} called on NativeVectorT's of different lengths.", op)
+        }
     }
+}
 
-    pub fn ModByTwoEq(&mut self) -> &mut Self {
-        unimplemented!()
-    }
+impl<T: NativeInt> BigVectorInterface for NativeVectorT<T> {
+    type Integer = NativeIntegerT<T>;
 
-    pub fn MultiplyAndRound(&self, p: IntegerType, q: IntegerType) -> NativeVectorT<T> {
-        unimplemented!()
+    fn new(length: usize, modulus: Self::Integer) -> Self {
+        NativeVectorT::new(length, modulus)
     }
 
-    pub fn MultiplyAndRoundEq(&mut self, p: IntegerType, q: IntegerType) -> &mut Self {
-        unimplemented!()
+    fn from_values(values: &[Self::Integer], modulus: Self::Integer) -> Self {
+        let values: Vec<T> = values.iter().map(|x| x.value()).collect();
+        NativeVectorT::from_values(&values, modulus)
     }
 
-    pub fn DivideAndRound(&self, q: IntegerType) -> NativeVectorT<T> {
-        unimplemented!()
+    fn get_modulus(&self) -> &Self::Integer {
+        NativeVectorT::get_modulus(self)
     }
 
-    pub fn DivideAndRoundEq(&mut self, q: IntegerType) -> &mut Self {
-        unimplemented!()
+    fn set_modulus(&mut self, value: Self::Integer) {
+        NativeVectorT::set_modulus(self, value)
     }
 
-    pub fn GetDigitAtIndexForBase(&self, index: usint, base: usint) -> NativeVectorT<T> {
-        unimplemented!()
+    fn get_length(&self) -> usize {
+        NativeVectorT::get_length(self)
     }
-}
 
-impl<T> Debug for NativeVectorT<T> {
-    fn fmt(&self, f: &mut Formatter) -> Result {
-        let len = self.m_data.len();
-        write!(f, "[")?;
-        for i in 0..len {
-            write!(f, "{}", self.m_data[i])?;
-            if i == len - 1 {
-                write!(f, "]")?;
-            } else {
-                write!(f, " ")?;
-            }
-        }
-        write!(f, " modulus: {}", self.m_modulus)
+    fn switch_modulus(&mut self, new_modulus: Self::Integer) {
+        NativeVectorT::switch_modulus(self, new_modulus)
     }
-}
 
-impl<T> PartialEq for NativeVectorT<T> {
-    fn eq(&self, other: &Self) -> bool {
-        self.m_data == other.m_data && self.m_modulus == other.m_modulus
+    fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> Self {
+        NativeVectorT::get_digit_at_index_for_base(self, index, bits)
     }
-}
 
-impl<T> Eq for NativeVectorT<T> {}
-
-impl<T> PartialOrd for NativeVectorT<T> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    fn mod_add(&self, b: &Self::Integer) -> Self {
+        NativeVectorT::mod_add(self, b)
     }
-}
 
-impl<T> Ord for NativeVectorT<T> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.m_data.cmp(&other.m_data).then(self.m_modulus.cmp(&other.m_modulus))
+    fn mod_sub(&self, b: &Self::Integer) -> Self {
+        NativeVectorT::mod_sub(self, b)
     }
-}
 
-impl<T> Hash for NativeVectorT<T> {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.m_data.hash(state);
-        self.m_modulus.hash(state);
+    fn mod_mul(&self, b: &Self::Integer) -> Self {
+        NativeVectorT::mod_mul(self, b)
     }
-}
 
-impl<T> Display for NativeVectorT<T> {
-    fn fmt(&self, f: &mut Formatter) -> Result {
-        let len = self.m_data.len();
-        write!(f, "[")?;
-        for i in 0..len {
-            write!(f, "{}", self.m_data[i])?;
-            if i == len - 1 {
-                write!(f, "]")?;
-            } else {
-                write!(f, " ")?;
-            }
-        }
-        write!(f, " modulus: {}", self.m_modulus)
+    fn mod_exp(&self, b: &Self::Integer) -> Self {
+        NativeVectorT::mod_exp(self, b)
     }
-}
 
-impl<T> Deref for NativeVectorT<T> {
-    type Target = Vec<T>;
-
-    fn deref(&self) -> &Self::Target {
-        &self.m_data
+    fn mod_inverse(&self) -> Self {
+        NativeVectorT::mod_inverse(self)
     }
-}
 
-impl<T> DerefMut for NativeVectorT<T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.m_data
+    fn mod_add_vec(&self, b: &Self) -> Self {
+        NativeVectorT::mod_add_vec(self, b)
     }
-}
 
-impl<T> NativeVectorT<T> {
-    pub fn SerializedObjectName(&self) -> String {
-        "NativeVectorT".to_string()
+    fn mod_sub_vec(&self, b: &Self) -> Self {
+        NativeVectorT::mod_sub_vec(self, b)
     }
 
-    pub fn SerializedVersion() -> u32 {
-        1
+    fn mod_mul_vec(&self, b: &Self) -> Self {
+        NativeVectorT::mod_mul_vec(self, b)
     }
 }
 
-impl<T> Serialize for NativeVectorT<T> {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let len = self.m_data.len();
-        let mut seq = serializer.serialize_seq(Some(len + 1))?;
-        for i in 0..len {
-            seq.serialize_element(&self.m_data[i])?;
-        }
-        seq.serialize_element(&self.m_modulus)?;
-        seq.end()
+impl<T: NativeInt> Index<usize> for NativeVectorT<T> {
+    type Output = NativeIntegerT<T>;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.m_data[idx]
     }
 }
 
-impl<'de, T> Deserialize<'de> for NativeVectorT<T> {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        struct NativeVectorTVisitor<T> {
-            marker: PhantomData<fn() -> NativeVectorT<T>>,
-        }
-
-        impl<'de, T> Visitor<'de> for NativeVectorTVisitor<T>
-        where
-            T: Deserialize<'de>,
-        {
-            type Value = NativeVectorT<T>;
-
-            fn expecting(&self, formatter: &mut Formatter) -> Result {
-                formatter.write_str("struct NativeVectorT")
-            }
-
-            fn visit_seq<V>(self, mut seq: V) -> Result<NativeVectorT<T>, V::Error>
-            where
-                V: SeqAccess<'de>,
-            {
-                let mut m_data = Vec::new();
-                while let Some(value) = seq.next_element()? {
-                    m_data.push(value);
-                }
-                let m_modulus = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
-                Ok(NativeVectorT { m_data, m_modulus })
-            }
-        }
-
-        deserializer.deserialize_seq(NativeVectorTVisitor {
-            marker: PhantomData,
-        })
+/// Запись через индекс не приводит значение по модулю: записывайте только
+/// значения меньше модуля
+impl<T: NativeInt> IndexMut<usize> for NativeVectorT<T> {
+    fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
+        &mut self.m_data[idx]
     }
 }
 
-impl<T> NativeVectorT<T> {
-    pub fn save<S>(&self, serializer: S, version: u32) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let len = self.m_data.len();
-        let mut seq = serializer.serialize_seq(Some(len + 1))?;
-        for i in 0..len {
-            seq.serialize_element(&self.m_data[i])?;
-        }
-        seq.serialize_element(&self.m_modulus)?;
-        seq.end()
+impl<T: NativeInt> Add for NativeVectorT<T> {
+    type Output = Self;
+    fn add(mut self, rhs: Self) -> Self {
+        self.mod_add_vec_eq(&rhs);
+        self
     }
+}
 
-    pub fn load<'de, D>(&mut self, deserializer: D, version: u32) -> Result<(), D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        struct NativeVectorTVisitor<T> {
-            marker: PhantomData<fn() -> NativeVectorT<T>>,
-        }
-
-        impl<'de, T> Visitor<'de> for NativeVectorTVisitor<T>
-        where
-            T: Deserialize<'de>,
-        {
-            type Value = NativeVectorT<T>;
-
-            fn expecting(&self, formatter: &mut Formatter) -> Result {
-                formatter.write_str("struct NativeVectorT")
-            }
-
-            fn visit_seq<V>(self, mut seq: V) -> Result<NativeVectorT<T>, V::Error>
-            where
-                V: SeqAccess<'de>,
-            {
-                let mut m_data = Vec::new();
-                while let Some(value) = seq.next_element()? {
-                    m_data.push(value);
-                }
-                let m_modulus = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
-                Ok(NativeVectorT { m_data, m_modulus })
-            }
-        }
-
-        let visitor = NativeVectorTVisitor {
-            marker: PhantomData,
-        };
-        deserializer.deserialize_seq(visitor)
+impl<T: NativeInt> Sub for NativeVectorT<T> {
+    type Output = Self;
+    fn sub(mut self, rhs: Self) -> Self {
+        self.mod_sub_vec_eq(&rhs);
+        self
     }
 }
 
-impl<T> NativeVectorT<T> {
-    pub fn save<S>(&self, serializer: S, version: u32) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let len = self.m_data.len();
-        let mut seq = serializer.serialize_seq(Some(len + 1))?;
-        for i in 0..len {
-            seq.serialize_element(&self.m_data[i])?;
-        }
-        seq.serialize_element(&self.m_modulus)?;
-        seq.end()
+impl<T: NativeInt> Mul for NativeVectorT<T> {
+    type Output = Self;
+    fn mul(mut self, rhs: Self) -> Self {
+        self.mod_mul_vec_eq(&rhs);
+        self
     }
+}
 
-    pub fn load<'de, D>(&mut self, deserializer: D, version: u32) -> Result<(), D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        struct NativeVectorTVisitor<T> {
-            marker: PhantomData<fn() -> NativeVectorT<T>>,
-        }
-
-        impl<'de, T> Visitor<'de> for NativeVectorTVisitor<T>
-        where
-            T: Deserialize<'de>,
-        {
-            type Value = NativeVectorT<T>;
-
-            fn expecting(&self, formatter: &mut Formatter) -> Result {
-                formatter.write_str("struct NativeVectorT")
-            }
-
-            fn visit_seq<V>(self, mut seq: V) -> Result<NativeVectorT<T>, V::Error>
-            where
-                V: SeqAccess<'de>,
-            {
-                let mut m_data = Vec::new();
-                while let Some(value) = seq.next_element()? {
-                    m_data.push(value);
-                }
-                let m_modulus = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
-                Ok(NativeVectorT { m_data, m_modulus })
+impl<T: NativeInt> fmt::Display for NativeVectorT<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, x) in self.m_data.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
             }
+            write!(f, "{}", x)?;
         }
-
-        let visitor = NativeVectorTVisitor {
-            marker: PhantomData,
-        };
-        deserializer.deserialize_seq(visitor)
+        write!(f, "] modulus: {}", self.m_modulus)
     }
 }
-
-impl<T> NativeVectorT<T> {
-    pub fn save<S>(&self, serializer: S, version: u32) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let len = self.m_data.len();
-        let mut seq = serializer.serialize_seq(Some(len + 1))?;
-        for i in 0..len {
-            seq.serialize_element(&self.m_data[i])?;
-        }
-        seq.serialize_element(&self.m_modulus)?;
-        seq.end()
-    }
-
-    pub fn load<'de, D>(&mut self, deserializer: D, version: u32) -> Result<(), D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        struct NativeVectorTVisitor<T> {
-            marker: PhantomData<fn() -> NativeVectorT<T>>,
-        }
-
-        impl<'de, T> Visitor<'de> for NativeVectorTVisitor<T>
-        where
-            T: Deserialize<'de>,
-        {
-            type Value = NativeVectorT<T>;
-
-            fn expecting(&self, formatter: &mut
-
@@ -1,215 +1,35 @@
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
-
-struct HashPair;
-
-impl HashPair {
-    fn hash_combine(lhs: usize, rhs: usize) -> usize {
-        lhs ^ (rhs + 0x9e3779b9 + (lhs << 6) + (lhs >> 2))
-    }
-}
-
-impl<T1, T2> Hash for (T1, T2)
-where
-    T1: Hash,
-    T2: Hash,
-{
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        let hash1 = std::hash::Hash::hash(&self.0, state);
-        let hash2 = std::hash::Hash::hash(&self.1, state);
-        let combined_hash = HashPair::hash_combine(hash1, hash2);
-        combined_hash.hash(state);
-    }
-}
-
-struct NumberTheoreticTransformNat<VecType> {
-    element: VecType,
-    root_of_unity_table: VecType,
-    result: VecType,
-}
-
-impl<VecType> NumberTheoreticTransformNat<VecType> {
-    fn forward_transform_iterative(&self) {
-        // implementation
-    }
-
-    fn inverse_transform_iterative(&self) {
-        // implementation
-    }
-
-    fn forward_transform_to_bit_reverse(&self) {
-        // implementation
-    }
-
-    fn forward_transform_to_bit_reverse_in_place(&self) {
-        // implementation
-    }
-
-    fn forward_transform_to_bit_reverse_with_precon(&self) {
-        // implementation
-    }
-
-    fn forward_transform_to_bit_reverse_in_place_with_precon(&self) {
-        // implementation
-    }
-
-    fn inverse_transform_from_bit_reverse(&self) {
-        // implementation
-    }
-
-    fn inverse_transform_from_bit_reverse_in_place(&self) {
-        // implementation
-    }
-
-    fn inverse_transform_from_bit_reverse_with_precon(&self) {
-        // implementation
-    }
-
-    fn inverse_transform_from_bit_reverse_in_place_with_precon(&self) {
-        // implementation
-    }
-}
-
-struct ChineseRemainderTransformFTTNat<VecType> {
-    element: VecType,
-    root_of_unity: IntType,
-    cyclo_order: usint,
-    result: VecType,
-}
-
-impl<VecType> ChineseRemainderTransformFTTNat<VecType> {
-    fn forward_transform_to_bit_reverse(&self) {
-        // implementation
-    }
-
-    fn forward_transform_to_bit_reverse_in_place(&self) {
-        // implementation
-    }
-
-    fn inverse_transform_from_bit_reverse(&self) {
-        // implementation
-    }
-
-    fn inverse_transform_from_bit_reverse_in_place(&self) {
-        // implementation
-    }
-
-    fn pre_compute(&self) {
-        // implementation
-    }
-
-    fn pre_compute_with_moduli_chain(&self) {
-        // implementation
-    }
-
-    fn reset(&self) {
-        // implementation
-    }
-}
-
-struct BluesteinFFTNat<VecType> {
-    element: VecType,
-    root: IntType,
-    cyclo_order: usint,
-}
-
-impl<VecType> BluesteinFFTNat<VecType> {
-    fn forward_transform(&self) -> VecType {
-        // implementation
-    }
-
-    fn forward_transform_with_ntt_modulus_root(&self) -> VecType {
-        // implementation
-    }
-
-    fn pad_zeros(&self, a: VecType, final_size: usint) -> VecType {
-        // implementation
-    }
-
-    fn resize(&self, a: VecType, lo: usint, hi: usint) -> VecType {
-        // implementation
-    }
-
-    fn pre_compute_default_ntt_modulus_root(&self) {
-        // implementation
-    }
-
-    fn pre_compute_root_table_for_ntt(&self) {
-        // implementation
-    }
-
-    fn pre_compute_powers(&self) {
-        // implementation
-    }
-
-    fn pre_compute_rb_table(&self) {
-        // implementation
-    }
-
-    fn reset(&self) {
-        // implementation
-    }
-}
-
-
-use std::collections::HashMap;
-
-struct ChineseRemainderTransformArbNat<VecType> {
-    m_cyclotomic_poly_map: HashMap<IntType, VecType>,
-    m_cyclotomic_poly_reverse_ntt_map: HashMap<IntType, VecType>,
-    m_cyclotomic_poly_ntt_map: HashMap<IntType, VecType>,
-    m_root_of_unity_division_table_by_modulus: HashMap<IntType, VecType>,
-    m_root_of_unity_division_inverse_table_by_modulus: HashMap<IntType, VecType>,
-    m_division_ntt_modulus: HashMap<IntType, IntType>,
-    m_division_ntt_root_of_unity: HashMap<IntType, IntType>,
-    m_ntt_division_dim: HashMap<usint, usint>,
-}
-
-impl<VecType> ChineseRemainderTransformArbNat<VecType> {
-    fn set_cylotomic_polynomial(&mut self, poly: &VecType, modulus: &IntType) {
-        // implementation
-    }
-    
-    fn forward_transform(&self, element: &VecType, root: &IntType, big_mod: &IntType, big_root: &IntType,
-                         cyclo_order: usint) -> VecType {
-        // implementation
-    }
-    
-    fn inverse_transform(&self, element: &VecType, root: &IntType, big_mod: &IntType, big_root: &IntType,
-                         cyclo_order: usint) -> VecType {
-        // implementation
-    }
-    
-    fn reset(&mut self) {
-        // implementation
-    }
-    
-    fn pre_compute(&mut self, cycloto_order: usint, modulus: &IntType) {
-        // implementation
-    }
-    
-    fn set_pre_computed_ntt_modulus(&mut self, cycloto_order: usint, modulus: &IntType, ntt_mod: &IntType,
-                                    ntt_root: &IntType) {
-        // implementation
-    }
-    
-    fn set_pre_computed_ntt_division_modulus(&mut self, cycloto_order: usint, modulus: &IntType, ntt_mod: &IntType,
-                                             ntt_root: &IntType) {
-        // implementation
-    }
-    
-    fn inverse_poly_mod(&self, cyclo_poly: &VecType, modulus: &IntType, power: usint) -> VecType {
-        // implementation
-    }
-    
-    fn pad(&self, element: &VecType, cyclo_order: usint, forward: bool) -> VecType {
-        // implementation
-    }
-    
-    fn drop(&self, element: &VecType, cyclo_order: usint, forward: bool, big_mod: &IntType,
-            big_root: &IntType) -> VecType {
-        // implementation
-    }
-}
-
-
+/*
+ This file contains the linear transform interface functionality for the native math backend
+*/
+
+/// Теоретико-числовое преобразование (NTT) машинных векторов.
+///
+/// Таблицы корней передаются явно: root_of_unity_table[i] = w^i для
+/// итеративного варианта и w^(rev(i)) для вариантов с обращением битов.
+/// Варианты с обращением битов умножают по Шоупу с предвычисленными
+/// константами и держат промежуточные значения в [0, 4q) (бабочки Харви),
+/// поэтому модуль не должен превышать BITS - 2 бит.
+pub struct NumberTheoreticTransformNat;
+
+/// Преобразование в CRT-представление по степени двойки порядка круговой
+/// группы: NTT многочлена по модулю x^n + 1, n = m / 2.
+///
+/// Таблицы корней кэшируются глобально по паре (модуль, порядок);
+/// reset очищает кэш.
+pub struct ChineseRemainderTransformFTTNat;
+
+/// Быстрое преобразование Фурье Блюстейна над Z_q для произвольной длины m:
+/// результат - значения многочлена в степенях корня порядка m, свёртка
+/// вычисляется NTT по вспомогательному модулю ntt_modulus > m q^2.
+///
+/// Таблицы степеней корня и преобразованное ядро свёртки кэшируются по
+/// тройке (модуль, корень, порядок); reset очищает кэш.
+pub struct BluesteinFFTNat;
+
+/// Преобразование в CRT-представление для произвольного порядка круговой
+/// группы m: значения многочлена степени меньше φ(m) в первообразных корнях
+/// порядка m и обратно, с приведением по модулю многочлена деления круга Φ_m.
+///
+/// Многочлены деления круга и вспомогательные модули NTT кэшируются по паре
+/// (модуль, порядок); reset очищает оба кэша вместе с таблицами Блюстейна.
+pub struct ChineseRemainderTransformArbNat;
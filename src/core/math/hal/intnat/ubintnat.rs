@@ -1,1318 +1,667 @@
-struct NativeIntegerT<IntType> {}
-
-type NativeInteger = NativeIntegerT<BasicInteger>;
-
-struct NativeVectorT<IntType> {}
-
-struct DataTypes<utype> {}
-
-impl DataTypes<uint32_t> {
-    type SignedType = int32_t;
-    type DoubleType = uint64_t;
-    type SignedDoubleType = int64_t;
-}
-
-impl DataTypes<uint64_t> {
-    type SignedType = int64_t;
-    type DoubleType = uint64_t;
-    type SignedDoubleType = int64_t;
-}
-
-#[cfg(HAVE_INT128)]
-impl DataTypes<uint64_t> {
-    type DoubleType = uint128_t;
-    type SignedDoubleType = int128_t;
-}
-
-#[cfg(HAVE_INT128)]
-struct DataTypes<uint128_t> {
-    type SignedType = int128_t;
-    type DoubleType = uint128_t;
-    type SignedDoubleType = int128_t;
-}
-
-
-use std::ops::{Add, Sub, Mul, Div};
-
-pub struct NativeIntegerT<NativeInt> {
-    m_value: NativeInt,
-}
-
-impl<NativeInt> NativeIntegerT<NativeInt> {
-    const M_UINT_MAX: NativeInt = std::mem::size_of::<NativeInt>() as NativeInt;
-    const M_UINT_BIT_LENGTH: usint = std::mem::size_of::<NativeInt>() * 8;
-
-    pub fn new() -> Self {
-        Self { m_value: 0 }
-    }
-
-    pub fn from_value(val: NativeInt) -> Self {
-        Self { m_value: val }
-    }
+/*
+  This file contains the main class for native integers. It implements the same methods as other
+  mathematical backends, so native integers can be used interchangeably with big integers
+*/
+use crate::core::math::hal::basicint::BasicInteger;
+use crate::core::math::hal::integer::{BigIntegerInterface, IntegerInterface};
+use num::traits::{NumCast, PrimInt, Signed, Unsigned, WrappingAdd, WrappingMul, WrappingSub};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Debug, Display};
+use std::hash::Hash;
+use std::ops::{
+    Add, AddAssign, BitAnd, BitOr, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Shl, ShlAssign,
+    Shr, ShrAssign, Sub, SubAssign,
+};
+use std::str::FromStr;
 
-    pub fn from_string(val: &str) -> Self {
-        let mut acc: NativeInt = 0;
-        let mut tst: NativeInt = 0;
-        for c in val.chars() {
-            if !c.is_digit(10) {
-                panic!("String contains a non-digit");
-            }
-            let digit = c.to_digit(10).unwrap() as NativeInt;
-            if acc.checked_mul(10).is_none() || acc.checked_add(digit).is_none() {
-                panic!("{} is too large to fit in this native integer object", val);
+/// Машинное беззнаковое слово, над которым строится NativeIntegerT
+/// (аналог специализаций DataTypes в OpenFHE).
+///
+/// Произведение двух слов не помещается в слово, поэтому модульное
+/// умножение собирает его из двух половин (hi, lo). Для u32 и u64 для этого
+/// есть тип двойной ширины, для u128 произведение собирается из половин
+/// слова.
+pub trait NativeInt:
+    PrimInt
+    + Unsigned
+    + WrappingAdd
+    + WrappingSub
+    + WrappingMul
+    + Hash
+    + Debug
+    + Display
+    + Default
+    + FromStr
+    + Send
+    + Sync
+    + 'static
+{
+    /// Знаковый тип той же ширины
+    type SignedType: PrimInt + Signed + Debug + Display;
+    /// Тип двойной ширины (для u128 более широкого типа нет)
+    type DoubleType: PrimInt + Unsigned;
+
+    /// Разрядность слова
+    const BITS: u32;
+
+    /// Полное произведение a * b как пара слов (hi, lo)
+    fn mul_wide(a: Self, b: Self) -> (Self, Self) {
+        let h = Self::BITS as usize / 2;
+        let mask = (Self::one() << h) - Self::one();
+        let (a0, a1) = (a & mask, a >> h);
+        let (b0, b1) = (b & mask, b >> h);
+
+        let p00 = a0 * b0;
+        let p01 = a0 * b1;
+        let p10 = a1 * b0;
+        let p11 = a1 * b1;
+
+        let mid = (p00 >> h) + (p01 & mask) + (p10 & mask);
+        let lo = (p00 & mask) | (mid << h);
+        let hi = p11 + (p01 >> h) + (p10 >> h) + (mid >> h);
+        (hi, lo)
+    }
+
+    /// Частное и остаток от деления (hi * 2^BITS + lo) на d при hi < d
+    fn div_rem_wide(hi: Self, lo: Self, d: Self) -> (Self, Self) {
+        debug_assert!(hi < d, "The quotient does not fit into a word");
+        let top = Self::BITS as usize - 1;
+        let mut rem = hi;
+        let mut quot = Self::zero();
+        for i in (0..Self::BITS as usize).rev() {
+            let carry = (rem >> top) == Self::one();
+            rem = (rem << 1) | ((lo >> i) & Self::one());
+            quot = quot << 1;
+            if carry || rem >= d {
+                rem = rem.wrapping_sub(&d);
+                quot = quot | Self::one();
             }
-            acc = acc * 10 + digit;
-            tst = acc;
         }
-        Self { m_value: acc }
-    }
-
-    pub fn set_value(&mut self, val: NativeIntegerT<NativeInt>) {
-        self.m_value = val.m_value;
-    }
-
-    pub fn set_identity(&mut self) {
-        self.m_value = 1;
+        (quot, rem)
     }
 }
 
-impl<NativeInt> From<NativeIntegerT<NativeInt>> for NativeInt {
-    fn from(val: NativeIntegerT<NativeInt>) -> Self {
-        val.m_value
-    }
-}
+impl NativeInt for u32 {
+    type SignedType = i32;
+    type DoubleType = u64;
+    const BITS: u32 = u32::BITS;
 
-impl<NativeInt> From<NativeIntegerT<NativeInt>> for bool {
-    fn from(val: NativeIntegerT<NativeInt>) -> Self {
-        val.m_value != 0
+    fn mul_wide(a: Self, b: Self) -> (Self, Self) {
+        let p = a as u64 * b as u64;
+        ((p >> 32) as u32, p as u32)
     }
-}
-
-impl<NativeInt> Add for NativeIntegerT<NativeInt>
-where
-    NativeInt: Add<NativeInt, Output = NativeInt>,
-{
-    type Output = Self;
 
-    fn add(self, other: Self) -> Self {
-        Self::from_value(self.m_value + other.m_value)
+    fn div_rem_wide(hi: Self, lo: Self, d: Self) -> (Self, Self) {
+        let x = ((hi as u64) << 32) | lo as u64;
+        ((x / d as u64) as u32, (x % d as u64) as u32)
     }
 }
 
-impl<NativeInt> Sub for NativeIntegerT<NativeInt>
-where
-    NativeInt: Sub<NativeInt, Output = NativeInt>,
-{
-    type Output = Self;
+impl NativeInt for u64 {
+    type SignedType = i64;
+    type DoubleType = u128;
+    const BITS: u32 = u64::BITS;
 
-    fn sub(self, other: Self) -> Self {
-        Self::from_value(self.m_value - other.m_value)
+    fn mul_wide(a: Self, b: Self) -> (Self, Self) {
+        let p = a as u128 * b as u128;
+        ((p >> 64) as u64, p as u64)
     }
-}
 
-impl<NativeInt> Mul for NativeIntegerT<NativeInt>
-where
-    NativeInt: Mul<NativeInt, Output = NativeInt>,
-{
-    type Output = Self;
-
-    fn mul(self, other: Self) -> Self {
-        Self::from_value(self.m_value * other.m_value)
+    fn div_rem_wide(hi: Self, lo: Self, d: Self) -> (Self, Self) {
+        let x = ((hi as u128) << 64) | lo as u128;
+        ((x / d as u128) as u64, (x % d as u128) as u64)
     }
 }
 
-impl<NativeInt> Div for NativeIntegerT<NativeInt>
-where
-    NativeInt: Div<NativeInt, Output = NativeInt>,
-{
-    type Output = Self;
-
-    fn div(self, other: Self) -> Self {
-        Self::from_value(self.m_value / other.m_value)
-    }
+impl NativeInt for u128 {
+    type SignedType = i128;
+    type DoubleType = u128;
+    const BITS: u32 = u128::BITS;
 }
 
-
-use std::ops::{Add, AddAssign, Div, DivAssign, Rem, RemAssign, Mul, MulAssign, ShrAssign};
-use std::convert::From;
-use std::cmp::PartialEq;
-use std::fmt::Debug;
-
-#[derive(Debug, Clone, Copy)]
-struct NativeIntegerT<T> {
+/// Целое число в машинном слове NativeInt.
+///
+/// Модульные операции принимают модуль q разрядностью не больше BITS - 2
+/// (для u64 это 62 бита, библиотека использует до MAX_MODULUS_SIZE бит):
+/// этот запас нужен редукции Барретта и умножению Шоупа. Обычное mod_mul
+/// работает с любым модулем.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct NativeIntegerT<T: NativeInt> {
     m_value: T,
 }
 
-impl<T> NativeIntegerT<T> {
-    fn new(value: T) -> Self {
-        NativeIntegerT { m_value: value }
-    }
-}
+/// Машинное целое текущей платформы
+pub type NativeInteger = NativeIntegerT<BasicInteger>;
 
-impl<T> From<T> for NativeIntegerT<T> {
-    fn from(value: T) -> Self {
-        NativeIntegerT { m_value: value }
+impl<T: NativeInt> NativeIntegerT<T> {
+    pub fn new(value: T) -> Self {
+        Self { m_value: value }
     }
-}
-
-impl<T> PartialEq for NativeIntegerT<T>
-where
-    T: PartialEq,
-{
-    fn eq(&self, other: &Self) -> bool {
-        self.m_value == other.m_value
-    }
-}
-
-impl<T> Add for NativeIntegerT<T>
-where
-    T: Add<Output = T>,
-{
-    type Output = Self;
 
-    fn add(self, other: Self) -> Self {
-        NativeIntegerT::new(self.m_value + other.m_value)
+    /// Максимальное значение слова
+    pub fn max() -> Self {
+        Self::new(T::max_value())
     }
-}
 
-impl<T> AddAssign for NativeIntegerT<T>
-where
-    T: AddAssign,
-{
-    fn add_assign(&mut self, other: Self) {
-        self.m_value += other.m_value;
+    /// Разрядность слова
+    pub fn max_bits() -> u32 {
+        T::BITS
     }
-}
 
-impl<T> Mul for NativeIntegerT<T>
-where
-    T: Mul<Output = T>,
-{
-    type Output = Self;
-
-    fn mul(self, other: Self) -> Self {
-        NativeIntegerT::new(self.m_value * other.m_value)
+    pub fn value(&self) -> T {
+        self.m_value
     }
-}
 
-impl<T> MulAssign for NativeIntegerT<T>
-where
-    T: MulAssign,
-{
-    fn mul_assign(&mut self, other: Self) {
-        self.m_value *= other.m_value;
+    pub fn set_value(&mut self, value: T) {
+        self.m_value = value;
     }
-}
 
-impl<T> Div for NativeIntegerT<T>
-where
-    T: Div<Output = T>,
-{
-    type Output = Self;
-
-    fn div(self, other: Self) -> Self {
-        NativeIntegerT::new(self.m_value / other.m_value)
+    pub fn set_identity(&mut self) {
+        self.m_value = T::one();
     }
-}
 
-impl<T> DivAssign for NativeIntegerT<T>
-where
-    T: DivAssign,
-{
-    fn div_assign(&mut self, other: Self) {
-        self.m_value /= other.m_value;
+    /// Значение в другом целочисленном типе; паникует, если не помещается
+    pub fn convert_to_int<I: NumCast>(&self) -> I {
+        I::from(self.m_value).expect("The value does not fit into the requested type")
     }
-}
 
-impl<T> Rem for NativeIntegerT<T>
-where
-    T: Rem<Output = T>,
-{
-    type Output = Self;
-
-    fn rem(self, other: Self) -> Self {
-        NativeIntegerT::new(self.m_value % other.m_value)
+    pub fn convert_to_double(&self) -> f64 {
+        self.m_value.to_f64().unwrap()
     }
-}
 
-impl<T> RemAssign for NativeIntegerT<T>
-where
-    T: RemAssign,
-{
-    fn rem_assign(&mut self, other: Self) {
-        self.m_value %= other.m_value;
+    /// Номер старшего ненулевого бита, считая с 1 (0 для нуля)
+    pub fn get_msb(&self) -> u32 {
+        T::BITS - self.m_value.leading_zeros()
     }
-}
 
-impl<T> ShrAssign<u32> for NativeIntegerT<T>
-where
-    T: ShrAssign<u32>,
-{
-    fn shr_assign(&mut self, other: u32) {
-        self.m_value >>= other;
+    /// Бит с номером index, младший бит имеет номер 0
+    pub fn get_bit_at_index(&self, index: u32) -> bool {
+        index < T::BITS && (self.m_value >> index as usize) & T::one() == T::one()
     }
-}
 
-impl<T> NativeIntegerT<T>
-where
-    T: Copy + Mul<Output = T> + Add<Output = T> + PartialEq + From<u32> + Debug,
-{
-    fn exp(&self, p: u32) -> Self {
-        let mut r: T = T::from(1);
-        let mut x = self.m_value;
-        let mut p = p;
-        while p > 0 {
-            p >>= 1;
-            x *= x;
-            r *= if p & 0x1 != 0 { x } else { T::from(1) };
+    /// Цифра с номером index (с 0) в системе счисления с основанием 2^bits
+    pub fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> T {
+        let shift = (index * bits) as usize;
+        if shift >= T::BITS as usize {
+            return T::zero();
         }
-        NativeIntegerT::new(r)
-    }
-
-    fn exp_eq(&mut self, p: u32) -> &mut Self {
-        let x = self.m_value;
-        self.m_value = T::from(1);
-        let mut p = p;
-        while p > 0 {
-            p >>= 1;
-            x *= x;
-            self.m_value *= if p & 0x1 != 0 { x } else { T::from(1) };
+        let digit = self.m_value >> shift;
+        if bits >= T::BITS {
+            digit
+        } else {
+            digit & ((T::one() << bits as usize) - T::one())
         }
-        self
     }
 
-    fn multiply_and_round(&self, p: &Self, q: &Self) -> Self {
-        if q.m_value == T::from(0) {
+    /// round(self * p / q) без потери точности
+    pub fn multiply_and_round(&self, p: &Self, q: &Self) -> Self {
+        if q.m_value.is_zero() {
             panic!("NativeIntegerT MultiplyAndRound: Divide by zero");
         }
-        NativeIntegerT::new(
-            (p.m_value as f64 * (self.m_value as f64 / q.m_value as f64) + 0.5) as T,
-        )
-    }
-
-    fn multiply_and_round_eq(&mut self, p: &Self, q: &Self) -> &mut Self {
-        if q.m_value == T::from(0) {
-            panic!("NativeIntegerT MultiplyAndRoundEq: Divide by zero");
+        let (hi, lo) = T::mul_wide(self.m_value, p.m_value);
+        if hi >= q.m_value {
+            panic!("NativeIntegerT MultiplyAndRound: the result does not fit into a word");
         }
-        self.m_value = (p.m_value as f64 * (self.m_value as f64 / q.m_value as f64) + 0.5) as T;
-        self
-    }
-
-    fn divide_and_round(&self, q: &Self) -> Self {
-        if q.m_value == T::from(0) {
-            panic!("NativeIntegerT DivideAndRound: zero");
-        }
-        let ans = self.m_value / q.m_value;
-        let rem = self.m_value % q.m_value;
-        let half_q = q.m_value >> 1;
-        if rem > half_q {
-            NativeIntegerT::new(ans + T::from(1))
+        let (quot, rem) = T::div_rem_wide(hi, lo, q.m_value);
+        if rem > q.m_value >> 1 {
+            match quot.checked_add(&T::one()) {
+                Some(quot) => Self::new(quot),
+                None => {
+                    panic!("NativeIntegerT MultiplyAndRound: the result does not fit into a word")
+                }
+            }
         } else {
-            NativeIntegerT::new(ans)
+            Self::new(quot)
         }
     }
 
-    fn divide_and_round_eq(&mut self, q: &Self) -> &mut Self {
-        if q.m_value == T::from(0) {
-            panic!("NativeIntegerT DivideAndRoundEq: zero");
+    /// round(self / q)
+    pub fn divide_and_round(&self, q: &Self) -> Self {
+        if q.m_value.is_zero() {
+            panic!("NativeIntegerT DivideAndRound: zero");
         }
         let ans = self.m_value / q.m_value;
         let rem = self.m_value % q.m_value;
-        let half_q = q.m_value >> 1;
-        if rem > half_q {
-            self.m_value = ans + T::from(1);
+        if rem > q.m_value >> 1 {
+            Self::new(ans + T::one())
         } else {
-            self.m_value = ans;
+            Self::new(ans)
         }
-        self
-    }
-
-    fn modulo(&self, modulus: &Self) -> Self {
-        NativeIntegerT::new(self.m_value % modulus.m_value)
     }
+}
 
-    fn modulo_eq(&mut self, modulus: &Self) -> &mut Self {
-        self.m_value %= modulus.m_value;
-        self
+/* Модульная арифметика */
+impl<T: NativeInt> NativeIntegerT<T> {
+    /// self mod modulus
+    pub fn modulo(&self, modulus: &Self) -> Self {
+        Self::new(self.m_value % modulus.m_value)
     }
 
-    fn compute_mu(&self) -> Self {
-        if self.m_value == T::from(0) {
+    /// Константа Барретта mu = floor(2^(2n) / q), где n - разрядность модуля q = self
+    pub fn compute_mu(&self) -> Self {
+        let q = self.m_value;
+        if q.is_zero() {
             panic!("NativeIntegerT ComputeMu: Divide by zero");
         }
-        let tmp = T::from(1) << (2 * self.m_value.leading_zeros() + 3);
-        NativeIntegerT::new(tmp / self.m_value)
-    }
-
-    fn modulo_with_mu(&self, modulus: &Self, mu: &Self) -> Self {
-        let mut tmp: T;
-        let mut ans = self.clone();
-        let msb = modulus.m_value.leading_zeros() - 2;
-        mod_mu(&mut tmp, &mut ans, modulus.m_value, mu.m_value, msb);
-        ans
-    }
-
-    fn modulo_with_mu_eq(&mut self, modulus: &Self, mu: &Self) -> &mut Self {
-        let mut tmp: T;
-        let msb = modulus.m_value.leading_zeros() - 2;
-        mod_mu(&mut tmp, self, modulus.m_value, mu.m_value, msb);
-        self
+        let n = self.checked_modulus_bits();
+        let (hi, lo) = if 2 * n >= T::BITS {
+            (T::one() << (2 * n - T::BITS) as usize, T::zero())
+        } else {
+            (T::zero(), T::one() << (2 * n) as usize)
+        };
+        Self::new(T::div_rem_wide(hi, lo, q).0)
     }
 
-    fn modulo_add(&self, b: &Self, modulus: &Self) -> Self {
-        let av = self.m_value;
-        let bv = b.m_value;
-        let mv = modulus.m_value;
-        let av = if av >= mv { av % mv } else { av };
-        let bv = if bv >= mv { bv % mv } else { bv };
-        let mut r = av + bv;
-        if r >= mv {
-            r -= mv;
+    /// self mod modulus редукцией Барретта, mu = modulus.compute_mu()
+    pub fn modulo_with_mu(&self, modulus: &Self, mu: &Self) -> Self {
+        let n = modulus.get_msb();
+        if 2 * n < T::BITS && (self.m_value >> (2 * n) as usize) != T::zero() {
+            return self.modulo(modulus);
         }
-        NativeIntegerT::new(r)
+        Self::new(Self::barrett(
+            T::zero(),
+            self.m_value,
+            modulus.m_value,
+            mu.m_value,
+        ))
     }
 
-    fn modulo_add_eq(&mut self, b: &Self, modulus: &Self) -> &mut Self {
-        let bv = b.m_value;
-        let mv = modulus.m_value;
-        if self.m_value >= mv {
-            self.m_value %= mv;
-        }
-        let bv = if bv >= mv { bv % mv } else { bv };
-        self.m_value += bv;
-        if self.m_value >= mv {
-            self.m_value -= mv;
-        }
-        self
+    /// (self + b) mod modulus
+    pub fn mod_add(&self, b: &Self, modulus: &Self) -> Self {
+        self.modulo(modulus)
+            .mod_add_fast(&b.modulo(modulus), modulus)
     }
 
-    fn modulo_add_fast(&self, b: &Self, modulus: &Self) -> Self {
-        let r = self.m_value + b.m_value;
-        let mv = modulus.m_value;
-        if r >= mv {
-            NativeIntegerT::new(r - mv)
+    /// (self + b) mod modulus для self, b < modulus
+    pub fn mod_add_fast(&self, b: &Self, modulus: &Self) -> Self {
+        // Сравнение с дополнением вместо суммы: сумма может не поместиться в слово
+        let complement = modulus.m_value - b.m_value;
+        if self.m_value >= complement {
+            Self::new(self.m_value - complement)
         } else {
-            NativeIntegerT::new(r)
+            Self::new(self.m_value + b.m_value)
         }
     }
 
-    fn modulo_add_fast_eq(&mut self, b: &Self, modulus: &Self) -> &mut Self {
-        let mv = modulus.m_value;
-        self.m_value += b.m_value;
-        if self.m_value >= mv {
-            self.m_value -= mv;
-        }
+    pub fn mod_add_eq(&mut self, b: &Self, modulus: &Self) -> &mut Self {
+        *self = self.mod_add(b, modulus);
         self
     }
 
-    fn modulo_add_with_mu(&self, b: &Self, modulus: &Self, mu: &Self) -> Self {
-        let mv = modulus.m_value;
-        let av = self.clone();
-        let bv = b.clone();
-        let av = if av.m_value >= mv {
-            av.modulo_with_mu(modulus, mu)
-        } else {
-            av
-        };
-        let bv = if bv.m_value >= mv {
-            bv.modulo_with_mu(modulus, mu)
-        } else {
-            bv
-        };
-        let mut r = av.m_value + bv.m_value;
-        if r >= mv {
-            r -= mv;
-        }
-        NativeIntegerT::new(r)
+    /// (self - b) mod modulus
+    pub fn mod_sub(&self, b: &Self, modulus: &Self) -> Self {
+        self.modulo(modulus)
+            .mod_sub_fast(&b.modulo(modulus), modulus)
     }
 
-    fn modulo_add_with_mu_eq(&mut self, b: &Self, modulus: &Self, mu: &Self) -> &mut Self {
-        let mv = modulus.m_value;
-        let bv = b.clone();
-        if self.m_value >= mv {
-            self.modulo_with_mu_eq(modulus, mu);
-        }
-        if bv.m_value >= mv {
-            bv.modulo_with_mu_eq(modulus, mu);
-        }
-        self.m_value += bv.m_value;
-        if self.m_value >= mv {
-            self.m_value -= mv;
+    /// (self - b) mod modulus для self, b < modulus
+    pub fn mod_sub_fast(&self, b: &Self, modulus: &Self) -> Self {
+        if self.m_value >= b.m_value {
+            Self::new(self.m_value - b.m_value)
+        } else {
+            Self::new(self.m_value + (modulus.m_value - b.m_value))
         }
-        self
     }
-}
-
-fn mod_mu<T>(tmp: &mut T, ans: &mut NativeIntegerT<T>, modulus: T, mu: T, msb: u32)
-where
-    T: Copy + Mul<Output = T> + Add<Output = T> + PartialEq + From<u32>,
-{
-    *tmp = T::from(1) << msb;
-    *ans = NativeIntegerT::new((*ans * mu) % modulus);
-}
-
-fn main() {
-    let a: NativeIntegerT<u32> = NativeIntegerT::new(10);
-    let b: NativeIntegerT<u32> = NativeIntegerT::new(5);
-    let modulus: NativeIntegerT<u32> = NativeIntegerT::new(7);
-    let mu: NativeIntegerT<u32> = NativeIntegerT::new(3);
-
-    let exp_result = a.exp(3);
-    println!("exp_result: {:?}", exp_result);
-
-    let mut exp_eq_result = a.clone();
-    exp_eq_result.exp_eq(3);
-    println!("exp_eq_result: {:?}", exp_eq_result);
-
-    let multiply_and_round_result = a.multiply_and_round(&b, &modulus);
-    println!("multiply_and_round_result: {:?}", multiply_and_round_result);
-
-    let mut multiply_and_round_eq_result = a.clone();
-    multiply_and_round_eq_result.multiply_and_round_eq(&b, &modulus);
-    println!("multiply_and_round_eq_result: {:?}", multiply_and_round_eq_result);
-
-    let divide_and_round_result = a.divide_and_round(&b);
-    println!("divide_and_round_result: {:?}", divide_and_round_result);
-
-    let mut divide_and_round_eq_result = a.clone();
-    divide_and_round_eq_result.divide_and_round_eq(&b);
-    println!("divide_and_round_eq_result: {:?}", divide_and_round_eq_result);
-
-    let modulo_result = a.modulo(&modulus);
-    println!("modulo_result: {:?}", modulo_result);
-
-    let mut modulo_eq_result = a.clone();
-    modulo_eq_result.modulo_eq(&modulus);
-    println!("modulo_eq_result: {:?}", modulo_eq_result);
-
-    let compute_mu_result = a.compute_mu();
-    println!("compute_mu_result: {:?}", compute_mu_result);
-
-    let modulo_with_mu_result = a.modulo_with_mu(&modulus, &mu);
-    println!("modulo_with_mu_result: {:?}", modulo_with_mu_result);
-
-    let mut modulo_with_mu_eq_result = a.clone();
-    modulo_with_mu_eq_result.modulo_with_mu_eq(&modulus, &mu);
-    println!("modulo_with_mu_eq_result: {:?}", modulo_with_mu_eq_result);
-
-    let modulo_add_result = a.modulo_add(&b, &modulus);
-    println!("modulo_add_result: {:?}", modulo_add_result);
-
-    let mut modulo_add_eq_result = a.clone();
-    modulo_add_eq_result.modulo_add_eq(&b, &modulus);
-    println!("modulo_add_eq_result: {:?}", modulo_add_eq_result);
-
-    let modulo_add_fast_result = a.modulo_add_fast(&b, &modulus);
-    println!("modulo_add_fast_result: {:?}", modulo_add_fast_result);
-
-    let mut modulo_add_fast_eq_result = a.clone();
-    modulo_add_fast_eq_result.modulo_add_fast_eq(&b, &modulus);
-    println!("modulo_add_fast_eq_result: {:?}", modulo_add_fast_eq_result);
-
-    let modulo_add_with_mu_result = a.modulo_add_with_mu(&b, &modulus, &mu);
-    println!("modulo_add_with_mu_result: {:?}", modulo_add_with_mu_result);
-
-    let mut modulo_add_with_mu_eq_result = a.clone();
-    modulo_add_with_mu_eq_result.modulo_add_with_mu_eq(&b, &modulus, &mu);
-    println!("modulo_add_with_mu_eq_result: {:?}", modulo_add_with_mu_eq_result);
-}
-
 
-fn mod_add_eq<T: NativeInt>(&mut self, b: &NativeIntegerT, modulus: &NativeIntegerT, mu: &NativeIntegerT) -> &mut NativeIntegerT
-where
-    T: DNativeInt,
-{
-    let mut av = self.clone();
-    let mut bv = b.clone();
-    let mv = modulus.m_value;
-    if av.m_value >= mv {
-        av.mod_eq(modulus, mu);
-    }
-    if bv.m_value >= mv {
-        bv.mod_eq(modulus, mu);
-    }
-    self.m_value = av.m_value + bv.m_value;
-    if self.m_value >= mv {
-        self.m_value -= mv;
+    pub fn mod_sub_eq(&mut self, b: &Self, modulus: &Self) -> &mut Self {
+        *self = self.mod_sub(b, modulus);
+        self
     }
-    self
-}
 
-fn mod_sub(&self, b: &NativeIntegerT, modulus: &NativeIntegerT) -> NativeIntegerT {
-    let av = self.m_value;
-    let bv = b.m_value;
-    let mv = modulus.m_value;
-    if av >= mv {
-        av %= mv;
-    }
-    if bv >= mv {
-        bv %= mv;
+    /// (self * b) mod modulus делением полного произведения, для любого модуля
+    pub fn mod_mul(&self, b: &Self, modulus: &Self) -> Self {
+        let q = modulus.m_value;
+        let (hi, lo) = T::mul_wide(self.m_value % q, b.m_value % q);
+        Self::new(T::div_rem_wide(hi, lo, q).1)
     }
-    if av < bv {
-        return NativeIntegerT::new(av + mv - bv);
-    }
-    NativeIntegerT::new(av - bv)
-}
 
-fn mod_sub_eq(&mut self, b: &NativeIntegerT, modulus: &NativeIntegerT) -> &mut NativeIntegerT {
-    let av = self.m_value;
-    let bv = b.m_value;
-    let mv = modulus.m_value;
-    if av >= mv {
-        av %= mv;
-    }
-    if bv >= mv {
-        bv %= mv;
-    }
-    if av < bv {
-        *self = NativeIntegerT::new(av + mv - bv);
-    } else {
-        *self = NativeIntegerT::new(av - bv);
+    pub fn mod_mul_eq(&mut self, b: &Self, modulus: &Self) -> &mut Self {
+        *self = self.mod_mul(b, modulus);
+        self
     }
-    self
-}
 
-fn mod_sub_fast(&self, b: &NativeIntegerT, modulus: &NativeIntegerT) -> NativeIntegerT {
-    if self.m_value < b.m_value {
-        return NativeIntegerT::new(self.m_value + modulus.m_value - b.m_value);
+    /// (self * b) mod modulus редукцией Барретта, mu = modulus.compute_mu()
+    pub fn mod_mul_with_mu(&self, b: &Self, modulus: &Self, mu: &Self) -> Self {
+        let a = self.modulo_with_mu(modulus, mu);
+        let b = b.modulo_with_mu(modulus, mu);
+        a.mod_mul_fast(&b, modulus, mu)
     }
-    NativeIntegerT::new(self.m_value - b.m_value)
-}
 
-fn mod_sub_fast_eq(&mut self, b: &NativeIntegerT, modulus: &NativeIntegerT) -> &mut NativeIntegerT {
-    if self.m_value < b.m_value {
-        *self = NativeIntegerT::new(self.m_value + modulus.m_value - b.m_value);
-    } else {
-        *self = NativeIntegerT::new(self.m_value - b.m_value);
+    /// (self * b) mod modulus редукцией Барретта для self, b < modulus
+    pub fn mod_mul_fast(&self, b: &Self, modulus: &Self, mu: &Self) -> Self {
+        let (hi, lo) = T::mul_wide(self.m_value, b.m_value);
+        Self::new(Self::barrett(hi, lo, modulus.m_value, mu.m_value))
     }
-    self
-}
 
-fn mod_sub<T: NativeInt>(&self, b: &NativeIntegerT, modulus: &NativeIntegerT, mu: &NativeIntegerT) -> NativeIntegerT
-where
-    T: DNativeInt,
-{
-    let mv = modulus.m_value;
-    #[cfg(NATIVEINT_BARRET_MOD)]
-    {
-        let mut av = self.clone();
-        let mut bv = b.clone();
-        if av.m_value >= mv {
-            av.mod_eq(modulus, mu);
-        }
-        if bv.m_value >= mv {
-            bv.mod_eq(modulus, mu);
-        }
-        if av.m_value < bv.m_value {
-            return NativeIntegerT::new(av.m_value + mv - bv.m_value);
-        }
-        NativeIntegerT::new(av.m_value - bv.m_value)
-    }
-    #[cfg(not(NATIVEINT_BARRET_MOD))]
-    {
-        let av = self.m_value;
-        let bv = b.m_value;
-        if av >= mv {
-            av %= mv;
-        }
-        if bv >= mv {
-            bv %= mv;
-        }
-        if av < bv {
-            return NativeIntegerT::new(av + mv - bv);
-        }
-        NativeIntegerT::new(av - bv)
-    }
-}
-
-fn mod_sub<T: NativeInt>(&self, b: &NativeIntegerT, modulus: &NativeIntegerT, mu: &NativeIntegerT) -> NativeIntegerT
-where
-    T: DNativeInt,
-{
-    let mut av = self.clone();
-    let mut bv = b.clone();
-    let mv = modulus.m_value;
-    if av.m_value >= mv {
-        av.mod_eq(modulus, mu);
-    }
-    if bv.m_value >= mv {
-        bv.mod_eq(modulus, mu);
-    }
-    if av.m_value < bv.m_value {
-        return NativeIntegerT::new(av.m_value + mv - bv.m_value);
+    pub fn mod_mul_fast_eq(&mut self, b: &Self, modulus: &Self, mu: &Self) -> &mut Self {
+        *self = self.mod_mul_fast(b, modulus, mu);
+        self
     }
-    NativeIntegerT::new(av.m_value - bv.m_value)
-}
 
-fn mod_sub_eq<T: NativeInt>(&mut self, b: &NativeIntegerT, modulus: &NativeIntegerT, mu: &NativeIntegerT) -> &mut NativeIntegerT
-where
-    T: DNativeInt,
-{
-    let mv = modulus.m_value;
-    #[cfg(NATIVEINT_BARRET_MOD)]
-    {
-        let mut av = self.clone();
-        let mut bv = b.clone();
-        if av.m_value >= mv {
-            av.mod_eq(modulus, mu);
-        }
-        if bv.m_value >= mv {
-            bv.mod_eq(modulus, mu);
-        }
-        if av.m_value < bv.m_value {
-            *self = NativeIntegerT::new(av.m_value + mv - bv.m_value);
+    /// Константа Шоупа для умножения на self < modulus:
+    /// floor(self * 2^BITS / modulus)
+    pub fn prep_mod_mul_const(&self, modulus: &Self) -> Self {
+        modulus.checked_modulus_bits();
+        if self.m_value >= modulus.m_value {
+            panic!("NativeIntegerT PrepModMulConst: the value must be less than the modulus");
+        }
+        Self::new(T::div_rem_wide(self.m_value, T::zero(), modulus.m_value).0)
+    }
+
+    /// (self * b) mod modulus по Шоупу: b < modulus - константа, для
+    /// которой заранее вычислено b_inv = b.prep_mod_mul_const(modulus).
+    /// Вместо деления нужны два умножения и одно условное вычитание.
+    pub fn mod_mul_fast_const(&self, b: &Self, modulus: &Self, b_inv: &Self) -> Self {
+        let q = modulus.m_value;
+        let (q_hat, _) = T::mul_wide(self.m_value, b_inv.m_value);
+        let r = self
+            .m_value
+            .wrapping_mul(&b.m_value)
+            .wrapping_sub(&q_hat.wrapping_mul(&q));
+        if r >= q {
+            Self::new(r - q)
         } else {
-            *self = NativeIntegerT::new(av.m_value - bv.m_value);
+            Self::new(r)
         }
     }
-    #[cfg(not(NATIVEINT_BARRET_MOD))]
-    {
-        let bv = b.m_value;
-        let av = self.m_value;
-        if bv >= mv {
-            bv %= mv;
-        }
-        if av >= mv {
-            av %= mv;
-        }
-        if av < bv {
-            *self = NativeIntegerT::new(av + mv - bv);
-        } else {
-            *self = NativeIntegerT::new(av - bv);
-        }
-    }
-    self
-}
 
-fn mod_sub_eq<T: NativeInt>(&mut self, b: &NativeIntegerT, modulus: &NativeIntegerT, mu: &NativeIntegerT) -> &mut NativeIntegerT
-where
-    T: DNativeInt,
-{
-    let mut av = self.clone();
-    let mut bv = b.clone();
-    let mv = modulus.m_value;
-    if av.m_value >= mv {
-        av.mod_eq(modulus, mu);
-    }
-    if bv.m_value >= mv {
-        bv.mod_eq(modulus, mu);
-    }
-    if av.m_value < bv.m_value {
-        *self = NativeIntegerT::new(av.m_value + mv - bv.m_value);
-    } else {
-        *self = NativeIntegerT::new(av.m_value - bv.m_value);
-    }
-    self
-}
-
-fn mod_mul<T: NativeInt>(&self, b: &NativeIntegerT, modulus: &NativeIntegerT) -> NativeIntegerT
-where
-    T: DNativeInt,
-{
-    let av = self.m_value;
-    let bv = b.m_value;
-    let mv = modulus.m_value;
-    if av >= mv {
-        av %= mv;
-    }
-    if bv >= mv {
-        bv %= mv;
-    }
-    let rv = av as DNativeInt * bv as DNativeInt;
-    let dmv = mv as DNativeInt;
-    if rv >= dmv {
-        return NativeIntegerT::new(rv % dmv);
-    }
-    NativeIntegerT::new(rv)
-}
-
-fn mod_mul<T: NativeInt>(&self, b: &NativeIntegerT, modulus: &NativeIntegerT) -> NativeIntegerT
-where
-    T: DNativeInt,
-{
-    let mut tmp = typeD::default();
-    let av = self.clone();
-    let mv = modulus.m_value;
-    let mu = modulus.compute_mu().m_value;
-    let n = modulus.get_msb() - 2;
-    if av.m_value >= mv {
-        mod_mu(&mut tmp, &av, mv, mu, n);
-    }
-    let bv = b.clone();
-    if bv.m_value >= mv {
-        mod_mu(&mut tmp, &bv, mv, mu, n);
-    }
-    mult_d(av.m_value, bv.m_value, &mut tmp);
-    let r = tmp;
-    mult_d(r_shift_d(&mut tmp, n), mu, &mut tmp);
-    mult_d(r_shift_d(&mut tmp, n + 7), mv, &mut tmp);
-    subtract_d(&mut r, &tmp);
-    if r.lo >= mv {
-        r.lo -= mv;
-    }
-    NativeIntegerT::new(r.lo)
-}
-
-fn mod_mul_eq<T: NativeInt>(&mut self, b: &NativeIntegerT, modulus: &NativeIntegerT) -> &mut NativeIntegerT
-where
-    T: DNativeInt,
-{
-    let av = self.m_value;
-    let bv = b.m_value;
-    let mv = modulus.m_value;
-    if av >= mv {
-        av %= mv;
-    }
-    if bv >= mv {
-        bv %= mv;
-    }
-    let rv = av as DNativeInt * bv as DNativeInt;
-    let dmv = mv as DNativeInt;
-    if rv >= dmv {
-        *self = NativeIntegerT::new(rv % dmv);
-    } else {
-        *self = NativeIntegerT::new(rv);
-    }
-    self
-}
-
-fn mod_mul_eq<T: NativeInt>(&mut self, b: &NativeIntegerT, modulus: &NativeIntegerT) -> &mut NativeIntegerT
-where
-    T: DNativeInt,
-{
-    let av = self.clone();
-    let mv = modulus.m_value;
-    let mut tmp = typeD::default();
-    let mu = modulus.compute_mu().m_value;
-    let n = modulus.get_msb() - 2;
-    if av.m_value >= mv {
-        mod_mu(&mut tmp, &av, mv, mu, n);
-    }
-    let bv = b.clone();
-    if bv.m_value >= mv {
-        mod_mu(&mut tmp, &bv, mv, mu, n);
-    }
-    mult_d(av.m_value, bv.m_value, &mut tmp);
-    let r = tmp;
-    mult_d(r_shift_d(&mut tmp, n), mu, &mut tmp);
-    mult_d(r_shift_d(&mut tmp, n + 7), mv, &mut tmp);
-    subtract_d(&mut r, &tmp);
-    self.m_value = r.lo;
-    if self.m_value >= mv {
-        self.m_value -= mv;
-    }
-    self
-}
-
-fn mod_mul<T: NativeInt>(&self, b: &NativeIntegerT, modulus: &NativeIntegerT, mu: &NativeIntegerT) -> NativeIntegerT
-where
-    T: DNativeInt,
-{
-    #[cfg(NATIVEINT_BARRET_MOD)]
-    {
-        let av = self.clone();
-        let mv = modulus.m_value;
-        let mut tmp = typeD::default();
-        let n = modulus.get_msb() - 2;
-        if av.m_value >= mv {
-            mod_mu(&mut tmp, &av, mv, mu.m_value, n);
-        }
-        let bv = b.clone();
-        if bv.m_value >= mv {
-            mod_mu(&mut tmp, &bv, mv, mu.m_value, n);
-        }
-        mult_d(av.m_value, bv.m_value, &mut tmp);
-        let rv = get_d(&tmp);
-        mult_d(r_shift_d(&mut tmp, n), mu.m_value, &mut tmp);
-        rv -= DNativeInt(mv) * (get_d(&tmp) >> n + 7);
-        let mut r = typeD::default();
-        r.lo = rv;
-        if r.lo >= mv {
-            r.lo -= mv;
-        }
-        NativeIntegerT::new(r.lo)
-    }
-    #[cfg(not(NATIVEINT_BARRET_MOD))]
-    {
-        let mv = modulus.m_value;
-        let bv = b.m_value;
-        let av = self.m_value;
-        if bv >= mv {
-            bv %= mv;
-        }
-        if av >= mv {
-            av %= mv;
-        }
-        let rv = av as DNativeInt * bv as DNativeInt;
-        let dmv = mv as DNativeInt;
-        if rv >= dmv {
-            return NativeIntegerT::new(rv % dmv);
-        }
-        NativeIntegerT::new(rv)
+    pub fn mod_mul_fast_const_eq(&mut self, b: &Self, modulus: &Self, b_inv: &Self) -> &mut Self {
+        *self = self.mod_mul_fast_const(b, modulus, b_inv);
+        self
     }
-}
 
-fn mod_mul<T: NativeInt>(&self, b: &NativeIntegerT, modulus: &NativeIntegerT, mu: &NativeIntegerT) -> NativeIntegerT
-where
-    T: DNativeInt,
-{
-    let av = self.clone();
-    let mv = modulus.m_value;
-    let mut tmp = typeD::default();
-    let n = modulus.get_msb() - 2;
-    if av.m_value >= mv {
-        mod_mu(&mut tmp, &av, mv, mu.m_value, n);
-    }
-    let bv = b.clone();
-    if bv.m_value >= mv {
-        mod_mu(&mut tmp, &bv, mv, mu.m_value, n);
-    }
-    mult_d(av.m_value, bv.m_value, &mut tmp);
-    let r = tmp;
-    mult_d(r_shift_d(&mut tmp, n), mu.m_value, &mut tmp);
-    mult_d(r_shift_d(&mut tmp, n + 7), mv, &mut tmp);
-    subtract_d(&mut r, &tmp);
-    if r.lo >= mv {
-        r.lo -= mv;
-    }
-    NativeIntegerT::new(r.lo)
-}
-
-fn mod_mul_eq<T: NativeInt>(&mut self, b: &NativeIntegerT, modulus: &NativeIntegerT, mu: &NativeIntegerT) -> &mut NativeIntegerT
-where
-    T: DNativeInt,
-{
-    #[cfg(NATIVEINT_BARRET_MOD)]
-    {
-        let av = self.clone();
-        let bv = b.clone();
-        let mv = modulus.m_value;
-        let mut tmp = typeD::default();
-        let muv = mu.m_value;
-        let n = modulus.get_msb() - 2;
-        if av.m_value >= mv {
-            mod_mu(&mut tmp, &av, mv, muv, n);
-        }
-        if bv.m_value >= mv {
-            mod_mu(&mut tmp, &bv, mv, muv, n);
-        }
-        mult_d(av.m_value, bv.m_value, &mut tmp);
-        let rv = get_d(&tmp);
-        mult_d(r_shift_d(&mut tmp, n), muv, &mut tmp);
-        rv -= DNativeInt(mv) * (get_d(&tmp) >> n + 7);
-        self.m_value = rv as NativeInt;
-        if self.m_value >= mv {
-            self.m_value -= mv;
-        }
-    }
-    #[cfg(not(NATIVEINT_BARRET_MOD))]
-    {
-        let bv = b.m_value;
-        let av = self.m_value;
-        let mv = modulus.m_value;
-        if bv >= mv {
-            bv %= mv;
-        }
-        if av >= mv {
-            av %= mv;
-        }
-        let rv = av as DNativeInt * bv as DNativeInt;
-        let dmv = mv as DNativeInt;
-        if rv >= dmv {
-            self.m_value = (rv % dmv) as NativeInt;
+    /// self^b mod modulus
+    pub fn mod_exp(&self, b: &Self, modulus: &Self) -> Self {
+        let fast = modulus.get_msb() <= T::BITS - 2;
+        let mu = if fast {
+            modulus.compute_mu()
         } else {
-            self.m_value = rv as NativeInt;
-        }
-        if self.m_value >= mv {
-            self.m_value -= mv;
-        }
-    }
-    self
-}
+            Self::default()
+        };
+        let mul = |x: &Self, y: &Self| {
+            if fast {
+                x.mod_mul_fast(y, modulus, &mu)
+            } else {
+                x.mod_mul(y, modulus)
+            }
+        };
 
-use std::ops::{Mul, Rem, Shr};
-
-impl<T: Into<i64> + From<i64> + Copy> NativeIntegerT<T> {
-    fn mod_mul_eq(&mut self, b: &NativeIntegerT<T>, modulus: &NativeIntegerT<T>, mu: &NativeIntegerT<T>) -> &mut Self {
-        let n = modulus.get_msb() - 2;
-        let av = *self;
-        let bv = *b;
-        let mv = modulus.m_value;
-        let mut tmp: i64;
-        if av.m_value >= mv {
-            mod_mu(&mut tmp, av, mv, mu.m_value, n);
-        }
-        if bv.m_value >= mv {
-            mod_mu(&mut tmp, bv, mv, mu.m_value, n);
-        }
-        mult_d(av.m_value, bv.m_value, tmp);
-        let r = tmp;
-        mult_d(r_shift_d(tmp, n), mu.m_value, tmp);
-        mult_d(r_shift_d(tmp, n + 7), mv, tmp);
-        subtract_d(r, tmp);
-        self.m_value = r.lo;
-        if r.lo >= mv {
-            self.m_value -= mv;
+        let mut res = Self::new(T::one()).modulo(modulus);
+        let base = self.modulo(modulus);
+        for i in (0..b.get_msb()).rev() {
+            res = mul(&res, &res);
+            if b.get_bit_at_index(i) {
+                res = mul(&res, &base);
+            }
         }
-        self
-    }
-
-    fn mod_mul_fast(&self, b: &NativeIntegerT<T>, modulus: &NativeIntegerT<T>) -> NativeIntegerT<T> {
-        let rv = DNativeInt::from(self.m_value) * DNativeInt::from(b.m_value);
-        let dmv = DNativeInt::from(modulus.m_value);
-        if rv >= dmv {
-            rv % dmv
+        res
+    }
+
+    /// self^-1 mod modulus; паникует, если self и modulus не взаимно просты
+    pub fn mod_inverse(&self, modulus: &Self) -> Self {
+        let q = modulus.m_value;
+        let (mut r0, mut r1) = (q, self.m_value % q);
+        // Коэффициенты Безу хранятся по модулю q, чтобы обойтись без знака
+        let (mut t0, mut t1) = (Self::new(T::zero()), Self::new(T::one()));
+        while !r1.is_zero() {
+            let quot = Self::new(r0 / r1);
+            let r = r0 % r1;
+            let t = t0.mod_sub_fast(&quot.mod_mul(&t1, modulus), modulus);
+            r0 = r1;
+            r1 = r;
+            t0 = t1;
+            t1 = t;
+        }
+        if r0 != T::one() {
+            panic!(
+                "NativeIntegerT ModInverse: {} is not invertible modulo {}",
+                self, modulus
+            )
+        }
+        t0.modulo(modulus)
+    }
+
+    /// Центрированный представитель из (-q/2, q/2] для self < modulus
+    pub fn convert_to_signed_centered(&self, modulus: &Self) -> T::SignedType {
+        let v = self.m_value % modulus.m_value;
+        if v > modulus.m_value >> 1 {
+            -<T::SignedType as NumCast>::from(modulus.m_value - v).unwrap()
         } else {
-            rv.into()
+            <T::SignedType as NumCast>::from(v).unwrap()
         }
     }
 
-    fn mod_mul_fast_eq(&mut self, b: &NativeIntegerT<T>, modulus: &NativeIntegerT<T>) -> &mut Self {
-        let rv = DNativeInt::from(self.m_value) * DNativeInt::from(b.m_value);
-        let dmv = DNativeInt::from(modulus.m_value);
-        if rv >= dmv {
-            rv % dmv
+    /// Вычет x mod modulus по знаковому представителю
+    pub fn from_signed_centered(x: T::SignedType, modulus: &Self) -> Self {
+        let q = modulus.m_value;
+        // -(x + 1) не переполняется и при x = MIN
+        let abs = if x.is_negative() {
+            <T as NumCast>::from(-(x + <T::SignedType as num::One>::one())).unwrap() + T::one()
+        } else {
+            <T as NumCast>::from(x).unwrap()
+        } % q;
+        if x.is_negative() && !abs.is_zero() {
+            Self::new(q - abs)
         } else {
-            rv.into()
+            Self::new(abs)
         }
-        self
     }
 
-    fn mod_mul_fast_const(&self, b: &NativeIntegerT<T>, modulus: &NativeIntegerT<T>, b_inv: &NativeIntegerT<T>) -> NativeIntegerT<T> {
-        let q = mult_d_hi(self.m_value, b_inv.m_value) + 1;
-        let yprime = (self.m_value * b.m_value - q * modulus.m_value) as SignedNativeInt;
-        if yprime >= 0 {
-            yprime.into()
-        } else {
-            (yprime + modulus.m_value).into()
+    /// Разрядность модуля с проверкой запаса для Барретта и Шоупа
+    fn checked_modulus_bits(&self) -> u32 {
+        let n = self.get_msb();
+        if n > T::BITS - 2 {
+            panic!(
+                "NativeIntegerT: the modulus {} has {} bits, at most {} are supported",
+                self,
+                n,
+                T::BITS - 2
+            )
         }
+        n
     }
 
-    fn mod_mul_fast_const_eq(&mut self, b: &NativeIntegerT<T>, modulus: &NativeIntegerT<T>, b_inv: &NativeIntegerT<T>) -> &mut Self {
-        let q = mult_d_hi(self.m_value, b_inv.m_value) + 1;
-        let yprime = (self.m_value * b.m_value - q * modulus.m_value) as SignedNativeInt;
-        self.m_value = if yprime >= 0 {
-            yprime
+    /// Редукция Барретта x = (hi, lo) < 2^(2n) по модулю q < 2^n (HAC 14.42)
+    fn barrett(hi: T, lo: T, q: T, mu: T) -> T {
+        let n = T::BITS - q.leading_zeros();
+        // q1 = floor(x / 2^(n-1)) занимает не больше n + 1 бит
+        let q1 = if n == 1 {
+            lo
         } else {
-            yprime + modulus.m_value
+            (lo >> (n - 1) as usize) | (hi << (T::BITS - n + 1) as usize)
         };
-        self
-    }
-
-    fn mod_exp(&self, b: &NativeIntegerT<T>, modulus: &NativeIntegerT<T>) -> NativeIntegerT<T> {
-        let t = self.m_value;
-        let p = b.m_value;
-        let m = modulus.m_value;
-        let mut r = 1;
-        if p & 0x1 != 0 {
-            r = r * t;
-            if r >= m {
-                r = r % m;
-            }
-        }
-        let mut p = p;
-        while p >>= 1 {
-            t = t * t;
-            if t >= m {
-                t = t % m;
-            }
-            if p & 0x1 != 0 {
-                r = r * t;
-                if r >= m {
-                    r = r % m;
-                }
-            }
+        // q3 = floor(q1 * mu / 2^(n+1)) отличается от floor(x / q) не больше чем на 2
+        let (h2, l2) = T::mul_wide(q1, mu);
+        let q3 = (l2 >> (n + 1) as usize) | (h2 << (T::BITS - n - 1) as usize);
+        // x - q3 * q < 3q помещается в слово, поэтому считается по модулю 2^BITS
+        let mut r = lo.wrapping_sub(&q3.wrapping_mul(&q));
+        while r >= q {
+            r = r - q;
         }
-        r.into()
+        r
     }
+}
 
-    fn mod_exp_eq(&mut self, b: &NativeIntegerT<T>, modulus: &NativeIntegerT<T>) -> &mut Self {
-        *self = self.mod_exp(b, modulus);
-        self
+impl<T: NativeInt> From<T> for NativeIntegerT<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
     }
+}
 
-    fn mod_inverse(&self, modulus: &NativeIntegerT<T>) -> NativeIntegerT<T> {
-        let modulus = modulus.m_value as SignedNativeInt;
-        let a = self.m_value % modulus;
-        if a == 0 {
-            let msg = format!("{} does not have a ModInverse using {}", self.m_value, modulus);
-            panic!(msg);
-        }
-        if modulus == 1 {
-            NativeIntegerT::default()
-        } else {
-            let mut y = 0;
-            let mut x = 1;
-            let mut a = a;
-            while a > 1 {
-                let t = modulus;
-                let q = a / t;
-                modulus = a % t;
-                a = t;
-                let t = y;
-                y = x - q * y;
-                x = t;
-            }
-            if x < 0 {
-                x += modulus;
-            }
-            x.into()
+impl<T: NativeInt> FromStr for NativeIntegerT<T> {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<T>() {
+            Ok(value) => Ok(Self::new(value)),
+            Err(_) => Err(format!(
+                "{} is not a valid {}-bit native integer",
+                s,
+                T::BITS
+            )),
         }
     }
-
-    fn mod_inverse_eq(&mut self, modulus: &NativeIntegerT<T>) -> &mut Self {
-        *self = self.mod_inverse(modulus);
-        self
-    }
-
-    fn l_shift(&self, shift: u16) -> NativeIntegerT<T> {
-        (self.m_value << shift).into()
-    }
-
-    fn l_shift_eq(&mut self, shift: u16) -> &mut Self {
-        self.m_value = self.m_value << shift;
-        self
-    }
-
-    fn r_shift(&self, shift: u16) -> NativeIntegerT<T> {
-        (self.m_value >> shift).into()
-    }
-
-    fn r_shift_eq(&mut self, shift: u16) -> &mut Self {
-        self.m_value = self.m_value >> shift;
-        self
-    }
 }
 
-fn mod_mu<T: Into<i64> + From<i64> + Copy>(tmp: &mut i64, av: NativeIntegerT<T>, mv: T, mu: T, n: i64) {
-    // implementation of ModMu function
-}
-
-fn mult_d<T: Into<i64> + From<i64> + Copy>(av: T, bv: T, tmp: &mut i64) {
-    // implementation of MultD function
+impl<T: NativeInt> fmt::LowerHex for NativeIntegerT<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:x}", self.m_value.to_u128().unwrap())
+    }
 }
 
-fn subtract_d<T: Into<i64> + From<i64> + Copy>(r: &mut i64, tmp: i64) {
-    // implementation of SubtractD function
+impl<T: NativeInt> Display for NativeIntegerT<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.m_value)
+    }
 }
 
-fn r_shift_d<T: Into<i64> + From<i64> + Copy>(tmp: i64, n: i64) -> i64 {
-    // implementation of RShiftD function
-}
+/* Обычная арифметика без приведения по модулю: переполнение - ошибка, как у
+ * машинных целых */
+macro_rules! native_integer_op {
+    ($op:ident, $method:ident, $op_assign:ident, $method_assign:ident) => {
+        impl<T: NativeInt> $op for NativeIntegerT<T> {
+            type Output = Self;
+            fn $method(self, rhs: Self) -> Self {
+                Self::new($op::$method(self.m_value, rhs.m_value))
+            }
+        }
 
-fn mult_d_hi<T: Into<i64> + From<i64> + Copy>(av: T, bv: T) -> i64 {
-    // implementation of MultDHi function
+        impl<T: NativeInt> $op_assign for NativeIntegerT<T> {
+            fn $method_assign(&mut self, rhs: Self) {
+                *self = $op::$method(*self, rhs);
+            }
+        }
+    };
 }
 
-struct NativeIntegerT<T> {
-    m_value: T,
-}
+native_integer_op!(Add, add, AddAssign, add_assign);
+native_integer_op!(Sub, sub, SubAssign, sub_assign);
+native_integer_op!(Mul, mul, MulAssign, mul_assign);
+native_integer_op!(Div, div, DivAssign, div_assign);
+native_integer_op!(Rem, rem, RemAssign, rem_assign);
 
-impl<T> NativeIntegerT<T> {
-    fn get_msb(&self) -> i64 {
-        // implementation of GetMSB function
+impl<T: NativeInt> BitAnd for NativeIntegerT<T> {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self {
+        Self::new(self.m_value & rhs.m_value)
     }
 }
 
-impl<T: Into<i64> + From<i64> + Copy> From<i64> for NativeIntegerT<T> {
-    fn from(value: i64) -> Self {
-        NativeIntegerT { m_value: value.into() }
+impl<T: NativeInt> BitOr for NativeIntegerT<T> {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self::new(self.m_value | rhs.m_value)
     }
 }
 
-impl<T: Into<i64> + From<i64> + Copy> Mul for NativeIntegerT<T> {
-    type Output = NativeIntegerT<T>;
-
-    fn mul(self, rhs: NativeIntegerT<T>) -> Self::Output {
-        self.mod_mul_fast(&rhs, &NativeIntegerT::default())
+impl<T: NativeInt> Shl<u32> for NativeIntegerT<T> {
+    type Output = Self;
+    fn shl(self, shift: u32) -> Self {
+        Self::new(self.m_value << shift as usize)
     }
 }
 
-impl<T: Into<i64> + From<i64> + Copy> Rem for NativeIntegerT<T> {
-    type Output = NativeIntegerT<T>;
-
-    fn rem(self, rhs: NativeIntegerT<T>) -> Self::Output {
-        self.mod_mul_fast(&rhs, &NativeIntegerT::default())
+impl<T: NativeInt> ShlAssign<u32> for NativeIntegerT<T> {
+    fn shl_assign(&mut self, shift: u32) {
+        *self = *self << shift;
     }
 }
 
-impl<T: Into<i64> + From<i64> + Copy> Shr<u16> for NativeIntegerT<T> {
-    type Output = NativeIntegerT<T>;
-
-    fn shr(self, rhs: u16) -> Self::Output {
-        self.r_shift(rhs)
+impl<T: NativeInt> Shr<u32> for NativeIntegerT<T> {
+    type Output = Self;
+    fn shr(self, shift: u32) -> Self {
+        Self::new(self.m_value >> shift as usize)
     }
 }
 
-use std::convert::TryInto;
-use std::ops::{BitAnd, BitOr, Shl, Shr, Sub};
-use std::cmp::PartialOrd;
-use std::fmt::{Display, Formatter, Result as FmtResult};
-use std::str::FromStr;
-
-#[derive(Debug, Clone, Copy)]
-struct NativeIntegerT {
-    m_value: u64,
-}
-
-impl NativeIntegerT {
-    fn new(value: u64) -> Self {
-        NativeIntegerT { m_value: value }
-    }
-
-    fn compare(&self, a: &NativeIntegerT) -> i32 {
-        if self.m_value < a.m_value {
-            -1
-        } else if self.m_value > a.m_value {
-            1
-        } else {
-            0
-        }
-    }
-
-    fn convert_to_int<T>(&self) -> T
-    where
-        T: From<u64>,
-    {
-        self.m_value.try_into().unwrap()
+impl<T: NativeInt> ShrAssign<u32> for NativeIntegerT<T> {
+    fn shr_assign(&mut self, shift: u32) {
+        *self = *self >> shift;
     }
+}
 
-    fn convert_to_double(&self) -> f64 {
-        self.m_value as f64
+impl<T: NativeInt> IntegerInterface for NativeIntegerT<T> {
+    fn from_u64(value: u64) -> Self {
+        Self::new(T::from(value).expect("The value does not fit into the native integer"))
     }
 
-    fn from_binary_string(bit_string: &str) -> Self {
-        if bit_string.len() > NativeIntegerT::max_bits() {
-            panic!("Bit string is too long to fit in an intnat");
-        }
-        let mut v: u64 = 0;
-        for c in bit_string.chars() {
-            let n = c.to_digit(10).unwrap();
-            if n < 0 || n > 1 {
-                panic!("Bit string must contain only 0 or 1");
-            }
-            v = (v << 1) | n as u64;
-        }
-        NativeIntegerT::new(v)
+    fn to_u64(&self) -> Option<u64> {
+        self.m_value.to_u64()
     }
 
-    fn get_msb(&self) -> usize {
-        self.m_value.leading_zeros() as usize
+    fn get_msb(&self) -> u32 {
+        NativeIntegerT::get_msb(self)
     }
 
-    fn get_length_for_base(&self, base: usize) -> usize {
-        NativeIntegerT::get_msb()
+    fn get_bit_at_index(&self, index: u32) -> bool {
+        NativeIntegerT::get_bit_at_index(self, index)
     }
 
-    fn get_digit_at_index_for_base(&self, index: usize, base: usize) -> usize {
-        let digit_len = f64::ceil(f64::log2(base as f64)) as usize;
-        let mut digit = 0;
-        let mut new_index = 1 + (index - 1) * digit_len;
-        for i in 1..base {
-            digit += self.get_bit_at_index(new_index) * i;
-            new_index += 1;
-        }
-        digit
+    fn mod_mul(&self, b: &Self, modulus: &Self) -> Self {
+        NativeIntegerT::mod_mul(self, b, modulus)
     }
 
-    fn get_bit_at_index(&self, index: usize) -> u8 {
-        if index == 0 {
-            panic!("Zero index in GetBitAtIndex");
-        }
-        ((self.m_value >> (index - 1)) & 0x1) as u8
+    fn mod_add(&self, b: &Self, modulus: &Self) -> Self {
+        NativeIntegerT::mod_add(self, b, modulus)
     }
 
-    fn allocator() -> Self {
-        NativeIntegerT::new(0)
+    fn mod_sub(&self, b: &Self, modulus: &Self) -> Self {
+        NativeIntegerT::mod_sub(self, b, modulus)
     }
 
-    fn to_string(&self) -> String {
-        self.m_value.to_string()
+    fn mod_exp(&self, e: &Self, modulus: &Self) -> Self {
+        NativeIntegerT::mod_exp(self, e, modulus)
     }
+}
 
-    fn integer_type_name() -> &'static str {
-        "UBNATINT"
+/* Машинные целые реализуют интерфейс длинных, поэтому многочлены и векторы
+ * над ними собираются тем же обобщённым кодом */
+impl<T: NativeInt> BigIntegerInterface for NativeIntegerT<T> {
+    fn modulo(&self, modulus: &Self) -> Self {
+        NativeIntegerT::modulo(self, modulus)
     }
 
-    fn max_bits() -> usize {
-        64
+    fn compute_mu(&self) -> Self {
+        NativeIntegerT::compute_mu(self)
     }
-}
 
-impl Display for NativeIntegerT {
-    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        write!(f, "{}", self.to_string())
+    fn modulo_with_mu(&self, modulus: &Self, mu: &Self) -> Self {
+        NativeIntegerT::modulo_with_mu(self, modulus, mu)
     }
-}
 
-impl PartialEq for NativeIntegerT {
-    fn eq(&self, other: &Self) -> bool {
-        self.m_value == other.m_value
+    fn mod_add_fast(&self, b: &Self, modulus: &Self) -> Self {
+        NativeIntegerT::mod_add_fast(self, b, modulus)
     }
-}
 
-impl PartialOrd for NativeIntegerT {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.m_value.partial_cmp(&other.m_value)
+    fn mod_sub_fast(&self, b: &Self, modulus: &Self) -> Self {
+        NativeIntegerT::mod_sub_fast(self, b, modulus)
     }
-}
 
-impl BitAnd for NativeIntegerT {
-    type Output = Self;
-
-    fn bitand(self, rhs: Self) -> Self::Output {
-        NativeIntegerT::new(self.m_value & rhs.m_value)
+    fn mod_mul_fast(&self, b: &Self, modulus: &Self, mu: &Self) -> Self {
+        NativeIntegerT::mod_mul_fast(self, b, modulus, mu)
     }
-}
-
-impl BitOr for NativeIntegerT {
-    type Output = Self;
 
-    fn bitor(self, rhs: Self) -> Self::Output {
-        NativeIntegerT::new(self.m_value | rhs.m_value)
+    fn mod_inverse(&self, modulus: &Self) -> Self {
+        NativeIntegerT::mod_inverse(self, modulus)
     }
-}
-
-impl Shl<usize> for NativeIntegerT {
-    type Output = Self;
 
-    fn shl(self, rhs: usize) -> Self::Output {
-        NativeIntegerT::new(self.m_value << rhs)
+    fn multiply_and_round(&self, p: &Self, q: &Self) -> Self {
+        NativeIntegerT::multiply_and_round(self, p, q)
     }
-}
 
-impl Shr<usize> for NativeIntegerT {
-    type Output = Self;
-
-    fn shr(self, rhs: usize) -> Self::Output {
-        NativeIntegerT::new(self.m_value >> rhs)
+    fn divide_and_round(&self, q: &Self) -> Self {
+        NativeIntegerT::divide_and_round(self, q)
     }
-}
-
-impl Sub for NativeIntegerT {
-    type Output = Self;
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        NativeIntegerT::new(self.m_value - rhs.m_value)
+    fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> u64 {
+        NativeIntegerT::get_digit_at_index_for_base(self, index, bits)
+            .to_u64()
+            .unwrap()
     }
-}
-
-impl FromStr for NativeIntegerT {
-    type Err = std::num::ParseIntError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let value = u64::from_str(s)?;
-        Ok(NativeIntegerT::new(value))
+    fn convert_to_double(&self) -> f64 {
+        NativeIntegerT::convert_to_double(self)
     }
 }
-
-fn main() {
-    let a = NativeIntegerT::new(10);
-    let b = NativeIntegerT::new(5);
-    let c = a + b;
-    println!("{}", c);
-}
-
-
-
-
@@ -1,4 +1,9 @@
+pub mod basicint;
+pub mod bigintbackend;
 pub mod bigintdyn;
 pub mod bigintfxd;
 pub mod bigintntl;
+pub mod integer;
 pub mod intnat;
+pub mod transform;
+pub mod vector;
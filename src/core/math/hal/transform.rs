@@ -0,0 +1,217 @@
+/*
+  This file contains the interface of the CRT transforms the lattice layer uses for every vector
+  backend
+*/
+use super::bigintbackend::{M2Vector, M4Vector, M6Vector};
+use super::bigintntl::transformntl::ChineseRemainderTransformFTTNtl;
+use super::integer::{BigIntegerInterface, IntegerInterface};
+use super::intnat::transformnat::{
+    ChineseRemainderTransformArbNat, ChineseRemainderTransformFTTNat,
+};
+use super::vector::{BigVectorInterface, NativeVector};
+use crate::core::math::nbtheory::reverse_bits;
+use lazy_static::lazy_static;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Преобразования в CRT-представление, которыми PolyImpl переключает формат.
+///
+/// Для степени двойки m значения многочлена по модулю x^n + 1, n = m / 2,
+/// хранятся в порядке обращения битов: элемент с номером rev(i) - значение в
+/// root^(2i + 1). Реализация по умолчанию - обычное NTT с таблицами,
+/// кэшированными по (тип, модуль, порядок); бэкенды со своими
+/// преобразованиями переопределяют методы. Произвольный порядок m
+/// поддерживают только машинные векторы.
+pub trait TransformInterface: BigVectorInterface {
+    /// Прямое преобразование на месте; root_of_unity - первообразный корень
+    /// порядка cyclo_order по модулю вектора
+    fn forward_transform_to_bit_reverse_in_place(
+        root_of_unity: &Self::Integer,
+        cyclo_order: usize,
+        element: &mut Self,
+    ) {
+        let tables = tables::<Self>(root_of_unity, cyclo_order, element);
+        let q = element.get_modulus().clone();
+        let n = element.get_length();
+        let (mut m, mut t) = (1, n >> 1);
+        while m < n {
+            for i in 0..m {
+                let omega = &tables.root_of_unity_reverse[i + m];
+                let j1 = 2 * i * t;
+                for j in j1..j1 + t {
+                    let omega_factor = element[j + t].mod_mul(omega, &q);
+                    let lo = element[j].clone();
+                    element[j] = lo.mod_add(&omega_factor, &q);
+                    element[j + t] = lo.mod_sub(&omega_factor, &q);
+                }
+            }
+            m <<= 1;
+            t >>= 1;
+        }
+    }
+
+    /// Обратное преобразование на месте
+    fn inverse_transform_from_bit_reverse_in_place(
+        root_of_unity: &Self::Integer,
+        cyclo_order: usize,
+        element: &mut Self,
+    ) {
+        let tables = tables::<Self>(root_of_unity, cyclo_order, element);
+        let q = element.get_modulus().clone();
+        let n = element.get_length();
+        let (mut m, mut t) = (n >> 1, 1);
+        while m >= 1 {
+            for i in 0..m {
+                let omega = &tables.root_of_unity_inverse_reverse[i + m];
+                let j1 = 2 * i * t;
+                for j in j1..j1 + t {
+                    let (lo, hi) = (element[j].clone(), element[j + t].clone());
+                    element[j] = lo.mod_add(&hi, &q);
+                    element[j + t] = lo.mod_sub(&hi, &q).mod_mul(omega, &q);
+                }
+            }
+            m >>= 1;
+            t <<= 1;
+        }
+        *element = element.mod_mul(&tables.cyclo_order_inv);
+    }
+
+    /// Значения многочлена степени меньше φ(m) в первообразных корнях порядка
+    /// m = cyclo_order; root - корень порядка 2m
+    fn forward_transform_arb(_element: &Self, _root: &Self::Integer, _cyclo_order: usize) -> Self {
+        panic!("Arbitrary cyclotomics are supported only for native vectors")
+    }
+
+    /// Многочлен степени меньше φ(m) по его значениям
+    fn inverse_transform_arb(_element: &Self, _root: &Self::Integer, _cyclo_order: usize) -> Self {
+        panic!("Arbitrary cyclotomics are supported only for native vectors")
+    }
+}
+
+impl TransformInterface for NativeVector {
+    fn forward_transform_to_bit_reverse_in_place(
+        root_of_unity: &Self::Integer,
+        cyclo_order: usize,
+        element: &mut Self,
+    ) {
+        ChineseRemainderTransformFTTNat::forward_transform_to_bit_reverse_in_place(
+            root_of_unity,
+            cyclo_order,
+            element,
+        )
+    }
+
+    fn inverse_transform_from_bit_reverse_in_place(
+        root_of_unity: &Self::Integer,
+        cyclo_order: usize,
+        element: &mut Self,
+    ) {
+        ChineseRemainderTransformFTTNat::inverse_transform_from_bit_reverse_in_place(
+            root_of_unity,
+            cyclo_order,
+            element,
+        )
+    }
+
+    fn forward_transform_arb(element: &Self, root: &Self::Integer, cyclo_order: usize) -> Self {
+        ChineseRemainderTransformArbNat::forward_transform(element, root, cyclo_order)
+    }
+
+    fn inverse_transform_arb(element: &Self, root: &Self::Integer, cyclo_order: usize) -> Self {
+        ChineseRemainderTransformArbNat::inverse_transform(element, root, cyclo_order)
+    }
+}
+
+impl TransformInterface for M6Vector {
+    fn forward_transform_to_bit_reverse_in_place(
+        root_of_unity: &Self::Integer,
+        cyclo_order: usize,
+        element: &mut Self,
+    ) {
+        ChineseRemainderTransformFTTNtl::forward_transform_to_bit_reverse_in_place(
+            root_of_unity,
+            cyclo_order,
+            element,
+        )
+    }
+
+    fn inverse_transform_from_bit_reverse_in_place(
+        root_of_unity: &Self::Integer,
+        cyclo_order: usize,
+        element: &mut Self,
+    ) {
+        ChineseRemainderTransformFTTNtl::inverse_transform_from_bit_reverse_in_place(
+            root_of_unity,
+            cyclo_order,
+            element,
+        )
+    }
+}
+
+impl TransformInterface for M2Vector {}
+
+impl TransformInterface for M4Vector {}
+
+/// Таблицы преобразования по умолчанию: степени корня и обратного к нему в
+/// порядке обращения битов и n^(-1) mod q
+struct NttTables<I> {
+    root_of_unity_reverse: Vec<I>,
+    root_of_unity_inverse_reverse: Vec<I>,
+    cyclo_order_inv: I,
+}
+
+type TablesKey = (TypeId, String, usize);
+
+lazy_static! {
+    static ref TABLES: RwLock<HashMap<TablesKey, Arc<dyn Any + Send + Sync>>> =
+        RwLock::new(HashMap::new());
+}
+
+/// Очищает таблицы преобразования по умолчанию
+pub fn reset() {
+    TABLES.write().unwrap().clear();
+}
+
+/// Таблицы для модуля element и порядка cyclo_order; вычисляются при первом
+/// обращении
+fn tables<V: BigVectorInterface>(
+    root_of_unity: &V::Integer,
+    cyclo_order: usize,
+    element: &V,
+) -> Arc<NttTables<V::Integer>> {
+    if !cyclo_order.is_power_of_two() {
+        panic!("CyclotomicOrder is not a power of two");
+    }
+    let n = cyclo_order >> 1;
+    if element.get_length() != n {
+        panic!("element size must be equal to CyclotomicOrder / 2");
+    }
+    let q = element.get_modulus();
+    let key = (TypeId::of::<V::Integer>(), q.to_string(), cyclo_order);
+    let cached = TABLES.read().unwrap().get(&key).cloned();
+    if let Some(tables) = cached {
+        return tables.downcast().unwrap();
+    }
+
+    let msb = n.trailing_zeros();
+    let root_inv = root_of_unity.mod_inverse(q);
+    let mut powers = vec![V::Integer::one()];
+    let mut powers_inv = vec![V::Integer::one()];
+    for i in 1..n {
+        powers.push(powers[i - 1].mod_mul(root_of_unity, q));
+        powers_inv.push(powers_inv[i - 1].mod_mul(&root_inv, q));
+    }
+    let reverse = |table: &[V::Integer]| -> Vec<V::Integer> {
+        (0..n)
+            .map(|i| table[reverse_bits(i as u64, msb) as usize].clone())
+            .collect()
+    };
+    let tables = Arc::new(NttTables {
+        root_of_unity_reverse: reverse(&powers),
+        root_of_unity_inverse_reverse: reverse(&powers_inv),
+        cyclo_order_inv: V::Integer::from_u64(n as u64).mod_inverse(q),
+    });
+    TABLES.write().unwrap().insert(key, tables.clone());
+    tables
+}
@@ -0,0 +1,66 @@
+/*
+  This file contains the interface every big vector backend provides to the lattice layer
+*/
+use super::integer::BigIntegerInterface;
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+/// Вектор выбранного бэкенда и вектор машинных целых
+pub use super::bigintbackend::BigVector;
+pub use super::intnat::mubintvecnat::NativeVector;
+
+/// Общий интерфейс векторов длинных целых по модулю q (аналог
+/// BigVectorInterface в OpenFHE).
+///
+/// Все элементы приведены в [0, q); операции над двумя векторами требуют
+/// одинаковых модулей и длин. Операторы +, -, * выполняются поэлементно по
+/// модулю.
+pub trait BigVectorInterface:
+    Clone
+    + Debug
+    + Display
+    + PartialEq
+    + Eq
+    + Index<usize, Output = Self::Integer>
+    + IndexMut<usize>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+{
+    type Integer: BigIntegerInterface;
+
+    /// Нулевой вектор длины length с модулем modulus
+    fn new(length: usize, modulus: Self::Integer) -> Self;
+
+    /// Вектор из значений values, приведённых по модулю modulus
+    fn from_values(values: &[Self::Integer], modulus: Self::Integer) -> Self;
+
+    fn get_modulus(&self) -> &Self::Integer;
+
+    /// Меняет модуль без пересчёта элементов
+    fn set_modulus(&mut self, value: Self::Integer);
+
+    fn get_length(&self) -> usize;
+
+    /// Переводит элементы в модуль new_modulus через центрированный подъём
+    fn switch_modulus(&mut self, new_modulus: Self::Integer);
+
+    /// Цифры всех элементов с номером index в системе счисления с основанием 2^bits
+    fn get_digit_at_index_for_base(&self, index: u32, bits: u32) -> Self;
+
+    fn mod_add(&self, b: &Self::Integer) -> Self;
+
+    fn mod_sub(&self, b: &Self::Integer) -> Self;
+
+    fn mod_mul(&self, b: &Self::Integer) -> Self;
+
+    fn mod_exp(&self, b: &Self::Integer) -> Self;
+
+    fn mod_inverse(&self) -> Self;
+
+    fn mod_add_vec(&self, b: &Self) -> Self;
+
+    fn mod_sub_vec(&self, b: &Self) -> Self;
+
+    fn mod_mul_vec(&self, b: &Self) -> Self;
+}
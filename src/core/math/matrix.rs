@@ -0,0 +1,338 @@
+/*
+  This code provides a templated matrix over ring elements: integers, polynomials, f64
+*/
+use rayon::prelude::*;
+use std::fmt;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Range, Sub, SubAssign};
+use std::sync::Arc;
+
+/// Функция, создающая нулевой элемент кольца. Ноль многочлена зависит от
+/// параметров кольца, поэтому матрица хранит эту функцию вместо Default
+pub type AllocFunc<T> = Arc<dyn Fn() -> T + Send + Sync>;
+
+/// Матрица над кольцом T, элементы хранятся по строкам.
+///
+/// От T требуются только кольцевые операции по значению, поэтому элементами
+/// могут быть целые, Poly, DCRTPoly, Field2n или f64. Модульная арифметика
+/// целых и разложение Холецкого для f64 находятся в matrix_impl.
+#[derive(Clone)]
+pub struct Matrix<T> {
+    data: Vec<T>,
+    rows: usize,
+    cols: usize,
+    alloc_zero: AllocFunc<T>,
+}
+
+impl<T: Clone> Matrix<T> {
+    /// Нулевая матрица rows x cols
+    pub fn new(alloc_zero: AllocFunc<T>, rows: usize, cols: usize) -> Self {
+        let data = (0..rows * cols).map(|_| alloc_zero()).collect();
+        Self {
+            data,
+            rows,
+            cols,
+            alloc_zero,
+        }
+    }
+
+    /// Матрица rows x cols из элементов data, перечисленных по строкам
+    pub fn from_data(alloc_zero: AllocFunc<T>, rows: usize, cols: usize, data: Vec<T>) -> Self {
+        if data.len() != rows * cols {
+            panic!("The data length must be equal to rows * cols");
+        }
+        Self {
+            data,
+            rows,
+            cols,
+            alloc_zero,
+        }
+    }
+
+    /// Нулевая матрица того же кольца
+    pub fn zero_like(&self, rows: usize, cols: usize) -> Self {
+        Self::new(self.alloc_zero.clone(), rows, cols)
+    }
+
+    pub fn get_rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn get_cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get_allocator(&self) -> &AllocFunc<T> {
+        &self.alloc_zero
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        &mut self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn col(&self, col: usize) -> impl Iterator<Item = &T> + '_ {
+        self.data.iter().skip(col).step_by(self.cols.max(1))
+    }
+
+    /// Строка row как матрица 1 x cols
+    pub fn extract_row(&self, row: usize) -> Self {
+        self.extract_rows(row..row + 1)
+    }
+
+    /// Столбец col как матрица rows x 1
+    pub fn extract_col(&self, col: usize) -> Self {
+        let data = self.col(col).cloned().collect();
+        Self::from_data(self.alloc_zero.clone(), self.rows, 1, data)
+    }
+
+    /// Строки из диапазона rows
+    pub fn extract_rows(&self, rows: Range<usize>) -> Self {
+        let data = self.data[rows.start * self.cols..rows.end * self.cols].to_vec();
+        Self::from_data(self.alloc_zero.clone(), rows.len(), self.cols, data)
+    }
+
+    pub fn swap_rows(&mut self, a: usize, b: usize) {
+        for col in 0..self.cols {
+            self.data.swap(a * self.cols + col, b * self.cols + col);
+        }
+    }
+
+    pub fn fill(&mut self, value: T) -> &mut Self {
+        for x in self.data.iter_mut() {
+            *x = value.clone();
+        }
+        self
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut data = Vec::with_capacity(self.data.len());
+        for col in 0..self.cols {
+            data.extend(self.col(col).cloned());
+        }
+        Self::from_data(self.alloc_zero.clone(), self.cols, self.rows, data)
+    }
+
+    /// Дописывает строки other снизу
+    pub fn vstack(&mut self, other: &Self) -> &mut Self {
+        if self.cols != other.cols {
+            panic!("VStack: the numbers of columns must be equal");
+        }
+        self.data.extend_from_slice(&other.data);
+        self.rows += other.rows;
+        self
+    }
+
+    /// Дописывает столбцы other справа
+    pub fn hstack(&mut self, other: &Self) -> &mut Self {
+        if self.rows != other.rows {
+            panic!("HStack: the numbers of rows must be equal");
+        }
+        let cols = self.cols + other.cols;
+        let mut data = Vec::with_capacity(self.rows * cols);
+        for row in 0..self.rows {
+            data.extend_from_slice(self.row(row));
+            data.extend_from_slice(other.row(row));
+        }
+        self.data = data;
+        self.cols = cols;
+        self
+    }
+}
+
+impl<T: Clone + Default + 'static> Matrix<T> {
+    /// Нулевая матрица для колец, у которых ноль - Default (целые, f64)
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self::new(Arc::new(T::default), rows, cols)
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Clone + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+    pub fn scalar_mult(&self, other: &T) -> Self {
+        let data = self
+            .data
+            .iter()
+            .map(|x| x.clone() * other.clone())
+            .collect();
+        Self::from_data(self.alloc_zero.clone(), self.rows, self.cols, data)
+    }
+
+    /// Определитель разложением Лапласа по первой строке.
+    ///
+    /// Сложность O(d!), но нужны только кольцевые операции, поэтому метод
+    /// подходит и для многочленов; над полем вычетов есть determinant_mod за O(d^3).
+    pub fn determinant(&self) -> T {
+        if self.rows != self.cols {
+            panic!("Supported only for square matrix");
+        }
+        if self.rows < 1 {
+            panic!("Dimension should be at least one");
+        }
+        let n = self.rows;
+        if n == 1 {
+            return self[(0, 0)].clone();
+        }
+        if n == 2 {
+            return self[(0, 0)].clone() * self[(1, 1)].clone()
+                - self[(1, 0)].clone() * self[(0, 1)].clone();
+        }
+
+        let mut determinant = (self.alloc_zero)();
+        let mut minor = self.zero_like(n - 1, n - 1);
+        for j1 in 0..n {
+            for i in 1..n {
+                for (j2, j) in (0..n).filter(|&j| j != j1).enumerate() {
+                    minor[(i - 1, j2)] = self[(i, j)].clone();
+                }
+            }
+            let term = self[(0, j1)].clone() * minor.determinant();
+            determinant = if j1 % 2 == 0 {
+                determinant + term
+            } else {
+                determinant - term
+            };
+        }
+        determinant
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Clone + Send + Sync + Add<Output = T> + Mul<Output = T>,
+{
+    /// Произведение матриц; строки результата вычисляются параллельно
+    pub fn mult(&self, other: &Self) -> Self {
+        if self.cols != other.rows {
+            panic!("incompatible matrix multiplication");
+        }
+        let mut result = self.zero_like(self.rows, other.cols);
+        if other.cols == 0 {
+            return result;
+        }
+        result
+            .data
+            .par_chunks_mut(other.cols)
+            .enumerate()
+            .for_each(|(row, result_row)| {
+                for (i, a) in self.row(row).iter().enumerate() {
+                    for (x, b) in result_row.iter_mut().zip(other.row(i)) {
+                        *x = x.clone() + a.clone() * b.clone();
+                    }
+                }
+            });
+        result
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        &self.data[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        &mut self.data[row * self.cols + col]
+    }
+}
+
+impl<T: Clone + Add<Output = T>> AddAssign<&Matrix<T>> for Matrix<T> {
+    fn add_assign(&mut self, other: &Matrix<T>) {
+        if self.rows != other.rows || self.cols != other.cols {
+            panic!("Addition operands have incompatible dimensions");
+        }
+        for (x, y) in self.data.iter_mut().zip(&other.data) {
+            *x = x.clone() + y.clone();
+        }
+    }
+}
+
+impl<T: Clone + Sub<Output = T>> SubAssign<&Matrix<T>> for Matrix<T> {
+    fn sub_assign(&mut self, other: &Matrix<T>) {
+        if self.rows != other.rows || self.cols != other.cols {
+            panic!("Subtraction operands have incompatible dimensions");
+        }
+        for (x, y) in self.data.iter_mut().zip(&other.data) {
+            *x = x.clone() - y.clone();
+        }
+    }
+}
+
+impl<T: Clone + Add<Output = T>> Add for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn add(self, other: Self) -> Matrix<T> {
+        let mut result = self.clone();
+        result += other;
+        result
+    }
+}
+
+impl<T: Clone + Sub<Output = T>> Sub for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn sub(self, other: Self) -> Matrix<T> {
+        let mut result = self.clone();
+        result -= other;
+        result
+    }
+}
+
+impl<T: Clone + Neg<Output = T>> Neg for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn neg(self) -> Matrix<T> {
+        let data = self.data.iter().map(|x| -x.clone()).collect();
+        Matrix::from_data(self.alloc_zero.clone(), self.rows, self.cols, data)
+    }
+}
+
+impl<T> Mul for &Matrix<T>
+where
+    T: Clone + Send + Sync + Add<Output = T> + Mul<Output = T>,
+{
+    type Output = Matrix<T>;
+
+    fn mul(self, other: Self) -> Matrix<T> {
+        self.mult(other)
+    }
+}
+
+impl<T: PartialEq> PartialEq for Matrix<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.rows == other.rows && self.cols == other.cols && self.data == other.data
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Matrix<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Matrix")
+            .field("rows", &self.rows)
+            .field("cols", &self.cols)
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Matrix<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.rows {
+            write!(f, "[")?;
+            for col in 0..self.cols {
+                if col > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{}", self.data[row * self.cols + col])?;
+            }
+            writeln!(f, "]")?;
+        }
+        Ok(())
+    }
+}
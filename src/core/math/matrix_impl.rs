@@ -1,160 +1,179 @@
 /*
- This code provide a templated matrix implementation
+  This code provides type specific matrix operations: linear algebra modulo a prime for
+  integers, the Cholesky decomposition for f64 and the centered conversion to i32
 */
-use std::vec::Vec;
+use super::hal::integer::IntegerInterface;
+use super::matrix::Matrix;
+use rayon::prelude::*;
 
-pub struct Matrix<Element> {
-    data: Vec<Vec<Element>>,
-    rows: usize,
-    cols: usize,
-    allocZero: alloc_func,
-}
-
-impl<Element> Matrix<Element> {
-    pub fn new(allocZero: alloc_func, rows: usize, cols: usize, allocGen: alloc_func) -> Self {
-        let mut data = Vec::new();
-        data.resize(rows, Vec::new());
-        for row in data.iter_mut() {
-            for _ in 0..cols {
-                row.push(allocGen());
-            }
-        }
-        Matrix {
-            data,
-            rows,
-            cols,
-            allocZero,
+impl<T: IntegerInterface + Send + Sync> Matrix<T> {
+    /// Произведение по модулю modulus для элементов, меньших модуля;
+    /// строки результата вычисляются параллельно
+    pub fn mult_mod(&self, other: &Self, modulus: &T) -> Self {
+        if self.get_cols() != other.get_rows() {
+            panic!("incompatible matrix multiplication");
         }
+        let rows: Vec<Vec<T>> = (0..self.get_rows())
+            .into_par_iter()
+            .map(|row| {
+                let mut result_row = vec![T::zero(); other.get_cols()];
+                for (i, a) in self.row(row).iter().enumerate() {
+                    for (x, b) in result_row.iter_mut().zip(other.row(i)) {
+                        *x = x.mod_add(&a.mod_mul(b, modulus), modulus);
+                    }
+                }
+                result_row
+            })
+            .collect();
+        Self::from_data(
+            self.get_allocator().clone(),
+            self.get_rows(),
+            other.get_cols(),
+            rows.concat(),
+        )
     }
 
-    pub fn fill(&mut self, val: Element) -> &mut Self {
-        for row in self.data.iter_mut() {
-            for col in row.iter_mut() {
-                *col = val;
+    /// Приведённая ступенчатая форма по модулю простого modulus и ранг матрицы
+    pub fn gaussian_elimination_mod(&self, modulus: &T) -> (Self, usize) {
+        let (rows, cols) = (self.get_rows(), self.get_cols());
+        let mut reduced = self.clone();
+        let mut rank = 0;
+        for col in 0..cols {
+            if rank == rows {
+                break;
             }
-        }
-        self
-    }
-
-    pub fn mult(&self, other: &Matrix<Element>) -> Result<Matrix<Element>, String> {
-        if self.cols != other.rows {
-            return Err(String::from("incompatible matrix multiplication"));
-        }
-        let mut result = Matrix::new(self.allocZero, self.rows, other.cols, self.allocZero);
-        if self.rows == 1 {
-            for col in 0..result.cols {
-                for i in 0..self.cols {
-                    result.data[0][col] += self.data[0][i] * other.data[i][col];
-                }
+            let pivot = match (rank..rows).find(|&row| !reduced[(row, col)].is_zero()) {
+                Some(pivot) => pivot,
+                None => continue,
+            };
+            reduced.swap_rows(pivot, rank);
+            let pivot_inv = mod_inverse_prime(&reduced[(rank, col)], modulus);
+            for x in reduced.row_mut(rank) {
+                *x = x.mod_mul(&pivot_inv, modulus);
             }
-        } else {
-            for row in 0..result.rows {
-                for i in 0..self.cols {
-                    for col in 0..result.cols {
-                        result.data[row][col] += self.data[row][i] * other.data[i][col];
-                    }
+            let pivot_row = reduced.row(rank).to_vec();
+            for row in (0..rows).filter(|&row| row != rank) {
+                let factor = reduced[(row, col)].clone();
+                if factor.is_zero() {
+                    continue;
+                }
+                for (x, p) in reduced.row_mut(row).iter_mut().zip(&pivot_row) {
+                    *x = x.mod_sub(&factor.mod_mul(p, modulus), modulus);
                 }
             }
+            rank += 1;
         }
-        Ok(result)
+        (reduced, rank)
     }
 
-    pub fn add_assign(&mut self, other: &Matrix<Element>) -> Result<&mut Self, String> {
-        if self.rows != other.rows || self.cols != other.cols {
-            return Err(String::from(
-                "Addition operands have incompatible dimensions",
-            ));
+    /// Определитель по модулю простого modulus приведением к треугольному виду
+    pub fn determinant_mod(&self, modulus: &T) -> T {
+        let n = self.get_rows();
+        if n != self.get_cols() {
+            panic!("Supported only for square matrix");
         }
-        for j in 0..self.cols {
-            for i in 0..self.rows {
-                self.data[i][j] += other.data[i][j];
+        let mut reduced = self.clone();
+        let mut determinant = T::one();
+        for col in 0..n {
+            let pivot = match (col..n).find(|&row| !reduced[(row, col)].is_zero()) {
+                Some(pivot) => pivot,
+                None => return T::zero(),
+            };
+            if pivot != col {
+                reduced.swap_rows(pivot, col);
+                determinant = T::zero().mod_sub(&determinant, modulus);
+            }
+            let pivot_val = reduced[(col, col)].clone();
+            determinant = determinant.mod_mul(&pivot_val, modulus);
+            let pivot_inv = mod_inverse_prime(&pivot_val, modulus);
+            for row in col + 1..n {
+                let factor = reduced[(row, col)].mod_mul(&pivot_inv, modulus);
+                for j in col..n {
+                    let prod = factor.mod_mul(&reduced[(col, j)], modulus);
+                    reduced[(row, j)] = reduced[(row, j)].mod_sub(&prod, modulus);
+                }
             }
         }
-        Ok(self)
+        determinant
     }
 
-    pub fn sub_assign(&mut self, other: &Matrix<Element>) -> Result<&mut Self, String> {
-        if self.rows != other.rows || self.cols != other.cols {
-            return Err(String::from(
-                "Subtraction operands have incompatible dimensions",
-            ));
+    /// Обратная матрица по модулю простого modulus; None для вырожденной
+    pub fn inverse_mod(&self, modulus: &T) -> Option<Self> {
+        let n = self.get_rows();
+        if n != self.get_cols() {
+            panic!("Supported only for square matrix");
         }
-        for j in 0..self.cols {
-            for i in 0..self.rows {
-                self.data[i][j] -= other.data[i][j];
-            }
+        let mut identity = self.zero_like(n, n);
+        for i in 0..n {
+            identity[(i, i)] = T::one();
         }
-        Ok(self)
-    }
-
-    pub fn transpose(&self) -> Matrix<Element> {
-        let mut result = Matrix::new(self.allocZero, self.cols, self.rows, self.allocZero);
-        for row in 0..self.rows {
-            for col in 0..self.cols {
-                result.data[col][row] = self.data[row][col];
-            }
+        let mut augmented = self.clone();
+        augmented.hstack(&identity);
+        let (reduced, _) = augmented.gaussian_elimination_mod(modulus);
+        if (0..n).any(|i| reduced[(i, i)] != T::one()) {
+            return None;
         }
-        result
+        let data = (0..n)
+            .flat_map(|row| reduced.row(row)[n..].to_vec())
+            .collect();
+        Some(Self::from_data(self.get_allocator().clone(), n, n, data))
     }
 }
 
-// YSP The signature of this method needs to be changed in the future
-// Laplace's formula is used to find the determinant
-// Complexity is O(d!), where d is the dimension
-// The determinant of a matrix is expressed in terms of its minors
-// recursive implementation
-// There are O(d^3) decomposition algorithms that can be implemented to support
-// larger dimensions. Examples include the LU decomposition, the QR
-// decomposition or the Cholesky decomposition(for positive definite matrices).
-impl<Element> Matrix<Element> {
-    fn determinant(&self) -> Element
-    where
-        Element: std::ops::Sub<Output = Element>
-            + std::ops::Mul<Output = Element>
-            + std::ops::Add<Output = Element>
-            + std::ops::Neg<Output = Element>
-            + Copy,
-    {
-        if self.rows != self.cols {
+impl Matrix<f64> {
+    /// Разложение Холецкого: нижнетреугольная L, L L^T = self, для симметричной
+    /// положительно определённой матрицы
+    pub fn cholesky(&self) -> Self {
+        let n = self.get_rows();
+        if n != self.get_cols() {
             panic!("Supported only for square matrix");
         }
-
-        if self.rows < 1 {
-            panic!("Dimension should be at least one");
+        let mut lower = self.zero_like(n, n);
+        for j in 0..n {
+            let diag = self[(j, j)] - (0..j).map(|k| lower[(j, k)] * lower[(j, k)]).sum::<f64>();
+            if diag <= 0.0 {
+                panic!("Cholesky: the matrix is not positive definite");
+            }
+            lower[(j, j)] = diag.sqrt();
+            for i in j + 1..n {
+                let dot: f64 = (0..j).map(|k| lower[(i, k)] * lower[(j, k)]).sum();
+                lower[(i, j)] = (self[(i, j)] - dot) / lower[(j, j)];
+            }
         }
+        lower
+    }
+}
 
-        if self.rows == 1 {
-            return self.data[0][0];
-        } else if self.rows == 2 {
-            return self.data[0][0] * self.data[1][1] - self.data[1][0] * self.data[0][1];
+/// Центрированный подъём элементов из [0, modulus) в i32: x > modulus / 2
+/// становится отрицательным числом x - modulus
+pub fn convert_to_int32<T: IntegerInterface>(input: &Matrix<T>, modulus: &T) -> Matrix<i32> {
+    let half = modulus.clone() / T::from_u64(2);
+    let to_i32 = |x: &T| {
+        let (abs, negative) = if *x > half {
+            (modulus.clone() - x.clone(), true)
         } else {
-            let n = self.rows;
-            let mut determinant = Element::default();
-            let mut result = Matrix::new(self.rows - 1, self.cols - 1);
-
-            for j1 in 0..n {
-                for i in 1..n {
-                    let mut j2 = 0;
-
-                    for j in 0..n {
-                        if j == j1 {
-                            continue;
-                        }
-
-                        result.data[i - 1][j2] = self.data[i][j];
-                        j2 += 1;
-                    }
-                }
-
-                let temp_determinant = result.determinant();
-                if j1 % 2 == 0 {
-                    determinant = determinant + self.data[0][j1] * temp_determinant;
-                } else {
-                    determinant = determinant - self.data[0][j1] * temp_determinant;
-                }
-            }
-
-            determinant
+            (x.clone(), false)
+        };
+        let abs = abs
+            .to_u64()
+            .and_then(|v| i32::try_from(v).ok())
+            .expect("The centered value does not fit into i32");
+        if negative {
+            -abs
+        } else {
+            abs
+        }
+    };
+    let mut result = Matrix::zeros(input.get_rows(), input.get_cols());
+    for row in 0..input.get_rows() {
+        for (x, y) in result.row_mut(row).iter_mut().zip(input.row(row)) {
+            *x = to_i32(y);
         }
     }
+    result
+}
+
+/// a^-1 mod q для простого q по малой теореме Ферма
+fn mod_inverse_prime<T: IntegerInterface>(a: &T, modulus: &T) -> T {
+    a.mod_exp(&(modulus.clone() - T::from_u64(2)), modulus)
 }
@@ -0,0 +1,20 @@
+/*
+ This file contains the interface of the Strassen-Winograd matrix multiplication
+*/
+
+/// Порог по умолчанию: блоки с наименьшей стороной не больше 64 умножаются обычным способом
+pub const DEFAULT_STRASSEN_CUTOFF: usize = 64;
+
+/// Умножение матриц по схеме Штрассена в варианте Винограда: 7 умножений
+/// и 15 сложений блоков на уровень рекурсии.
+///
+/// Рекурсия продолжается, пока наименьшая из трёх размерностей произведения
+/// больше cutoff; каждая размерность дополняется нулями до кратной 2^depth,
+/// поэтому операнды могут быть прямоугольными любого размера. Семь
+/// произведений уровня вычисляются параллельно, листья рекурсии - через
+/// Matrix::mult. Выгода появляется, когда умножение элементов намного дороже
+/// сложения, как у многочленов; порог подбирается под тип элементов.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatrixStrassen {
+    pub cutoff: usize,
+}
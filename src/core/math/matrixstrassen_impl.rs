@@ -0,0 +1,146 @@
+/*
+ This code provides the Strassen-Winograd multiplication of generic matrices
+*/
+use super::matrix::Matrix;
+use super::matrixstrassen::{MatrixStrassen, DEFAULT_STRASSEN_CUTOFF};
+use rayon::prelude::*;
+use std::ops::{Add, Mul, Sub};
+
+impl Default for MatrixStrassen {
+    fn default() -> Self {
+        Self::new(DEFAULT_STRASSEN_CUTOFF)
+    }
+}
+
+impl MatrixStrassen {
+    pub fn new(cutoff: usize) -> Self {
+        assert!(cutoff > 0, "The Strassen cutoff must be positive");
+        Self { cutoff }
+    }
+
+    /// Число уровней рекурсии для произведения rows x inner на inner x cols
+    pub fn depth(&self, rows: usize, inner: usize, cols: usize) -> u32 {
+        let mut side = rows.min(inner).min(cols);
+        let mut depth = 0;
+        while side > self.cutoff {
+            side = side.div_ceil(2);
+            depth += 1;
+        }
+        depth
+    }
+
+    /// Произведение a b
+    pub fn mult<T>(&self, a: &Matrix<T>, b: &Matrix<T>) -> Matrix<T>
+    where
+        T: Clone + Send + Sync + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+    {
+        if a.get_cols() != b.get_rows() {
+            panic!("incompatible matrix multiplication");
+        }
+        let (rows, inner, cols) = (a.get_rows(), a.get_cols(), b.get_cols());
+        let depth = self.depth(rows, inner, cols);
+        if depth == 0 {
+            return a.mult(b);
+        }
+        let padded = |n: usize| n.div_ceil(1 << depth) << depth;
+        if (padded(rows), padded(inner), padded(cols)) == (rows, inner, cols) {
+            return multiply(a, b, depth);
+        }
+        let product = multiply(
+            &pad(a, padded(rows), padded(inner)),
+            &pad(b, padded(inner), padded(cols)),
+            depth,
+        );
+        pad(&product, rows, cols)
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Clone + Send + Sync + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+    /// Произведение по Штрассену-Винограду с порогом DEFAULT_STRASSEN_CUTOFF
+    pub fn mult_strassen(&self, other: &Self) -> Self {
+        MatrixStrassen::default().mult(self, other)
+    }
+}
+
+/// Рекурсивное умножение; все размерности кратны 2^depth
+fn multiply<T>(a: &Matrix<T>, b: &Matrix<T>, depth: u32) -> Matrix<T>
+where
+    T: Clone + Send + Sync + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+    if depth == 0 {
+        return a.mult(b);
+    }
+    let [a11, a12, a21, a22] = split(a);
+    let [b11, b12, b21, b22] = split(b);
+
+    let s1 = &a21 + &a22;
+    let s2 = &s1 - &a11;
+    let s3 = &a11 - &a21;
+    let s4 = &a12 - &s2;
+    let t1 = &b12 - &b11;
+    let t2 = &b22 - &t1;
+    let t3 = &b22 - &b12;
+    let t4 = &t2 - &b21;
+
+    let products: Vec<Matrix<T>> = [
+        (&a11, &b11),
+        (&a12, &b21),
+        (&s4, &b22),
+        (&a22, &t4),
+        (&s1, &t1),
+        (&s2, &t2),
+        (&s3, &t3),
+    ]
+    .par_iter()
+    .map(|(x, y)| multiply(x, y, depth - 1))
+    .collect();
+    let [p1, p2, p3, p4, p5, p6, p7] = <[Matrix<T>; 7]>::try_from(products).ok().unwrap();
+
+    let u2 = &p1 + &p6;
+    let u3 = &u2 + &p7;
+    let u4 = &u2 + &p5;
+    let mut result = a.zero_like(a.get_rows(), b.get_cols());
+    for (block, (row0, col0)) in [
+        (&p1 + &p2, (0, 0)),
+        (&u4 + &p3, (0, 1)),
+        (&u3 - &p4, (1, 0)),
+        (&u3 + &p5, (1, 1)),
+    ] {
+        let (rows, cols) = (block.get_rows(), block.get_cols());
+        for row in 0..rows {
+            result.row_mut(row0 * rows + row)[col0 * cols..(col0 + 1) * cols]
+                .clone_from_slice(block.row(row));
+        }
+    }
+    result
+}
+
+/// Блоки [[11, 12], [21, 22]] матрицы чётного размера
+fn split<T: Clone>(m: &Matrix<T>) -> [Matrix<T>; 4] {
+    let (half_rows, half_cols) = (m.get_rows() / 2, m.get_cols() / 2);
+    let block = |row0: usize, col0: usize| {
+        let data = (row0..row0 + half_rows)
+            .flat_map(|row| m.row(row)[col0..col0 + half_cols].to_vec())
+            .collect();
+        Matrix::from_data(m.get_allocator().clone(), half_rows, half_cols, data)
+    };
+    [
+        block(0, 0),
+        block(0, half_cols),
+        block(half_rows, 0),
+        block(half_rows, half_cols),
+    ]
+}
+
+/// Левый верхний угол rows x cols матрицы m, дополненный нулями при необходимости
+fn pad<T: Clone>(m: &Matrix<T>, rows: usize, cols: usize) -> Matrix<T> {
+    let mut result = m.zero_like(rows, cols);
+    let width = cols.min(m.get_cols());
+    for row in 0..rows.min(m.get_rows()) {
+        result.row_mut(row)[..width].clone_from_slice(&m.row(row)[..width]);
+    }
+    result
+}
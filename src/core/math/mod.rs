@@ -1 +1,16 @@
+pub mod binaryuniformgenerator;
+pub mod chebyshev;
+pub mod dftransform;
+pub mod discretegaussiangenerator;
+pub mod discreteuniformgenerator;
+pub mod distributiongenerator;
 pub mod hal;
+pub mod matrix;
+pub mod matrix_impl;
+pub mod matrixstrassen;
+pub mod matrixstrassen_impl;
+pub mod nbtheory;
+pub mod nbtheory_impl;
+pub mod rnsbase;
+pub mod rnsbase_impl;
+pub mod ternaryuniformgenerator;
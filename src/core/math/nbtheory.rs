@@ -0,0 +1,116 @@
+/*
+  This code provides number theory utilities: primality, prime generation, roots of unity,
+  totients, cyclotomic polynomials and bit reversal
+*/
+pub use super::nbtheory_impl::*;
+
+/// Номер старшего ненулевого бита, считая с 1 (0 для нуля)
+pub fn get_msb64(x: u64) -> u32 {
+    u64::BITS - x.leading_zeros()
+}
+
+/// Обращает порядок младших msb битов числа num
+pub fn reverse_bits(num: u64, msb: u32) -> u64 {
+    if msb == 0 {
+        return 0;
+    }
+    num.reverse_bits() >> (u64::BITS - msb)
+}
+
+/// Переставляет элементы вектора длины 2^k в порядке обращения битов индекса
+pub fn bit_reverse_permute<T>(vals: &mut [T]) {
+    let size = vals.len();
+    assert!(
+        size.is_power_of_two(),
+        "The vector size must be a power of 2"
+    );
+    let msb = get_msb64(size as u64) - 1;
+    for i in 0..size {
+        let j = reverse_bits(i as u64, msb) as usize;
+        if i < j {
+            vals.swap(i, j);
+        }
+    }
+}
+
+/// Перестановка значений для автоморфизма x -> x^k многочлена по модулю
+/// x^n + 1 в представлении значений с обращением битов:
+/// result[rev(j)] = values[precomp[rev(j)]]. Элемент с номером rev(j) - значение
+/// в ψ^(2j + 1), поэтому он берётся из значения в ψ^((2j + 1) k mod 2n).
+pub fn precompute_auto_map(n: usize, k: u32) -> Vec<u32> {
+    assert!(
+        n.is_power_of_two(),
+        "The ring dimension must be a power of 2"
+    );
+    assert!(k % 2 == 1, "Automorphism index not odd");
+    let m = 2 * n as u64;
+    let logn = get_msb64(n as u64) - 1;
+    let mut precomp = vec![0; n];
+    for j in 0..n as u64 {
+        let idx = ((2 * j + 1) * k as u64 % m) >> 1;
+        precomp[reverse_bits(j, logn) as usize] = reverse_bits(idx, logn) as u32;
+    }
+    precomp
+}
+
+/// Функция Эйлера: количество чисел от 1 до n, взаимно простых с n
+pub fn get_totient(n: u64) -> u64 {
+    prime_factorize(&n)
+        .iter()
+        .fold(n, |totient, p| totient / p * (p - 1))
+}
+
+/// Числа от 1 до n, взаимно простые с n
+pub fn get_totient_list(n: u64) -> Vec<u64> {
+    if n == 1 {
+        return vec![1];
+    }
+    (1..n)
+        .filter(|k| greatest_common_divisor(k, &n) == 1)
+        .collect()
+}
+
+/// Функция Мёбиуса
+pub fn mobius(n: u64) -> i64 {
+    let factors = prime_factorize(&n);
+    if factors.iter().product::<u64>() != n {
+        0
+    } else if factors.len().is_multiple_of(2) {
+        1
+    } else {
+        -1
+    }
+}
+
+/// Целочисленные коэффициенты многочлена деления круга Φ_m по возрастанию
+/// степеней: Φ_m(X) = prod_{d | m} (X^d - 1)^μ(m/d).
+pub fn get_cyclotomic_polynomial_coefficients(m: u64) -> Vec<i64> {
+    assert!(m >= 1, "The cyclotomic order must be positive");
+    let divisors: Vec<u64> = (1..=m).filter(|&d| m.is_multiple_of(d)).collect();
+
+    // Сначала умножаем на все множители со степенью +1, затем делим на
+    // множители со степенью -1: все деления точные.
+    let mut poly: Vec<i128> = vec![1];
+    for &d in divisors.iter().filter(|&&d| mobius(m / d) == 1) {
+        let d = d as usize;
+        let mut res = vec![0; poly.len() + d];
+        for (i, &c) in poly.iter().enumerate() {
+            res[i + d] += c;
+            res[i] -= c;
+        }
+        poly = res;
+    }
+    for &d in divisors.iter().filter(|&&d| mobius(m / d) == -1) {
+        // poly / (X^d - 1) = -poly * (1 + X^d + X^2d + ...)
+        let d = d as usize;
+        let mut res = vec![0; poly.len() - d];
+        for i in 0..res.len() {
+            res[i] = -poly[i] + if i >= d { res[i - d] } else { 0 };
+        }
+        poly = res;
+    }
+
+    poly.iter()
+        .map(|&c| i64::try_from(c).expect("The cyclotomic coefficient does not fit into i64"))
+        .collect()
+}
@@ -0,0 +1,306 @@
+/*
+  This code provides number theory utilities that are generic over the integer backends
+*/
+use super::hal::integer::IntegerInterface;
+use std::collections::BTreeSet;
+
+/// Основания детерминированного теста Миллера-Рабина: первых 12 простых
+/// достаточно для всех n < 3.3 * 10^24 (в том числе для всех 64-битных n).
+/// Для больших чисел дополнительно проверяются основания до 131, и тест
+/// остаётся детерминированным, но уже не доказанно точным.
+const MILLER_RABIN_BASES: [u64; 32] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131,
+];
+
+/// Граница, ниже которой первых 12 оснований достаточно (около 2^81)
+const MILLER_RABIN_PROVEN_BITS: u32 = 81;
+
+/// Наибольший общий делитель
+pub fn greatest_common_divisor<T: IntegerInterface>(a: &T, b: &T) -> T {
+    let (mut a, mut b) = (a.clone(), b.clone());
+    while !b.is_zero() {
+        let r = a % b.clone();
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// a^e mod modulus
+pub fn mod_exp<T: IntegerInterface>(a: &T, e: &T, modulus: &T) -> T {
+    a.mod_exp(e, modulus)
+}
+
+/// Обратный к a по модулю modulus расширенным алгоритмом Евклида.
+/// Возвращает None, если a и modulus не взаимно просты.
+pub fn mod_inverse<T: IntegerInterface>(a: &T, modulus: &T) -> Option<T> {
+    // Коэффициенты Безу хранятся по модулю modulus, чтобы обойтись без знака
+    let (mut r0, mut r1) = (modulus.clone(), a.clone() % modulus.clone());
+    let (mut t0, mut t1) = (T::zero(), T::one());
+    while !r1.is_zero() {
+        let q = r0.clone() / r1.clone();
+        let r = r0.clone() % r1.clone();
+        let t = t0.mod_sub(&q.mod_mul(&t1, modulus), modulus);
+        r0 = r1;
+        r1 = r;
+        t0 = t1;
+        t1 = t;
+    }
+    if r0 == T::one() {
+        Some(t0 % modulus.clone())
+    } else {
+        None
+    }
+}
+
+/// Детерминированный тест Миллера-Рабина
+pub fn miller_rabin_primality_test<T: IntegerInterface>(p: &T) -> bool {
+    let two = T::from_u64(2);
+    if *p < two {
+        return false;
+    }
+    for &base in MILLER_RABIN_BASES.iter() {
+        let base = T::from_u64(base);
+        if *p == base {
+            return true;
+        }
+        if (p.clone() % base).is_zero() {
+            return false;
+        }
+    }
+
+    // p - 1 = d * 2^s, d нечётно
+    let p_minus_one = p.clone() - T::one();
+    let mut d = p_minus_one.clone();
+    let mut s = 0;
+    while !d.get_bit_at_index(0) {
+        d = d / two.clone();
+        s += 1;
+    }
+
+    let rounds = if p.get_msb() <= MILLER_RABIN_PROVEN_BITS {
+        12
+    } else {
+        MILLER_RABIN_BASES.len()
+    };
+    MILLER_RABIN_BASES[..rounds].iter().all(|&base| {
+        let mut x = T::from_u64(base).mod_exp(&d, p);
+        if x == T::one() || x == p_minus_one {
+            return true;
+        }
+        for _ in 1..s {
+            x = x.mod_mul(&x, p);
+            if x == p_minus_one {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// Находит нетривиальный делитель составного n rho-методом Полларда
+/// с поиском цикла по Флойду. Многочлены x^2 + c перебираются детерминированно.
+pub fn pollard_rho_factorization<T: IntegerInterface>(n: &T) -> T {
+    if !n.get_bit_at_index(0) {
+        return T::from_u64(2);
+    }
+
+    let mut c = T::one();
+    loop {
+        let f = |x: &T| x.mod_mul(x, n).mod_add(&c, n);
+        let (mut x, mut y) = (T::from_u64(2), T::from_u64(2));
+        let mut divisor = T::one();
+        while divisor == T::one() {
+            x = f(&x);
+            y = f(&f(&y));
+            let diff = if x > y {
+                x.clone() - y.clone()
+            } else {
+                y.clone() - x.clone()
+            };
+            divisor = greatest_common_divisor(&diff, n);
+        }
+        if divisor != *n {
+            return divisor;
+        }
+        c = c + T::one();
+    }
+}
+
+/// Множество простых делителей n
+pub fn prime_factorize<T: IntegerInterface>(n: &T) -> BTreeSet<T> {
+    let mut factors = BTreeSet::new();
+    prime_factorize_into(n.clone(), &mut factors);
+    factors
+}
+
+fn prime_factorize_into<T: IntegerInterface>(mut n: T, factors: &mut BTreeSet<T>) {
+    if n <= T::one() {
+        return;
+    }
+
+    // Маленькие делители отделяем пробным делением: rho-метод на них зацикливается
+    for &p in MILLER_RABIN_BASES.iter() {
+        let p = T::from_u64(p);
+        while (n.clone() % p.clone()).is_zero() {
+            factors.insert(p.clone());
+            n = n / p.clone();
+        }
+    }
+    if n == T::one() {
+        return;
+    }
+
+    if miller_rabin_primality_test(&n) {
+        factors.insert(n);
+        return;
+    }
+    let divisor = pollard_rho_factorization(&n);
+    prime_factorize_into(n.clone() / divisor.clone(), factors);
+    prime_factorize_into(divisor, factors);
+}
+
+/// Первообразный корень по простому модулю q: наименьший g, для которого
+/// g^((q-1)/p) != 1 при каждом простом делителе p числа q - 1.
+pub fn find_generator<T: IntegerInterface>(q: &T) -> T {
+    let q_minus_one = q.clone() - T::one();
+    let factors = prime_factorize(&q_minus_one);
+
+    let mut g = T::from_u64(2);
+    while g < *q {
+        let is_generator = factors
+            .iter()
+            .all(|p| g.mod_exp(&(q_minus_one.clone() / p.clone()), q) != T::one());
+        if is_generator {
+            return g;
+        }
+        g = g + T::one();
+    }
+    T::one()
+}
+
+/// Первообразный корень степени m из единицы по простому модулю q, m | q - 1.
+/// Из всех первообразных корней степени m возвращается наименьший, поэтому
+/// результат не зависит от выбора порождающего элемента.
+pub fn root_of_unity<T: IntegerInterface>(m: u64, q: &T) -> T {
+    let big_m = T::from_u64(m);
+    let q_minus_one = q.clone() - T::one();
+    if !(q_minus_one.clone() % big_m.clone()).is_zero() {
+        panic!(
+            "Please provide a primeModulus(q) and a cyclotomic number(m) satisfying the condition: (q-1)/m is an integer. q = {}, m = {}",
+            q, m
+        )
+    }
+
+    let generator = find_generator(q);
+    let root = generator.mod_exp(&(q_minus_one / big_m), q);
+
+    // Первообразные корни степени m - это root^k при k, взаимно простых с m
+    let mut min_root = root.clone();
+    let mut x = root.clone();
+    for k in 2..m {
+        x = x.mod_mul(&root, q);
+        if greatest_common_divisor(&k, &m) == 1 && x < min_root {
+            min_root = x.clone();
+        }
+    }
+    min_root
+}
+
+/// Наименьшее простое q = 1 mod m, большее 2^n_bits (q занимает n_bits + 1 бит)
+pub fn first_prime<T: IntegerInterface>(n_bits: u32, m: u64) -> T {
+    let big_m = T::from_u64(m);
+    let q = T::from_u64(2).mod_exp(&T::from_u64(n_bits as u64), &big_m);
+    let power = power_of_two::<T>(n_bits);
+    let mut candidate = power.clone() + T::one() - q.clone();
+    if !q.is_zero() {
+        candidate = candidate + big_m.clone();
+    }
+
+    while !miller_rabin_primality_test(&candidate) {
+        candidate = candidate + big_m.clone();
+    }
+    if candidate.get_msb() != n_bits + 1 {
+        panic!(
+            "FirstPrime: no prime of {} bits with q = 1 mod {}",
+            n_bits + 1,
+            m
+        )
+    }
+    candidate
+}
+
+/// Наибольшее простое q = 1 mod m, меньшее 2^n_bits (q занимает n_bits бит)
+pub fn last_prime<T: IntegerInterface>(n_bits: u32, m: u64) -> T {
+    let big_m = T::from_u64(m);
+    let r = T::from_u64(2).mod_exp(&T::from_u64(n_bits as u64), &big_m);
+    let power = power_of_two::<T>(n_bits);
+    // Наибольшее число вида 1 + k*m, меньшее 2^n_bits
+    let mut candidate = if r.is_zero() {
+        power - big_m.clone() + T::one()
+    } else {
+        power - r + T::one()
+    };
+
+    while !miller_rabin_primality_test(&candidate) {
+        if candidate <= big_m {
+            panic!(
+                "LastPrime: no prime of {} bits with q = 1 mod {}",
+                n_bits, m
+            )
+        }
+        candidate = candidate - big_m.clone();
+    }
+    if candidate.get_msb() != n_bits {
+        panic!(
+            "LastPrime: no prime of {} bits with q = 1 mod {}",
+            n_bits, m
+        )
+    }
+    candidate
+}
+
+/// Следующее простое q' = 1 mod m после q (q = 1 mod m)
+pub fn next_prime<T: IntegerInterface>(q: &T, m: u64) -> T {
+    let big_m = T::from_u64(m);
+    let mut candidate = q.clone() + big_m.clone();
+    while !miller_rabin_primality_test(&candidate) {
+        candidate = candidate + big_m.clone();
+    }
+    candidate
+}
+
+/// Предыдущее простое q' = 1 mod m перед q (q = 1 mod m)
+pub fn previous_prime<T: IntegerInterface>(q: &T, m: u64) -> T {
+    let big_m = T::from_u64(m);
+    let mut candidate = q.clone();
+    loop {
+        if candidate <= big_m {
+            panic!("PreviousPrime: no prime q' = 1 mod {} below {}", m, q)
+        }
+        candidate = candidate - big_m.clone();
+        if miller_rabin_primality_test(&candidate) {
+            return candidate;
+        }
+    }
+}
+
+/// Многочлен деления круга Φ_m по модулю q, коэффициенты по возрастанию степеней
+pub fn get_cyclotomic_polynomial<T: IntegerInterface>(m: u64, modulus: &T) -> Vec<T> {
+    super::nbtheory::get_cyclotomic_polynomial_coefficients(m)
+        .iter()
+        .map(|&c| {
+            let abs = T::from_u64(c.unsigned_abs()) % modulus.clone();
+            if c < 0 {
+                T::zero().mod_sub(&abs, modulus)
+            } else {
+                abs
+            }
+        })
+        .collect()
+}
+
+fn power_of_two<T: IntegerInterface>(n_bits: u32) -> T {
+    (0..n_bits).fold(T::one(), |x, _| x.clone() + x)
+}
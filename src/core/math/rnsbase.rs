@@ -0,0 +1,47 @@
+/*
+ This file contains the RNS bases and the precomputed tables of the CRT base conversion
+*/
+use super::hal::integer::{BigInteger, BigIntegerInterface, NativeInteger};
+use serde::{Deserialize, Serialize};
+
+/// Основание RNS: попарно взаимно простые машинные модули q_0..q_(k-1),
+/// Q = q_0 ... q_(k-1), и таблицы CRT: q̂_i = Q / q_i, [q̂_i^-1]_(q_i) с
+/// константами Шоупа и 1 / q_i.
+///
+/// Число x из [0, Q) восстанавливается по остаткам x_i как
+/// sum_i y_i q̂_i mod Q, где y_i = [x_i q̂_i^-1]_(q_i).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct RnsBaseT<I: BigIntegerInterface> {
+    pub(super) moduli: Vec<NativeInteger>,
+    #[serde(with = "super::rnsbase_impl::big_integer")]
+    pub(super) modulus: I,
+    #[serde(with = "super::rnsbase_impl::big_integers")]
+    pub(super) q_hat: Vec<I>,
+    pub(super) q_hat_inv_mod_q: Vec<NativeInteger>,
+    pub(super) q_hat_inv_mod_q_precon: Vec<NativeInteger>,
+    pub(super) q_inv: Vec<f64>,
+}
+
+/// Таблицы преобразования остатков из основания Q = q_0..q_(k-1) в
+/// основание P = p_0..p_(l-1) (аналог SwitchCRTBasis и ApproxSwitchCRTBasis
+/// в OpenFHE): q̂_i mod p_j и alpha Q mod p_j для alpha = 0..k.
+///
+/// Быстрое преобразование вычисляет sum_i y_i q̂_i mod p_j, то есть x + u Q
+/// с 0 <= u < k. Точное вычитает u Q, оценив u = round(sum_i y_i / q_i) в
+/// числах с плавающей точкой, и даёт остатки центрированного представителя
+/// x из [-Q/2, Q/2).
+///
+/// Таблицы для пары оснований кэшируются глобально, см. get и reset.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct RnsBaseConverterT<I: BigIntegerInterface> {
+    pub(super) from: RnsBaseT<I>,
+    pub(super) to: RnsBaseT<I>,
+    pub(super) q_hat_mod_p: Vec<Vec<NativeInteger>>,
+    pub(super) alpha_q_mod_p: Vec<Vec<NativeInteger>>,
+}
+
+/// Основание RNS над бэкендом длинной арифметики, выбранным признаком Cargo
+pub type RnsBase = RnsBaseT<BigInteger>;
+pub type RnsBaseConverter = RnsBaseConverterT<BigInteger>;
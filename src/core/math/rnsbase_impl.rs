@@ -0,0 +1,355 @@
+/*
+ This code provides the CRT reconstruction and the fast base conversion of RNS residues
+*/
+use super::hal::basicint::MAX_MODULUS_SIZE;
+use super::hal::integer::{BigIntegerInterface, IntegerInterface, NativeInteger};
+use super::hal::vector::NativeVector;
+use super::nbtheory_impl::greatest_common_divisor;
+use super::rnsbase::{RnsBaseConverterT, RnsBaseT};
+use lazy_static::lazy_static;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+impl<I: BigIntegerInterface> RnsBaseT<I> {
+    pub fn new(moduli: Vec<NativeInteger>) -> Self {
+        assert!(
+            !moduli.is_empty(),
+            "The RNS base must contain at least one modulus"
+        );
+        for (i, q) in moduli.iter().enumerate() {
+            if q.value() < 2 || q.get_msb() > MAX_MODULUS_SIZE {
+                panic!(
+                    "The RNS modulus {} must be in [2, 2^{})",
+                    q, MAX_MODULUS_SIZE
+                );
+            }
+            if moduli[..i]
+                .iter()
+                .any(|p| greatest_common_divisor(p, q) != NativeInteger::one())
+            {
+                panic!("The RNS moduli must be pairwise coprime");
+            }
+        }
+
+        let modulus = moduli
+            .iter()
+            .fold(I::one(), |acc, q| acc * I::from_u64(q.value()));
+        let q_hat: Vec<I> = moduli
+            .iter()
+            .map(|q| modulus.clone() / I::from_u64(q.value()))
+            .collect();
+        let q_hat_inv_mod_q: Vec<NativeInteger> = q_hat
+            .iter()
+            .zip(&moduli)
+            .map(|(q_hat, q)| residue(q_hat, q).mod_inverse(q))
+            .collect();
+        let q_hat_inv_mod_q_precon = q_hat_inv_mod_q
+            .iter()
+            .zip(&moduli)
+            .map(|(x, q)| x.prep_mod_mul_const(q))
+            .collect();
+        let q_inv = moduli.iter().map(|q| 1.0 / q.convert_to_double()).collect();
+        Self {
+            moduli,
+            modulus,
+            q_hat,
+            q_hat_inv_mod_q,
+            q_hat_inv_mod_q_precon,
+            q_inv,
+        }
+    }
+
+    pub fn get_moduli(&self) -> &[NativeInteger] {
+        &self.moduli
+    }
+
+    /// Q
+    pub fn get_modulus(&self) -> &I {
+        &self.modulus
+    }
+
+    /// Q / q_i
+    pub fn get_q_hat(&self) -> &[I] {
+        &self.q_hat
+    }
+
+    /// [(Q / q_i)^-1]_(q_i)
+    pub fn get_q_hat_inv_mod_q(&self) -> &[NativeInteger] {
+        &self.q_hat_inv_mod_q
+    }
+
+    /// Константы Шоупа для get_q_hat_inv_mod_q
+    pub fn get_q_hat_inv_mod_q_precon(&self) -> &[NativeInteger] {
+        &self.q_hat_inv_mod_q_precon
+    }
+
+    /// 1 / q_i
+    pub fn get_q_inv(&self) -> &[f64] {
+        &self.q_inv
+    }
+
+    pub fn len(&self) -> usize {
+        self.moduli.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moduli.is_empty()
+    }
+
+    /// Остатки x mod q_i
+    pub fn decompose(&self, x: &I) -> Vec<NativeInteger> {
+        self.moduli.iter().map(|q| residue(x, q)).collect()
+    }
+
+    /// x из [0, Q) по остаткам x_i (точная CRT-реконструкция)
+    pub fn reconstruct(&self, residues: &[NativeInteger]) -> I {
+        self.check_length(residues.len());
+        let mut scaled = vec![NativeInteger::default(); self.len()];
+        self.scale_residues(residues, &mut scaled);
+        scaled
+            .iter()
+            .zip(&self.q_hat)
+            .fold(I::zero(), |acc, (y, q_hat)| {
+                acc + q_hat.clone() * I::from_u64(y.value())
+            })
+            .modulo(&self.modulus)
+    }
+
+    /// y_i = [x_i q̂_i^-1]_(q_i)
+    pub fn scale_residues(&self, residues: &[NativeInteger], scaled: &mut [NativeInteger]) {
+        for (i, (y, x)) in scaled.iter_mut().zip(residues).enumerate() {
+            *y = x.mod_mul_fast_const(
+                &self.q_hat_inv_mod_q[i],
+                &self.moduli[i],
+                &self.q_hat_inv_mod_q_precon[i],
+            );
+        }
+    }
+
+    fn check_length(&self, length: usize) {
+        if length != self.len() {
+            panic!("Expected {} RNS residues, got {}", self.len(), length);
+        }
+    }
+}
+
+impl<I: BigIntegerInterface> RnsBaseConverterT<I> {
+    pub fn new(from: RnsBaseT<I>, to: RnsBaseT<I>) -> Self {
+        let q_hat_mod_p = from.q_hat.iter().map(|q_hat| to.decompose(q_hat)).collect();
+        let q_mod_p = to.decompose(&from.modulus);
+        let alpha_q_mod_p = (0..=from.len() as u64)
+            .map(|alpha| {
+                q_mod_p
+                    .iter()
+                    .zip(&to.moduli)
+                    .map(|(q, p)| q.mod_mul(&NativeInteger::new(alpha).modulo(p), p))
+                    .collect()
+            })
+            .collect();
+        Self {
+            from,
+            to,
+            q_hat_mod_p,
+            alpha_q_mod_p,
+        }
+    }
+
+    pub fn get_from(&self) -> &RnsBaseT<I> {
+        &self.from
+    }
+
+    pub fn get_to(&self) -> &RnsBaseT<I> {
+        &self.to
+    }
+
+    /// [q̂_i]_(p_j), индексы [i][j]
+    pub fn get_q_hat_mod_p(&self) -> &[Vec<NativeInteger>] {
+        &self.q_hat_mod_p
+    }
+
+    /// [alpha Q]_(p_j), индексы [alpha][j], alpha = 0..k
+    pub fn get_alpha_q_mod_p(&self) -> &[Vec<NativeInteger>] {
+        &self.alpha_q_mod_p
+    }
+
+    /// Остатки x + u Q по модулям P, 0 <= u < k
+    pub fn fast_base_conv(&self, residues: &[NativeInteger]) -> Vec<NativeInteger> {
+        self.from.check_length(residues.len());
+        let mut result = vec![NativeInteger::default(); self.to.len()];
+        self.convert(
+            residues,
+            false,
+            &mut vec![NativeInteger::default(); self.from.len()],
+            &mut result,
+        );
+        result
+    }
+
+    /// Остатки центрированного представителя x из [-Q/2, Q/2) по модулям P
+    pub fn exact_base_conv(&self, residues: &[NativeInteger]) -> Vec<NativeInteger> {
+        self.from.check_length(residues.len());
+        let mut result = vec![NativeInteger::default(); self.to.len()];
+        self.convert(
+            residues,
+            true,
+            &mut vec![NativeInteger::default(); self.from.len()],
+            &mut result,
+        );
+        result
+    }
+
+    /// fast_base_conv для каждого коэффициента многочлена в CRT-представлении
+    pub fn fast_base_conv_vectors(&self, towers: &[NativeVector]) -> Vec<NativeVector> {
+        self.convert_vectors(towers, false)
+    }
+
+    /// exact_base_conv для каждого коэффициента многочлена в CRT-представлении
+    pub fn exact_base_conv_vectors(&self, towers: &[NativeVector]) -> Vec<NativeVector> {
+        self.convert_vectors(towers, true)
+    }
+
+    fn convert(
+        &self,
+        residues: &[NativeInteger],
+        exact: bool,
+        scaled: &mut [NativeInteger],
+        result: &mut [NativeInteger],
+    ) {
+        self.from.scale_residues(residues, scaled);
+        for (j, (out, p)) in result.iter_mut().zip(&self.to.moduli).enumerate() {
+            *out = scaled
+                .iter()
+                .zip(&self.q_hat_mod_p)
+                .fold(NativeInteger::default(), |acc, (y, q_hat)| {
+                    acc.mod_add(&y.mod_mul(&q_hat[j], p), p)
+                });
+        }
+        if exact {
+            let alpha = scaled
+                .iter()
+                .zip(&self.from.q_inv)
+                .map(|(y, q_inv)| y.convert_to_double() * q_inv)
+                .sum::<f64>()
+                .round() as usize;
+            for ((out, p), alpha_q) in result
+                .iter_mut()
+                .zip(&self.to.moduli)
+                .zip(&self.alpha_q_mod_p[alpha])
+            {
+                *out = out.mod_sub(alpha_q, p);
+            }
+        }
+    }
+
+    fn convert_vectors(&self, towers: &[NativeVector], exact: bool) -> Vec<NativeVector> {
+        self.from.check_length(towers.len());
+        for (tower, q) in towers.iter().zip(&self.from.moduli) {
+            if tower.get_modulus() != q || tower.get_length() != towers[0].get_length() {
+                panic!("The towers do not match the RNS base");
+            }
+        }
+        let n = towers[0].get_length();
+        let mut result: Vec<NativeVector> = self
+            .to
+            .moduli
+            .iter()
+            .map(|p| NativeVector::new(n, *p))
+            .collect();
+        let mut residues = vec![NativeInteger::default(); self.from.len()];
+        let mut scaled = residues.clone();
+        let mut converted = vec![NativeInteger::default(); self.to.len()];
+        for c in 0..n {
+            for (x, tower) in residues.iter_mut().zip(towers) {
+                *x = tower[c];
+            }
+            self.convert(&residues, exact, &mut scaled, &mut converted);
+            for (tower, y) in result.iter_mut().zip(&converted) {
+                tower[c] = *y;
+            }
+        }
+        result
+    }
+}
+
+impl<I: BigIntegerInterface + Send + Sync + 'static> RnsBaseConverterT<I> {
+    /// Таблицы преобразования из основания from в основание to из глобального кэша
+    pub fn get(from: &[NativeInteger], to: &[NativeInteger]) -> Arc<Self> {
+        let key = (TypeId::of::<I>(), from.to_vec(), to.to_vec());
+        if let Some(converter) = CONVERTERS.read().unwrap().get(&key) {
+            return converter.clone().downcast().unwrap();
+        }
+        let converter = Arc::new(Self::new(
+            RnsBaseT::new(from.to_vec()),
+            RnsBaseT::new(to.to_vec()),
+        ));
+        CONVERTERS.write().unwrap().insert(key, converter.clone());
+        converter
+    }
+
+    /// Очищает кэш таблиц для всех бэкендов
+    pub fn reset() {
+        CONVERTERS.write().unwrap().clear();
+    }
+}
+
+/// x mod q
+fn residue<I: BigIntegerInterface>(x: &I, q: &NativeInteger) -> NativeInteger {
+    NativeInteger::new(x.modulo(&I::from_u64(q.value())).to_u64().unwrap())
+}
+
+/// Длинные целые сериализуются десятичной строкой, одинаковой для всех бэкендов
+pub(super) mod big_integer {
+    use super::BigIntegerInterface;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<I: BigIntegerInterface, S: Serializer>(
+        x: &I,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(x)
+    }
+
+    pub fn deserialize<'de, I: BigIntegerInterface, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<I, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse()
+            .map_err(|_| D::Error::custom(format!("invalid big integer {}", s)))
+    }
+}
+
+pub(super) mod big_integers {
+    use super::BigIntegerInterface;
+    use serde::{de::Error, ser::SerializeSeq, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<I: BigIntegerInterface, S: Serializer>(
+        values: &[I],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(values.len()))?;
+        for x in values {
+            seq.serialize_element(&x.to_string())?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, I: BigIntegerInterface, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<I>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|s| {
+                s.parse()
+                    .map_err(|_| D::Error::custom(format!("invalid big integer {}", s)))
+            })
+            .collect()
+    }
+}
+
+type ConverterKey = (TypeId, Vec<NativeInteger>, Vec<NativeInteger>);
+
+lazy_static! {
+    static ref CONVERTERS: RwLock<HashMap<ConverterKey, Arc<dyn Any + Send + Sync>>> =
+        RwLock::new(HashMap::new());
+}
@@ -0,0 +1,4 @@
+/*
+  This code provides generation of a uniform distribution of ternary values {-1, 0, 1}
+*/
+pub use fhe_core::math::ternary_uniform_generator::*;
@@ -27,7 +27,9 @@ use super::Element;
 ///
 /// реализация для генератора полей:
 /// ```
-/// let gf5 = FieldGenerator::new(5);
+/// use fhe::gf_context::GFContext;
+///
+/// let gf5 = GFContext::new(5);
 /// println!("{:?}",gf5);
 /// println!("{:?}",gf5.element(3));
 /// ```
@@ -40,6 +40,7 @@ impl Mul for GFElement {
 
 impl Div for GFElement {
     type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
     fn div(self, other: Self) -> Self {
         self * other.inv()
     }
@@ -73,7 +74,7 @@ impl GFElement {
     pub fn new(value: Element, modulo: Element) -> Self {
         Self {
             value: value % modulo,
-            modulo: modulo,
+            modulo,
         }
     }
 
@@ -81,7 +82,7 @@ impl GFElement {
         self.value
     }
 
-    pub fn set<T>(&mut self, x: T) -> ()
+    pub fn set<T>(&mut self, x: T)
     where
         Element: From<T>,
     {
@@ -95,7 +96,7 @@ impl GFElement {
         let mut b = self.modulo;
         let (mut x1, mut y1) = (1, 0);
         let (mut x2, mut y2) = (0, 1);
-        while a % b != 0 {
+        while !a.is_multiple_of(b) {
             let q: Element = a / b;
             let r: Element = a % b;
 
@@ -128,8 +129,8 @@ impl fmt::Display for GFElement {
         write!(
             f,
             "{0} mod {1}",
-            self.value.to_string(),
-            self.modulo.to_string()
+            self.value,
+            self.modulo
         )
     }
 }
@@ -0,0 +1,182 @@
+use super::core::math::distributiongenerator::PRNG;
+use super::gf_element::GFElement;
+use super::gf_ext_element::{self, GFExtElement};
+use super::Element;
+use rand::Rng;
+use std::sync::Arc;
+
+/// GFExtContext управляет контекстом поля Галуа GF(p^n) - расширения
+/// простого поля GF(p) степени n.
+///
+/// Элементы расширения - это многочлены степени меньше n с коэффициентами
+/// из GF(p), а все операции выполняются по модулю неприводимого многочлена
+/// f(X) степени n. Неприводимость f гарантирует, что каждый ненулевой
+/// элемент обратим, то есть факторкольцо GF(p)[X]/(f) является полем.
+///
+/// Такие поля нужны для алгебры слотов открытого текста в BGV/BFV:
+/// если t не сравнимо с 1 по модулю m, то каждый слот - это элемент
+/// GF(t^d), где d - порядок t по модулю m.
+///
+/// реализация для поля GF(3^2) = GF(3)[X]/(X^2+1):
+/// ```
+/// use fhe::gf_ext_context::GFExtContext;
+/// let gf9 = GFExtContext::new(3, &[1, 0, 1]);
+/// let x = gf9.element(&[0, 1]);
+/// assert_eq!(gf9.element(&[2, 0]), x.clone() * x);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct GFExtContext {
+    /// Характеристика поля p
+    modulo: Element,
+    /// Неприводимый унитарный многочлен f, коэффициенты по возрастанию степеней
+    poly: Arc<Vec<Element>>,
+}
+
+impl GFExtContext {
+    /// Создаёт поле GF(p)[X]/(f). Многочлен f задаётся коэффициентами по
+    /// возрастанию степеней и должен быть неприводим над GF(p).
+    pub fn new(p: u64, poly: &[u64]) -> Self {
+        let mut poly: Vec<Element> = poly.iter().map(|&x| x % p).collect();
+        gf_ext_element::trim(&mut poly);
+        assert!(
+            poly.len() >= 2,
+            "The field polynomial must have degree >= 1"
+        );
+
+        // Приводим f к унитарному виду, чтобы упростить деление с остатком
+        let lead_inv = gf_ext_element::inv_mod(*poly.last().unwrap(), p);
+        let poly: Vec<Element> = poly
+            .iter()
+            .map(|&x| gf_ext_element::mul_mod(x, lead_inv, p))
+            .collect();
+
+        if !GFExtContext::is_irreducible(p, &poly) {
+            panic!("The field polynomial is not irreducible over GF({})", p)
+        }
+
+        Self {
+            modulo: p,
+            poly: Arc::new(poly),
+        }
+    }
+
+    /// Поле GF(p^n), заданное случайным неприводимым многочленом степени n
+    pub fn random(p: u64, n: usize, prng: &mut PRNG) -> Self {
+        Self::new(p, &GFExtContext::random_irreducible(p, n, prng))
+    }
+
+    pub fn element(&self, x: &[u64]) -> GFExtElement {
+        GFExtElement::new(x, self.modulo, self.poly.clone())
+    }
+
+    pub fn zero(&self) -> GFExtElement {
+        self.element(&[])
+    }
+
+    pub fn one(&self) -> GFExtElement {
+        self.element(&[1])
+    }
+
+    /// Порождающий элемент X расширения
+    pub fn generator(&self) -> GFExtElement {
+        self.element(&[0, 1])
+    }
+
+    /// Элемент расширения по вектору коэффициентов из GF(p)
+    pub fn from_gf_elements(&self, x: &[GFElement]) -> GFExtElement {
+        for e in x {
+            if e.modulo != self.modulo {
+                panic!("This operation is not enclose in its field.")
+            }
+        }
+        let coefficients: Vec<Element> = x.iter().map(|e| e.value).collect();
+        self.element(&coefficients)
+    }
+
+    /// Характеристика поля p
+    pub fn characteristic(&self) -> Element {
+        self.modulo
+    }
+
+    /// Степень расширения n
+    pub fn degree(&self) -> usize {
+        self.poly.len() - 1
+    }
+
+    /// Неприводимый многочлен, задающий поле
+    pub fn poly(&self) -> &[Element] {
+        &self.poly
+    }
+}
+
+impl GFExtContext {
+    /// Тест неприводимости Рабина: унитарный многочлен f степени n неприводим
+    /// над GF(p) тогда и только тогда, когда X^(p^n) = X mod f и
+    /// gcd(X^(p^(n/r)) - X, f) = 1 для каждого простого делителя r числа n.
+    pub fn is_irreducible(p: u64, poly: &[u64]) -> bool {
+        let mut f: Vec<Element> = poly.iter().map(|&x| x % p).collect();
+        gf_ext_element::trim(&mut f);
+        let n = match f.len() {
+            0 | 1 => return false,
+            len => len - 1,
+        };
+        if n == 1 {
+            return true;
+        }
+
+        let x = vec![0, 1];
+        // X^(p^k) mod f для k = 0..n
+        let mut powers = vec![x.clone()];
+        for k in 0..n {
+            let next = gf_ext_element::poly_pow_mod(&powers[k], p as u128, &f, p);
+            powers.push(next);
+        }
+
+        if powers[n] != x {
+            return false;
+        }
+
+        for r in GFExtContext::prime_factors(n) {
+            let h = gf_ext_element::poly_sub(&powers[n / r], &x, p);
+            let g = gf_ext_element::poly_gcd(&f, &h, p);
+            if g.len() != 1 {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Случайный унитарный неприводимый многочлен степени n над GF(p).
+    /// Доля неприводимых многочленов среди унитарных примерно 1/n,
+    /// поэтому поиск перебором завершается быстро.
+    pub fn random_irreducible(p: u64, n: usize, prng: &mut PRNG) -> Vec<Element> {
+        assert!(n >= 1, "The extension degree must be positive");
+        loop {
+            let mut poly: Vec<Element> = (0..n).map(|_| prng.gen_range(0..p)).collect();
+            poly.push(1);
+            if GFExtContext::is_irreducible(p, &poly) {
+                return poly;
+            }
+        }
+    }
+
+    fn prime_factors(mut n: usize) -> Vec<usize> {
+        let mut factors = vec![];
+        let mut d = 2;
+        while d * d <= n {
+            if n.is_multiple_of(d) {
+                factors.push(d);
+                while n.is_multiple_of(d) {
+                    n /= d;
+                }
+            }
+            d += 1;
+        }
+        if n > 1 {
+            factors.push(n);
+        }
+        factors
+    }
+}
@@ -0,0 +1,310 @@
+use super::gf_element::GFElement;
+use super::Element;
+use std::ops::*;
+use std::sync::Arc;
+
+/// Элемент расширения GF(p^n)
+/// --
+/// Многочлен степени меньше n с коэффициентами из GF(p), взятый по модулю
+/// неприводимого многочлена поля. Коэффициенты хранятся по возрастанию
+/// степеней, старшие нулевые коэффициенты отбрасываются.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GFExtElement {
+    pub value: Vec<Element>,
+    pub modulo: Element,
+    pub poly: Arc<Vec<Element>>,
+}
+
+impl GFExtElement {
+    pub fn new(value: &[Element], modulo: Element, poly: Arc<Vec<Element>>) -> Self {
+        let value: Vec<Element> = value.iter().map(|&x| x % modulo).collect();
+        let (_, value) = poly_divmod(&value, &poly, modulo);
+        Self {
+            value,
+            modulo,
+            poly,
+        }
+    }
+
+    /// Степень расширения n
+    pub fn degree(&self) -> usize {
+        self.poly.len() - 1
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// Коэффициенты элемента как вектор из n элементов GF(p)
+    pub fn to_gf_elements(&self) -> Vec<GFElement> {
+        (0..self.degree())
+            .map(|i| GFElement::new(*self.value.get(i).unwrap_or(&0), self.modulo))
+            .collect()
+    }
+
+    /// Возведение в степень e быстрым возведением в квадрат
+    pub fn pow(&self, e: u128) -> Self {
+        let value = poly_pow_mod(&self.value, e, &self.poly, self.modulo);
+        self.with_value(value)
+    }
+
+    /// Отображение Фробениуса x -> x^(p^k). Это автоморфизм поля над GF(p),
+    /// и при k = n он тождественен.
+    pub fn frobenius(&self, k: usize) -> Self {
+        let mut res = self.clone();
+        for _ in 0..k % self.degree() {
+            res = res.pow(self.modulo as u128);
+        }
+        res
+    }
+
+    fn with_value(&self, value: Vec<Element>) -> Self {
+        Self {
+            value,
+            modulo: self.modulo,
+            poly: self.poly.clone(),
+        }
+    }
+
+    fn check(&self, other: &Self) {
+        if self.modulo != other.modulo || self.poly != other.poly {
+            panic!("This operation is not enclose in its field.")
+        }
+    }
+}
+
+impl GFExtElement {
+    /// Обратный элемент через расширенный алгоритм Евклида для многочленов:
+    /// находим u, v такие, что u * a + v * f = gcd(a, f) = 1, тогда a^-1 = u.
+    pub fn inv(&self) -> Self {
+        if self.is_zero() {
+            panic!(
+                "Zero has no inverse in GF({}^{})",
+                self.modulo,
+                self.degree()
+            )
+        }
+        let p = self.modulo;
+
+        let (mut r0, mut r1) = (self.poly.to_vec(), self.value.clone());
+        let (mut u0, mut u1): (Vec<Element>, Vec<Element>) = (vec![], vec![1]);
+        while !r1.is_empty() {
+            let (q, r) = poly_divmod(&r0, &r1, p);
+            let u = poly_sub(&u0, &poly_mul(&q, &u1, p), p);
+            r0 = r1;
+            r1 = r;
+            u0 = u1;
+            u1 = u;
+        }
+
+        // r0 - ненулевая константа, так как f неприводим
+        let c = inv_mod(r0[0], p);
+        let value: Vec<Element> = u0.iter().map(|&x| mul_mod(x, c, p)).collect();
+        let (_, value) = poly_divmod(&value, &self.poly, p);
+        self.with_value(value)
+    }
+}
+
+impl Add for GFExtElement {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        self.check(&other);
+        let value = poly_add(&self.value, &other.value, self.modulo);
+        self.with_value(value)
+    }
+}
+
+impl Sub for GFExtElement {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        self.check(&other);
+        let value = poly_sub(&self.value, &other.value, self.modulo);
+        self.with_value(value)
+    }
+}
+
+impl Neg for GFExtElement {
+    type Output = Self;
+    fn neg(self) -> Self {
+        let value = poly_sub(&[], &self.value, self.modulo);
+        self.with_value(value)
+    }
+}
+
+impl Mul for GFExtElement {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        self.check(&other);
+        let product = poly_mul(&self.value, &other.value, self.modulo);
+        let (_, value) = poly_divmod(&product, &self.poly, self.modulo);
+        self.with_value(value)
+    }
+}
+
+impl Div for GFExtElement {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        self * other.inv()
+    }
+}
+
+impl AddAssign for GFExtElement {
+    fn add_assign(&mut self, other: Self) {
+        *self = self.clone() + other;
+    }
+}
+
+impl SubAssign for GFExtElement {
+    fn sub_assign(&mut self, other: Self) {
+        *self = self.clone() - other;
+    }
+}
+
+impl MulAssign for GFExtElement {
+    fn mul_assign(&mut self, other: Self) {
+        *self = self.clone() * other;
+    }
+}
+
+impl DivAssign for GFExtElement {
+    fn div_assign(&mut self, other: Self) {
+        *self = self.clone() / other;
+    }
+}
+
+use std::fmt;
+impl fmt::Display for GFExtElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for i in 0..self.degree() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", self.value.get(i).unwrap_or(&0))?;
+        }
+        write!(f, "] mod ({0}, {1:?})", self.modulo, self.poly)
+    }
+}
+
+/* Арифметика многочленов над GF(p). Коэффициенты хранятся по возрастанию
+ * степеней, нулевой многочлен - пустой вектор. */
+
+pub(crate) fn mul_mod(a: Element, b: Element, p: Element) -> Element {
+    ((a as u128 * b as u128) % p as u128) as Element
+}
+
+pub(crate) fn inv_mod(a: Element, p: Element) -> Element {
+    // p простое, поэтому a^-1 = a^(p-2) по малой теореме Ферма
+    let mut res = 1;
+    let mut base = a % p;
+    let mut e = p - 2;
+    while e > 0 {
+        if e & 1 == 1 {
+            res = mul_mod(res, base, p);
+        }
+        base = mul_mod(base, base, p);
+        e >>= 1;
+    }
+    res
+}
+
+pub(crate) fn trim(a: &mut Vec<Element>) {
+    while a.last() == Some(&0) {
+        a.pop();
+    }
+}
+
+pub(crate) fn poly_add(a: &[Element], b: &[Element], p: Element) -> Vec<Element> {
+    let mut res: Vec<Element> = (0..a.len().max(b.len()))
+        .map(|i| {
+            let x = *a.get(i).unwrap_or(&0) as u128 + *b.get(i).unwrap_or(&0) as u128;
+            (x % p as u128) as Element
+        })
+        .collect();
+    trim(&mut res);
+    res
+}
+
+pub(crate) fn poly_sub(a: &[Element], b: &[Element], p: Element) -> Vec<Element> {
+    let neg: Vec<Element> = b.iter().map(|&x| if x == 0 { 0 } else { p - x }).collect();
+    poly_add(a, &neg, p)
+}
+
+pub(crate) fn poly_mul(a: &[Element], b: &[Element], p: Element) -> Vec<Element> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let mut res = vec![0; a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            let term = mul_mod(x, y, p) as u128;
+            res[i + j] = ((res[i + j] as u128 + term) % p as u128) as Element;
+        }
+    }
+    trim(&mut res);
+    res
+}
+
+/// Деление с остатком a = q * b + r, deg r < deg b
+pub(crate) fn poly_divmod(
+    a: &[Element],
+    b: &[Element],
+    p: Element,
+) -> (Vec<Element>, Vec<Element>) {
+    let mut r = a.to_vec();
+    trim(&mut r);
+    if r.len() < b.len() {
+        return (vec![], r);
+    }
+
+    let lead_inv = inv_mod(*b.last().unwrap(), p);
+    let mut q = vec![0; r.len() - b.len() + 1];
+    while r.len() >= b.len() {
+        let shift = r.len() - b.len();
+        let c = mul_mod(*r.last().unwrap(), lead_inv, p);
+        q[shift] = c;
+        for (i, &y) in b.iter().enumerate() {
+            let t = mul_mod(c, y, p);
+            r[i + shift] = if r[i + shift] >= t {
+                r[i + shift] - t
+            } else {
+                p - (t - r[i + shift])
+            };
+        }
+        trim(&mut r);
+    }
+    trim(&mut q);
+    (q, r)
+}
+
+/// Наибольший общий делитель, приведённый к унитарному виду
+pub(crate) fn poly_gcd(a: &[Element], b: &[Element], p: Element) -> Vec<Element> {
+    let (mut a, mut b) = (a.to_vec(), b.to_vec());
+    trim(&mut a);
+    trim(&mut b);
+    while !b.is_empty() {
+        let (_, r) = poly_divmod(&a, &b, p);
+        a = b;
+        b = r;
+    }
+    if let Some(&lead) = a.last() {
+        let lead_inv = inv_mod(lead, p);
+        a.iter_mut().for_each(|x| *x = mul_mod(*x, lead_inv, p));
+    }
+    a
+}
+
+/// a^e mod f
+pub(crate) fn poly_pow_mod(a: &[Element], mut e: u128, f: &[Element], p: Element) -> Vec<Element> {
+    let mut res = vec![1];
+    let (_, mut base) = poly_divmod(a, f, p);
+    while e > 0 {
+        if e & 1 == 1 {
+            res = poly_divmod(&poly_mul(&res, &base, p), f, p).1;
+        }
+        base = poly_divmod(&poly_mul(&base, &base, p), f, p).1;
+        e >>= 1;
+    }
+    poly_divmod(&res, f, p).1
+}
@@ -1,14 +1,14 @@
+use crate::core::math::distributiongenerator::{with_prng, PRNG};
 use rand::{distributions::Uniform, Rng};
 use std::fmt::Write;
 use std::sync::Arc;
 
-fn generate_unique_key_id() -> String {
+fn generate_unique_key_id(prng: &mut PRNG) -> String {
     let ints_in_id = 128 / (std::mem::size_of::<u32>() * 8);
     let distribution = Uniform::new_inclusive(0, u32::MAX);
-    let mut rng = rand::thread_rng();
     let mut s = String::new();
     for _ in 0..ints_in_id {
-        write!(s, "{:08x}", rng.sample(&distribution)).unwrap();
+        write!(s, "{:08x}", prng.sample(&distribution)).unwrap();
     }
     s
 }
@@ -41,7 +41,7 @@ impl<Element> PrivateKeyImpl<Element> {
     pub fn new(context: Arc<Element>) -> Self {
         PrivateKeyImpl {
             context,
-            key_tag: generate_unique_key_id(),
+            key_tag: with_prng(generate_unique_key_id),
             m_sk: Default::default(),
         }
     }
@@ -57,7 +57,7 @@ impl<Element> PrivateKeyImpl<Element> {
     pub fn from_parts(context: Arc<Element>, m_sk: Element) -> Self {
         PrivateKeyImpl {
             context,
-            key_tag: generate_unique_key_id(),
+            key_tag: with_prng(generate_unique_key_id),
             m_sk,
         }
     }
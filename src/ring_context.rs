@@ -1,4 +1,5 @@
 use super::ring_element::RingElement;
+use super::ring_poly::RingPoly;
 use super::Element;
 
 /// RingContext: Эта структура хранит modulo, что указывает на модуль,
@@ -23,4 +24,11 @@ impl RingContext {
     pub fn element(&self, x: u64) -> RingElement {
         RingElement::new(x, self.modulo)
     }
+    /// Многочлен кольца Z_q[X]/(X^N+1), где N - количество коэффициентов
+    pub fn poly(&self, coefficients: &[u64]) -> RingPoly {
+        RingPoly::from_coefficients(self, coefficients)
+    }
+    pub fn modulo(&self) -> Element {
+        self.modulo
+    }
 }
@@ -22,8 +22,8 @@ impl fmt::Display for RingElement {
         write!(
             f,
             "{0} mod {1}",
-            self.value.to_string(),
-            self.modulo.to_string()
+            self.value,
+            self.modulo
         )
     }
 }
@@ -32,7 +32,7 @@ impl RingElement {
     pub fn new(value: Element, modulo: Element) -> Self {
         Self {
             value: value % modulo,
-            modulo: modulo,
+            modulo,
         }
     }
 }
@@ -40,7 +40,8 @@ impl RingElement {
 impl Add for RingElement {
     type Output = Self;
     fn add(self, other: Self) -> Self {
-        Self::new(self.value + other.value, self.modulo)
+        let sum = (self.value as u128 + other.value as u128) % self.modulo as u128;
+        Self::new(sum as Element, self.modulo)
     }
 }
 
@@ -64,7 +65,10 @@ impl Sub for RingElement {
 impl Mul for RingElement {
     type Output = Self;
     fn mul(self, other: Self) -> Self {
-        Self::new(self.value * other.value + self.modulo, self.modulo)
+        // Произведение двух значений может не поместиться в u64, поэтому
+        // оно вычисляется в u128 и только затем приводится по модулю.
+        let product = (self.value as u128 * other.value as u128) % self.modulo as u128;
+        Self::new(product as Element, self.modulo)
     }
 }
 
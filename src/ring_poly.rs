@@ -0,0 +1,283 @@
+use super::ring_context::RingContext;
+use super::ring_element::RingElement;
+use super::Element;
+use std::ops::*;
+
+/// Многочлен кольца
+/// --
+/// Элемент кольца Z_q[X]/(X^N+1), в котором работает CKKS. Коэффициенты
+/// хранятся как остатки по модулю q в диапазоне [0, q), а умножение
+/// выполняется по модулю X^N+1 (негациклическая свёртка): так как X^N = -1,
+/// слагаемые со степенью N и выше переносятся в начало с обратным знаком.
+///
+/// Размерность N должна быть степенью двойки, тогда X^N+1 - круговой
+/// многочлен порядка 2N и определены автоморфизмы X -> X^k для нечётных k.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RingPoly {
+    /// Коэффициенты многочлена в порядке возрастания степеней
+    pub coefficients: Vec<Element>,
+    /// Модуль относительно которого выполняются операции с коэффициентами
+    pub modulo: Element,
+}
+
+use std::fmt;
+impl fmt::Display for RingPoly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, c) in self.coefficients.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", c)?;
+        }
+        write!(f, "] mod {}", self.modulo)
+    }
+}
+
+impl RingPoly {
+    /// Нулевой многочлен размерности n
+    pub fn zero(context: &RingContext, n: usize) -> Self {
+        Self::from_coefficients(context, &vec![0; n])
+    }
+
+    /// Многочлен с заданными коэффициентами, размерность равна их количеству
+    pub fn from_coefficients(context: &RingContext, coefficients: &[Element]) -> Self {
+        Self::with_modulo(context.modulo(), coefficients)
+    }
+
+    /// Многочлен со знаковыми коэффициентами, которые приводятся по модулю q
+    pub fn from_signed<T: Into<i128> + Copy>(context: &RingContext, coefficients: &[T]) -> Self {
+        let q = context.modulo() as i128;
+        let coefficients: Vec<Element> = coefficients
+            .iter()
+            .map(|&x| x.into().rem_euclid(q) as Element)
+            .collect();
+        Self::with_modulo(context.modulo(), &coefficients)
+    }
+
+    fn with_modulo(modulo: Element, coefficients: &[Element]) -> Self {
+        assert!(
+            coefficients.len().is_power_of_two(),
+            "Ring dimension must be a power of 2"
+        );
+        Self {
+            coefficients: coefficients.iter().map(|&x| x % modulo).collect(),
+            modulo,
+        }
+    }
+
+    /// Размерность кольца N
+    pub fn dimension(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    /// Коэффициент при X^i как элемент кольца Z_q
+    pub fn element(&self, i: usize) -> RingElement {
+        RingElement::new(self.coefficients[i], self.modulo)
+    }
+
+    /// Центрированные представители коэффициентов из (-q/2, q/2]
+    pub fn to_centered(&self) -> Vec<i64> {
+        self.coefficients
+            .iter()
+            .map(|&x| {
+                // Считаем в i128: при q >= 2^63 модуль не помещается в i64,
+                // а центрированный представитель по модулю меньше 2^63
+                let (x, q) = (x as i128, self.modulo as i128);
+                let centered = if x > q / 2 { x - q } else { x };
+                i64::try_from(centered).unwrap()
+            })
+            .collect()
+    }
+
+    /// Умножение на скаляр из Z_q
+    pub fn scalar_mul(&self, scalar: Element) -> Self {
+        let scalar = scalar % self.modulo;
+        Self {
+            coefficients: self
+                .coefficients
+                .iter()
+                .map(|&x| mul_mod(x, scalar, self.modulo))
+                .collect(),
+            modulo: self.modulo,
+        }
+    }
+
+    /// Автоморфизм X -> X^k, k должно быть нечётным.
+    ///
+    /// Коэффициент при X^i переходит к X^(i*k mod 2N), и так как X^N = -1,
+    /// при попадании в верхнюю половину [N, 2N) знак меняется.
+    pub fn automorphism(&self, k: usize) -> Self {
+        assert!(k % 2 == 1, "Automorphism index must be odd");
+        let n = self.dimension();
+        let mut coefficients = vec![0; n];
+
+        for (i, &c) in self.coefficients.iter().enumerate() {
+            let j = i * k % (2 * n);
+            if j < n {
+                coefficients[j] = c;
+            } else {
+                coefficients[j - n] = neg_mod(c, self.modulo);
+            }
+        }
+
+        Self {
+            coefficients,
+            modulo: self.modulo,
+        }
+    }
+
+    /// Бесконечная норма: максимальный модуль центрированного коэффициента
+    pub fn norm_inf(&self) -> u64 {
+        self.to_centered()
+            .iter()
+            .map(|x| x.unsigned_abs())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Норма L1: сумма модулей центрированных коэффициентов
+    pub fn norm_l1(&self) -> u128 {
+        self.to_centered()
+            .iter()
+            .map(|x| x.unsigned_abs() as u128)
+            .sum()
+    }
+
+    /// Евклидова норма центрированных коэффициентов
+    pub fn norm_l2(&self) -> f64 {
+        self.to_centered()
+            .iter()
+            .map(|&x| (x as f64) * (x as f64))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    fn check(&self, other: &Self) {
+        if self.modulo != other.modulo || self.dimension() != other.dimension() {
+            panic!("This operation is not enclose in its ring.")
+        }
+    }
+}
+
+/// Сложение по модулю без переполнения u64
+fn add_mod(a: Element, b: Element, modulo: Element) -> Element {
+    ((a as u128 + b as u128) % modulo as u128) as Element
+}
+
+/// Умножение по модулю: произведение вычисляется в u128
+fn mul_mod(a: Element, b: Element, modulo: Element) -> Element {
+    ((a as u128 * b as u128) % modulo as u128) as Element
+}
+
+fn neg_mod(a: Element, modulo: Element) -> Element {
+    if a == 0 {
+        0
+    } else {
+        modulo - a
+    }
+}
+
+impl Add for RingPoly {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        self.check(&other);
+        Self {
+            coefficients: self
+                .coefficients
+                .iter()
+                .zip(other.coefficients.iter())
+                .map(|(&a, &b)| add_mod(a, b, self.modulo))
+                .collect(),
+            modulo: self.modulo,
+        }
+    }
+}
+
+impl Sub for RingPoly {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        self.check(&other);
+        Self {
+            coefficients: self
+                .coefficients
+                .iter()
+                .zip(other.coefficients.iter())
+                .map(|(&a, &b)| add_mod(a, neg_mod(b, self.modulo), self.modulo))
+                .collect(),
+            modulo: self.modulo,
+        }
+    }
+}
+
+impl Neg for RingPoly {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self {
+            coefficients: self
+                .coefficients
+                .iter()
+                .map(|&a| neg_mod(a, self.modulo))
+                .collect(),
+            modulo: self.modulo,
+        }
+    }
+}
+
+impl Mul for RingPoly {
+    type Output = Self;
+    /// Негациклическое умножение: c_k = sum_{i+j=k} a_i b_j - sum_{i+j=k+N} a_i b_j
+    fn mul(self, other: Self) -> Self {
+        self.check(&other);
+        let n = self.dimension();
+        let q = self.modulo;
+        let mut coefficients = vec![0; n];
+
+        for (i, &a) in self.coefficients.iter().enumerate() {
+            if a == 0 {
+                continue;
+            }
+            for (j, &b) in other.coefficients.iter().enumerate() {
+                let term = mul_mod(a, b, q);
+                if i + j < n {
+                    coefficients[i + j] = add_mod(coefficients[i + j], term, q);
+                } else {
+                    coefficients[i + j - n] = add_mod(coefficients[i + j - n], neg_mod(term, q), q);
+                }
+            }
+        }
+
+        Self {
+            coefficients,
+            modulo: q,
+        }
+    }
+}
+
+impl Mul<RingElement> for RingPoly {
+    type Output = Self;
+    fn mul(self, other: RingElement) -> Self {
+        if self.modulo != other.modulo {
+            panic!("This operation is not enclose in its ring.")
+        }
+        self.scalar_mul(other.value)
+    }
+}
+
+impl AddAssign for RingPoly {
+    fn add_assign(&mut self, other: Self) {
+        *self = self.clone() + other;
+    }
+}
+
+impl SubAssign for RingPoly {
+    fn sub_assign(&mut self, other: Self) {
+        *self = self.clone() - other;
+    }
+}
+
+impl MulAssign for RingPoly {
+    fn mul_assign(&mut self, other: Self) {
+        *self = self.clone() * other;
+    }
+}
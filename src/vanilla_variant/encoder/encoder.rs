@@ -1,126 +1,147 @@
 use super::plain_text::PlainText;
+use super::polynomial::Polynomial;
+use crate::core::math::dftransform::DiscreteFourierTransform;
+use crate::core::math::distributiongenerator::PRNG;
 use core::f64;
-use error::LinalgError;
+use num::Complex;
+use rand::Rng;
 use std::f64::consts::PI;
 
 // Крейт `ndarray` предоставляет *n*-мерный контейнер для общих элементов.
 // и для числовых значений.
-use ndarray::{s, Array, Array1, Array2};
+use ndarray::{s, Array1};
 
-// Крейт `ndarray-linalg` предоставляет функции линейной алгебры для `ArrayBase`,
-// структуры данных n-мерного массива, предоставляемой
-// [`ndarray`](https://github.com/rust-ndarray/ndarray).
+// Из `ndarray-linalg` берётся только тип комплексного числа: кодирование и
+// декодирование выполняются через БПФ и не обращаются к LAPACK.
 use ndarray_linalg::types::c64;
-use ndarray_linalg::*;
 
 /// !Класс, отвечающий за кодирование и декодирование данных в контексте CKKS.
+/// --
+/// Слоты упорядочены по группе вращений: слот k - это значение многочлена в
+/// корне ξ^(5^k), ξ = e^(2 pi i / m). Поэтому автоморфизм X -> X^5 циклически
+/// сдвигает слоты. Кодирование и декодирование выполняются специальным БПФ
+/// за O(N log N), N = m/2.
+///
+/// Количество слотов - любая степень двойки не больше N/2. Разреженный
+/// вектор из slots < N/2 значений кодируется многочленом от X^gap,
+/// gap = N / (2 * slots), и при декодировании всех N/2 слотов повторяется
+/// N / (2 * slots) раз.
 pub struct CKKSEncoder {
-    /// Количество элементов для которых создается матрица Вандермонда.
+    /// Порядок круговой группы m = 2N.
     m: usize,
     /// Единичный элемент
     unity: c64,
-    /// Базис для преобразований
-    basis: Array2<c64>,
     /// Коэффициент для масштабирования
     scale: usize,
 }
 
 impl CKKSEncoder {
     pub fn new(m: usize, scale: usize) -> CKKSEncoder {
+        assert!(
+            m.is_power_of_two() && m >= 4,
+            "The cyclotomic order must be a power of 2 not less than 4"
+        );
         // В результате получается комплексное число, представляющее собой
         // (e^{\frac{2\pi i}{m}}), то есть корень (m)-й степени из единицы.
-        // Этот корень используется для генерации элементов матрицы Вандермонда
-        // в последующем коде.
-        let unity = (2f64 * c64::i() /* Возвращает мнимую единицу */ 
-            / c64::new(
-                // Вещественная часть комплексного числа
-                m as f64, 
-                // Мнимая часть комплексного числа
-                0f64)
-            * PI)
-            .exp();
-
-        let basis = CKKSEncoder::vandermonde(m, unity).t().to_owned();
-        CKKSEncoder {
-            m,
-            unity,
-            basis,
-            scale,
-        }
+        let unity = c64::from_polar(&1f64, &(2f64 * PI / m as f64));
+
+        CKKSEncoder { m, unity, scale }
     }
 
     pub fn unity(&self) -> c64 {
         self.unity
     }
 
-    pub fn basis(&self) -> Array2<c64> {
-        self.basis.clone()
+    /// Максимальное количество слотов N/2
+    pub fn slots(&self) -> usize {
+        self.m / 4
     }
 }
 
 impl CKKSEncoder {
-    /// !Кодирование, где z - одномерный массив
-    pub fn encode(&self, z: Array1<c64>) -> Result<PlainText, LinalgError> {
-        // C^n/2 -> H
-        let pi_z = self.pi_inverse(&z);
-
-        // Поддерживается точность. Перемножаем все элементы pi_z на scale.
-        let scaled = pi_z.mapv(|x| self.scale as f64 * x);
-
-        // H -> sigma(R) s.t. sigma(R) \in H
-        let rounded = self.into_integer_basis(scaled);
-
-        /* sigma(R) -> R . инверсия сигмы */
-        let mut plain_text = self.sigma_inverse(rounded)?;
-
-        /* содержит значения c64. Необходимо преобразовать их в целые числа. */
-        plain_text.0.mapv_inplace(|x| c64::new(x.re.round(), x.im));
+    /// !Кодирование, где z - одномерный массив из slots значений, slots -
+    /// степень двойки не больше N/2.
+    /// Случайное округление берёт случайность из prng, поэтому при
+    /// одинаковом зерне кодирование воспроизводится побитово.
+    pub fn encode(&self, z: Array1<c64>, prng: &mut PRNG) -> PlainText {
+        let n = self.m / 2;
+        let nh = n / 2;
+        let slots = z.len();
+        assert!(
+            slots.is_power_of_two() && slots <= nh,
+            "The number of slots must be a power of 2 not greater than N/2"
+        );
+
+        /* C^slots -> коэффициенты многочлена от X^gap обратным специальным БПФ */
+        let mut vals: Vec<Complex<f64>> = z.iter().map(|x| Complex::new(x.re, x.im)).collect();
+        DiscreteFourierTransform::fft_special_inv(&mut vals, self.m as u32);
+
+        // Вещественные части ложатся в коэффициенты при X^(j*gap), мнимые -
+        // при X^(N/2 + j*gap): в корнях ξ^(5^k) значение X^(N/2) равно i.
+        // Поддерживается точность: все коэффициенты умножаются на scale.
+        let gap = nh / slots;
+        let mut coefficients = vec![0f64; n];
+        for (j, x) in vals.iter().enumerate() {
+            coefficients[j * gap] = x.re * self.scale as f64;
+            coefficients[nh + j * gap] = x.im * self.scale as f64;
+        }
 
-        Ok(plain_text)
+        /* Округляем один раз и дальше храним целые коэффициенты. */
+        PlainText::new(CKKSEncoder::coordinates_wise_random_rounding(
+            coefficients,
+            prng,
+        ))
     }
 
-    /// !Декодирование :: R ->  sigma(R) -> C^n/2
-    pub fn decode(&self, p: PlainText) -> Result<Array1<c64>, LinalgError> {
-        /*  деление коэффициентов p на масштаб .*/
-        let rescaled: PlainText = p / self.scale;
-
-        /*  R -> sigma(R) .*/
-        let rounded = self.sigma(rescaled)?;
-
-        /* sigma(R) -> C^n/2 */
-        Ok(self.pi(&rounded))
+    /// !Декодирование всех N/2 слотов :: R -> C^N/2
+    pub fn decode(&self, p: PlainText) -> Array1<c64> {
+        self.decode_slots(p, self.slots())
     }
 
-    /// !Декодирование: \mathcal{R}->\sigma(\mathcal{R})$
-    /// Вычисление x .
-    /// x = A z
-    ///
-    /// Для вычисления x присвойте корень в A, вычислите его и возьмите произведение с z.
-    /// Где: A - матрица Вандермонда. $z \в \mathcal{R},x \в \mathcal{\sigma{R}}$
-    pub fn sigma(&self, poly: PlainText) -> Result<Array1<c64>, LinalgError> {
+    /// !Декодирование первых slots слотов, slots - степень двойки не больше N/2.
+    /// Для открытого текста, закодированного из slots значений, возвращает
+    /// исходный вектор.
+    pub fn decode_slots(&self, p: PlainText, slots: usize) -> Array1<c64> {
         let n = self.m / 2;
-        let mut z = vec![];
-
-        for i in 0..n {
-            let root = self.unity.powu(2 * i as u32 + 1);
-            let res = poly.eval(root);
-            z.push(res);
-        }
+        let nh = n / 2;
+        assert!(
+            slots.is_power_of_two() && slots <= nh,
+            "The number of slots must be a power of 2 not greater than N/2"
+        );
+
+        /*  подъём целых коэффициентов во float и деление на масштаб .*/
+        let rescaled = CKKSEncoder::reduce(&(Polynomial::from(&p) / self.scale), n);
+
+        let gap = nh / slots;
+        let mut vals: Vec<Complex<f64>> = (0..slots)
+            .map(|j| Complex::new(rescaled[j * gap].re, rescaled[nh + j * gap].re))
+            .collect();
+        DiscreteFourierTransform::fft_special(&mut vals, self.m as u32);
+
+        vals.iter().map(|x| c64::new(x.re, x.im)).collect()
+    }
 
-        // TODO: позволяет нам обнаружить isize::MAX < res.len().
-        Ok(Array::from(z))
+    /// !Каноническое вложение: \mathcal{R}->\sigma(\mathcal{R})$
+    /// Значения многочлена в корнях X^N + 1 в естественном порядке:
+    /// x_i = p(unity^(2i+1)), i = 0..N. Многочлен предварительно приводится
+    /// по модулю X^N + 1, значения вычисляются БПФ.
+    pub fn sigma(&self, poly: Polynomial) -> Array1<c64> {
+        let coefficients = CKKSEncoder::reduce(&poly, self.m / 2);
+        DiscreteFourierTransform::forward_transform(&coefficients)
+            .iter()
+            .map(|x| c64::new(x.re, x.im))
+            .collect()
     }
 
-    /// !Кодирование: $\sigma(\mathbf{R})->\mathcal{R}$
-    /// Решает следующее выражение:
-    /// $ z = A^-1 x $
-    ///
-    /// Где: A - матрица Вандермонда.
-    pub fn sigma_inverse(&self, z: Array1<c64>) -> Result<PlainText, LinalgError> {
-        let vandermonde = CKKSEncoder::vandermonde(self.m, self.unity);
-        // Решает систему линейных уравнений `A * x = b`, где `A` - это `self`, `b` - аргумент, а `x` - успешный результат.
-        let coefficients = vandermonde.solve_into(z)?;
-        Ok(PlainText::new(coefficients))
+    /// !Обратное к каноническому вложению: $\sigma(\mathbf{R})->\mathcal{R}$
+    /// Коэффициенты многочлена по его значениям в корнях X^N + 1, вычисляются
+    /// обратным БПФ вместо решения системы с матрицей Вандермонда.
+    pub fn sigma_inverse(&self, z: Array1<c64>) -> Polynomial {
+        let n = self.m / 2;
+        assert_eq!(z.len(), n, "The vector must have N = m/2 elements");
+        let values: Vec<Complex<f64>> = z.iter().map(|x| Complex::new(x.re, x.im)).collect();
+        let coefficients = DiscreteFourierTransform::inverse_transform(&values);
+        Polynomial::new(coefficients.iter().map(|x| c64::new(x.re, x.im)).collect())
     }
 
     pub fn pi(&self, z: &Array1<c64>) -> Array1<c64> {
@@ -136,80 +157,40 @@ impl CKKSEncoder {
         //2. Преобразование элементов 'z' в сопряженные
         let zd_conjugate = zd.mapv(|x| x.conj());
 
-        let mut res = vec![];
-        for i in z.into_iter().chain(zd_conjugate.into_iter()) {
-            res.push(*i)
-        }
-
-        // TODO: позволяет нам обнаружить isize::MAX < res.len().
-        Array::from(res)
-    }
-
-    pub fn into_integer_basis(&self, z: Array1<c64>) -> Array1<c64> {
-        let real_coordinates = CKKSEncoder::compute_basis_coordinate(&self.basis, z);
-        let rounded_coordinates = CKKSEncoder::coordinates_wise_random_rounding(real_coordinates);
-        self.basis.t().dot(&rounded_coordinates)
+        z.iter().chain(zd_conjugate.iter()).copied().collect::<Array1<_>>()
     }
 }
 
 /* Вспомогательные методы */
 impl CKKSEncoder {
-    /// Выполняются следующие вычисления:
-    ///
-    /// $<z,bi>/|b_i|^2$
-    ///
-    fn compute_basis_coordinate(basis: &Array2<c64>, z: Array1<c64>) -> Array1<c64> {
-        let mut tmp = vec![];
-        for i in 0..basis.nrows() {
-            let bi = basis.row(i).to_owned();
-            let bi_conj = bi.mapv(|x| x.conj());
-            let mut zi = z.dot(&bi_conj) / bi.dot(&bi_conj);
-            zi.im = 0.;
-            tmp.push(zi);
+    /// Коэффициенты многочлена по модулю X^n + 1: X^(n+i) = -X^i
+    fn reduce(poly: &Polynomial, n: usize) -> Vec<Complex<f64>> {
+        let mut res = vec![Complex::new(0f64, 0f64); n];
+        for (i, x) in poly.0.iter().enumerate() {
+            let x = Complex::new(x.re, x.im);
+            if (i / n).is_multiple_of(2) {
+                res[i % n] += x;
+            } else {
+                res[i % n] -= x;
+            }
         }
-        Array1::from(tmp)
+        res
     }
 
-    fn coordinates_wise_random_rounding(coordinates: Array1<c64>) -> Array1<c64> {
-        use rand::distributions::weighted::WeightedIndex;
-        use rand::prelude::*;
-        let decimals = &coordinates - &coordinates.mapv(|x| c64::new(x.re.floor(), x.im));
+    /// Случайное округление каждой координаты: c округляется вверх с
+    /// вероятностью, равной дробной части c, и вниз в остальных случаях.
+    /// Коэффициенты многочлена - это координаты в ортогональном базисе
+    /// sigma(X^i), поэтому округление выполняется прямо по ним.
+    fn coordinates_wise_random_rounding(coordinates: Vec<f64>, prng: &mut PRNG) -> Vec<i128> {
         // Чем ближе целое число, тем выше вероятность округления.
-        // Например, если c=4,6, то вероятность округления до 4 равна 0,6, а вероятность округления до 5 - 0,4.
-        let subtract_decimals = decimals.mapv(|c| {
-            let choices = [c, c - 1.];
-            let weights = [1. - c.re, c.re];
-            let mut rng = rand::thread_rng();
-            let dist = WeightedIndex::new(&weights).unwrap();
-            choices[dist.sample(&mut rng)]
-        });
-        // Координаты, округленные до ближайшего целого числа.
-        // Например, координата = [1.2,1.7,2.6] и предположим, что subtract_decimals = [-0.8,0.7,-0.4].
-        // Вы можете выполнить вычитание по координатам.
-        // координата - subtract_decimals = [2.0,1.0,3.0].
-        coordinates - subtract_decimals
-    }
-
-    /// Создаёт матрицу Вандермонда.
-    /// --
-    /// В данном контексте, метод vandermonde использует единичный элемент unity
-    /// (комплексное число, обозначающее корень из единицы) для генерации набора
-    /// значений (\alpha_i). Матрица формируется для (m/2) элементов, где каждый
-    /// элемент — это степень единицы, возводимая в соответствующую степень для
-    /// каждой строки матрицы. Это делается для подготовки основания для преобразования
-    /// данных в процессе кодирования и декодирования в CKKSEncoder.
-    fn vandermonde(m: usize, unity: c64) -> Array2<c64> {
-        let n: usize = m / 2;
-        let mut mat = vec![];
-
-        for i in 0..n {
-            let root = unity.powu(2 * i as u32 + 1);
-            for j in 0..n {
-                mat.push(root.powu(j as u32));
-            }
-        }
-
-        let res = Array::from_shape_vec((n, n), mat).unwrap();
-        res
+        // Например, если c=4,6, то вероятность округления до 4 равна 0,4, а вероятность округления до 5 - 0,6.
+        coordinates
+            .iter()
+            .map(|&c| {
+                let floor = c.floor();
+                let round_up = prng.gen::<f64>() < c - floor;
+                floor as i128 + round_up as i128
+            })
+            .collect()
     }
 }
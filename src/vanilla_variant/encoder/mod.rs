@@ -1,3 +1,4 @@
-#[allow(dead_code)]
+#[allow(dead_code, clippy::module_inception)]
 pub mod encoder;
 pub mod plain_text;
+pub mod polynomial;
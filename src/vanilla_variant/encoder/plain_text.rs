@@ -1,61 +1,265 @@
-use ndarray::{Array, Array1};
-use ndarray_linalg::types::c64;
+use crate::core::lattice::hal::default::dcrtpoly::DCRTPolyImpl;
+use crate::core::lattice::hal::default::ildcrtparams::ILDCRTParams;
+use crate::core::lattice::hal::default::ilparams::ILParamsImpl;
+use crate::core::lattice::hal::default::poly::{Format, PolyImpl};
+use crate::core::math::hal::integer::{BigIntegerInterface, IntegerInterface};
+use crate::core::math::hal::transform::TransformInterface;
+use crate::core::math::hal::vector::NativeVector;
+use crate::ring_context::RingContext;
+use crate::ring_poly::RingPoly;
+use fhe_core::lattice::hal::element::ILElement;
+use num::{BigInt, Integer, ToPrimitive};
 use std::ops::*;
+use std::sync::Arc;
 
-/// Эта структура представляет основу для манипуляции с текстом на уровне
-/// высокоуровневой абстракции в контексте схемы CKKS.Структура
-/// сконцентрирована на представлении и манипуляции с полиномами. Или,
-/// в другом контексте, могут представлять вектор коэффициентов для полинома,
-/// где коэффициенты являются комплексными числами (c64).
-#[derive(Debug, Clone)]
-pub struct PlainText(pub Array1<c64>);
+/// Открытый текст CKKS: многочлен с целыми знаковыми коэффициентами.
+/// --
+/// Кодировщик округляет масштабированный многочлен до целых один раз, после
+/// чего открытый текст хранится точно и переводится в кольцо Z_q[X]/(X^N+1)
+/// без накопления погрешности. Для кольца с одним модулем используется
+/// представление вычетов (как в Poly), для RNS - вычеты по каждому модулю
+/// базы (башни DCRTPoly). Перевод в многочлены решётки PolyImpl и
+/// DCRTPolyImpl даёт вход для RNS-конвейера, обратный перевод центрирует
+/// коэффициенты.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlainText(pub Vec<i128>);
 
 impl PlainText {
-    pub fn new(vec: Array1<c64>) -> PlainText {
+    pub fn new(vec: Vec<i128>) -> PlainText {
         PlainText(vec)
     }
 
-    /// Вычисляет значение полинома в заданном коэффициенте
-    #[allow(dead_code)]
-    pub fn eval(&self, root: c64) -> c64 {
-        let mut sum = c64::new(0f64, 0f64);
-        for i in 0..self.0.len() {
-            sum = sum + root.powu(i as u32) * self.0[i];
-        }
-        sum
+    /// Создаёт многочлен по целочисленным коэффициентам
+    pub fn from_coefficients(coefficients: &[i128]) -> PlainText {
+        PlainText(coefficients.to_vec())
+    }
+
+    /// Целочисленные коэффициенты многочлена
+    pub fn coefficients(&self) -> &[i128] {
+        &self.0
+    }
+
+    /// Количество коэффициентов
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
     }
 
-    /// Возвращает "размер" полинома, вычисленный как корень из суммы
-    /// квадратов модулей коэффициентов. Размер функции:
-    ///|h| = (a_0^2 + a_1^2 + ... + a_n^2 )^1/2
-    #[allow(dead_code)]
-    pub fn size(&self) -> c64 {
+    /// Бесконечная норма: максимальный модуль коэффициента
+    pub fn norm_inf(&self) -> u128 {
+        self.0.iter().map(|x| x.unsigned_abs()).max().unwrap_or(0)
+    }
+
+    /// Переводит многочлен в кольцо Z_q[X]/(X^N+1) заданного контекста
+    pub fn to_ring(&self, context: &RingContext) -> RingPoly {
+        RingPoly::from_signed(context, &self.0)
+    }
+
+    /// Создаёт многочлен по элементу кольца, используя центрированные
+    /// представители коэффициентов из (-q/2, q/2]
+    pub fn from_ring(poly: &RingPoly) -> PlainText {
+        PlainText::from_residues(&poly.coefficients, poly.modulo)
+    }
+
+    /// Вычеты коэффициентов по модулю q из [0, q)
+    pub fn to_residues(&self, modulus: u64) -> Vec<u64> {
         self.0
             .iter()
-            .fold(c64::new(0f64, 0f64), |sum, a| sum + a.powi(2))
-            .powf(0.5)
+            .map(|&x| x.rem_euclid(modulus as i128) as u64)
+            .collect()
+    }
+
+    /// Центрированный подъём вычетов по модулю q в (-q/2, q/2]
+    pub fn from_residues(residues: &[u64], modulus: u64) -> PlainText {
+        PlainText(
+            residues
+                .iter()
+                .map(|&x| {
+                    let x = (x % modulus) as i128;
+                    if x > (modulus / 2) as i128 {
+                        x - modulus as i128
+                    } else {
+                        x
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// RNS-представление: для каждого модуля q_i базы вектор вычетов
+    /// коэффициентов по q_i
+    pub fn to_rns(&self, moduli: &[u64]) -> Vec<Vec<u64>> {
+        moduli.iter().map(|&q| self.to_residues(q)).collect()
+    }
+
+    /// Восстанавливает многочлен из RNS-представления по китайской теореме
+    /// об остатках и центрирует результат по модулю Q = q_0 * q_1 * ...
+    /// Модули должны быть попарно взаимно просты.
+    pub fn from_rns(residues: &[Vec<u64>], moduli: &[u64]) -> PlainText {
+        assert_eq!(
+            residues.len(),
+            moduli.len(),
+            "Every RNS tower must have its modulus"
+        );
+        let big_q: BigInt = moduli.iter().map(|&q| BigInt::from(q)).product();
+        let half = &big_q / 2;
+
+        // Q_i = Q / q_i и [Q_i^-1]_{q_i}
+        let basis: Vec<(BigInt, BigInt)> = moduli
+            .iter()
+            .map(|&q| {
+                let q = BigInt::from(q);
+                let q_hat = &big_q / &q;
+                let gcd = q_hat.extended_gcd(&q);
+                assert!(
+                    gcd.gcd == BigInt::from(1),
+                    "RNS moduli must be pairwise coprime"
+                );
+                (q_hat, gcd.x.mod_floor(&q))
+            })
+            .collect();
+
+        let n = residues.first().map_or(0, |r| r.len());
+        let coefficients = (0..n)
+            .map(|j| {
+                let mut x = BigInt::from(0);
+                for (tower, (q_hat, q_hat_inv)) in residues.iter().zip(basis.iter()) {
+                    x += BigInt::from(tower[j]) * q_hat_inv * q_hat;
+                }
+                let mut x = x.mod_floor(&big_q);
+                if x > half {
+                    x -= &big_q;
+                }
+                x.to_i128().expect("The coefficient does not fit into i128")
+            })
+            .collect();
+
+        PlainText(coefficients)
+    }
+
+    /// Многочлен решётки по модулю params в формате COEFFICIENT; число
+    /// коэффициентов должно совпадать с размерностью кольца
+    pub fn to_poly<V: TransformInterface>(
+        &self,
+        params: &Arc<ILParamsImpl<V::Integer>>,
+    ) -> PolyImpl<V> {
+        self.check_ring_dimension(params.get_ring_dimension() as usize);
+        let modulus = params.get_modulus();
+        let values: Vec<V::Integer> = self
+            .0
+            .iter()
+            .map(|&x| {
+                let r = big_from_u128::<V::Integer>(x.unsigned_abs()).modulo(modulus);
+                if x < 0 && !r.is_zero() {
+                    modulus.clone() - r
+                } else {
+                    r
+                }
+            })
+            .collect();
+        PolyImpl::with_values(
+            params.clone(),
+            Format::COEFFICIENT,
+            V::from_values(&values, modulus.clone()),
+        )
+    }
+
+    /// Многочлен в представлении двойного CRT: башни - вычеты коэффициентов
+    /// по модулям params, формат COEFFICIENT
+    pub fn to_dcrt_poly<V: TransformInterface>(
+        &self,
+        params: &Arc<ILDCRTParams<V::Integer>>,
+    ) -> DCRTPolyImpl<V> {
+        self.check_ring_dimension(params.get_ring_dimension() as usize);
+        let towers = params
+            .get_params()
+            .iter()
+            .map(|p| {
+                let q = *p.get_modulus();
+                let values = NativeVector::from_values(&self.to_residues(q.value()), q);
+                PolyImpl::with_values(p.clone(), Format::COEFFICIENT, values)
+            })
+            .collect();
+        DCRTPolyImpl::with_towers(params.clone(), towers)
+    }
+
+    /// Центрированный подъём коэффициентов многочлена решётки в (-q/2, q/2]
+    pub fn from_poly<V: TransformInterface>(poly: &PolyImpl<V>) -> PlainText {
+        let mut poly = poly.clone();
+        poly.set_format(Format::COEFFICIENT);
+        let modulus = poly.get_modulus().clone();
+        PlainText(
+            (0..poly.get_length())
+                .map(|j| {
+                    let x = poly[j].clone();
+                    if x.clone() + x.clone() > modulus {
+                        -big_to_i128(&(modulus.clone() - x))
+                    } else {
+                        big_to_i128(&x)
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// Центрированный подъём по модулю Q = q_0 q_1 ... через восстановление
+    /// по CRT
+    pub fn from_dcrt_poly<V: TransformInterface>(poly: &DCRTPolyImpl<V>) -> PlainText {
+        PlainText::from_poly(&poly.crt_interpolate())
+    }
+
+    fn check_ring_dimension(&self, n: usize) {
+        if self.0.len() != n {
+            panic!(
+                "PlainText: {} coefficients do not match the ring dimension {}",
+                self.0.len(),
+                n
+            );
+        }
     }
 }
 
 impl Add for PlainText {
     type Output = Self;
     fn add(self, rhs: Self) -> Self {
-        PlainText(self.0 + rhs.0)
+        let len = self.0.len().max(rhs.0.len());
+        PlainText(
+            (0..len)
+                .map(|i| self.0.get(i).unwrap_or(&0) + rhs.0.get(i).unwrap_or(&0))
+                .collect(),
+        )
+    }
+}
+
+impl Sub for PlainText {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Neg for PlainText {
+    type Output = Self;
+    fn neg(self) -> Self {
+        PlainText(self.0.iter().map(|x| -x).collect())
     }
 }
 
 impl Mul for PlainText {
     type Output = Self;
+    /// Обычное произведение многочленов без приведения по модулю X^N+1
     fn mul(self, rhs: Self) -> Self {
-        let d = self.0.len() + rhs.0.len() - 1;
-        let mut poly = Array::zeros(d);
+        if self.0.is_empty() || rhs.0.is_empty() {
+            return PlainText(vec![]);
+        }
+        let mut poly = vec![0; self.0.len() + rhs.0.len() - 1];
 
-        for k in 0..=d {
-            for i in 0..=k {
-                if self.0.len() <= i || rhs.0.len() <= k - i {
-                    continue;
-                }
-                poly[k] = poly[k] + self.0[i] * rhs.0[k - i];
+        for (i, a) in self.0.iter().enumerate() {
+            for (j, b) in rhs.0.iter().enumerate() {
+                poly[i + j] += a * b;
             }
         }
 
@@ -63,9 +267,23 @@ impl Mul for PlainText {
     }
 }
 
-impl Div<usize> for PlainText {
-    type Output = Self;
-    fn div(self, rhs: usize) -> Self {
-        PlainText::new(self.0.mapv(|x| x / rhs as f64))
+/// 2^64 в длинной арифметике
+fn big_word<I: BigIntegerInterface>() -> I {
+    I::from_u64(1 << 32) * I::from_u64(1 << 32)
+}
+
+fn big_from_u128<I: BigIntegerInterface>(x: u128) -> I {
+    I::from_u64((x >> 64) as u64) * big_word() + I::from_u64(x as u64)
+}
+
+/// Значение длинного целого как i128; паникует, если оно не помещается
+fn big_to_i128<I: BigIntegerInterface>(x: &I) -> i128 {
+    let word = big_word::<I>();
+    match (
+        (x.clone() / word.clone()).to_u64(),
+        (x.clone() % word).to_u64(),
+    ) {
+        (Some(hi), Some(lo)) if hi < 1 << 63 => ((hi as i128) << 64) | lo as i128,
+        _ => panic!("PlainText: the coefficient {} does not fit into i128", x),
     }
 }
@@ -0,0 +1,85 @@
+use super::plain_text::PlainText;
+use ndarray::{Array, Array1};
+use ndarray_linalg::types::c64;
+use std::ops::*;
+
+/// Многочлен с комплексными коэффициентами (c64). В нём живут промежуточные
+/// значения кодировщика: sigma^-1 отображает вектор слотов в такой многочлен,
+/// а декодирование вычисляет его значения в корнях из единицы. В отличие от
+/// PlainText коэффициенты здесь не округлены и могут быть нецелыми.
+#[derive(Debug, Clone)]
+pub struct Polynomial(pub Array1<c64>);
+
+impl Polynomial {
+    pub fn new(vec: Array1<c64>) -> Polynomial {
+        Polynomial(vec)
+    }
+
+    /// Вычисляет значение полинома в заданном коэффициенте
+    #[allow(dead_code)]
+    pub fn eval(&self, root: c64) -> c64 {
+        let mut sum = c64::new(0f64, 0f64);
+        for i in 0..self.0.len() {
+            sum += root.powu(i as u32) * self.0[i];
+        }
+        sum
+    }
+
+    /// Возвращает "размер" полинома, вычисленный как корень из суммы
+    /// квадратов модулей коэффициентов. Размер функции:
+    ///|h| = (a_0^2 + a_1^2 + ... + a_n^2 )^1/2
+    #[allow(dead_code)]
+    pub fn size(&self) -> c64 {
+        self.0
+            .iter()
+            .fold(c64::new(0f64, 0f64), |sum, a| sum + a.powi(2))
+            .powf(0.5)
+    }
+
+    /// Округляет вещественные части коэффициентов до целых. Мнимые части
+    /// после sigma^-1 от сопряжённо-симметричного вектора равны нулю с
+    /// точностью до погрешности вычислений и отбрасываются.
+    pub fn round(&self) -> PlainText {
+        PlainText::new(self.0.iter().map(|x| x.re.round() as i128).collect())
+    }
+}
+
+impl From<&PlainText> for Polynomial {
+    /// Поднимает целые коэффициенты открытого текста во float
+    fn from(p: &PlainText) -> Self {
+        Polynomial(p.0.iter().map(|&x| c64::new(x as f64, 0f64)).collect())
+    }
+}
+
+impl Add for Polynomial {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Polynomial(self.0 + rhs.0)
+    }
+}
+
+impl Mul for Polynomial {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let d = self.0.len() + rhs.0.len() - 1;
+        let mut poly = Array::zeros(d);
+
+        for k in 0..=d {
+            for i in 0..=k {
+                if self.0.len() <= i || rhs.0.len() <= k - i {
+                    continue;
+                }
+                poly[k] += self.0[i] * rhs.0[k - i];
+            }
+        }
+
+        Polynomial(poly)
+    }
+}
+
+impl Div<usize> for Polynomial {
+    type Output = Self;
+    fn div(self, rhs: usize) -> Self {
+        Polynomial::new(self.0.mapv(|x| x / rhs as f64))
+    }
+}
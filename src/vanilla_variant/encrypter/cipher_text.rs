@@ -1,11 +1,176 @@
-use crate::vanilla_variant::encoder::plain_text::PlainText;
+use super::encrypter::RelinearizationKey;
+use crate::ring_context::RingContext;
+use crate::ring_poly::RingPoly;
+use std::ops::*;
 
+/// Шифротекст CKKS: набор многочленов (c0, c1, ...) из кольца Z_q[X]/(X^N+1).
+/// Свежий шифротекст состоит из двух компонент, а расшифрование вычисляет
+/// c0 + c1 * s + c2 * s^2 + ...
+#[derive(Debug, Clone)]
 pub struct CipherText {
-    value: PlainText,
+    /// Компоненты шифротекста
+    pub value: Vec<RingPoly>,
+    /// Модуль q, по которому вычисляются коэффициенты компонент
+    pub modulo: u64,
 }
 
 impl CipherText {
-    pub fn new() -> Self {
-        todo!()
+    pub fn new(c0: RingPoly, c1: RingPoly) -> Self {
+        let modulo = c0.modulo;
+        Self {
+            value: vec![c0, c1],
+            modulo,
+        }
+    }
+
+    /// Степень шифротекста по секретному ключу (для свежего шифротекста равна 1)
+    pub fn degree(&self) -> usize {
+        self.value.len() - 1
+    }
+
+    /// Контекст кольца, в котором лежат компоненты шифротекста
+    pub fn context(&self) -> RingContext {
+        RingContext::new(self.modulo)
+    }
+}
+
+impl CipherText {
+    /// !Релинеаризация: шифротекст степени 2 (c0, c1, c2) превращается в
+    /// (c0', c1'), расшифровывающийся в тот же открытый текст. Компонента c2
+    /// раскладывается по основанию T и умножается на ключ релинеаризации.
+    pub fn relinearize(&self, key: &RelinearizationKey) -> CipherText {
+        assert_eq!(
+            self.degree(),
+            2,
+            "Relinearization expects a degree 2 ciphertext"
+        );
+        let context = self.context();
+
+        let mut c0 = self.value[0].clone();
+        let mut c1 = self.value[1].clone();
+
+        let digits = CipherText::decompose(&self.value[2], key.base, key.value.len());
+        for (digit, (k0, k1)) in digits.into_iter().zip(key.value.iter()) {
+            // Ключ построен по модулю q шифратора, а модуль шифротекста делит q
+            let k0 = RingPoly::from_signed(&context, &k0.to_centered());
+            let k1 = RingPoly::from_signed(&context, &k1.to_centered());
+            c0 += digit.clone() * k0;
+            c1 += digit * k1;
+        }
+
+        CipherText::new(c0, c1)
+    }
+
+    /// !Перемасштабирование: коэффициенты делятся на scale с округлением,
+    /// а модуль уменьшается с q до q / scale.
+    pub fn rescale(&self, scale: u64) -> CipherText {
+        assert_eq!(
+            self.modulo % scale,
+            0,
+            "The modulus must be divisible by the scale"
+        );
+        let context = RingContext::new(self.modulo / scale);
+        let delta = scale as i64;
+
+        let value = self
+            .value
+            .iter()
+            .map(|c| {
+                let coefficients: Vec<i64> = c
+                    .to_centered()
+                    .iter()
+                    .map(|&x| (x + delta / 2).div_euclid(delta))
+                    .collect();
+                RingPoly::from_signed(&context, &coefficients)
+            })
+            .collect();
+
+        CipherText {
+            value,
+            modulo: context.modulo(),
+        }
+    }
+
+    /// Разложение многочлена c (коэффициенты в [0, q)) по основанию base:
+    /// c = d_0 + d_1 * base + d_2 * base^2 + ...
+    fn decompose(c: &RingPoly, base: u64, count: usize) -> Vec<RingPoly> {
+        let mut rest = c.coefficients.clone();
+        let mut digits = vec![];
+
+        for _ in 0..count {
+            let digit: Vec<u64> = rest.iter().map(|x| x % base).collect();
+            rest.iter_mut().for_each(|x| *x /= base);
+            digits.push(RingPoly {
+                coefficients: digit,
+                modulo: c.modulo,
+            });
+        }
+
+        digits
+    }
+
+    /// Выравнивает количество компонент двух шифротекстов
+    fn components(&self, rhs: &CipherText) -> (Vec<RingPoly>, Vec<RingPoly>) {
+        assert_eq!(
+            self.modulo, rhs.modulo,
+            "Ciphertexts must be at the same modulus"
+        );
+        let len = self.value.len().max(rhs.value.len());
+        let zero = RingPoly::zero(&self.context(), self.value[0].dimension());
+        let pad = |v: &Vec<RingPoly>| {
+            let mut v = v.clone();
+            v.resize(len, zero.clone());
+            v
+        };
+        (pad(&self.value), pad(&rhs.value))
+    }
+}
+
+impl Add for CipherText {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let (a, b) = self.components(&rhs);
+        let value = a.into_iter().zip(b).map(|(x, y)| x + y).collect();
+        CipherText {
+            value,
+            modulo: self.modulo,
+        }
+    }
+}
+
+impl Sub for CipherText {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        let (a, b) = self.components(&rhs);
+        let value = a.into_iter().zip(b).map(|(x, y)| x - y).collect();
+        CipherText {
+            value,
+            modulo: self.modulo,
+        }
+    }
+}
+
+impl Mul for CipherText {
+    type Output = Self;
+    /// Тензорное произведение: (a0, a1) * (b0, b1) = (a0 b0, a0 b1 + a1 b0, a1 b1).
+    /// Результат имеет степень 2 и масштаб, равный квадрату исходного.
+    fn mul(self, rhs: Self) -> Self {
+        assert_eq!(
+            self.modulo, rhs.modulo,
+            "Ciphertexts must be at the same modulus"
+        );
+        let zero = RingPoly::zero(&self.context(), self.value[0].dimension());
+        let mut value = vec![zero; self.value.len() + rhs.value.len() - 1];
+
+        for (i, a) in self.value.iter().enumerate() {
+            for (j, b) in rhs.value.iter().enumerate() {
+                value[i + j] += a.clone() * b.clone();
+            }
+        }
+
+        CipherText {
+            value,
+            modulo: self.modulo,
+        }
     }
 }
@@ -1,38 +1,149 @@
+use crate::core::math::distributiongenerator::PRNG;
+use crate::core::math::ternaryuniformgenerator::TernaryUniformGenerator;
+use crate::ring_context::RingContext;
+use crate::ring_poly::RingPoly;
 use crate::vanilla_variant::{encoder::plain_text::PlainText, encrypter::cipher_text::CipherText};
-struct CKKSEncrypter {
-    public_key: (PlainText, PlainText),
-    secret_key: PlainText,
+use rand::Rng;
+use std::f64::consts::PI;
+
+/// !Шифратор CKKS на основе задачи RLWE в кольце Z_q[X]/(X^N+1).
+pub struct CKKSEncrypter {
+    /// Кольцо шифротекстов Z_q
+    context: RingContext,
+    /// Размерность кольца N
+    n: usize,
+    /// Открытый ключ (b, a) = (-a * s + e, a)
+    public_key: (RingPoly, RingPoly),
+    /// Секретный ключ s с коэффициентами из {-1, 0, 1}
+    secret_key: RingPoly,
+    /// Стандартное отклонение гауссовой ошибки
+    sigma: f64,
 }
 
 impl CKKSEncrypter {
-    /// a: choose on uniform with random
-    /// s: small secret polynomial
-    /// e: small noisy polynomial
-    pub fn new(_mu: f64, _sigma: f64) -> CKKSEncrypter {
-        // use rand::distributions::Distribution;
-        // use rand::Rng;
-        // let mut rng = rand::thread_rng();
-        // let gaussian = Normal::new(mu, sigma.sqrt()).unwrap();
-
-        // let a = rng.gen_range(0..10000);
-        // let noise = gaussian.sample(&mut rng);
-
-        // let public_key = (-a + secret * noise, a);
-        // let secret_key = secret;
-        // CKKSEncrypter {
-        //     public_key,
-        //     secret_key,
-        // }
-        todo!()
-    }
-
-    pub fn encrypt(&self, _plain_text: PlainText) -> CipherText {
-        // CipherText::new(plain_text + public_key.0, public_key.1)
-        todo!()
-    }
-
-    pub fn decrypt(&self, _ctxt: CipherText) -> PlainText {
-        // PlainText::new(ctxt.0 + ctxt.1 * secret_key)
-        todo!()
+    /// n: степень кольца N (количество коэффициентов открытого текста)
+    /// modulo: модуль шифротекста q
+    /// sigma: стандартное отклонение шума
+    ///
+    /// a: выбирается равномерно по модулю q
+    /// s: маленький секретный многочлен
+    /// e: маленький шумовой многочлен
+    ///
+    /// Все случайные величины берутся из prng: одинаковое зерно даёт одинаковые ключи.
+    pub fn new(n: usize, modulo: u64, sigma: f64, prng: &mut PRNG) -> CKKSEncrypter {
+        let context = RingContext::new(modulo);
+        let secret_key = CKKSEncrypter::sample_ternary(&context, n, prng);
+        let a = CKKSEncrypter::sample_uniform(&context, n, prng);
+        let e = CKKSEncrypter::sample_gaussian(&context, n, sigma, prng);
+
+        let b = e - a.clone() * secret_key.clone();
+
+        CKKSEncrypter {
+            context,
+            n,
+            public_key: (b, a),
+            secret_key,
+            sigma,
+        }
+    }
+
+    pub fn public_key(&self) -> &(RingPoly, RingPoly) {
+        &self.public_key
+    }
+
+    pub fn modulo(&self) -> u64 {
+        self.context.modulo()
+    }
+
+    /// !Шифрование: (b * u + e0 + m, a * u + e1), где u - тернарный многочлен.
+    pub fn encrypt(&self, plain_text: PlainText, prng: &mut PRNG) -> CipherText {
+        let (b, a) = self.public_key.clone();
+
+        let u = CKKSEncrypter::sample_ternary(&self.context, self.n, prng);
+        let e0 = CKKSEncrypter::sample_gaussian(&self.context, self.n, self.sigma, prng);
+        let e1 = CKKSEncrypter::sample_gaussian(&self.context, self.n, self.sigma, prng);
+
+        let c0 = b * u.clone() + e0 + plain_text.to_ring(&self.context);
+        let c1 = a * u + e1;
+
+        CipherText::new(c0, c1)
+    }
+
+    /// !Расшифрование: c0 + c1 * s + c2 * s^2 + ... по модулю q шифротекста.
+    /// Вычисляется по схеме Горнера, поэтому подходит для шифротекстов любой степени.
+    pub fn decrypt(&self, ctxt: CipherText) -> PlainText {
+        // После перемасштабирования модуль шифротекста меньше q и делит его
+        let s = RingPoly::from_signed(&ctxt.context(), &self.secret_key.to_centered());
+        let mut components = ctxt.value.into_iter().rev();
+        let mut res = components.next().unwrap();
+
+        for c in components {
+            res = res * s.clone() + c;
+        }
+
+        PlainText::from_ring(&res)
+    }
+}
+
+/* Вспомогательные методы выборки */
+impl CKKSEncrypter {
+    /// Многочлен с коэффициентами, равномерно выбранными из {-1, 0, 1}
+    fn sample_ternary(context: &RingContext, n: usize, prng: &mut PRNG) -> RingPoly {
+        let coefficients = TernaryUniformGenerator::generate_int_vector(n, 0, prng);
+        RingPoly::from_signed(context, &coefficients)
+    }
+
+    /// Многочлен с коэффициентами, равномерно выбранными из [0, q)
+    fn sample_uniform(context: &RingContext, n: usize, prng: &mut PRNG) -> RingPoly {
+        let coefficients: Vec<u64> = (0..n)
+            .map(|_| prng.gen_range(0..context.modulo()))
+            .collect();
+        RingPoly::from_coefficients(context, &coefficients)
+    }
+
+    /// Многочлен с округлёнными гауссовыми коэффициентами (преобразование Бокса-Мюллера)
+    fn sample_gaussian(context: &RingContext, n: usize, sigma: f64, prng: &mut PRNG) -> RingPoly {
+        let coefficients: Vec<i64> = (0..n)
+            .map(|_| {
+                let u1: f64 = 1f64 - prng.gen::<f64>();
+                let u2: f64 = prng.gen::<f64>();
+                let z = (-2f64 * u1.ln()).sqrt() * (2f64 * PI * u2).cos();
+                (z * sigma).round() as i64
+            })
+            .collect();
+        RingPoly::from_signed(context, &coefficients)
+    }
+}
+
+/// Ключ релинеаризации: шифрования T^i * s^2 для i = 0..L, где T - основание
+/// разложения, а L = ceil(log_T q). Для каждого i хранится пара
+/// (-a_i * s + e_i + T^i * s^2, a_i).
+pub struct RelinearizationKey {
+    /// Основание разложения T
+    pub base: u64,
+    /// Пары многочленов ключа для каждой степени основания
+    pub value: Vec<(RingPoly, RingPoly)>,
+}
+
+impl CKKSEncrypter {
+    /// !Генерация ключа релинеаризации с основанием разложения base.
+    /// Ключ построен по модулю q шифратора и годится для любого модуля,
+    /// делящего q, поэтому его можно применять и после перемасштабирования.
+    pub fn relinearization_key(&self, base: u64, prng: &mut PRNG) -> RelinearizationKey {
+        let q = self.modulo();
+        let s2 = self.secret_key.clone() * self.secret_key.clone();
+
+        let mut value = vec![];
+        let mut power: u128 = 1;
+        while power < q as u128 {
+            let a = CKKSEncrypter::sample_uniform(&self.context, self.n, prng);
+            let e = CKKSEncrypter::sample_gaussian(&self.context, self.n, self.sigma, prng);
+
+            let b = e - a.clone() * self.secret_key.clone() + s2.scalar_mul(power as u64);
+            value.push((b, a));
+            power *= base as u128;
+        }
+
+        RelinearizationKey { base, value }
     }
 }
@@ -1,2 +1,3 @@
 pub mod cipher_text;
+#[allow(clippy::module_inception)]
 pub mod encrypter;